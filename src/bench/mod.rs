@@ -6,8 +6,8 @@
 use anyhow::Result;
 use colored::Colorize;
 // NOTE: Rayon intentionally not used here - see comment below about ONNX thread pool conflict
-use serde::Serialize;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use crate::chunker::{Chunk, SemanticChunker};
@@ -32,6 +32,84 @@ const TEST_QUERIES: &[(&str, &str)] = &[
 
 const FALSE_POSITIVE_QUERY: &str = "kubernetes deployment yaml helm chart";
 
+fn default_false_positive_query() -> String {
+    FALSE_POSITIVE_QUERY.to_string()
+}
+
+/// A benchmark query with the path substring its best match should contain
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BenchQuery {
+    pub query: String,
+    pub expected_file: String,
+}
+
+/// Query set used to score accuracy, loaded from `--queries` or built in
+///
+/// The file format matches `benchmark-config.json` from the portable
+/// benchmark example: `{"queries": [{"query", "expected_file"}], "false_positive_query"}`.
+/// A bare array of queries is also accepted.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchQueries {
+    pub queries: Vec<BenchQuery>,
+    #[serde(default = "default_false_positive_query")]
+    pub false_positive_query: String,
+}
+
+impl BenchQueries {
+    /// Queries tied to demongrep's own source layout
+    pub fn builtin() -> Self {
+        Self {
+            queries: TEST_QUERIES
+                .iter()
+                .map(|(query, expected_file)| BenchQuery {
+                    query: query.to_string(),
+                    expected_file: expected_file.to_string(),
+                })
+                .collect(),
+            false_positive_query: default_false_positive_query(),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(content)?;
+        let parsed: Self = if value.is_array() {
+            Self {
+                queries: serde_json::from_value(value)?,
+                false_positive_query: default_false_positive_query(),
+            }
+        } else {
+            serde_json::from_value(value)?
+        };
+
+        if parsed.queries.is_empty() {
+            return Err(anyhow::anyhow!("Queries file contains no queries"));
+        }
+        Ok(parsed)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+            .map_err(|e| anyhow::anyhow!("Invalid queries file {}: {}", path.display(), e))
+    }
+}
+
+/// Check whether `path` is the demongrep repository (where the built-in queries apply)
+fn is_demongrep_repo(path: &Path) -> bool {
+    std::fs::read_to_string(path.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+        .and_then(|manifest| {
+            manifest
+                .get("package")?
+                .get("name")?
+                .as_str()
+                .map(|name| name == "demongrep")
+        })
+        .unwrap_or(false)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BenchProfile {
     Smoke,
@@ -67,13 +145,17 @@ pub struct BenchResult {
     pub short_name: String,
     pub dimensions: usize,
     pub quantized: bool,
-    // Performance
+    // Performance (embed and query-eval timings are means over the measured runs)
     pub model_load_ms: u64,
     pub embed_total_ms: u64,
+    pub embed_stddev_ms: f64,
     pub query_eval_ms: u64,
+    pub query_eval_stddev_ms: f64,
     pub embed_throughput: f32,
     pub avg_query_ms: f64,
     pub total_index_ms: u64,
+    /// Measured runs behind each timing (after warmups)
+    pub runs: usize,
     // Quality
     pub accuracy: f32,
     pub avg_score: f32,
@@ -84,6 +166,117 @@ pub struct BenchResult {
     pub chunks_count: usize,
 }
 
+/// Mean and standard deviation of repeated timings
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RunStats {
+    mean_ms: f64,
+    stddev_ms: f64,
+}
+
+impl RunStats {
+    /// Population standard deviation; 0 for a single run
+    fn from_durations(durations: &[Duration]) -> Self {
+        let ms: Vec<f64> = durations.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        let n = ms.len().max(1) as f64;
+        let mean_ms = ms.iter().sum::<f64>() / n;
+        let variance = ms.iter().map(|x| (x - mean_ms).powi(2)).sum::<f64>() / n;
+        Self {
+            mean_ms,
+            stddev_ms: variance.sqrt(),
+        }
+    }
+}
+
+/// How often each timed benchmark step runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchRuns {
+    /// Untimed runs first, to warm caches and the ONNX session
+    pub warmup: usize,
+    /// Timed runs averaged into the result
+    pub measured: usize,
+}
+
+/// Run `step` `warmup` times untimed, then `measured` times
+///
+/// `step` returns its output and how long the timed part took; the output of
+/// the last run is returned with the statistics of the measured durations.
+fn timed_runs<T>(
+    runs: BenchRuns,
+    mut step: impl FnMut() -> Result<(T, Duration)>,
+) -> Result<(T, RunStats)> {
+    for _ in 0..runs.warmup {
+        step()?;
+    }
+
+    let mut durations = Vec::with_capacity(runs.measured);
+    let mut output = None;
+    for _ in 0..runs.measured.max(1) {
+        let (value, elapsed) = step()?;
+        durations.push(elapsed);
+        output = Some(value);
+    }
+
+    let output = output.expect("at least one measured run");
+    Ok((output, RunStats::from_durations(&durations)))
+}
+
+/// The exact file set a benchmark ran over, recorded so runs are comparable
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BenchCorpus {
+    /// Files in discovery order (sorted by path), relative to the project root
+    pub files: Vec<String>,
+    pub files_count: usize,
+    pub chunks_count: usize,
+}
+
+/// Full benchmark output: the corpus plus one result per model
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub corpus: BenchCorpus,
+    pub results: Vec<BenchResult>,
+}
+
+/// Discover and chunk the benchmark corpus deterministically
+///
+/// Files are sorted by path before `limit` is applied, so the same tree and
+/// limit always yield the same file set and chunks regardless of walk order.
+fn collect_corpus(project_path: &Path, limit: Option<usize>) -> Result<(BenchCorpus, Vec<Chunk>)> {
+    let walker = FileWalker::new(project_path.to_path_buf());
+    let (mut files, _stats) = walker.walk()?;
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    if let Some(max_files) = limit {
+        files.truncate(max_files);
+    }
+
+    let mut chunker = SemanticChunker::new(100, 2000, 10);
+    let mut all_chunks = Vec::new();
+    for file in &files {
+        if let Ok(content) = std::fs::read_to_string(&file.path) {
+            if let Ok(chunks) = chunker.chunk_semantic(file.language, &file.path, &content) {
+                all_chunks.extend(chunks);
+            }
+        }
+    }
+
+    let corpus = BenchCorpus {
+        files: files
+            .iter()
+            .map(|f| {
+                f.path
+                    .strip_prefix(project_path)
+                    .unwrap_or(&f.path)
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect(),
+        files_count: files.len(),
+        chunks_count: all_chunks.len(),
+    };
+
+    Ok((corpus, all_chunks))
+}
+
 /// Get current process RSS in MB (macOS + Linux)
 fn get_rss_mb() -> f64 {
     let pid = std::process::id().to_string();
@@ -155,6 +348,8 @@ fn benchmark_model(
     model_type: ModelType,
     chunks: &[Chunk],
     base_prepared_texts: &[String],
+    queries: &BenchQueries,
+    runs: BenchRuns,
 ) -> Result<BenchResult> {
     let chunks_count = chunks.len();
 
@@ -198,38 +393,56 @@ fn benchmark_model(
         .unwrap_or(base_prepared_texts);
 
     // 4. Embed all chunks
-    let start = Instant::now();
-    let embeddings = embedder.embed_batch_refs(prepared_texts)?;
-    let embed_total_ms = start.elapsed().as_millis() as u64;
-    let embed_throughput = if embed_total_ms > 0 {
-        chunks_count as f32 / (embed_total_ms as f32 / 1000.0)
+    let (embeddings, embed) = timed_runs(runs, || {
+        let start = Instant::now();
+        let embeddings = embedder.embed_batch_refs(prepared_texts)?;
+        Ok((embeddings, start.elapsed()))
+    })?;
+    let embed_total_ms = embed.mean_ms.round() as u64;
+    let embed_throughput = if embed.mean_ms > 0.0 {
+        chunks_count as f32 / (embed.mean_ms as f32 / 1000.0)
     } else {
         0.0
     };
 
     println!(
-        "   Embedded {} chunks in {}ms ({:.0} ch/sec)",
-        chunks_count, embed_total_ms, embed_throughput
+        "   Embedded {} chunks in {} ({:.0} ch/sec)",
+        chunks_count,
+        format_timing(embed, runs.measured),
+        embed_throughput
     );
 
-    // 5. Run accuracy tests
+    // 5. Embed the test queries and the false positive query
+    let ((query_embeddings, fp_embedding), query_eval) = timed_runs(runs, || {
+        let start = Instant::now();
+        let query_embeddings = queries
+            .queries
+            .iter()
+            .map(|q| embedder.embed_one(&model_type.format_query(&q.query)))
+            .collect::<Result<Vec<_>>>()?;
+        let fp_embedding =
+            embedder.embed_one(&model_type.format_query(&queries.false_positive_query))?;
+        Ok(((query_embeddings, fp_embedding), start.elapsed()))
+    })?;
+
+    // 6. Run accuracy tests
     let mut correct = 0;
     let mut total_score = 0.0f32;
-    let mut query_times = Vec::new();
-
-    for (query, expected_file) in TEST_QUERIES {
-        let start = Instant::now();
-        let formatted_query = model_type.format_query(query);
-        let query_embedding = embedder.embed_one(&formatted_query)?;
-        let query_elapsed = start.elapsed();
-        query_times.push(query_elapsed);
 
+    for (
+        BenchQuery {
+            query,
+            expected_file,
+        },
+        query_embedding,
+    ) in queries.queries.iter().zip(&query_embeddings)
+    {
         // Find best match via brute-force cosine similarity
         let mut best_score = 0.0f32;
         let mut best_idx = 0;
 
         for (i, emb) in embeddings.iter().enumerate() {
-            let score = cosine_similarity(&query_embedding, emb);
+            let score = cosine_similarity(query_embedding, emb);
             if score > best_score {
                 best_score = score;
                 best_idx = i;
@@ -237,18 +450,14 @@ fn benchmark_model(
         }
 
         let best_chunk = &chunks[best_idx];
-        let is_correct = best_chunk.path.contains(expected_file);
+        let is_correct = best_chunk.path.contains(expected_file.as_str());
 
         if is_correct {
             correct += 1;
         }
         total_score += best_score;
 
-        let query_short = if query.len() > 35 {
-            format!("{}...", &query[..32])
-        } else {
-            query.to_string()
-        };
+        let query_short = crate::output::truncate_chars(query, 32);
         let file_short = best_chunk
             .path
             .split('/')
@@ -263,37 +472,31 @@ fn benchmark_model(
         );
     }
 
-    // 6. False positive test
-    let fp_start = Instant::now();
-    let fp_query = model_type.format_query(FALSE_POSITIVE_QUERY);
-    let fp_embedding = embedder.embed_one(&fp_query)?;
-    let fp_elapsed = fp_start.elapsed();
+    // 7. False positive test
     let false_positive_score = embeddings
         .iter()
         .map(|emb| cosine_similarity(&fp_embedding, emb))
         .fold(0.0f32, f32::max);
     println!("   FP score: {:.3}", false_positive_score);
 
-    let accuracy = correct as f32 / TEST_QUERIES.len() as f32;
-    let avg_score = total_score / TEST_QUERIES.len() as f32;
-    let avg_query_ms = if query_times.is_empty() {
-        0.0
-    } else {
-        query_times.iter().sum::<Duration>().as_secs_f64() * 1000.0 / query_times.len() as f64
-    };
-    let query_eval_ms = (query_times.iter().sum::<Duration>() + fp_elapsed).as_millis() as u64;
+    let accuracy = correct as f32 / queries.queries.len() as f32;
+    let avg_score = total_score / queries.queries.len() as f32;
+    // Per query, counting the false positive query
+    let avg_query_ms = query_eval.mean_ms / (queries.queries.len() + 1) as f64;
+    let query_eval_ms = query_eval.mean_ms.round() as u64;
     let total_index_ms = embed_total_ms;
 
     println!(
-        "   Query eval in {}ms ({:.1} ms/query avg)",
-        query_eval_ms, avg_query_ms
+        "   Query eval in {} ({:.1} ms/query avg)",
+        format_timing(query_eval, runs.measured),
+        avg_query_ms
     );
 
-    // 7. Estimated DB size (vectors only: dims * chunks * 4 bytes)
+    // 8. Estimated DB size (vectors only: dims * chunks * 4 bytes)
     let estimated_db_mb = (model_type.dimensions() * chunks_count * 4) as f64 / (1024.0 * 1024.0);
 
-    // Drop embedder to free memory before next model
-    drop(embedder);
+    // Release the ONNX session so the next model's RSS baseline doesn't include it
+    embedder.shutdown();
     drop(embeddings);
 
     Ok(BenchResult {
@@ -303,10 +506,13 @@ fn benchmark_model(
         quantized: model_type.is_quantized(),
         model_load_ms,
         embed_total_ms,
+        embed_stddev_ms: embed.stddev_ms,
         query_eval_ms,
+        query_eval_stddev_ms: query_eval.stddev_ms,
         embed_throughput,
         avg_query_ms,
         total_index_ms,
+        runs: runs.measured,
         accuracy,
         avg_score,
         false_positive_score,
@@ -316,6 +522,19 @@ fn benchmark_model(
     })
 }
 
+/// "900ms", or "900±12ms" over several runs
+fn format_timing(stats: RunStats, runs: usize) -> String {
+    format_ms(stats.mean_ms.round() as u64, stats.stddev_ms, runs)
+}
+
+fn format_ms(mean_ms: u64, stddev_ms: f64, runs: usize) -> String {
+    if runs > 1 {
+        format!("{}±{:.0}ms", mean_ms, stddev_ms)
+    } else {
+        format!("{}ms", mean_ms)
+    }
+}
+
 fn print_summary_table(results: &[BenchResult]) {
     println!();
     println!(
@@ -337,7 +556,7 @@ fn print_summary_table(results: &[BenchResult]) {
 
     // Header
     println!(
-        "{:<18} {:>5} {:>5} {:>8} {:>8} {:>8} {:>8} {:>5} {:>6} {:>7}",
+        "{:<18} {:>5} {:>5} {:>8} {:>12} {:>8} {:>10} {:>5} {:>6} {:>7}",
         "Model".bold(),
         "Dims".bold(),
         "Quant".bold(),
@@ -349,7 +568,7 @@ fn print_summary_table(results: &[BenchResult]) {
         "FP".bold(),
         "RSS".bold(),
     );
-    println!("{}", "─".repeat(103));
+    println!("{}", "─".repeat(109));
 
     // Sort by accuracy desc, then throughput desc
     let mut sorted: Vec<&BenchResult> = results.iter().collect();
@@ -384,21 +603,21 @@ fn print_summary_table(results: &[BenchResult]) {
         };
 
         println!(
-            "{:<18} {:>5} {:>5} {:>6}ms {:>6}ms {:>8.0} {:>6}ms {:>5} {:>6} {:>5.0}MB",
+            "{:<18} {:>5} {:>5} {:>6}ms {:>12} {:>8.0} {:>10} {:>5} {:>6} {:>5.0}MB",
             r.short_name,
             r.dimensions,
             if r.quantized { "yes" } else { "no" },
             r.model_load_ms,
-            r.embed_total_ms,
+            format_ms(r.embed_total_ms, r.embed_stddev_ms, r.runs),
             r.embed_throughput,
-            r.query_eval_ms,
+            format_ms(r.query_eval_ms, r.query_eval_stddev_ms, r.runs),
             acc_colored,
             fp_colored,
             r.rss_delta_mb,
         );
     }
 
-    println!("{}", "─".repeat(103));
+    println!("{}", "─".repeat(109));
 
     // Winners
     if let Some(best_acc) = sorted.iter().max_by(|a, b| {
@@ -444,7 +663,8 @@ fn print_summary_table(results: &[BenchResult]) {
     println!();
 }
 
-fn save_markdown_report(results: &[BenchResult], path: &std::path::Path) -> Result<()> {
+fn save_markdown_report(report: &BenchReport, path: &std::path::Path) -> Result<()> {
+    let results = &report.results;
     let mut md = String::new();
 
     md.push_str("# Demongrep Model Benchmark Results\n\n");
@@ -452,9 +672,12 @@ fn save_markdown_report(results: &[BenchResult], path: &std::path::Path) -> Resu
         "**Date**: {}  \n",
         chrono::Local::now().format("%Y-%m-%d %H:%M")
     ));
-    if let Some(first) = results.first() {
-        md.push_str(&format!("**Chunks**: {}  \n\n", first.chunks_count));
+    md.push_str(&format!("**Files**: {}  \n", report.corpus.files_count));
+    md.push_str(&format!("**Chunks**: {}  \n", report.corpus.chunks_count));
+    if let Some(r) = results.first() {
+        md.push_str(&format!("**Runs**: {} per timing  \n", r.runs));
     }
+    md.push('\n');
 
     // Summary table
     md.push_str("## Summary\n\n");
@@ -468,8 +691,8 @@ fn save_markdown_report(results: &[BenchResult], path: &std::path::Path) -> Resu
             r.dimensions,
             if r.quantized { "yes" } else { "no" },
             r.model_load_ms,
-            r.embed_total_ms,
-            r.query_eval_ms,
+            format_ms(r.embed_total_ms, r.embed_stddev_ms, r.runs).trim_end_matches("ms"),
+            format_ms(r.query_eval_ms, r.query_eval_stddev_ms, r.runs).trim_end_matches("ms"),
             r.embed_throughput,
             r.avg_query_ms,
             r.accuracy * 100.0,
@@ -488,8 +711,14 @@ fn save_markdown_report(results: &[BenchResult], path: &std::path::Path) -> Resu
         md.push_str(&format!("- **Dimensions**: {}\n", r.dimensions));
         md.push_str(&format!("- **Quantized**: {}\n", r.quantized));
         md.push_str(&format!("- **Model load**: {} ms\n", r.model_load_ms));
-        md.push_str(&format!("- **Embed total**: {} ms\n", r.embed_total_ms));
-        md.push_str(&format!("- **Query eval total**: {} ms\n", r.query_eval_ms));
+        md.push_str(&format!(
+            "- **Embed total**: {}\n",
+            format_ms(r.embed_total_ms, r.embed_stddev_ms, r.runs)
+        ));
+        md.push_str(&format!(
+            "- **Query eval total**: {}\n",
+            format_ms(r.query_eval_ms, r.query_eval_stddev_ms, r.runs)
+        ));
         md.push_str(&format!(
             "- **Embedding throughput**: {:.0} chunks/sec\n",
             r.embed_throughput
@@ -508,24 +737,109 @@ fn save_markdown_report(results: &[BenchResult], path: &std::path::Path) -> Resu
         ));
     }
 
+    // Corpus file list, so runs over the same tree can be compared
+    md.push_str("## Corpus\n\n");
+    for file in &report.corpus.files {
+        md.push_str(&format!("- `{}`\n", file));
+    }
+
     std::fs::write(path, md)?;
     println!("📄 Report saved to: {}", path.display());
 
     Ok(())
 }
 
+/// `BenchResult` fields in CSV column order
+const CSV_COLUMNS: &[&str] = &[
+    "model_name",
+    "short_name",
+    "dimensions",
+    "quantized",
+    "model_load_ms",
+    "embed_total_ms",
+    "embed_stddev_ms",
+    "query_eval_ms",
+    "query_eval_stddev_ms",
+    "embed_throughput",
+    "avg_query_ms",
+    "total_index_ms",
+    "runs",
+    "accuracy",
+    "avg_score",
+    "false_positive_score",
+    "rss_delta_mb",
+    "estimated_db_mb",
+    "chunks_count",
+];
+
+/// Quote a CSV field if it contains a delimiter, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render one CSV row per result, with a header, from the results' JSON serialization
+fn results_csv(results: &[BenchResult]) -> Result<String> {
+    let mut csv = CSV_COLUMNS.join(",");
+    csv.push('\n');
+
+    for result in results {
+        let value = serde_json::to_value(result)?;
+        let row: Vec<String> = CSV_COLUMNS
+            .iter()
+            .map(|column| match &value[*column] {
+                serde_json::Value::String(s) => csv_field(s),
+                other => other.to_string(),
+            })
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    Ok(csv)
+}
+
+fn save_csv_report(results: &[BenchResult], path: &Path) -> Result<()> {
+    std::fs::write(path, results_csv(results)?)?;
+    println!("📄 CSV saved to: {}", path.display());
+    Ok(())
+}
+
 /// Run benchmark across selected models or a predefined benchmark profile.
+#[allow(clippy::too_many_arguments)]
 pub async fn bench(
     models_filter: Option<String>,
     profile: String,
     limit: Option<usize>,
     path: Option<PathBuf>,
     output: Option<PathBuf>,
+    csv_output: Option<PathBuf>,
     json_output: bool,
+    queries_file: Option<PathBuf>,
+    runs: BenchRuns,
 ) -> Result<()> {
+    if runs.measured == 0 {
+        return Err(anyhow::anyhow!("--runs must be at least 1"));
+    }
     let profile = BenchProfile::from_str(&profile)?;
     let models = select_models(models_filter.as_deref(), profile)?;
     let using_custom_model_list = models_filter.is_some();
+    let project_path = path.unwrap_or_else(|| PathBuf::from("."));
+
+    let queries = match &queries_file {
+        Some(file) => BenchQueries::load(file)?,
+        None if is_demongrep_repo(&project_path) => BenchQueries::builtin(),
+        None => {
+            return Err(anyhow::anyhow!(
+                "The built-in queries only apply to demongrep's own source. \
+                 Pass --queries <file.json> with queries for {}",
+                project_path.display()
+            ))
+        }
+    };
 
     if !json_output {
         println!(
@@ -546,6 +860,16 @@ pub async fn bench(
         } else {
             println!("Profile: {}", profile.as_str());
         }
+        match &queries_file {
+            Some(file) => println!("Queries: {} from {}", queries.queries.len(), file.display()),
+            None => println!("Queries: {} built-in", queries.queries.len()),
+        }
+        if runs.warmup > 0 || runs.measured > 1 {
+            println!(
+                "Runs: {} measured after {} warmup",
+                runs.measured, runs.warmup
+            );
+        }
         println!("Models to benchmark: {}", models.len());
         for m in &models {
             println!(
@@ -559,35 +883,18 @@ pub async fn bench(
     }
 
     // Phase 1: Collect files and create chunks (shared across all models)
-    let project_path = path.unwrap_or_else(|| PathBuf::from("."));
     if !json_output {
         println!("📂 Project: {}", project_path.display());
     }
 
-    let walker = FileWalker::new(project_path.clone());
-    let (mut files, _stats) = walker.walk()?;
-
-    if let Some(max_files) = limit {
-        files.truncate(max_files);
-    }
-
-    if !json_output {
-        println!("🔍 Discovered {} files", files.len());
-        println!("🔪 Chunking...");
-    }
-
-    let mut chunker = SemanticChunker::new(100, 2000, 10);
-    let mut all_chunks = Vec::new();
-    for file in &files {
-        if let Ok(content) = std::fs::read_to_string(&file.path) {
-            if let Ok(chunks) = chunker.chunk_semantic(file.language, &file.path, &content) {
-                all_chunks.extend(chunks);
-            }
-        }
-    }
+    let (corpus, all_chunks) = collect_corpus(&project_path, limit)?;
 
     if !json_output {
-        println!("   {} chunks from {} files", all_chunks.len(), files.len());
+        println!("🔍 Discovered {} files", corpus.files_count);
+        println!(
+            "   {} chunks from {} files",
+            corpus.chunks_count, corpus.files_count
+        );
         println!();
     }
 
@@ -620,7 +927,13 @@ pub async fn bench(
 
         // Run benchmark directly - ONNX Runtime has its own thread management
         let model_type = *model_type;
-        match benchmark_model(model_type, &all_chunks, &base_prepared_texts) {
+        match benchmark_model(
+            model_type,
+            &all_chunks,
+            &base_prepared_texts,
+            &queries,
+            runs,
+        ) {
             Ok(result) => results.push(result),
             Err(e) => {
                 if !json_output {
@@ -639,15 +952,20 @@ pub async fn bench(
     }
 
     // Phase 3: Output results
+    let report = BenchReport { corpus, results };
     if json_output {
-        println!("{}", serde_json::to_string_pretty(&results)?);
+        println!("{}", serde_json::to_string_pretty(&report)?);
     } else {
-        print_summary_table(&results);
+        print_summary_table(&report.results);
     }
 
     // Save markdown report if requested
     if let Some(output_path) = output {
-        save_markdown_report(&results, &output_path)?;
+        save_markdown_report(&report, &output_path)?;
+    }
+
+    if let Some(csv_path) = csv_output {
+        save_csv_report(&report.results, &csv_path)?;
     }
 
     Ok(())
@@ -682,6 +1000,158 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_queries_file() {
+        let config = r#"{
+            "queries": [
+                {"query": "auth middleware", "expected_file": "src/auth", "description": "auth"},
+                {"query": "db pool", "expected_file": "pool.rs"}
+            ],
+            "false_positive_query": "banana bread recipe"
+        }"#;
+        let parsed = BenchQueries::parse(config).unwrap();
+        assert_eq!(parsed.queries.len(), 2);
+        assert_eq!(parsed.queries[0].query, "auth middleware");
+        assert_eq!(parsed.queries[1].expected_file, "pool.rs");
+        assert_eq!(parsed.false_positive_query, "banana bread recipe");
+
+        let bare = r#"[{"query": "db pool", "expected_file": "pool.rs"}]"#;
+        let parsed = BenchQueries::parse(bare).unwrap();
+        assert_eq!(parsed.queries.len(), 1);
+        assert_eq!(parsed.false_positive_query, FALSE_POSITIVE_QUERY);
+
+        assert!(BenchQueries::parse(r#"{"queries": []}"#).is_err());
+        assert!(BenchQueries::parse(r#"[{"query": "missing expected"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_builtin_queries_only_for_demongrep() {
+        assert_eq!(BenchQueries::builtin().queries.len(), TEST_QUERIES.len());
+        assert!(is_demongrep_repo(Path::new(env!("CARGO_MANIFEST_DIR"))));
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"other\"\n",
+        )
+        .unwrap();
+        assert!(!is_demongrep_repo(dir.path()));
+    }
+
+    #[test]
+    fn test_corpus_is_deterministic() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["zeta.rs", "alpha.rs", "mid.py", "beta.rs"] {
+            std::fs::write(
+                dir.path().join(name),
+                "fn first() -> u32 {\n    1\n}\n\nfn second() -> u32 {\n    2\n}\n",
+            )
+            .unwrap();
+        }
+
+        let (first, first_chunks) = collect_corpus(dir.path(), Some(3)).unwrap();
+        let (second, second_chunks) = collect_corpus(dir.path(), Some(3)).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(first_chunks.len(), second_chunks.len());
+        assert_eq!(first.chunks_count, first_chunks.len());
+        assert!(first.chunks_count > 0);
+        // --limit keeps the first files in path order
+        assert_eq!(first.files, vec!["alpha.rs", "beta.rs", "mid.py"]);
+        assert_eq!(first.files_count, 3);
+    }
+
+    fn bench_result(short_name: &str, accuracy: f32) -> BenchResult {
+        BenchResult {
+            model_name: format!("org/{} (quantized)", short_name),
+            short_name: short_name.to_string(),
+            dimensions: 384,
+            quantized: true,
+            model_load_ms: 120,
+            embed_total_ms: 900,
+            embed_stddev_ms: 12.5,
+            query_eval_ms: 40,
+            query_eval_stddev_ms: 2.0,
+            embed_throughput: 250.0,
+            avg_query_ms: 4.5,
+            total_index_ms: 1020,
+            runs: 3,
+            accuracy,
+            avg_score: 0.5,
+            false_positive_score: 0.25,
+            rss_delta_mb: 80.0,
+            estimated_db_mb: 1.5,
+            chunks_count: 225,
+        }
+    }
+
+    #[test]
+    fn test_results_csv() {
+        let results = vec![
+            bench_result("minilm-l6-q", 0.75),
+            bench_result("mxbai", 1.0),
+        ];
+        let csv = results_csv(&results).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 1 + results.len());
+        assert_eq!(lines[0], CSV_COLUMNS.join(","));
+        assert_eq!(
+            lines[1],
+            "org/minilm-l6-q (quantized),minilm-l6-q,384,true,120,900,12.5,40,2.0,250.0,4.5,1020,3,0.75,0.5,0.25,80.0,1.5,225"
+        );
+        assert!(lines[2].starts_with("org/mxbai (quantized),mxbai,"));
+
+        // Every serialized field has a column
+        let value = serde_json::to_value(&results[0]).unwrap();
+        for key in value.as_object().unwrap().keys() {
+            assert!(
+                CSV_COLUMNS.contains(&key.as_str()),
+                "missing column {}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_timed_runs_aggregate_measured_runs() {
+        // Stub timing source: two warmups (slow, ignored), then 10, 20, 30ms
+        let mut timings = [500, 400, 10, 20, 30]
+            .into_iter()
+            .map(Duration::from_millis)
+            .enumerate();
+        let runs = BenchRuns {
+            warmup: 2,
+            measured: 3,
+        };
+
+        let (last_run, stats) = timed_runs(runs, || Ok(timings.next().unwrap())).unwrap();
+
+        assert_eq!(last_run, 4);
+        assert!(timings.next().is_none());
+        assert!((stats.mean_ms - 20.0).abs() < 1e-9);
+        // Population standard deviation of 10, 20, 30
+        assert!((stats.stddev_ms - (200.0f64 / 3.0).sqrt()).abs() < 1e-9);
+
+        let single = RunStats::from_durations(&[Duration::from_millis(7)]);
+        assert_eq!(
+            single,
+            RunStats {
+                mean_ms: 7.0,
+                stddev_ms: 0.0
+            }
+        );
+        assert_eq!(format_timing(stats, 3), "20±8ms");
+        assert_eq!(format_timing(single, 1), "7ms");
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
     #[test]
     fn test_invalid_profile() {
         let err = BenchProfile::from_str("fastest").unwrap_err().to_string();
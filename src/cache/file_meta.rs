@@ -80,6 +80,12 @@ impl FileMetaStore {
         }
     }
 
+    /// Load the store saved in a database directory as it is, if there is one
+    pub fn load(db_path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(db_path.join(Self::FILENAME)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
     /// Save to database directory
     pub fn save(&self, db_path: &Path) -> Result<()> {
         let meta_path = db_path.join(Self::FILENAME);
@@ -105,14 +111,20 @@ impl FileMetaStore {
 
     /// Check if a file needs re-indexing
     /// Returns: (needs_reindex, existing_chunk_ids_to_delete)
-    pub fn check_file(&self, path: &Path) -> Result<(bool, Vec<u32>)> {
+    ///
+    /// Change detection is content-based: mtime and size are only a fast
+    /// prefilter, and a file whose mtime moved (e.g. after `git checkout`)
+    /// but whose SHA256 still matches is reported as unchanged. In that case
+    /// the stored mtime/size are refreshed so the next check hits the
+    /// prefilter again instead of re-hashing.
+    pub fn check_file(&mut self, path: &Path) -> Result<(bool, Vec<u32>)> {
         let path_str = path.to_string_lossy().to_string();
 
         // Get current file stats
         let current_mtime = Self::get_mtime(path)?;
         let current_size = fs::metadata(path)?.len();
 
-        if let Some(meta) = self.files.get(&path_str) {
+        if let Some(meta) = self.files.get_mut(&path_str) {
             // Quick check: if mtime and size unchanged, file is unchanged
             if meta.mtime == current_mtime && meta.size == current_size {
                 return Ok((false, vec![]));
@@ -122,6 +134,8 @@ impl FileMetaStore {
             let current_hash = Self::compute_hash(path)?;
             if meta.hash == current_hash {
                 // Content same, just update mtime
+                meta.mtime = current_mtime;
+                meta.size = current_size;
                 return Ok((false, vec![]));
             }
 
@@ -160,11 +174,36 @@ impl FileMetaStore {
         self.files.remove(&path_str)
     }
 
+    /// Look up the tracked key and chunk IDs for a file
+    ///
+    /// Tries the path as given first, then any tracked path that resolves to
+    /// the same file (keys keep whatever form the file was synced under).
+    pub fn file_chunk_ids(&self, path: &Path) -> Option<(String, Vec<u32>)> {
+        let path_str = path.to_string_lossy().to_string();
+        if let Some(meta) = self.files.get(&path_str) {
+            return Some((path_str, meta.chunk_ids.clone()));
+        }
+
+        let target = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.files.iter().find_map(|(key, meta)| {
+            let key_path = Path::new(key);
+            let resolved = key_path
+                .canonicalize()
+                .unwrap_or_else(|_| key_path.to_path_buf());
+            (resolved == target).then(|| (key.clone(), meta.chunk_ids.clone()))
+        })
+    }
+
     /// Get all tracked files
     pub fn tracked_files(&self) -> impl Iterator<Item = &String> {
         self.files.keys()
     }
 
+    /// Every tracked file with the modification time recorded when it was synced
+    pub fn file_mtimes(&self) -> impl Iterator<Item = (&String, u64)> {
+        self.files.iter().map(|(path, meta)| (path, meta.mtime))
+    }
+
     /// Find files that were deleted (exist in store but not on disk)
     pub fn find_deleted_files(&self) -> Vec<(String, Vec<u32>)> {
         self.files
@@ -255,4 +294,48 @@ mod tests {
         let loaded = FileMetaStore::load_or_create(db_path, "test-model", 384).unwrap();
         assert_eq!(loaded.files.len(), 1);
     }
+
+    #[test]
+    fn test_mtime_change_without_content_change() {
+        let dir = tempdir().unwrap();
+        let mut store = FileMetaStore::new("test-model".to_string(), 384);
+
+        let test_file = dir.path().join("test.txt");
+        fs::write(&test_file, "hello world").unwrap();
+        store.update_file(&test_file, vec![7, 8]).unwrap();
+
+        // Simulate `git checkout` touching the file without changing content
+        let key = test_file.to_string_lossy().to_string();
+        store.files.get_mut(&key).unwrap().mtime -= 60;
+
+        let (needs_reindex, old_chunks) = store.check_file(&test_file).unwrap();
+        assert!(!needs_reindex);
+        assert!(old_chunks.is_empty());
+
+        // Stored mtime is refreshed so the fast prefilter applies next time
+        let current_mtime = FileMetaStore::get_mtime(&test_file).unwrap();
+        assert_eq!(store.files[&key].mtime, current_mtime);
+        assert_eq!(store.files[&key].chunk_ids, vec![7, 8]);
+    }
+
+    #[test]
+    fn test_file_chunk_ids_matches_other_path_forms() {
+        let dir = tempdir().unwrap();
+        let mut store = FileMetaStore::new("test-model".to_string(), 384);
+
+        let test_file = dir.path().join("test.txt");
+        fs::write(&test_file, "hello world").unwrap();
+        store.update_file(&test_file, vec![4, 5]).unwrap();
+
+        let key = test_file.to_string_lossy().to_string();
+        assert_eq!(
+            store.file_chunk_ids(&test_file),
+            Some((key.clone(), vec![4, 5]))
+        );
+
+        // The same file reached through a different path form keeps the stored key
+        let dotted = dir.path().join(".").join("test.txt");
+        assert_eq!(store.file_chunk_ids(&dotted), Some((key, vec![4, 5])));
+        assert_eq!(store.file_chunk_ids(&dir.path().join("other.txt")), None);
+    }
 }
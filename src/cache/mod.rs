@@ -1,6 +1,8 @@
 mod file_meta;
+mod search_results;
 
 pub use file_meta::FileMetaStore;
+pub use search_results::{index_state_hash, SearchResultCache};
 
 use moka::sync::Cache;
 use std::sync::atomic::{AtomicU64, Ordering};
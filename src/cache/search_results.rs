@@ -0,0 +1,182 @@
+use moka::sync::Cache;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// In-memory cache of search responses for long-running processes (`serve`, MCP)
+///
+/// Entries are keyed by the request (query plus every option that affects the
+/// output) and a hash of the index state on disk, so a reindex by this or any
+/// other process makes old entries unreachable. `invalidate_all` drops them
+/// eagerly after an in-process update.
+pub struct SearchResultCache<T> {
+    cache: Cache<(String, u64), Arc<T>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<T: Send + Sync + 'static> SearchResultCache<T> {
+    pub fn new(max_entries: u64) -> Self {
+        Self {
+            cache: Cache::new(max_entries),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Look up a cached response for `key` computed against index state `state`
+    pub fn get(&self, key: &str, state: u64) -> Option<Arc<T>> {
+        let value = self.cache.get(&(key.to_string(), state));
+        let counter = if value.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    /// Store a response for `key` computed against index state `state`
+    pub fn insert(&self, key: &str, state: u64, value: T) -> Arc<T> {
+        let value = Arc::new(value);
+        self.cache.insert((key.to_string(), state), value.clone());
+        value
+    }
+
+    /// Drop every entry (e.g. after reindexing)
+    pub fn invalidate_all(&self) {
+        self.cache.invalidate_all();
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+/// Files in a database directory that change when the index is written
+const INDEX_STATE_FILES: [&str; 2] = ["data.mdb", "metadata.json"];
+
+/// Directory of the full-text index, whose segment files change on commit
+const INDEX_STATE_FTS_DIR: &str = "fts";
+
+/// Hash of the on-disk state of the given databases
+///
+/// Covers the size and modification time of the LMDB data, the metadata and
+/// the FTS segments, which all change when the index is written. The
+/// embedding and rerank caches beside them are left out: filling them
+/// doesn't change any search result.
+pub fn index_state_hash<'a>(db_paths: impl IntoIterator<Item = &'a Path>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for db_path in db_paths {
+        db_path.hash(&mut hasher);
+        for name in INDEX_STATE_FILES {
+            name.hash(&mut hasher);
+            hash_file_state(&db_path.join(name), &mut hasher);
+        }
+        hash_dir_state(&db_path.join(INDEX_STATE_FTS_DIR), &mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Hash the names and states of the files directly in `dir`
+fn hash_dir_state(dir: &Path, hasher: &mut DefaultHasher) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        entry.file_name().hash(hasher);
+        hash_file_state(&entry.path(), hasher);
+    }
+}
+
+/// Hash the size and modification time of the file at `path`, if any
+fn hash_file_state(path: &Path, hasher: &mut DefaultHasher) {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return;
+    };
+    if metadata.is_dir() {
+        return;
+    }
+    metadata.len().hash(hasher);
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .hash(hasher);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_identical_searches_hit_cache_until_index_changes() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("store");
+        std::fs::create_dir_all(db_path.join("fts")).unwrap();
+        std::fs::write(db_path.join("data.mdb"), "v1").unwrap();
+        std::fs::write(db_path.join("fts").join("meta.json"), "{}").unwrap();
+
+        let cache: SearchResultCache<String> = SearchResultCache::new(16);
+        let mut computed = 0;
+        let mut search = |cache: &SearchResultCache<String>, query: &str| {
+            let state = index_state_hash([db_path.as_path()]);
+            if let Some(hit) = cache.get(query, state) {
+                return (*hit).clone();
+            }
+            computed += 1;
+            cache
+                .insert(query, state, format!("results for {}", query))
+                .to_string()
+        };
+
+        assert_eq!(search(&cache, "auth"), "results for auth");
+        assert_eq!(search(&cache, "auth"), "results for auth");
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 1);
+
+        // Writing to the index changes its state, so the next search recomputes
+        std::fs::write(db_path.join("fts").join("meta.json"), "{\"segments\":[]}").unwrap();
+        search(&cache, "auth");
+        assert_eq!(cache.misses(), 2);
+
+        cache.invalidate_all();
+        search(&cache, "auth");
+        assert_eq!(cache.misses(), 3);
+        drop(search);
+        assert_eq!(computed, 3);
+    }
+
+    #[test]
+    fn test_cache_writes_beside_the_index_keep_its_state() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path();
+        std::fs::create_dir_all(db_path.join("fts")).unwrap();
+        std::fs::write(db_path.join("data.mdb"), "v1").unwrap();
+        std::fs::write(db_path.join("metadata.json"), "{}").unwrap();
+        let state = index_state_hash([db_path]);
+
+        // A search that embeds its query or reranks writes these
+        for cache in ["embedding_cache", "rerank_cache"] {
+            std::fs::create_dir_all(db_path.join(cache)).unwrap();
+            std::fs::write(db_path.join(cache).join("data.mdb"), "scores").unwrap();
+        }
+        std::fs::write(db_path.join("lock.mdb"), "readers").unwrap();
+        assert_eq!(index_state_hash([db_path]), state);
+
+        std::fs::write(db_path.join("metadata.json"), r#"{"indexed_at": "now"}"#).unwrap();
+        assert_ne!(index_state_hash([db_path]), state);
+    }
+}
@@ -1,5 +1,6 @@
 use super::ChunkKind;
 use crate::file::Language;
+use std::ops::Range;
 use tree_sitter::Node;
 
 /// Language-specific code extraction logic
@@ -24,6 +25,14 @@ pub trait LanguageExtractor: Send + Sync {
     /// Returns None if the node has no name (anonymous)
     fn extract_name(&self, node: Node, source: &[u8]) -> Option<String>;
 
+    /// Name of a definition in a symbol path such as `auth::Session::verify`
+    ///
+    /// Defaults to [`Self::extract_name`]; definitions without one are left
+    /// out of the path.
+    fn scope_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        self.extract_name(node, source)
+    }
+
     /// Extract a function/method signature
     ///
     /// Examples:
@@ -40,6 +49,32 @@ pub trait LanguageExtractor: Send + Sync {
     /// - JavaScript/TypeScript: JSDoc `/** */`
     fn extract_docstring(&self, node: Node, source: &[u8]) -> Option<String>;
 
+    /// Byte range in the source of the docstring [`Self::extract_docstring`] found
+    ///
+    /// Defaults to the run of comment nodes directly above the definition
+    /// (attributes in between are skipped) that make up the docstring;
+    /// languages that keep it inside the definition override this.
+    fn docstring_range(&self, node: Node, source: &[u8]) -> Option<Range<usize>> {
+        let doc = self.extract_docstring(node, source)?;
+        let mut range: Option<Range<usize>> = None;
+        let mut prev = node.prev_named_sibling();
+        while let Some(sibling) = prev {
+            let kind = sibling.kind();
+            if kind.contains("comment") || kind == "haddock" {
+                let text = sibling.utf8_text(source).ok()?.trim();
+                if text.is_empty() || !doc.contains(text) {
+                    break;
+                }
+                let end = range.map_or(sibling.end_byte(), |r| r.end);
+                range = Some(sibling.start_byte()..end);
+            } else if range.is_some() || kind != "attribute_item" {
+                break;
+            }
+            prev = sibling.prev_named_sibling();
+        }
+        range
+    }
+
     /// Classify a node into a ChunkKind
     fn classify(&self, node: Node) -> ChunkKind;
 
@@ -83,7 +118,13 @@ pub fn get_extractor(language: Language) -> Option<Box<dyn LanguageExtractor>> {
         Language::C | Language::Cpp => Some(Box::new(CppExtractor)),
         Language::Ruby => Some(Box::new(RubyExtractor)),
         Language::Php => Some(Box::new(PhpExtractor)),
+        Language::Dart => Some(Box::new(DartExtractor)),
+        Language::Haskell => Some(Box::new(HaskellExtractor)),
         Language::Shell => Some(Box::new(BashExtractor)),
+        Language::Nix => Some(Box::new(NixExtractor)),
+        Language::GraphQL => Some(Box::new(GraphqlExtractor)),
+        Language::Scala => Some(Box::new(ScalaExtractor)),
+        Language::OCaml => Some(Box::new(OCamlExtractor)),
         _ => None,
     }
 }
@@ -114,6 +155,19 @@ impl LanguageExtractor for RustExtractor {
             .map(String::from)
     }
 
+    fn scope_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        if node.kind() != "impl_item" {
+            return self.extract_name(node, source);
+        }
+        // The implemented type, without generics: `impl<T> Session<T>` -> Session
+        let ty = node.child_by_field_name("type")?;
+        let ty = match ty.kind() {
+            "generic_type" => ty.child_by_field_name("type")?,
+            _ => ty,
+        };
+        ty.utf8_text(source).ok().map(String::from)
+    }
+
     fn extract_signature(&self, node: Node, source: &[u8]) -> Option<String> {
         match node.kind() {
             "function_item" => {
@@ -237,30 +291,32 @@ impl LanguageExtractor for RustExtractor {
     }
 
     fn extract_docstring(&self, node: Node, source: &[u8]) -> Option<String> {
-        // Look for line_comment or block_comment nodes immediately before this node
-        // Tree-sitter includes them as named siblings in some grammars
-
-        // For now, we'll look at the previous siblings
-        let parent = node.parent()?;
-        let node_index = (0..parent.named_child_count())
-            .find(|&i| parent.named_child(i).map(|c| c.id()) == Some(node.id()))?;
-
-        if node_index > 0 {
-            if let Some(prev) = parent.named_child(node_index - 1) {
-                if prev.kind() == "line_comment" || prev.kind() == "block_comment" {
-                    if let Ok(text) = prev.utf8_text(source) {
-                        // Check if it's a doc comment (/// or /**)
-                        if text.trim_start().starts_with("///")
-                            || text.trim_start().starts_with("/**")
-                        {
-                            return Some(text.to_string());
-                        }
-                    }
-                }
+        // Doc comments are the line_comment/block_comment siblings right before
+        // the item; each `///` line is its own node, and attributes such as
+        // `#[derive(...)]` may sit between the comments and the item
+        let mut first = None;
+        let mut last = None;
+        let mut prev = node.prev_named_sibling();
+        while let Some(sibling) = prev {
+            match sibling.kind() {
+                "attribute_item" if last.is_none() => {}
+                "line_comment" | "block_comment" => {
+                    let text = sibling.utf8_text(source).ok()?.trim_start();
+                    // Check if it's a doc comment (/// or /**)
+                    if !(text.starts_with("///") || text.starts_with("/**")) {
+                        break;
+                    }
+                    last.get_or_insert(sibling);
+                    first = Some(sibling);
+                }
+                _ => break,
             }
+            prev = sibling.prev_named_sibling();
         }
 
-        None
+        let (first, last) = (first?, last?);
+        let text = std::str::from_utf8(&source[first.start_byte()..last.end_byte()]).ok()?;
+        Some(text.trim_end().to_string())
     }
 
     fn classify(&self, node: Node) -> ChunkKind {
@@ -294,6 +350,22 @@ impl LanguageExtractor for RustExtractor {
 /// Python language extractor
 pub struct PythonExtractor;
 
+impl PythonExtractor {
+    /// The string literal that is the first statement of a body, if any
+    fn docstring_node<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        let body = node.child_by_field_name("body")?;
+        let first = body.named_child(0)?;
+        if first.kind() != "expression_statement" {
+            return None;
+        }
+        let mut cursor = first.walk();
+        let string = first
+            .named_children(&mut cursor)
+            .find(|child| child.kind() == "string");
+        string
+    }
+}
+
 impl LanguageExtractor for PythonExtractor {
     fn definition_types(&self) -> &[&'static str] {
         &["function_definition", "class_definition"]
@@ -357,24 +429,14 @@ impl LanguageExtractor for PythonExtractor {
 
     fn extract_docstring(&self, node: Node, source: &[u8]) -> Option<String> {
         // Python docstrings are the first statement in the body if it's a string
-        let body = node.child_by_field_name("body")?;
-
-        let mut cursor = body.walk();
-        for child in body.named_children(&mut cursor) {
-            if child.kind() == "expression_statement" {
-                // Check if it contains a string
-                let mut expr_cursor = child.walk();
-                for expr_child in child.named_children(&mut expr_cursor) {
-                    if expr_child.kind() == "string" {
-                        return expr_child.utf8_text(source).ok().map(String::from);
-                    }
-                }
-            }
-            // Only check first statement
-            break;
-        }
+        self.docstring_node(node)?
+            .utf8_text(source)
+            .ok()
+            .map(String::from)
+    }
 
-        None
+    fn docstring_range(&self, node: Node, _source: &[u8]) -> Option<Range<usize>> {
+        self.docstring_node(node).map(|string| string.byte_range())
     }
 
     fn classify(&self, node: Node) -> ChunkKind {
@@ -1209,6 +1271,200 @@ impl LanguageExtractor for PhpExtractor {
     }
 }
 
+/// Dart/Flutter language extractor
+pub struct DartExtractor;
+
+impl LanguageExtractor for DartExtractor {
+    fn definition_types(&self) -> &[&'static str] {
+        &[
+            "function_signature",
+            "method_signature",
+            "class_definition",
+            "mixin_declaration",
+            "enum_declaration",
+        ]
+    }
+
+    fn extract_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        if let Some(name) = node.child_by_field_name("name") {
+            return name.utf8_text(source).ok().map(String::from);
+        }
+
+        // method_signature wraps a function/getter/setter/constructor signature
+        if node.kind() == "method_signature" {
+            let inner = node.named_child(0)?;
+            return self.extract_name(inner, source);
+        }
+
+        // mixin_declaration has no name field - use the first identifier
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                if child.kind() == "identifier" {
+                    return child.utf8_text(source).ok().map(String::from);
+                }
+            }
+        }
+        None
+    }
+
+    fn extract_signature(&self, node: Node, source: &[u8]) -> Option<String> {
+        match node.kind() {
+            "function_signature" | "method_signature" => {
+                // Signature nodes exclude the body: `ReturnType name(params)`
+                let text = node.utf8_text(source).ok()?;
+                Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+            "class_definition" | "mixin_declaration" | "enum_declaration" => {
+                let keyword = match node.kind() {
+                    "class_definition" => "class",
+                    "mixin_declaration" => "mixin",
+                    _ => "enum",
+                };
+                let mut sig = String::from(keyword);
+                sig.push(' ');
+
+                if let Some(name) = self.extract_name(node, source) {
+                    sig.push_str(&name);
+                }
+
+                Some(sig)
+            }
+            _ => None,
+        }
+    }
+
+    fn extract_docstring(&self, node: Node, source: &[u8]) -> Option<String> {
+        // Dart uses `///` doc comments (one node per line) before definitions
+        let mut lines = Vec::new();
+        let mut prev = node.prev_named_sibling();
+
+        while let Some(comment) = prev {
+            if comment.kind() != "comment" && comment.kind() != "documentation_comment" {
+                break;
+            }
+            let text = comment.utf8_text(source).ok()?;
+            if !text.starts_with("///") && !text.starts_with("/**") {
+                break;
+            }
+            lines.push(text);
+            prev = comment.prev_named_sibling();
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        lines.reverse();
+        Some(lines.join("\n"))
+    }
+
+    fn classify(&self, node: Node) -> ChunkKind {
+        match node.kind() {
+            "function_signature" => {
+                if let Some(parent) = node.parent() {
+                    if parent.kind() == "method_signature" || parent.kind() == "class_body" {
+                        return ChunkKind::Method;
+                    }
+                }
+                ChunkKind::Function
+            }
+            "method_signature" => ChunkKind::Method,
+            "class_definition" => ChunkKind::Class,
+            "mixin_declaration" => ChunkKind::Trait,
+            "enum_declaration" => ChunkKind::Enum,
+            _ => ChunkKind::Other,
+        }
+    }
+}
+
+/// Haskell language extractor
+pub struct HaskellExtractor;
+
+impl HaskellExtractor {
+    /// The `name :: Type` signature directly above a function or bind
+    fn type_signature<'a>(&self, node: Node<'a>, source: &[u8]) -> Option<Node<'a>> {
+        let prev = node.prev_named_sibling()?;
+        (prev.kind() == "signature"
+            && self.extract_name(prev, source) == self.extract_name(node, source))
+        .then_some(prev)
+    }
+}
+
+impl LanguageExtractor for HaskellExtractor {
+    fn definition_types(&self) -> &[&'static str] {
+        // Type signatures (`name :: Type`) aren't chunks of their own: they
+        // belong to the function or bind that follows them
+        &[
+            "function",
+            "bind",
+            "data_type",
+            "newtype",
+            "type_synomym",
+            "class",
+            "instance",
+        ]
+    }
+
+    fn extract_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        node.child_by_field_name("name")?
+            .utf8_text(source)
+            .ok()
+            .map(String::from)
+    }
+
+    fn extract_signature(&self, node: Node, source: &[u8]) -> Option<String> {
+        let text = node.utf8_text(source).ok()?;
+        let head = match node.kind() {
+            // A `name :: Type` declaration right above is the signature
+            "function" | "bind" => match self.type_signature(node, source) {
+                Some(signature) => signature.utf8_text(source).ok()?,
+                // Otherwise keep the left-hand side of `name args = ...` / guards
+                None => text.split(['=', '|']).next().unwrap_or(text),
+            },
+            // Declarations: keep everything before the body
+            _ => text.split(" where").next().unwrap_or(text),
+        };
+        Some(head.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    fn extract_docstring(&self, node: Node, source: &[u8]) -> Option<String> {
+        // Haddock comments (`-- |` or `{- | -}`) precede the declaration, or
+        // its type signature
+        let declaration = self.type_signature(node, source).unwrap_or(node);
+        let prev = declaration.prev_named_sibling()?;
+        if prev.kind() != "haddock" && prev.kind() != "comment" {
+            return None;
+        }
+        let text = prev.utf8_text(source).ok()?;
+        if text.starts_with("-- |") || text.starts_with("{- |") {
+            Some(text.to_string())
+        } else {
+            None
+        }
+    }
+
+    fn classify(&self, node: Node) -> ChunkKind {
+        match node.kind() {
+            "function" | "bind" => {
+                // Declarations inside a class or instance body are methods
+                let mut parent = node.parent();
+                while let Some(p) = parent {
+                    if p.kind() == "class" || p.kind() == "instance" {
+                        return ChunkKind::Method;
+                    }
+                    parent = p.parent();
+                }
+                ChunkKind::Function
+            }
+            "data_type" | "newtype" => ChunkKind::Struct,
+            "type_synomym" => ChunkKind::TypeAlias,
+            "class" => ChunkKind::Trait,
+            "instance" => ChunkKind::Impl,
+            _ => ChunkKind::Other,
+        }
+    }
+}
+
 /// Bash/Shell language extractor
 pub struct BashExtractor;
 
@@ -1263,6 +1519,495 @@ impl LanguageExtractor for BashExtractor {
     }
 }
 
+/// Nix language extractor
+///
+/// Chunks top-level attribute bindings (`name = expr;`) and bindings of
+/// lambdas (`name = args: body;`) at any depth, named by their attribute path.
+pub struct NixExtractor;
+
+impl NixExtractor {
+    /// The lambda a binding is bound to, if any
+    fn bound_function<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        node.child_by_field_name("expression")
+            .filter(|expr| expr.kind() == "function_expression")
+    }
+}
+
+impl LanguageExtractor for NixExtractor {
+    fn definition_types(&self) -> &[&'static str] {
+        &["binding"]
+    }
+
+    fn is_definition(&self, node: Node) -> bool {
+        if node.kind() != "binding" {
+            return false;
+        }
+        if self.bound_function(node).is_some() {
+            return true;
+        }
+        // Only top-level plain bindings; nested ones (`enable = true;`) stay in their parent
+        let mut parent = node.parent();
+        while let Some(p) = parent {
+            if p.kind() == "binding" {
+                return false;
+            }
+            parent = p.parent();
+        }
+        true
+    }
+
+    fn extract_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        let attrpath = node
+            .child_by_field_name("attrpath")?
+            .utf8_text(source)
+            .ok()?;
+        Some(attrpath.split_whitespace().collect())
+    }
+
+    fn extract_signature(&self, node: Node, source: &[u8]) -> Option<String> {
+        let name = self.extract_name(node, source)?;
+        let Some(function) = self.bound_function(node) else {
+            return Some(name);
+        };
+
+        // Keep every curried argument: `name = { pkgs, ... }: system:`
+        let mut body = function.child_by_field_name("body")?;
+        while body.kind() == "function_expression" {
+            match body.child_by_field_name("body") {
+                Some(inner) => body = inner,
+                None => break,
+            }
+        }
+        let args = std::str::from_utf8(&source[function.start_byte()..body.start_byte()]).ok()?;
+        Some(format!(
+            "{} = {}",
+            name,
+            args.split_whitespace().collect::<Vec<_>>().join(" ")
+        ))
+    }
+
+    fn extract_docstring(&self, node: Node, source: &[u8]) -> Option<String> {
+        // Consecutive `#` comments directly above the binding
+        let mut comments = Vec::new();
+        let mut prev = node.prev_sibling();
+        while let Some(p) = prev {
+            if p.kind() != "comment" {
+                break;
+            }
+            comments.push(p.utf8_text(source).ok()?);
+            prev = p.prev_sibling();
+        }
+        if comments.is_empty() {
+            return None;
+        }
+        comments.reverse();
+        Some(comments.join("\n"))
+    }
+
+    fn classify(&self, node: Node) -> ChunkKind {
+        match node.kind() {
+            "binding" if self.bound_function(node).is_some() => ChunkKind::Function,
+            "binding" => ChunkKind::Const,
+            _ => ChunkKind::Other,
+        }
+    }
+}
+
+/// GraphQL schema (SDL) extractor
+///
+/// Chunks `type`, `interface`, `enum`, `input` and `scalar` definitions with
+/// their field lists. Signatures are the definition head with the fields
+/// elided: `type User implements Node { ... }`.
+pub struct GraphqlExtractor;
+
+impl GraphqlExtractor {
+    /// The description string in front of a definition, if it has one
+    fn description<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        (0..node.named_child_count())
+            .filter_map(|i| node.named_child(i))
+            .find(|child| child.kind() == "description")
+    }
+
+    /// The `{ ... }` field or value list of a definition, if it has one
+    fn field_list<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        (0..node.named_child_count())
+            .filter_map(|i| node.named_child(i))
+            .find(|child| {
+                matches!(
+                    child.kind(),
+                    "fields_definition" | "input_fields_definition" | "enum_values_definition"
+                )
+            })
+    }
+}
+
+impl LanguageExtractor for GraphqlExtractor {
+    fn definition_types(&self) -> &[&'static str] {
+        &[
+            "object_type_definition",
+            "interface_type_definition",
+            "enum_type_definition",
+            "input_object_type_definition",
+            "scalar_type_definition",
+        ]
+    }
+
+    fn extract_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        (0..node.named_child_count())
+            .filter_map(|i| node.named_child(i))
+            .find(|child| child.kind() == "name")
+            .and_then(|name| name.utf8_text(source).ok())
+            .map(|name| name.to_string())
+    }
+
+    fn extract_signature(&self, node: Node, source: &[u8]) -> Option<String> {
+        // From the keyword (after any description) up to the field list
+        let start = (0..node.child_count())
+            .filter_map(|i| node.child(i))
+            .find(|child| child.kind() != "description")?
+            .start_byte();
+        let fields = self.field_list(node);
+        let end = fields.map_or(node.end_byte(), |f| f.start_byte());
+        let head = std::str::from_utf8(&source[start..end]).ok()?;
+        let head = head.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        Some(match fields {
+            Some(_) => format!("{} {{ ... }}", head),
+            None => head,
+        })
+    }
+
+    fn extract_docstring(&self, node: Node, source: &[u8]) -> Option<String> {
+        // The `"""block"""` or `"inline"` description before the definition
+        let description = self.description(node)?;
+        let text = description.utf8_text(source).ok()?.trim();
+        let text = text
+            .strip_prefix("\"\"\"")
+            .and_then(|t| t.strip_suffix("\"\"\""))
+            .or_else(|| text.strip_prefix('"').and_then(|t| t.strip_suffix('"')))
+            .unwrap_or(text);
+        Some(text.trim().to_string()).filter(|t| !t.is_empty())
+    }
+
+    fn docstring_range(&self, node: Node, source: &[u8]) -> Option<Range<usize>> {
+        self.extract_docstring(node, source)?;
+        self.description(node)
+            .map(|description| description.byte_range())
+    }
+
+    fn classify(&self, node: Node) -> ChunkKind {
+        match node.kind() {
+            "object_type_definition" | "input_object_type_definition" => ChunkKind::Struct,
+            "interface_type_definition" => ChunkKind::Interface,
+            "enum_type_definition" => ChunkKind::Enum,
+            "scalar_type_definition" => ChunkKind::TypeAlias,
+            _ => ChunkKind::Other,
+        }
+    }
+}
+
+/// Scala language extractor
+///
+/// Chunks `def`s, classes, objects, traits and member `val`/`var`s; locals
+/// inside a `def` body stay in their enclosing function.
+pub struct ScalaExtractor;
+
+impl ScalaExtractor {
+    /// Whether `node` sits inside a function body
+    fn in_function(&self, node: Node) -> bool {
+        let mut parent = node.parent();
+        while let Some(p) = parent {
+            match p.kind() {
+                "function_definition" => return true,
+                "class_definition" | "object_definition" | "trait_definition" => return false,
+                _ => parent = p.parent(),
+            }
+        }
+        false
+    }
+}
+
+impl LanguageExtractor for ScalaExtractor {
+    fn definition_types(&self) -> &[&'static str] {
+        &[
+            "function_definition",
+            "function_declaration",
+            "class_definition",
+            "object_definition",
+            "trait_definition",
+            "val_definition",
+            "var_definition",
+        ]
+    }
+
+    fn is_definition(&self, node: Node) -> bool {
+        match node.kind() {
+            "val_definition" | "var_definition" => !self.in_function(node),
+            kind => self.definition_types().contains(&kind),
+        }
+    }
+
+    fn extract_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        // val/var bind a pattern rather than a name
+        node.child_by_field_name("name")
+            .or_else(|| node.child_by_field_name("pattern"))?
+            .utf8_text(source)
+            .ok()
+            .map(String::from)
+    }
+
+    fn extract_signature(&self, node: Node, source: &[u8]) -> Option<String> {
+        let name = self.extract_name(node, source)?;
+        match node.kind() {
+            "function_definition" | "function_declaration" => {
+                // Build: def name[T](params)(implicits): Return
+                let mut sig = format!("def {}", name);
+
+                if let Some(type_params) = node.child_by_field_name("type_parameters") {
+                    if let Ok(text) = type_params.utf8_text(source) {
+                        sig.push_str(text);
+                    }
+                }
+
+                // Curried functions have one parameter list per clause
+                let mut cursor = node.walk();
+                for params in node.children_by_field_name("parameters", &mut cursor) {
+                    if let Ok(text) = params.utf8_text(source) {
+                        sig.push_str(text);
+                    }
+                }
+
+                if let Some(return_type) = node.child_by_field_name("return_type") {
+                    if let Ok(text) = return_type.utf8_text(source) {
+                        sig.push_str(": ");
+                        sig.push_str(text);
+                    }
+                }
+
+                Some(sig.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+            "val_definition" | "var_definition" => {
+                let keyword = if node.kind() == "val_definition" {
+                    "val"
+                } else {
+                    "var"
+                };
+                let mut sig = format!("{} {}", keyword, name);
+
+                if let Some(ty) = node.child_by_field_name("type") {
+                    if let Ok(text) = ty.utf8_text(source) {
+                        sig.push_str(": ");
+                        sig.push_str(text);
+                    }
+                }
+
+                Some(sig)
+            }
+            kind => {
+                let keyword = kind.trim_end_matches("_definition");
+                Some(format!("{} {}", keyword, name))
+            }
+        }
+    }
+
+    fn extract_docstring(&self, node: Node, source: &[u8]) -> Option<String> {
+        // Scaladoc `/** */` comment immediately before the definition
+        let prev = node.prev_named_sibling()?;
+        if prev.kind() != "block_comment" && prev.kind() != "comment" {
+            return None;
+        }
+        let text = prev.utf8_text(source).ok()?;
+        if text.starts_with("/**") {
+            Some(text.to_string())
+        } else {
+            None
+        }
+    }
+
+    fn classify(&self, node: Node) -> ChunkKind {
+        match node.kind() {
+            "function_definition" | "function_declaration" => {
+                // Defs in a class, object or trait body are methods
+                let mut parent = node.parent();
+                while let Some(p) = parent {
+                    match p.kind() {
+                        "function_definition" => break,
+                        "class_definition" | "object_definition" | "trait_definition" => {
+                            return ChunkKind::Method
+                        }
+                        _ => parent = p.parent(),
+                    }
+                }
+                ChunkKind::Function
+            }
+            "class_definition" => ChunkKind::Class,
+            "object_definition" => ChunkKind::Mod,
+            "trait_definition" => ChunkKind::Trait,
+            "val_definition" => ChunkKind::Const,
+            "var_definition" => ChunkKind::Static,
+            _ => ChunkKind::Other,
+        }
+    }
+}
+
+/// OCaml language extractor
+///
+/// Chunks top-level and module-level `let` bindings, `module`, `module type`
+/// and `type` definitions, and the `val`/`external` declarations of interface
+/// files; `let ... in` locals stay in their enclosing binding.
+pub struct OCamlExtractor;
+
+impl OCamlExtractor {
+    /// The binding that names a definition (`let f x = ...` -> `f x = ...`)
+    ///
+    /// `let ... and ...` groups are named after their first binding.
+    fn binding<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        let binding_kind = match node.kind() {
+            "value_definition" => "let_binding",
+            "module_definition" => "module_binding",
+            "type_definition" => "type_binding",
+            _ => return Some(node),
+        };
+        let mut cursor = node.walk();
+        let binding = node
+            .named_children(&mut cursor)
+            .find(|child| child.kind() == binding_kind);
+        binding
+    }
+
+    /// Whether a `let` binding defines a function rather than a value
+    fn is_function(&self, binding: Node) -> bool {
+        let mut cursor = binding.walk();
+        let has_params = binding
+            .children_by_field_name("parameter", &mut cursor)
+            .next()
+            .is_some();
+        has_params
+            || binding
+                .child_by_field_name("body")
+                .is_some_and(|body| matches!(body.kind(), "fun_expression" | "function_expression"))
+    }
+}
+
+impl LanguageExtractor for OCamlExtractor {
+    fn definition_types(&self) -> &[&'static str] {
+        &[
+            "value_definition",
+            "module_definition",
+            "module_type_definition",
+            "type_definition",
+            "value_specification",
+            "external",
+        ]
+    }
+
+    fn is_definition(&self, node: Node) -> bool {
+        match node.kind() {
+            // `let x = ... in body` is a local, not a definition
+            "value_definition" => !node
+                .parent()
+                .is_some_and(|parent| parent.kind() == "let_expression"),
+            kind => self.definition_types().contains(&kind),
+        }
+    }
+
+    fn extract_name(&self, node: Node, source: &[u8]) -> Option<String> {
+        if matches!(node.kind(), "value_specification" | "external") {
+            // `val name : type`: the name has no field of its own
+            let mut cursor = node.walk();
+            let name = node
+                .named_children(&mut cursor)
+                .find(|child| matches!(child.kind(), "value_name" | "parenthesized_operator"))?;
+            return name.utf8_text(source).ok().map(String::from);
+        }
+        let binding = self.binding(node)?;
+        binding
+            .child_by_field_name("name")
+            .or_else(|| binding.child_by_field_name("pattern"))?
+            .utf8_text(source)
+            .ok()
+            .map(String::from)
+    }
+
+    fn extract_signature(&self, node: Node, source: &[u8]) -> Option<String> {
+        let name = self.extract_name(node, source)?;
+        match node.kind() {
+            "value_definition" => {
+                // Build: let rec name param1 param2
+                let mut cursor = node.walk();
+                let is_rec = node
+                    .children(&mut cursor)
+                    .any(|child| child.kind() == "rec");
+                let mut sig = if is_rec {
+                    format!("let rec {}", name)
+                } else {
+                    format!("let {}", name)
+                };
+
+                if let Some(binding) = self.binding(node) {
+                    let mut cursor = binding.walk();
+                    for param in binding.children_by_field_name("parameter", &mut cursor) {
+                        if let Ok(text) = param.utf8_text(source) {
+                            sig.push(' ');
+                            sig.push_str(text);
+                        }
+                    }
+                }
+
+                Some(sig.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+            "module_definition" => Some(format!("module {}", name)),
+            "module_type_definition" => Some(format!("module type {}", name)),
+            "type_definition" => Some(format!("type {}", name)),
+            // `val name : type` is already a signature
+            "value_specification" | "external" => {
+                let text = node.utf8_text(source).ok()?;
+                // Drop the primitive names of `external f : t = "c_name"`
+                let text = text.split('=').next().unwrap_or(text);
+                Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+            _ => None,
+        }
+    }
+
+    fn extract_docstring(&self, node: Node, source: &[u8]) -> Option<String> {
+        // odoc `(** *)` comment immediately before the definition
+        let prev = node.prev_named_sibling()?;
+        if prev.kind() != "comment" {
+            return None;
+        }
+        let text = prev.utf8_text(source).ok()?;
+        if text.starts_with("(**") && !text.starts_with("(***") {
+            Some(text.to_string())
+        } else {
+            None
+        }
+    }
+
+    fn classify(&self, node: Node) -> ChunkKind {
+        match node.kind() {
+            "value_definition" => match self.binding(node) {
+                Some(binding) if self.is_function(binding) => ChunkKind::Function,
+                _ => ChunkKind::Const,
+            },
+            "module_definition" | "module_type_definition" => ChunkKind::Mod,
+            "type_definition" => ChunkKind::TypeAlias,
+            "value_specification" | "external" => {
+                // `val f : a -> b` declares a function, `val x : t` a value
+                let is_function = node
+                    .child_by_field_name("type")
+                    .is_some_and(|t| t.kind() == "function_type");
+                if is_function {
+                    ChunkKind::Function
+                } else {
+                    ChunkKind::Const
+                }
+            }
+            _ => ChunkKind::Other,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1280,7 +2025,13 @@ mod tests {
         assert!(get_extractor(Language::Cpp).is_some());
         assert!(get_extractor(Language::Ruby).is_some());
         assert!(get_extractor(Language::Php).is_some());
+        assert!(get_extractor(Language::Dart).is_some());
+        assert!(get_extractor(Language::Haskell).is_some());
         assert!(get_extractor(Language::Shell).is_some());
+        assert!(get_extractor(Language::Nix).is_some());
+        assert!(get_extractor(Language::GraphQL).is_some());
+        assert!(get_extractor(Language::Scala).is_some());
+        assert!(get_extractor(Language::OCaml).is_some());
         assert!(get_extractor(Language::Markdown).is_none());
     }
 
@@ -1363,6 +2114,92 @@ mod tests {
         assert!(types.contains(&"trait_declaration"));
     }
 
+    #[test]
+    fn test_dart_definition_types() {
+        let extractor = DartExtractor;
+        let types = extractor.definition_types();
+
+        assert!(types.contains(&"function_signature"));
+        assert!(types.contains(&"class_definition"));
+        assert!(types.contains(&"mixin_declaration"));
+    }
+
+    #[test]
+    fn test_dart_classify_mixin() {
+        let source = "/// Things that fly\nmixin Flying on Animal {\n  void fly() {}\n}\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_dart::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let root = tree.root_node();
+        let mixin = (0..root.named_child_count())
+            .filter_map(|i| root.named_child(i))
+            .find(|n| n.kind() == "mixin_declaration")
+            .unwrap();
+
+        let extractor = DartExtractor;
+        assert_eq!(extractor.classify(mixin), ChunkKind::Trait);
+        assert_eq!(
+            extractor.extract_name(mixin, source.as_bytes()),
+            Some("Flying".to_string())
+        );
+        assert_eq!(
+            extractor.build_label(mixin, source.as_bytes()),
+            Some("Trait: Flying".to_string())
+        );
+    }
+
+    #[test]
+    fn test_haskell_definition_types() {
+        let extractor = HaskellExtractor;
+        let types = extractor.definition_types();
+
+        assert!(!types.contains(&"signature"));
+        assert!(types.contains(&"function"));
+        assert!(types.contains(&"data_type"));
+        assert!(types.contains(&"class"));
+        assert!(types.contains(&"instance"));
+    }
+
+    #[test]
+    fn test_haskell_type_signature_belongs_to_its_function() {
+        let source = r#"module Greet where
+
+-- | Greet someone by name
+greet :: String -> String
+greet name = "Hello, " ++ name
+"#;
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_haskell::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let extractor = HaskellExtractor;
+        let mut definitions = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            if extractor.is_definition(node) {
+                definitions.push(node);
+            }
+            stack.extend((0..node.child_count()).filter_map(|i| node.child(i)));
+        }
+
+        assert_eq!(definitions.len(), 1);
+        let greet = definitions[0];
+        assert_eq!(greet.kind(), "function");
+        assert_eq!(
+            extractor.extract_signature(greet, source.as_bytes()),
+            Some("greet :: String -> String".to_string())
+        );
+        assert_eq!(
+            extractor.extract_docstring(greet, source.as_bytes()),
+            Some("-- | Greet someone by name".to_string())
+        );
+    }
+
     #[test]
     fn test_bash_definition_types() {
         let extractor = BashExtractor;
@@ -1370,4 +2207,190 @@ mod tests {
 
         assert!(types.contains(&"function_definition"));
     }
+
+    #[test]
+    fn test_nix_definition_types() {
+        let extractor = NixExtractor;
+        let types = extractor.definition_types();
+
+        assert!(types.contains(&"binding"));
+    }
+
+    #[test]
+    fn test_nix_classify_bindings() {
+        let source = r#"{
+  description = "demo flake";
+
+  # Build outputs
+  outputs = { self, nixpkgs }: {
+    packages.x86_64-linux.default = nixpkgs.legacyPackages.x86_64-linux.hello;
+    lib.mkGreeting = name: "hello ${name}";
+  };
+}
+"#;
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_nix::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let extractor = NixExtractor;
+        let mut definitions = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            if extractor.is_definition(node) {
+                definitions.push(node);
+            }
+            stack.extend((0..node.child_count()).filter_map(|i| node.child(i)));
+        }
+        definitions.sort_by_key(|n| n.start_byte());
+
+        let labels: Vec<String> = definitions
+            .iter()
+            .filter_map(|&n| extractor.build_label(n, source.as_bytes()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Const: description",
+                "Function: outputs",
+                "Function: lib.mkGreeting",
+            ]
+        );
+
+        let outputs = definitions[1];
+        assert_eq!(
+            extractor.extract_signature(outputs, source.as_bytes()),
+            Some("outputs = { self, nixpkgs }:".to_string())
+        );
+        assert_eq!(
+            extractor.extract_docstring(outputs, source.as_bytes()),
+            Some("# Build outputs".to_string())
+        );
+    }
+
+    #[test]
+    fn test_graphql_definition_types() {
+        let extractor = GraphqlExtractor;
+        let types = extractor.definition_types();
+
+        assert!(types.contains(&"object_type_definition"));
+        assert!(types.contains(&"interface_type_definition"));
+        assert!(types.contains(&"enum_type_definition"));
+        assert!(types.contains(&"input_object_type_definition"));
+        assert!(types.contains(&"scalar_type_definition"));
+    }
+
+    #[test]
+    fn test_graphql_classify_definitions() {
+        let source = r#"scalar DateTime
+
+"""
+Anything with a global ID
+"""
+interface Node {
+  id: ID!
+}
+
+"A registered user"
+type User implements Node @key(fields: "id") {
+  id: ID!
+  name: String
+  createdAt: DateTime
+}
+
+enum Role {
+  ADMIN
+  MEMBER
+}
+
+input CreateUserInput {
+  name: String!
+  role: Role = MEMBER
+}
+"#;
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_graphql::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let extractor = GraphqlExtractor;
+        let mut definitions = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            if extractor.is_definition(node) {
+                definitions.push(node);
+            }
+            stack.extend((0..node.child_count()).filter_map(|i| node.child(i)));
+        }
+        definitions.sort_by_key(|n| n.start_byte());
+
+        let labels: Vec<String> = definitions
+            .iter()
+            .filter_map(|&n| extractor.build_label(n, source.as_bytes()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Type: DateTime",
+                "Interface: Node",
+                "Struct: User",
+                "Enum: Role",
+                "Struct: CreateUserInput",
+            ]
+        );
+
+        let signatures: Vec<String> = definitions
+            .iter()
+            .filter_map(|&n| extractor.extract_signature(n, source.as_bytes()))
+            .collect();
+        assert_eq!(
+            signatures,
+            vec![
+                "scalar DateTime",
+                "interface Node { ... }",
+                "type User implements Node @key(fields: \"id\") { ... }",
+                "enum Role { ... }",
+                "input CreateUserInput { ... }",
+            ]
+        );
+
+        assert_eq!(
+            extractor.extract_docstring(definitions[1], source.as_bytes()),
+            Some("Anything with a global ID".to_string())
+        );
+        assert_eq!(
+            extractor.extract_docstring(definitions[2], source.as_bytes()),
+            Some("A registered user".to_string())
+        );
+        assert_eq!(
+            extractor.extract_docstring(definitions[3], source.as_bytes()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_scala_definition_types() {
+        let extractor = ScalaExtractor;
+        let types = extractor.definition_types();
+
+        assert!(types.contains(&"function_definition"));
+        assert!(types.contains(&"class_definition"));
+        assert!(types.contains(&"object_definition"));
+        assert!(types.contains(&"trait_definition"));
+        assert!(types.contains(&"val_definition"));
+        assert!(types.contains(&"var_definition"));
+    }
+
+    #[test]
+    fn test_ocaml_definition_types() {
+        let extractor = OCamlExtractor;
+        let types = extractor.definition_types();
+
+        assert!(types.contains(&"value_definition"));
+        assert!(types.contains(&"module_definition"));
+        assert!(types.contains(&"module_type_definition"));
+        assert!(types.contains(&"type_definition"));
+    }
 }
@@ -1,6 +1,7 @@
 use crate::file::Language;
 use anyhow::{anyhow, Result};
 use dashmap::DashMap;
+use std::path::Path;
 use std::sync::Arc;
 use tracing::{debug, warn};
 use tree_sitter::Language as TsLanguage;
@@ -55,6 +56,19 @@ impl GrammarManager {
         }
     }
 
+    /// Get the grammar for a file in the given language
+    ///
+    /// Same as [`Self::get_grammar`], except for extensions with a grammar of
+    /// their own: OCaml interfaces (`.mli`).
+    pub fn get_grammar_for_path(&self, language: Language, path: &Path) -> Option<Arc<TsLanguage>> {
+        match (language, path.extension().and_then(|e| e.to_str())) {
+            (Language::OCaml, Some("mli")) => {
+                Some(Arc::new(tree_sitter_ocaml::LANGUAGE_OCAML_INTERFACE.into()))
+            }
+            _ => self.get_grammar(language),
+        }
+    }
+
     /// Load the compiled grammar for a language
     fn load_grammar(&self, language: Language) -> Result<TsLanguage> {
         match language {
@@ -72,7 +86,13 @@ impl GrammarManager {
             Language::Cpp => Ok(tree_sitter_cpp::LANGUAGE.into()),
             Language::Ruby => Ok(tree_sitter_ruby::LANGUAGE.into()),
             Language::Php => Ok(tree_sitter_php::LANGUAGE_PHP.into()),
+            Language::Dart => Ok(tree_sitter_dart::LANGUAGE.into()),
+            Language::Haskell => Ok(tree_sitter_haskell::LANGUAGE.into()),
             Language::Shell => Ok(tree_sitter_bash::LANGUAGE.into()),
+            Language::Nix => Ok(tree_sitter_nix::LANGUAGE.into()),
+            Language::GraphQL => Ok(tree_sitter_graphql::LANGUAGE.into()),
+            Language::Scala => Ok(tree_sitter_scala::LANGUAGE.into()),
+            Language::OCaml => Ok(tree_sitter_ocaml::LANGUAGE_OCAML.into()),
             _ => Err(anyhow!(
                 "Language {} does not support tree-sitter",
                 language.name()
@@ -94,7 +114,13 @@ impl GrammarManager {
             Language::Cpp,
             Language::Ruby,
             Language::Php,
+            Language::Dart,
+            Language::Haskell,
             Language::Shell,
+            Language::Nix,
+            Language::GraphQL,
+            Language::Scala,
+            Language::OCaml,
         ]
     }
 
@@ -2,9 +2,12 @@ use anyhow::Result;
 use sha2::{Digest, Sha256};
 use std::path::Path;
 
+use crate::file::Language;
+
 mod dedup;
 mod extractor;
 mod grammar;
+mod notebook;
 mod parser;
 mod semantic;
 
@@ -31,6 +34,10 @@ pub struct Chunk {
     /// Context breadcrumbs (e.g., ["File: main.rs", "Class: Server", "Function: handle_request"])
     pub context: Vec<String>,
 
+    /// Names of the enclosing definitions, ending with this one's
+    /// (e.g., ["auth", "Session", "verify"]); empty outside any definition
+    pub scope: Vec<String>,
+
     /// File path this chunk belongs to
     pub path: String,
 
@@ -58,6 +65,9 @@ pub struct Chunk {
 
     /// Extracted string literals for better search (e.g., "API-VERSION", "2")
     pub string_literals: Vec<String>,
+
+    /// Language of the source file
+    pub language: Language,
 }
 
 impl Chunk {
@@ -77,6 +87,7 @@ impl Chunk {
             end_line,
             kind,
             context: Vec::new(),
+            scope: Vec::new(),
             path,
             signature: None,
             docstring: None,
@@ -86,6 +97,7 @@ impl Chunk {
             context_prev: None,
             context_next: None,
             string_literals: Vec::new(),
+            language: Language::Unknown,
         }
     }
 
@@ -101,6 +113,12 @@ impl Chunk {
         self.hash == other.hash
     }
 
+    /// Symbol path of the chunk joined with its language's separator
+    /// (e.g., "auth::Session::verify"); None outside any definition
+    pub fn scope_path(&self) -> Option<String> {
+        (!self.scope.is_empty()).then(|| self.scope.join(self.language.scope_separator()))
+    }
+
     /// Get the number of lines in this chunk
     pub fn line_count(&self) -> usize {
         self.end_line.saturating_sub(self.start_line)
@@ -162,9 +180,106 @@ pub enum ChunkKind {
     Static,    // Static variable
     Block,     // Gap/unstructured code
     Anchor,    // File-level summary chunk
+    Doc,       // Doc comment of a definition (index --index-docs)
     Other,     // Catch-all
 }
 
+impl ChunkKind {
+    /// Every kind, in declaration order
+    pub fn all() -> &'static [ChunkKind] {
+        &[
+            Self::Function,
+            Self::Class,
+            Self::Method,
+            Self::Struct,
+            Self::Enum,
+            Self::Trait,
+            Self::Interface,
+            Self::Impl,
+            Self::Mod,
+            Self::TypeAlias,
+            Self::Const,
+            Self::Static,
+            Self::Block,
+            Self::Anchor,
+            Self::Doc,
+            Self::Other,
+        ]
+    }
+
+    /// Whether chunks of this kind are named definitions, as opposed to
+    /// constants, gaps, summaries, docs and catch-all content
+    pub fn is_definition(&self) -> bool {
+        matches!(
+            self,
+            Self::Function
+                | Self::Method
+                | Self::Struct
+                | Self::Class
+                | Self::Trait
+                | Self::Enum
+                | Self::Interface
+                | Self::Impl
+                | Self::Mod
+                | Self::TypeAlias
+        )
+    }
+
+    /// Stable name of the kind, as stored in the index and accepted by
+    /// `--kind` / `--exclude-kind` (listed by `demongrep kinds`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Function => "Function",
+            Self::Class => "Class",
+            Self::Method => "Method",
+            Self::Struct => "Struct",
+            Self::Enum => "Enum",
+            Self::Trait => "Trait",
+            Self::Interface => "Interface",
+            Self::Impl => "Impl",
+            Self::Mod => "Mod",
+            Self::TypeAlias => "TypeAlias",
+            Self::Const => "Const",
+            Self::Static => "Static",
+            Self::Block => "Block",
+            Self::Anchor => "Anchor",
+            Self::Doc => "Doc",
+            Self::Other => "Other",
+        }
+    }
+
+    /// Parse a kind name case-insensitively (e.g. "function", "TypeAlias")
+    pub fn from_str(s: &str) -> Option<Self> {
+        let name = s.trim();
+        Self::all()
+            .iter()
+            .copied()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(name))
+    }
+
+    /// One-line description for `demongrep kinds`
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Function => "Standalone function",
+            Self::Class => "Class definition",
+            Self::Method => "Method within a class or impl block",
+            Self::Struct => "Struct definition",
+            Self::Enum => "Enum definition",
+            Self::Trait => "Trait definition",
+            Self::Interface => "Interface definition",
+            Self::Impl => "Impl block",
+            Self::Mod => "Module definition",
+            Self::TypeAlias => "Type alias",
+            Self::Const => "Constant",
+            Self::Static => "Static variable",
+            Self::Block => "Code between definitions",
+            Self::Anchor => "File-level summary",
+            Self::Doc => "Doc comment of a definition (index --index-docs)",
+            Self::Other => "Anything else",
+        }
+    }
+}
+
 /// Trait for chunking strategies
 pub trait Chunker: Send + Sync {
     /// Chunk a file into semantic pieces
@@ -180,6 +295,30 @@ mod tests {
         // TODO: Add tests
     }
 
+    #[test]
+    fn test_chunk_kind_parse() {
+        assert_eq!(ChunkKind::from_str("function"), Some(ChunkKind::Function));
+        assert_eq!(
+            ChunkKind::from_str(" TYPEALIAS "),
+            Some(ChunkKind::TypeAlias)
+        );
+        assert_eq!(ChunkKind::from_str("Other"), Some(ChunkKind::Other));
+        assert_eq!(ChunkKind::from_str("test"), None);
+    }
+
+    #[test]
+    fn test_chunk_kind_names_round_trip() {
+        for kind in ChunkKind::all() {
+            assert_eq!(ChunkKind::from_str(kind.as_str()), Some(*kind));
+            assert_eq!(
+                ChunkKind::from_str(&kind.as_str().to_lowercase()),
+                Some(*kind)
+            );
+            // Indexes written before as_str() stored the Debug name
+            assert_eq!(kind.as_str(), format!("{:?}", kind));
+        }
+    }
+
     #[test]
     fn test_extract_string_literals() {
         let code = r#"
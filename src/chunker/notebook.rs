@@ -0,0 +1,185 @@
+use crate::file::Language;
+use anyhow::Result;
+use serde::Deserialize;
+
+/// Kind of a notebook cell worth indexing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CellKind {
+    Code,
+    Markdown,
+}
+
+/// A code or markdown cell and the line its source starts on in the `.ipynb`
+#[derive(Debug, Clone)]
+pub(super) struct NotebookCell {
+    pub kind: CellKind,
+    pub source: String,
+    /// 0-indexed line of the `.ipynb` file holding the cell's first source line
+    pub start_line: usize,
+}
+
+/// The indexable contents of a Jupyter notebook
+#[derive(Debug, Clone)]
+pub(super) struct Notebook {
+    /// Kernel language of the code cells (Python when the notebook doesn't say)
+    pub language: Language,
+    pub cells: Vec<NotebookCell>,
+}
+
+#[derive(Deserialize)]
+struct RawNotebook {
+    #[serde(default)]
+    cells: Vec<RawCell>,
+    #[serde(default)]
+    metadata: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct RawCell {
+    cell_type: String,
+    #[serde(default)]
+    source: RawSource,
+}
+
+/// nbformat stores cell sources as a list of lines, but a plain string is valid too
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawSource {
+    Lines(Vec<String>),
+    Text(String),
+}
+
+impl Default for RawSource {
+    fn default() -> Self {
+        RawSource::Text(String::new())
+    }
+}
+
+impl RawSource {
+    fn into_text(self) -> String {
+        match self {
+            RawSource::Lines(lines) => lines.concat(),
+            RawSource::Text(text) => text,
+        }
+    }
+}
+
+/// Parse an `.ipynb` file into its code and markdown cells
+///
+/// Raw cells and outputs are dropped. Each cell's `start_line` points at its
+/// source in the notebook JSON, so results open where the cell is; notebooks
+/// not written one source line per JSON line (as Jupyter does) fall back to
+/// numbering the cells' lines consecutively.
+pub(super) fn parse_notebook(content: &str) -> Result<Notebook> {
+    let raw: RawNotebook = serde_json::from_str(content)?;
+
+    let language = ["/kernelspec/language", "/language_info/name"]
+        .iter()
+        .filter_map(|pointer| raw.metadata.pointer(pointer)?.as_str())
+        .filter_map(Language::parse)
+        .find(|language| *language != Language::Jupyter)
+        .unwrap_or(Language::Python);
+
+    let source_lines = source_start_lines(content);
+    let positions_match = source_lines.len() == raw.cells.len();
+
+    let mut cells = Vec::new();
+    let mut next_line = 0;
+    for (index, cell) in raw.cells.into_iter().enumerate() {
+        let source = cell.source.into_text();
+        let start_line = if positions_match {
+            source_lines[index]
+        } else {
+            next_line
+        };
+        next_line += source.lines().count() + 1;
+
+        let kind = match cell.cell_type.as_str() {
+            "code" => CellKind::Code,
+            "markdown" => CellKind::Markdown,
+            _ => continue,
+        };
+        cells.push(NotebookCell {
+            kind,
+            source,
+            start_line,
+        });
+    }
+
+    Ok(Notebook { language, cells })
+}
+
+/// Line of the first source line of each cell, in order
+///
+/// That's the line after `"source": [`, or the `"source"` line itself when
+/// the source is inline (a string or an empty list).
+fn source_start_lines(content: &str) -> Vec<usize> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.trim_start().starts_with("\"source\":"))
+        .map(|(i, line)| {
+            if line.trim_end().ends_with('[') {
+                i + 1
+            } else {
+                i
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cells_map_to_notebook_lines() {
+        let content = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "metadata": {},
+   "source": [
+    "# Analysis\n",
+    "Load the data"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 1,
+   "metadata": {},
+   "outputs": [],
+   "source": [
+    "import pandas as pd\n",
+    "df = pd.read_csv(\"data.csv\")"
+   ]
+  }
+ ],
+ "metadata": {
+  "kernelspec": {
+   "display_name": "R",
+   "language": "R",
+   "name": "ir"
+  }
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}"##;
+        let notebook = parse_notebook(content).unwrap();
+
+        // R has no Language variant, so code cells default to Python
+        assert_eq!(notebook.language, Language::Python);
+        assert_eq!(notebook.cells.len(), 2);
+        assert_eq!(notebook.cells[0].kind, CellKind::Markdown);
+        assert_eq!(notebook.cells[0].start_line, 6);
+        assert_eq!(notebook.cells[1].kind, CellKind::Code);
+        assert_eq!(notebook.cells[1].start_line, 16);
+
+        let lines: Vec<&str> = content.lines().collect();
+        assert!(lines[16].contains("import pandas"));
+        assert_eq!(
+            notebook.cells[1].source,
+            "import pandas as pd\ndf = pd.read_csv(\"data.csv\")"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::file::Language;
 use anyhow::{anyhow, Result};
-use tree_sitter::{Node, Parser, Tree};
+use std::path::Path;
+use std::sync::Arc;
+use tree_sitter::{Language as TsLanguage, Node, Parser, Tree};
 
 use super::grammar::GrammarManager;
 
@@ -21,11 +23,30 @@ impl CodeParser {
 
     /// Parse source code for a given language
     pub fn parse(&mut self, language: Language, source: &str) -> Result<ParsedCode> {
-        // Get grammar for language
-        let grammar = self
-            .grammar_manager
-            .get_grammar(language)
-            .ok_or_else(|| anyhow!("No grammar available for {}", language.name()))?;
+        let grammar = self.grammar_manager.get_grammar(language);
+        self.parse_with(grammar, language, source)
+    }
+
+    /// Parse a file's source code, with the grammar its extension calls for
+    /// (see [`GrammarManager::get_grammar_for_path`])
+    pub fn parse_file(
+        &mut self,
+        language: Language,
+        path: &Path,
+        source: &str,
+    ) -> Result<ParsedCode> {
+        let grammar = self.grammar_manager.get_grammar_for_path(language, path);
+        self.parse_with(grammar, language, source)
+    }
+
+    fn parse_with(
+        &mut self,
+        grammar: Option<Arc<TsLanguage>>,
+        language: Language,
+        source: &str,
+    ) -> Result<ParsedCode> {
+        let grammar =
+            grammar.ok_or_else(|| anyhow!("No grammar available for {}", language.name()))?;
 
         // Set language on parser
         self.parser
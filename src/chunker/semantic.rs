@@ -1,11 +1,17 @@
 use super::{Chunk, ChunkKind, Chunker, DEFAULT_CONTEXT_LINES};
 use crate::chunker::extractor::{get_extractor, LanguageExtractor};
+use crate::chunker::notebook::{parse_notebook, CellKind};
 use crate::chunker::parser::CodeParser;
 use crate::file::Language;
 use anyhow::Result;
+use std::ops::Range;
 use std::path::Path;
 use tree_sitter::Node;
 
+/// Share of a trailing window's lines that may repeat the previous window
+/// before the two are evened out
+const MAX_TAIL_OVERLAP: f32 = 0.5;
+
 /// Smart semantic chunker using tree-sitter and language-specific extractors
 pub struct SemanticChunker {
     parser: CodeParser,
@@ -13,6 +19,9 @@ pub struct SemanticChunker {
     max_chunk_chars: usize,
     overlap_lines: usize,
     context_lines: usize,
+    min_chunk_lines: usize,
+    min_chunk_chars: usize,
+    doc_chunks: bool,
 }
 
 impl SemanticChunker {
@@ -23,9 +32,39 @@ impl SemanticChunker {
             max_chunk_chars,
             overlap_lines,
             context_lines: DEFAULT_CONTEXT_LINES,
+            min_chunk_lines: 0,
+            min_chunk_chars: 0,
+            doc_chunks: false,
         }
     }
 
+    /// Skip chunks below `lines` lines or `chars` characters (0 disables each check)
+    ///
+    /// A trivial definition nested in another one (e.g. a one-line getter in
+    /// an impl block) is left to its parent chunk; a trivial top-level one is
+    /// merged into the surrounding gap chunk. Either way its signature stays
+    /// searchable. Gap chunks below the threshold (e.g. a lone import) are dropped.
+    pub fn with_min_chunk_size(mut self, lines: usize, chars: usize) -> Self {
+        self.min_chunk_lines = lines;
+        self.min_chunk_chars = chars;
+        self
+    }
+
+    /// Whether content spanning `line_count` lines is below the minimum chunk size
+    fn is_trivial(&self, line_count: usize, content: &str) -> bool {
+        line_count < self.min_chunk_lines || content.trim().chars().count() < self.min_chunk_chars
+    }
+
+    /// Also emit each definition's doc comment as a separate `Doc` chunk
+    ///
+    /// The doc chunk covers the comment's own lines and carries the
+    /// definition's breadcrumbs and signature, so usage questions can match
+    /// the prose without the code around it diluting the embedding.
+    pub fn with_doc_chunks(mut self, enabled: bool) -> Self {
+        self.doc_chunks = enabled;
+        self
+    }
+
     /// Set the number of context lines to extract before/after each chunk
     pub fn with_context_lines(mut self, lines: usize) -> Self {
         self.context_lines = lines;
@@ -39,34 +78,46 @@ impl SemanticChunker {
         path: &Path,
         content: &str,
     ) -> Result<Vec<Chunk>> {
+        if language == Language::Jupyter {
+            return self.chunk_notebook(path, content);
+        }
+
         // 1. Check if we have an extractor for this language
         let extractor = match get_extractor(language) {
             Some(ext) => ext,
             None => {
                 // Fall back to simple chunking for unsupported languages
-                return Ok(self.fallback_chunk(path, content));
+                return Ok(with_language(self.fallback_chunk(path, content), language));
             }
         };
 
         // 2. Parse the code
-        let parsed = self.parser.parse(language, content)?;
+        let parsed = self.parser.parse_file(language, path, content)?;
 
         // 3. Visit AST and extract chunks
         let mut definition_chunks = Vec::new();
         let mut gap_tracker = GapTracker::new(content);
 
-        let file_context = format!("File: {}", path.display());
+        let file_scope = Scope {
+            labels: vec![format!("File: {}", path.display())],
+            names: Vec::new(),
+        };
         self.visit_node(
             parsed.root_node(),
             parsed.source().as_bytes(),
             &*extractor,
-            &[file_context],
+            &file_scope,
             &mut definition_chunks,
             &mut gap_tracker,
         );
 
-        // 4. Extract gap chunks (code between definitions)
-        let gap_chunks = gap_tracker.extract_gaps(path);
+        // 4. Extract gap chunks (code between definitions), dropping trivial
+        // ones unless they absorbed a trivial definition
+        let mut gap_chunks = gap_tracker.extract_gaps(path);
+        gap_chunks.retain(|c| {
+            !self.is_trivial(c.line_count(), &c.content)
+                || gap_tracker.has_merged_definition(c.start_line, c.end_line)
+        });
 
         // 5. Combine and sort all chunks by position
         let mut all_chunks = definition_chunks;
@@ -83,7 +134,42 @@ impl SemanticChunker {
             .flat_map(|c| self.split_if_needed(c))
             .collect();
 
-        Ok(final_chunks)
+        Ok(with_language(final_chunks, language))
+    }
+
+    /// Chunk a Jupyter notebook cell by cell
+    ///
+    /// Code cells are chunked in the notebook's kernel language and markdown
+    /// cells as prose, with line numbers shifted to the cell's place in the
+    /// `.ipynb`. A file that isn't notebook JSON is chunked as plain text.
+    fn chunk_notebook(&mut self, path: &Path, content: &str) -> Result<Vec<Chunk>> {
+        let Ok(notebook) = parse_notebook(content) else {
+            return Ok(with_language(
+                self.fallback_chunk(path, content),
+                Language::Jupyter,
+            ));
+        };
+
+        let mut chunks = Vec::new();
+        for (index, cell) in notebook.cells.iter().enumerate() {
+            if cell.source.trim().is_empty() {
+                continue;
+            }
+            let language = match cell.kind {
+                CellKind::Code => notebook.language,
+                CellKind::Markdown => Language::Markdown,
+            };
+            for mut chunk in self.chunk_semantic(language, path, &cell.source)? {
+                chunk.start_line += cell.start_line;
+                chunk.end_line += cell.start_line;
+                // After the "File: ..." breadcrumb
+                let at = chunk.context.len().min(1);
+                chunk.context.insert(at, format!("Cell: {}", index + 1));
+                chunks.push(chunk);
+            }
+        }
+
+        Ok(chunks)
     }
 
     /// Populate context_prev and context_next for each chunk
@@ -125,16 +211,29 @@ impl SemanticChunker {
         node: Node,
         source: &[u8],
         extractor: &dyn LanguageExtractor,
-        context_stack: &[String],
+        scope: &Scope,
         chunks: &mut Vec<Chunk>,
         gap_tracker: &mut GapTracker,
     ) {
         // Check if this node is a definition
-        let is_definition = extractor.definition_types().contains(&node.kind());
+        let is_definition = extractor.is_definition(node);
 
         if is_definition {
+            let (start_row, end_row) = (node.start_position().row, node.end_position().row);
+            if self.min_chunk_lines > 0 || self.min_chunk_chars > 0 {
+                let text = node.utf8_text(source).unwrap_or_default();
+                if self.is_trivial(end_row - start_row + 1, text) {
+                    // Nested: the enclosing definition's chunk already holds it.
+                    // Top-level: leave it uncovered so it joins the gap chunk.
+                    if scope.labels.len() <= 1 {
+                        gap_tracker.mark_merged(start_row);
+                    }
+                    return;
+                }
+            }
+
             // Mark this range as covered (not a gap)
-            gap_tracker.mark_covered(node.start_position().row, node.end_position().row);
+            gap_tracker.mark_covered(start_row, end_row);
 
             // Extract metadata using the language extractor
             let kind = extractor.classify(node);
@@ -145,12 +244,11 @@ impl SemanticChunker {
             // Build label for context breadcrumb
             let label = extractor
                 .build_label(node, source)
-                .or_else(|| name.as_ref().map(|n| format!("{:?}: {}", kind, n)))
-                .unwrap_or_else(|| format!("{:?}", kind));
+                .or_else(|| name.as_ref().map(|n| format!("{}: {}", kind.as_str(), n)))
+                .unwrap_or_else(|| kind.as_str().to_string());
 
             // Build new context stack
-            let mut new_context = context_stack.to_vec();
-            new_context.push(label);
+            let new_scope = scope.enter(label, extractor.scope_name(node, source));
 
             // Extract content (without docstring if we have it separate)
             let content = match node.utf8_text(source) {
@@ -159,7 +257,8 @@ impl SemanticChunker {
             };
 
             // Create chunk
-            let path_str = context_stack
+            let path_str = scope
+                .labels
                 .first()
                 .map(|s| s.strip_prefix("File: ").unwrap_or(s))
                 .unwrap_or("")
@@ -172,39 +271,88 @@ impl SemanticChunker {
                 kind,
                 path_str,
             );
-            chunk.context = new_context.clone();
+            chunk.context = new_scope.labels.clone();
+            chunk.scope = new_scope.names.clone();
             chunk.signature = signature;
             chunk.docstring = docstring;
             chunk.string_literals = Chunk::extract_string_literals(&content);
 
+            let doc_chunk = self
+                .doc_chunks
+                .then(|| extractor.docstring_range(node, source))
+                .flatten()
+                .and_then(|range| doc_chunk(&chunk, source, range));
             chunks.push(chunk);
+            chunks.extend(doc_chunk);
 
             // Visit children with updated context
             let mut cursor = node.walk();
             for child in node.named_children(&mut cursor) {
-                self.visit_node(child, source, extractor, &new_context, chunks, gap_tracker);
+                self.visit_node(child, source, extractor, &new_scope, chunks, gap_tracker);
             }
         } else {
             // Not a definition, just visit children with same context
             let mut cursor = node.walk();
             for child in node.named_children(&mut cursor) {
-                self.visit_node(child, source, extractor, context_stack, chunks, gap_tracker);
+                self.visit_node(child, source, extractor, scope, chunks, gap_tracker);
+            }
+        }
+    }
+
+    /// Line windows `[start, end)` covering `total_lines` with the configured overlap
+    ///
+    /// Windows stop at the first one reaching the end. A trailing window that
+    /// would mostly repeat its predecessor is evened out with it instead: the
+    /// two split their lines in halves sharing `overlap_lines`, so we don't
+    /// index near-duplicate chunks, no window exceeds `max_chunk_lines` and
+    /// line ranges stay exact.
+    fn window_ranges(&self, total_lines: usize) -> Vec<(usize, usize)> {
+        let stride = self
+            .max_chunk_lines
+            .saturating_sub(self.overlap_lines)
+            .max(1);
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+        let mut i = 0;
+        while i < total_lines {
+            let end = (i + self.max_chunk_lines).min(total_lines);
+            ranges.push((i, end));
+            if end == total_lines {
+                break;
+            }
+            i += stride;
+        }
+
+        if let [.., (prev_start, prev_end), (start, end)] = ranges[..] {
+            let overlap = prev_end.saturating_sub(start);
+            if overlap as f32 > (end - start) as f32 * MAX_TAIL_OVERLAP {
+                // prev is a full window, so the two never fit in one
+                let span = end - prev_start;
+                let split = prev_start
+                    + (span + self.overlap_lines)
+                        .div_ceil(2)
+                        .min(self.max_chunk_lines);
+                ranges.truncate(ranges.len() - 2);
+                ranges.push((prev_start, split));
+                ranges.push((
+                    split.saturating_sub(self.overlap_lines).max(prev_start + 1),
+                    end,
+                ));
             }
         }
+
+        ranges
     }
 
     /// Fallback chunking for unsupported languages
     fn fallback_chunk(&self, path: &Path, content: &str) -> Vec<Chunk> {
         let lines: Vec<&str> = content.lines().collect();
         let mut chunks = Vec::new();
-        let stride = (self.max_chunk_lines - self.overlap_lines).max(1);
 
         let path_str = path.to_string_lossy().to_string();
         let context = vec![format!("File: {}", path_str)];
 
-        let mut i = 0;
-        while i < lines.len() {
-            let end = (i + self.max_chunk_lines).min(lines.len());
+        for (i, end) in self.window_ranges(lines.len()) {
             let chunk_lines = &lines[i..end];
 
             if !chunk_lines.is_empty() {
@@ -215,8 +363,6 @@ impl SemanticChunker {
                 chunk.string_literals = Chunk::extract_string_literals(&content);
                 chunks.push(chunk);
             }
-
-            i += stride;
         }
 
         chunks
@@ -234,14 +380,16 @@ impl SemanticChunker {
 
         // Need to split
         let lines: Vec<&str> = chunk.content.lines().collect();
-        let mut split_chunks = Vec::new();
-        let stride = (self.max_chunk_lines - self.overlap_lines).max(1);
+        let ranges = self.window_ranges(lines.len());
+        if ranges.len() <= 1 {
+            // Within the line limit (only too many chars): one window is the whole chunk
+            return vec![chunk];
+        }
 
-        let mut i = 0;
+        let mut split_chunks = Vec::new();
         let mut split_index = 0;
 
-        while i < lines.len() {
-            let end = (i + self.max_chunk_lines).min(lines.len());
+        for (i, end) in ranges {
             let chunk_lines = &lines[i..end];
 
             if !chunk_lines.is_empty() {
@@ -256,6 +404,7 @@ impl SemanticChunker {
 
                 // Preserve metadata
                 split_chunk.context = chunk.context.clone();
+                split_chunk.scope = chunk.scope.clone();
                 split_chunk.signature = chunk.signature.clone();
                 split_chunk.docstring = if split_index == 0 {
                     chunk.docstring.clone() // Only first chunk gets docstring
@@ -268,8 +417,6 @@ impl SemanticChunker {
                 split_chunks.push(split_chunk);
                 split_index += 1;
             }
-
-            i += stride;
         }
 
         // Add header to split chunks to indicate they're partial
@@ -296,7 +443,7 @@ impl SemanticChunker {
 impl Chunker for SemanticChunker {
     fn chunk_file(&self, path: &Path, content: &str) -> Result<Vec<Chunk>> {
         // Detect language from path
-        let language = Language::from_path(path);
+        let language = Language::detect(path, content);
 
         // Can't use &mut self in trait method, so we need a workaround
         // Create a temporary parser for this call
@@ -310,25 +457,95 @@ impl Chunker for SemanticChunker {
     }
 }
 
+/// Tag every chunk with the language of its source file
+fn with_language(mut chunks: Vec<Chunk>, language: Language) -> Vec<Chunk> {
+    for chunk in &mut chunks {
+        chunk.language = language;
+    }
+    chunks
+}
+
+/// A definition's doc comment as a `Doc` chunk of its own
+///
+/// `range` is where the comment sits in the source (above the item in Rust,
+/// inside it in Python), as reported by the language extractor.
+fn doc_chunk(definition: &Chunk, source: &[u8], range: Range<usize>) -> Option<Chunk> {
+    let doc = definition.docstring.as_deref()?.trim();
+    if doc.is_empty() {
+        return None;
+    }
+    let line_at = |byte: usize| source[..byte].iter().filter(|&&b| b == b'\n').count();
+    let start_line = line_at(range.start);
+    let end_line = line_at(range.end.saturating_sub(1)) + 1;
+
+    let mut chunk = Chunk::new(
+        doc.to_string(),
+        start_line,
+        end_line,
+        ChunkKind::Doc,
+        definition.path.clone(),
+    );
+    chunk.context = definition.context.clone();
+    chunk.scope = definition.scope.clone();
+    chunk.signature = definition.signature.clone();
+    Some(chunk)
+}
+
+/// Definitions enclosing an AST node, outermost first
+struct Scope {
+    /// Breadcrumb labels, starting with the file ("File: a.rs", "Impl: Session")
+    labels: Vec<String>,
+    /// Names of the enclosing definitions that have one ("auth", "Session")
+    names: Vec<String>,
+}
+
+impl Scope {
+    /// The scope inside a definition with this label and (optional) name
+    fn enter(&self, label: String, name: Option<String>) -> Self {
+        let mut labels = self.labels.clone();
+        labels.push(label);
+        let mut names = self.names.clone();
+        names.extend(name);
+        Self { labels, names }
+    }
+}
+
 /// Helper to track gaps (code between definitions)
 struct GapTracker<'a> {
     content: &'a str,
     lines: Vec<&'a str>,
     covered: Vec<bool>, // covered[i] = true if line i is part of a definition
+    merged: Vec<bool>,  // merged[i] = true if a trivial definition starting at line i joins a gap
 }
 
 impl<'a> GapTracker<'a> {
     fn new(content: &'a str) -> Self {
         let lines: Vec<&str> = content.lines().collect();
         let covered = vec![false; lines.len()];
+        let merged = vec![false; lines.len()];
 
         Self {
             content,
             lines,
             covered,
+            merged,
+        }
+    }
+
+    /// Record a trivial definition left in the gaps instead of its own chunk
+    fn mark_merged(&mut self, start_line: usize) {
+        if let Some(merged) = self.merged.get_mut(start_line) {
+            *merged = true;
         }
     }
 
+    /// Whether a trivial definition was merged into the lines `[start_line, end_line)`
+    fn has_merged_definition(&self, start_line: usize, end_line: usize) -> bool {
+        self.merged
+            .get(start_line..end_line.min(self.merged.len()))
+            .is_some_and(|lines| lines.iter().any(|&m| m))
+    }
+
     /// Mark a range of lines as covered by a definition
     fn mark_covered(&mut self, start_line: usize, end_line: usize) {
         for i in start_line..=end_line.min(self.covered.len().saturating_sub(1)) {
@@ -492,6 +709,106 @@ struct Point {
         }
     }
 
+    #[test]
+    fn test_doc_chunks_link_to_their_definition() {
+        let code = r#"use std::fmt;
+
+/// Parse a config file.
+///
+/// Returns the defaults when the file is missing.
+#[inline]
+pub fn parse_config(path: &str) -> Config {
+    Config::default()
+}
+
+fn undocumented() {}
+"#;
+        let path = Path::new("config.rs");
+
+        let mut chunker = SemanticChunker::new(100, 2000, 10);
+        let chunks = chunker.chunk_semantic(Language::Rust, path, code).unwrap();
+        assert!(chunks.iter().all(|c| c.kind != ChunkKind::Doc));
+
+        let mut chunker = SemanticChunker::new(100, 2000, 10).with_doc_chunks(true);
+        let chunks = chunker.chunk_semantic(Language::Rust, path, code).unwrap();
+        let docs: Vec<_> = chunks.iter().filter(|c| c.kind == ChunkKind::Doc).collect();
+        assert_eq!(docs.len(), 1);
+
+        let doc = docs[0];
+        let definition = chunks
+            .iter()
+            .find(|c| c.kind == ChunkKind::Function && c.content.contains("parse_config"))
+            .unwrap();
+        assert!(doc.content.starts_with("/// Parse a config file."));
+        assert!(doc
+            .content
+            .ends_with("/// Returns the defaults when the file is missing."));
+        assert_eq!((doc.start_line, doc.end_line), (2, 5));
+        assert_eq!(doc.context, definition.context);
+        assert_eq!(doc.signature, definition.signature);
+        assert_eq!(doc.language, Language::Rust);
+    }
+
+    #[test]
+    fn test_ocaml_interface_files_use_the_interface_grammar() {
+        let code = r#"(** Greet someone by name *)
+val greet : string -> string
+
+val default_name : string
+
+type t = { name : string }
+"#;
+        let mut chunker = SemanticChunker::new(100, 2000, 10);
+        let chunks = chunker
+            .chunk_semantic(Language::OCaml, Path::new("greet.mli"), code)
+            .unwrap();
+
+        let greet = chunks
+            .iter()
+            .find(|c| c.signature.as_deref() == Some("val greet : string -> string"))
+            .unwrap();
+        assert_eq!(greet.kind, ChunkKind::Function);
+        assert_eq!(
+            greet.docstring.as_deref(),
+            Some("(** Greet someone by name *)")
+        );
+        let default_name = chunks
+            .iter()
+            .find(|c| c.signature.as_deref() == Some("val default_name : string"))
+            .unwrap();
+        assert_eq!(default_name.kind, ChunkKind::Const);
+        assert!(chunks
+            .iter()
+            .any(|c| c.kind == ChunkKind::TypeAlias && c.signature.as_deref() == Some("type t")));
+    }
+
+    #[test]
+    fn test_doc_chunk_lines_come_from_the_comment_itself() {
+        // The doc text is repeated later in the body, and a Python docstring
+        // sits inside its function
+        let code = r#"/// Retry the request.
+fn retry() {
+    let usage = "/// Retry the request.";
+}
+"#;
+        let mut chunker = SemanticChunker::new(100, 2000, 10).with_doc_chunks(true);
+        let chunks = chunker
+            .chunk_semantic(Language::Rust, Path::new("retry.rs"), code)
+            .unwrap();
+        let doc = chunks.iter().find(|c| c.kind == ChunkKind::Doc).unwrap();
+        assert_eq!((doc.start_line, doc.end_line), (0, 1));
+
+        let code = r#"def retry():
+    """Retry the request."""
+    usage = """Retry the request."""
+"#;
+        let chunks = chunker
+            .chunk_semantic(Language::Python, Path::new("retry.py"), code)
+            .unwrap();
+        let doc = chunks.iter().find(|c| c.kind == ChunkKind::Doc).unwrap();
+        assert_eq!((doc.start_line, doc.end_line), (1, 2));
+    }
+
     #[test]
     fn test_chunk_python_code() {
         let mut chunker = SemanticChunker::new(100, 2000, 10);
@@ -516,6 +833,7 @@ class Calculator:
 
         // Should have at least 2 chunks (function + class)
         assert!(chunks.len() >= 2, "Expected at least 2 chunks");
+        assert!(chunks.iter().all(|c| c.language == Language::Python));
 
         // Check for docstrings
         let chunks_with_docs: Vec<_> = chunks.iter().filter(|c| c.docstring.is_some()).collect();
@@ -525,6 +843,74 @@ class Calculator:
         );
     }
 
+    #[test]
+    fn test_chunk_jupyter_notebook() {
+        let mut chunker = SemanticChunker::new(100, 2000, 10);
+
+        let notebook = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "metadata": {},
+   "source": [
+    "# Cleaning the sales data"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 1,
+   "metadata": {},
+   "outputs": [
+    {
+     "name": "stdout",
+     "output_type": "stream",
+     "text": ["cleaned\n"]
+    }
+   ],
+   "source": [
+    "def clean(df):\n",
+    "    \"\"\"Drop rows without a price\"\"\"\n",
+    "    return df.dropna(subset=[\"price\"])"
+   ]
+  }
+ ],
+ "metadata": {
+  "kernelspec": {
+   "display_name": "Python 3",
+   "language": "python",
+   "name": "python3"
+  }
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}
+"##;
+
+        let path = Path::new("analysis.ipynb");
+        let chunks = chunker
+            .chunk_semantic(Language::Jupyter, path, notebook)
+            .unwrap();
+
+        let function = chunks
+            .iter()
+            .find(|c| c.kind == ChunkKind::Function)
+            .expect("code cell should produce a function chunk");
+        assert_eq!(function.language, Language::Python);
+        assert!(function.content.starts_with("def clean(df):"));
+        assert!(function.signature.as_ref().unwrap().contains("def clean"));
+        // Line numbers point into the .ipynb, at the cell's source
+        let lines: Vec<&str> = notebook.lines().collect();
+        assert!(lines[function.start_line].contains("def clean(df)"));
+        assert!(function.context.contains(&"Cell: 2".to_string()));
+
+        // Markdown cells are indexed as prose; outputs are not indexed
+        assert!(chunks
+            .iter()
+            .any(|c| c.language == Language::Markdown && c.content.contains("sales data")));
+        assert!(!chunks.iter().any(|c| c.content.contains("cleaned")));
+        assert!(!chunks.iter().any(|c| c.content.contains("cell_type")));
+    }
+
     #[test]
     fn test_chunk_unsupported_language() {
         let mut chunker = SemanticChunker::new(100, 2000, 10);
@@ -540,6 +926,7 @@ class Calculator:
         // Should use fallback chunking
         assert!(!chunks.is_empty());
         assert!(chunks.iter().all(|c| c.kind == ChunkKind::Block));
+        assert!(chunks.iter().all(|c| c.language == Language::Unknown));
     }
 
     #[test]
@@ -595,6 +982,117 @@ class Calculator:
         }
     }
 
+    #[test]
+    fn test_split_evens_out_redundant_tail_window() {
+        let chunker = SemanticChunker::new(100, 100_000, 10);
+        let function = |lines: usize| {
+            let content = (0..lines)
+                .map(|i| format!("    let x{} = {};", i, i))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Chunk::new(
+                content,
+                0,
+                lines,
+                ChunkKind::Function,
+                "long.rs".to_string(),
+            )
+        };
+
+        // 195 lines: the third window [180, 195) would be 2/3 overlap, so it
+        // and the second one share [90, 195) evenly instead
+        let splits = chunker.split_if_needed(function(195));
+        let ranges: Vec<_> = splits.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(ranges, vec![(0, 100), (90, 148), (138, 195)]);
+        let tail_body: Vec<&str> = splits[2].content.lines().skip(1).collect();
+        assert_eq!(tail_body.len(), 57);
+        assert_eq!(tail_body[0], "    let x138 = 138;");
+        assert_eq!(tail_body[56], "    let x194 = 194;");
+
+        // 105 lines: too long for one chunk, so it's halved rather than
+        // followed by a 15-line near-duplicate
+        let splits = chunker.split_if_needed(function(105));
+        let ranges: Vec<_> = splits.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(ranges, vec![(0, 58), (48, 105)]);
+
+        // 250 lines: the tail [180, 250) is mostly new content and is kept
+        let splits = chunker.split_if_needed(function(250));
+        let ranges: Vec<_> = splits.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(ranges, vec![(0, 100), (90, 190), (180, 250)]);
+    }
+
+    #[test]
+    fn test_windows_never_exceed_max_chunk_lines() {
+        for (max_lines, overlap) in [(100, 10), (100, 0), (10, 6), (3, 2)] {
+            let chunker = SemanticChunker::new(max_lines, 100_000, overlap);
+            for total in max_lines + 1..=max_lines * 4 {
+                let ranges = chunker.window_ranges(total);
+                assert_eq!(ranges.first().map(|r| r.0), Some(0));
+                assert_eq!(ranges.last().map(|r| r.1), Some(total));
+                for &(start, end) in &ranges {
+                    assert!(
+                        start < end && end - start <= max_lines,
+                        "{:?} with max {} overlap {}",
+                        ranges,
+                        max_lines,
+                        overlap
+                    );
+                }
+                for pair in ranges.windows(2) {
+                    // Contiguous or overlapping, never a gap
+                    assert!(pair[1].0 <= pair[0].1, "{:?}", ranges);
+                    assert!(pair[1].0 > pair[0].0, "{:?}", ranges);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_chunk_size_filters_trivial_definitions() {
+        let code = r#"use std::fmt;
+
+struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    fn get(&self) -> u32 { self.count }
+
+    fn increment(&mut self) {
+        self.count += 1;
+        if self.count > 100 {
+            self.count = 0;
+        }
+    }
+}
+
+fn noop() {}
+"#;
+        let path = Path::new("counter.rs");
+
+        let mut chunker = SemanticChunker::new(100, 2000, 10);
+        let all = chunker.chunk_semantic(Language::Rust, path, code).unwrap();
+        assert!(all
+            .iter()
+            .any(|c| c.content == "fn get(&self) -> u32 { self.count }"));
+
+        let mut chunker = SemanticChunker::new(100, 2000, 10).with_min_chunk_size(3, 0);
+        let chunks = chunker.chunk_semantic(Language::Rust, path, code).unwrap();
+
+        // The one-line getter has no chunk of its own...
+        assert!(!chunks.iter().any(|c| c.content.starts_with("fn get")));
+        assert!(chunks.len() < all.len());
+        // ...but stays searchable through the impl block
+        assert!(chunks
+            .iter()
+            .any(|c| c.content.starts_with("impl Counter") && c.content.contains("fn get(&self)")));
+        // The top-level empty function joins a gap chunk rather than disappearing
+        assert!(chunks.iter().any(|c| c.content.contains("fn noop() {}")));
+        // The lone import is too small to be a chunk
+        assert!(!chunks.iter().any(|c| c.content.trim() == "use std::fmt;"));
+        assert!(chunks.iter().any(|c| c.content.contains("fn increment")));
+    }
+
     #[test]
     fn test_context_breadcrumbs() {
         let mut chunker = SemanticChunker::new(100, 2000, 10);
@@ -621,4 +1119,42 @@ impl MyStruct {
             assert!(chunk.context[0].contains("File:"));
         }
     }
+
+    #[test]
+    fn test_method_in_impl_records_scope() {
+        let mut chunker = SemanticChunker::new(100, 2000, 10);
+
+        let rust_code = r#"
+mod auth {
+    impl<T> Session<T> {
+        fn verify(&self) -> bool {
+            true
+        }
+    }
+}
+"#;
+
+        let chunks = chunker
+            .chunk_semantic(Language::Rust, Path::new("src/lib.rs"), rust_code)
+            .unwrap();
+        let scope_of = |kind: ChunkKind| {
+            chunks
+                .iter()
+                .find(|c| c.kind == kind)
+                .map(|c| c.scope.clone())
+                .unwrap()
+        };
+
+        assert_eq!(scope_of(ChunkKind::Mod), vec!["auth"]);
+        assert_eq!(scope_of(ChunkKind::Impl), vec!["auth", "Session"]);
+        assert_eq!(
+            scope_of(ChunkKind::Method),
+            vec!["auth", "Session", "verify"]
+        );
+        let method = chunks.iter().find(|c| c.kind == ChunkKind::Method).unwrap();
+        assert_eq!(
+            method.scope_path().as_deref(),
+            Some("auth::Session::verify")
+        );
+    }
 }
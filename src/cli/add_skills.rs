@@ -6,30 +6,115 @@ use std::process::Command;
 const REPO_OWNER: &str = "nahuelcio";
 const REPO_NAME: &str = "demongrep";
 
-pub fn run(skill: String, ref_name: Option<String>, dest: Option<PathBuf>) -> Result<()> {
+/// How a ref was resolved to a GitHub archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RefKind {
+    Tag,
+    Branch,
+}
+
+/// The ref a skills archive was downloaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ResolvedRef {
+    name: String,
+    kind: RefKind,
+}
+
+impl ResolvedRef {
+    /// Branches and pre-release tags aren't releases, so installing from them
+    /// needs confirmation
+    fn is_release(&self) -> bool {
+        self.kind == RefKind::Tag && !is_prerelease(&self.name)
+    }
+
+    fn describe(&self) -> String {
+        match self.kind {
+            RefKind::Branch => format!("branch '{}'", self.name),
+            RefKind::Tag if is_prerelease(&self.name) => {
+                format!("pre-release tag '{}'", self.name)
+            }
+            RefKind::Tag => format!("tag '{}'", self.name),
+        }
+    }
+}
+
+pub fn run(
+    skills: Vec<String>,
+    ref_name: Option<String>,
+    dest: Option<PathBuf>,
+    allow_prerelease: bool,
+) -> Result<()> {
     ensure_command("curl")?;
     ensure_command("tar")?;
 
-    let requested_skill = skill;
-    let skill = if requested_skill == "demongrep-agent-workflows" {
-        println!("Note: skill name 'demongrep-agent-workflows' is deprecated; using 'demongrep'.");
-        "demongrep".to_string()
-    } else {
-        requested_skill
-    };
+    let mut skill_names: Vec<String> = Vec::new();
+    for requested_skill in skills {
+        let skill = if requested_skill == "demongrep-agent-workflows" {
+            println!(
+                "Note: skill name 'demongrep-agent-workflows' is deprecated; using 'demongrep'."
+            );
+            "demongrep".to_string()
+        } else {
+            requested_skill
+        };
+        if !skill_names.contains(&skill) {
+            skill_names.push(skill);
+        }
+    }
 
+    let explicit_ref = ref_name.is_some();
     let ref_name = ref_name.unwrap_or_else(|| format!("v{}", env!("CARGO_PKG_VERSION")));
     let destinations = resolve_destinations(dest)?;
     let temp_root = create_temp_root()?;
 
     let tarball = temp_root.join("repo.tar.gz");
-    download_repo_archive(&ref_name, &tarball)?;
+    let resolved = resolve_ref(&ref_name, explicit_ref, |url| download(url, &tarball))?;
     extract_archive(&tarball, &temp_root)?;
 
     let repo_dir = find_extracted_repo_dir(&temp_root)?;
-    let skill_src = repo_dir.join("skills").join(&skill);
-    if !skill_src.exists() {
-        return Err(anyhow!("Skill '{}' not found in ref '{}'", skill, ref_name));
+    let version = archive_version(&repo_dir);
+    verify_version(&resolved, version.as_deref())?;
+
+    if !resolved.is_release() && !allow_prerelease {
+        use std::io::{self, IsTerminal, Write};
+
+        let version = version.as_deref().unwrap_or("unknown version");
+        // Without a terminal nobody can confirm; fail rather than report success
+        if !io::stdin().is_terminal() {
+            let _ = fs::remove_dir_all(&temp_root);
+            return Err(anyhow!(
+                "{} ({}) is not a release; pass --allow-prerelease to install it without confirmation",
+                resolved.describe(),
+                version
+            ));
+        }
+        print!(
+            "Install skills from {} ({}) instead of a release? (y/N): ",
+            resolved.describe(),
+            version
+        );
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if !input.trim().eq_ignore_ascii_case("y") {
+            let _ = fs::remove_dir_all(&temp_root);
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    // Check every skill before installing any, so a typo doesn't leave a partial install
+    for skill in &skill_names {
+        if !repo_dir.join("skills").join(skill).exists() {
+            let _ = fs::remove_dir_all(&temp_root);
+            return Err(anyhow!(
+                "Skill '{}' not found in {}",
+                skill,
+                resolved.describe()
+            ));
+        }
     }
 
     let mut installed_paths = Vec::new();
@@ -37,19 +122,27 @@ pub fn run(skill: String, ref_name: Option<String>, dest: Option<PathBuf>) -> Re
         fs::create_dir_all(&skills_root)
             .with_context(|| format!("Failed to create {}", skills_root.display()))?;
 
-        let skill_dest = skills_root.join(&skill);
-        if skill_dest.exists() {
-            fs::remove_dir_all(&skill_dest)
-                .with_context(|| format!("Failed to remove existing {}", skill_dest.display()))?;
-        }
+        for skill in &skill_names {
+            let skill_dest = skills_root.join(skill);
+            if skill_dest.exists() {
+                fs::remove_dir_all(&skill_dest).with_context(|| {
+                    format!("Failed to remove existing {}", skill_dest.display())
+                })?;
+            }
 
-        copy_dir_recursive(&skill_src, &skill_dest)?;
-        installed_paths.push(skill_dest);
+            copy_dir_recursive(&repo_dir.join("skills").join(skill), &skill_dest)?;
+            installed_paths.push(skill_dest);
+        }
     }
 
     let _ = fs::remove_dir_all(&temp_root);
 
-    println!("Installed skill: {}", skill);
+    if skill_names.len() == 1 {
+        println!("Installed skill: {}", skill_names[0]);
+    } else {
+        println!("Installed skills: {}", skill_names.join(", "));
+    }
+    println!("Source: {}", resolved.describe());
     for path in installed_paths {
         println!("Destination: {}", path.display());
     }
@@ -96,45 +189,112 @@ fn create_temp_root() -> Result<PathBuf> {
     Ok(root)
 }
 
-fn download_repo_archive(ref_name: &str, tarball: &Path) -> Result<()> {
-    let tag_url = format!(
-        "https://github.com/{}/{}/archive/refs/tags/{}.tar.gz",
-        REPO_OWNER, REPO_NAME, ref_name
-    );
-    let branch_url = format!(
-        "https://github.com/{}/{}/archive/{}.tar.gz",
-        REPO_OWNER, REPO_NAME, ref_name
-    );
+fn archive_url(ref_name: &str, kind: RefKind) -> String {
+    match kind {
+        RefKind::Tag => format!(
+            "https://github.com/{}/{}/archive/refs/tags/{}.tar.gz",
+            REPO_OWNER, REPO_NAME, ref_name
+        ),
+        RefKind::Branch => format!(
+            "https://github.com/{}/{}/archive/refs/heads/{}.tar.gz",
+            REPO_OWNER, REPO_NAME, ref_name
+        ),
+    }
+}
 
-    let tag_status = Command::new("curl")
-        .arg("-fsSL")
-        .arg(&tag_url)
-        .arg("-o")
-        .arg(tarball)
-        .status()
-        .context("Failed to run curl for tag archive")?;
+/// Download the archive for `ref_name` with `download`, trying it as a tag first
+///
+/// Only an explicit `--ref` may fall back to a branch: when the default
+/// version tag is missing, installing from a branch of the same name would
+/// silently pick up unreleased skills.
+fn resolve_ref(
+    ref_name: &str,
+    explicit: bool,
+    mut download: impl FnMut(&str) -> Result<bool>,
+) -> Result<ResolvedRef> {
+    let kinds: &[RefKind] = if explicit {
+        &[RefKind::Tag, RefKind::Branch]
+    } else {
+        &[RefKind::Tag]
+    };
 
-    if tag_status.success() {
-        return Ok(());
+    let mut tried = Vec::new();
+    for &kind in kinds {
+        let url = archive_url(ref_name, kind);
+        if download(&url)? {
+            return Ok(ResolvedRef {
+                name: ref_name.to_string(),
+                kind,
+            });
+        }
+        tried.push(url);
+    }
+
+    if explicit {
+        Err(anyhow!(
+            "Ref '{}' is neither a tag nor a branch (tried {})",
+            ref_name,
+            tried.join(", ")
+        ))
+    } else {
+        Err(anyhow!(
+            "Release tag '{}' not found (tried {}). Pass --ref <tag-or-branch> to install from another ref",
+            ref_name,
+            tried.join(", ")
+        ))
     }
+}
 
-    let branch_status = Command::new("curl")
+fn download(url: &str, tarball: &Path) -> Result<bool> {
+    let status = Command::new("curl")
         .arg("-fsSL")
-        .arg(&branch_url)
+        .arg(url)
         .arg("-o")
         .arg(tarball)
         .status()
-        .context("Failed to run curl for branch archive")?;
+        .with_context(|| format!("Failed to run curl for {}", url))?;
+    Ok(status.success())
+}
 
-    if !branch_status.success() {
-        return Err(anyhow!(
-            "Failed to download skill source from '{}' or '{}'",
-            tag_url,
-            branch_url
-        ));
+/// Whether a tag names a pre-release version, e.g. `v2.0.0-rc.1`
+fn is_prerelease(ref_name: &str) -> bool {
+    let version = ref_name.strip_prefix('v').unwrap_or(ref_name);
+    version.starts_with(|c: char| c.is_ascii_digit()) && version.contains('-')
+}
+
+/// Package version in the extracted repository's Cargo.toml
+fn archive_version(repo_dir: &Path) -> Option<String> {
+    let manifest = fs::read_to_string(repo_dir.join("Cargo.toml")).ok()?;
+    let manifest: toml::Table = toml::from_str(&manifest).ok()?;
+    manifest
+        .get("package")?
+        .get("version")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Check that a version tag's archive contains the version the tag names
+///
+/// Branches and tags that aren't versions have nothing to check against.
+fn verify_version(resolved: &ResolvedRef, archive_version: Option<&str>) -> Result<()> {
+    let tag_version = resolved.name.strip_prefix('v').unwrap_or(&resolved.name);
+    if resolved.kind != RefKind::Tag || !tag_version.starts_with(|c: char| c.is_ascii_digit()) {
+        return Ok(());
     }
 
-    Ok(())
+    match archive_version {
+        Some(version) if version == tag_version => Ok(()),
+        Some(version) => Err(anyhow!(
+            "Tag '{}' contains demongrep {}, not {}; refusing to install its skills",
+            resolved.name,
+            version,
+            tag_version
+        )),
+        None => Err(anyhow!(
+            "Could not read the demongrep version from tag '{}'",
+            resolved.name
+        )),
+    }
 }
 
 fn extract_archive(tarball: &Path, out_dir: &Path) -> Result<()> {
@@ -197,3 +357,66 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve_with(ref_name: &str, explicit: bool, existing: &[RefKind]) -> Result<ResolvedRef> {
+        resolve_ref(ref_name, explicit, |url| {
+            Ok(existing
+                .iter()
+                .any(|&kind| archive_url(ref_name, kind) == url))
+        })
+    }
+
+    #[test]
+    fn test_tag_preferred_over_branch() {
+        let resolved = resolve_with("v1.2.0", true, &[RefKind::Tag, RefKind::Branch]).unwrap();
+        assert_eq!(resolved.kind, RefKind::Tag);
+        assert!(resolved.is_release());
+    }
+
+    #[test]
+    fn test_default_ref_never_falls_back_to_branch() {
+        let err = resolve_with("v1.2.0", false, &[RefKind::Branch])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Release tag 'v1.2.0' not found"), "{}", err);
+        assert!(!err.contains("refs/heads"), "{}", err);
+    }
+
+    #[test]
+    fn test_explicit_ref_falls_back_to_branch() {
+        let resolved = resolve_with("main", true, &[RefKind::Branch]).unwrap();
+        assert_eq!(resolved.kind, RefKind::Branch);
+        assert!(!resolved.is_release());
+
+        assert!(resolve_with("nope", true, &[]).is_err());
+    }
+
+    #[test]
+    fn test_prerelease_tags_are_not_releases() {
+        let resolved = resolve_with("v2.0.0-rc.1", true, &[RefKind::Tag]).unwrap();
+        assert!(!resolved.is_release());
+        assert!(!is_prerelease("v2.0.0"));
+        assert!(!is_prerelease("feature-branch"));
+    }
+
+    #[test]
+    fn test_verify_version() {
+        let tag = ResolvedRef {
+            name: "v1.2.0".to_string(),
+            kind: RefKind::Tag,
+        };
+        assert!(verify_version(&tag, Some("1.2.0")).is_ok());
+        assert!(verify_version(&tag, Some("1.3.0")).is_err());
+        assert!(verify_version(&tag, None).is_err());
+
+        let branch = ResolvedRef {
+            name: "main".to_string(),
+            kind: RefKind::Branch,
+        };
+        assert!(verify_version(&branch, None).is_ok());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::config::{validate_config, ProjectConfig};
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = ".demongrep.toml";
+
+/// `.demongrep.toml` written by `config init`: every setting at its default
+const CONFIG_TEMPLATE: &str = r#"# demongrep configuration
+#
+# Read from .demongrep.toml in the project (or the current directory), falling
+# back to ~/.demongrep/config.toml. Every key is optional; the values below are
+# the defaults. Command-line flags override them.
+
+[embedding]
+# Embedding backend: "local" (ONNX models via fastembed) or "remote" (an
+# OpenAI-compatible /v1/embeddings endpoint)
+provider = "local"
+# Local model (see `demongrep models`), or the model sent to the remote endpoint
+model = "minilm-l6-q"
+# Texts embedded per batch (per request for the remote provider)
+batch_size = 32
+# In-memory embedding cache size in MB
+cache_size_mb = 512
+# Remote provider only: endpoint and embedding dimensions
+# base_url = "https://api.openai.com"
+# dimensions = 1536
+# Environment variable holding the remote API key
+api_key_env = "OPENAI_API_KEY"
+# Reject `index --model` with another model, and warn on `search --model`
+pinned = false
+# Load local models from this directory (same layout as the model cache) and
+# never download them
+# model_dir = "/opt/demongrep/models"
+
+[chunking]
+# Maximum chunk size in lines and characters; larger definitions are split
+max_lines = 75
+max_chars = 2000
+# Lines shared between consecutive pieces of a split chunk
+overlap_lines = 10
+# Lines of surrounding code stored with each chunk
+context_lines = 3
+# Don't index definitions shorter than this many lines / characters on their
+# own (0 = disabled); they stay searchable through their enclosing chunk
+min_chunk_lines = 0
+min_chunk_chars = 0
+
+[indexing]
+# Probably generated or minified files (*.min.js, *.pb.go, very long lines, ...)
+# are skipped; .gitignore-style patterns of ones to index anyway
+allow_generated = []
+
+[search]
+# RRF k for fusing vector and full-text rankings (lower favors top ranks)
+rrf_k = 20.0
+# Reranker share of the blended score with --rerank (0 = pure RRF, 1 = pure reranker)
+rerank_weight = 0.575
+# Default number of results
+default_limit = 25
+# Local ONNX cross-encoder for --rerank instead of the built-in model
+# rerank_model_path = "models/my-reranker"
+# Documents scored per reranker call with --rerank (lower bounds memory)
+rerank_batch_size = 32
+# Rerank every search by default (`search --no-rerank` turns it off)
+rerank_enabled = false
+# Number of top results to rerank
+rerank_top = 50
+
+[fts]
+# Full-text term filters; changes apply after `demongrep clear` and `index`
+# Drop terms shorter than this many characters
+min_term_length = 1
+# Drop common filler words and ubiquitous keywords
+stop_words = false
+
+[database]
+# Maximum database size in GB
+max_size_gb = 10
+
+[server]
+# Port for `demongrep serve`
+port = 4444
+# File watcher debounce in milliseconds
+debounce_ms = 300
+# Seconds a `serve` or `mcp` search may run before it fails with a timeout
+request_timeout_secs = 30
+
+[mcp]
+# Most results one MCP search returns; larger `limit` requests are clamped
+max_results = 10
+
+[models]
+# Team names for models, accepted wherever --model is, e.g.
+# aliases = { "our-model" = "jina-code-1.5b" }
+aliases = {}
+"#;
+
+/// Write `.demongrep.toml` into `dir`, returning its path
+fn write_template(dir: &Path, force: bool) -> Result<PathBuf> {
+    let config_path = dir.join(CONFIG_FILE);
+    if config_path.exists() && !force {
+        return Err(anyhow!(
+            "{} already exists (use --force to overwrite it)",
+            config_path.display()
+        ));
+    }
+    std::fs::write(&config_path, CONFIG_TEMPLATE)?;
+    Ok(config_path)
+}
+
+/// Scaffold a commented `.demongrep.toml` in `path` (defaults to the current directory)
+pub fn init(path: Option<PathBuf>, force: bool) -> Result<()> {
+    let dir = path.unwrap_or_else(|| PathBuf::from("."));
+    let config_path = write_template(&dir, force)?;
+    println!("{} Wrote {}", "✅".green(), config_path.display());
+    println!("   Uncomment or edit the settings you want to change.");
+    Ok(())
+}
+
+/// Print the configuration that applies to `path` and where it came from
+pub fn show(path: Option<PathBuf>, json: bool) -> Result<()> {
+    let (config, source) = ProjectConfig::load_with_source(path.as_deref());
+
+    if json {
+        let output = serde_json::json!({
+            "source": source.map(|p| p.display().to_string()),
+            "config": config,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    match source {
+        Some(source) => println!("# Loaded from {}", source.display()),
+        None => println!("# No config file found; showing the defaults"),
+    }
+    print!("{}", toml::to_string_pretty(&config)?);
+    Ok(())
+}
+
+/// Strictly check a config file, failing if it has any problems
+pub fn validate(path: Option<PathBuf>) -> Result<()> {
+    let config_path = match path {
+        Some(path) if path.is_dir() => path.join(CONFIG_FILE),
+        Some(path) => path,
+        None => match ProjectConfig::load_with_source(None).1 {
+            Some(source) => source,
+            None => {
+                println!("No config file found; the defaults apply");
+                return Ok(());
+            }
+        },
+    };
+
+    let content = std::fs::read_to_string(&config_path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", config_path.display(), e))?;
+    let problems = validate_config(&content);
+    if problems.is_empty() {
+        println!("{} {} is valid", "✅".green(), config_path.display());
+        return Ok(());
+    }
+
+    println!("{}", config_path.display());
+    for problem in &problems {
+        println!("  {} {}", "❌".red(), problem);
+    }
+    Err(anyhow!(
+        "{} problem(s) in {}",
+        problems.len(),
+        config_path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_writes_default_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = write_template(dir.path(), false).unwrap();
+
+        let content = std::fs::read_to_string(config_path).unwrap();
+        let config: ProjectConfig = toml::from_str(&content).unwrap();
+        assert_eq!(
+            toml::to_string(&config).unwrap(),
+            toml::to_string(&ProjectConfig::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_template_documents_every_setting() {
+        let defaults = toml::Value::try_from(ProjectConfig::default()).unwrap();
+        for (section, settings) in defaults.as_table().unwrap() {
+            assert!(CONFIG_TEMPLATE.contains(&format!("[{}]", section)));
+            for key in settings.as_table().unwrap().keys() {
+                assert!(
+                    CONFIG_TEMPLATE.contains(&format!("{} = ", key)),
+                    "{}.{} missing from the template",
+                    section,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_init_refuses_to_overwrite_without_force() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE), "[server]\nport = 1\n").unwrap();
+
+        let err = write_template(dir.path(), false).unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(CONFIG_FILE)).unwrap(),
+            "[server]\nport = 1\n"
+        );
+
+        write_template(dir.path(), true).unwrap();
+        let config = ProjectConfig::load(Some(dir.path()));
+        assert_eq!(config.server.port, 4444);
+    }
+
+    #[test]
+    fn test_template_is_valid() {
+        assert_eq!(validate_config(CONFIG_TEMPLATE), vec![]);
+    }
+}
@@ -27,10 +27,7 @@ pub fn resolve_project_path(project_path: Option<PathBuf>) -> Result<PathBuf> {
         None => env::current_dir().context("Failed to get current directory")?,
     };
 
-    let canonical = fs::canonicalize(&path)
-        .with_context(|| format!("Failed to resolve project path: {}", path.display()))?;
-
-    Ok(canonical)
+    crate::index::canonical_project_path(&path)
 }
 
 pub fn load_config(config_path: &Path) -> Result<Value> {
@@ -0,0 +1,34 @@
+use crate::chunker::ChunkKind;
+use anyhow::Result;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct KindEntry {
+    name: &'static str,
+    description: &'static str,
+    definition: bool,
+}
+
+pub fn run(json: bool) -> Result<()> {
+    let entries: Vec<KindEntry> = ChunkKind::all()
+        .iter()
+        .map(|kind| KindEntry {
+            name: kind.as_str(),
+            description: kind.description(),
+            definition: kind.is_definition(),
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    println!("Chunk kinds:");
+    for entry in &entries {
+        println!("  {:<10} {}", entry.name, entry.description);
+    }
+    println!();
+    println!("Use with search --kind <kind> or --exclude-kind <kind> (case-insensitive).");
+    Ok(())
+}
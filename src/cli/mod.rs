@@ -1,8 +1,15 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::chunker::ChunkKind;
+use crate::database::DatabaseScope;
 use crate::embed::ModelType;
+use crate::file::Language;
+use crate::index::{IndexOptions, IndexProfile};
+use crate::output::{ColorChoice, ProgressMode};
+use crate::search::{OpenFormat, PathBoost, SearchOptions, SnippetSize, SortOrder};
+use crate::vectordb::IndexMode;
 
 /// Fast, local semantic code search powered by Rust
 #[derive(Parser, Debug)]
@@ -24,18 +31,42 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub store: Option<String>,
 
-    /// Embedding model to use (e.g., minilm-l6-q, jina-v5-nano, jina-code-1.5b, mixedbread-ai/mxbai-embed-xsmall-v1)
-    /// Available: minilm-l6-q, jina-v5-nano, jina-code-1.5b, mixedbread-ai/mxbai-embed-xsmall-v1
+    /// Embedding model to use (run `demongrep models` to list available models)
     #[arg(long, global = true)]
     pub model: Option<String>,
+
+    /// When to use colors: always, auto (only on a terminal, respects NO_COLOR), or never
+    #[arg(long, global = true, default_value = "auto")]
+    pub color: String,
+
+    /// Embedding batch size, overriding DEMONGREP_BATCH_SIZE and the adaptive
+    /// per-model default (lower it on memory-constrained machines)
+    #[arg(long, global = true, value_name = "N")]
+    pub batch_size: Option<usize>,
+
+    /// Never download models; fail unless they're already in the local cache
+    /// (also enabled by DEMONGREP_OFFLINE=1)
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Directory to cache and load models from (overrides FASTEMBED_CACHE_DIR
+    /// and embedding.model_dir; must be writable)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub model_cache_dir: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Search the codebase using natural language
     Search {
-        /// Search query (e.g., "where do we handle authentication?")
-        query: String,
+        /// Search query (e.g., "where do we handle authentication?"); "-"
+        /// reads it from stdin
+        #[arg(required_unless_present = "query_file")]
+        query: Option<String>,
+
+        /// Read the query from a file (e.g. a pasted error message or snippet)
+        #[arg(long, value_name = "PATH", conflicts_with = "query")]
+        query_file: Option<PathBuf>,
 
         /// Offset for pagination (skip first N results)
         #[arg(long, default_value = "0")]
@@ -65,14 +96,26 @@ pub enum Commands {
         #[arg(short, long)]
         sync: bool,
 
-        /// Output JSON for agents
+        /// Output JSON for agents (compact, one line)
         #[arg(long)]
         json: bool,
 
+        /// Output indented JSON for reading (implies --json)
+        #[arg(long)]
+        json_pretty: bool,
+
         /// Path to search in (defaults to current directory)
         #[arg(long)]
         path: Option<PathBuf>,
 
+        /// Search only the project's local database
+        #[arg(long, conflicts_with = "global_only")]
+        local_only: bool,
+
+        /// Search only the project's global database (from `index --global`)
+        #[arg(long)]
+        global_only: bool,
+
         /// Use vector-only search (disable hybrid FTS)
         #[arg(long)]
         vector_only: bool,
@@ -81,22 +124,76 @@ pub enum Commands {
         #[arg(long, default_value = "20")]
         rrf_k: f32,
 
-        /// Enable neural reranking for better accuracy (uses Jina Reranker)
+        /// Enable neural reranking for better accuracy (uses Jina Reranker;
+        /// defaults to search.rerank_enabled from config)
         #[arg(long)]
         rerank: bool,
 
-        /// Number of top results to rerank (default 50)
-        #[arg(long, default_value = "50")]
-        rerank_top: usize,
+        /// Disable neural reranking even if search.rerank_enabled is set
+        #[arg(long, conflicts_with = "rerank")]
+        no_rerank: bool,
+
+        /// Number of top results to rerank (defaults to search.rerank_top from
+        /// config, 50)
+        #[arg(long)]
+        rerank_top: Option<usize>,
+
+        /// Weight of the reranker score in the blend with RRF, clamped to [0, 1]
+        /// (0 = pure RRF, 1 = pure reranker; defaults to search.rerank_weight from config)
+        #[arg(long)]
+        rerank_weight: Option<f32>,
+
+        /// Rerank with a local ONNX cross-encoder (directory with model.onnx or
+        /// onnx/model.onnx plus tokenizer files, or the .onnx file) instead of
+        /// the built-in model; defaults to search.rerank_model_path from config
+        #[arg(long, value_name = "PATH")]
+        rerank_model_path: Option<PathBuf>,
+
+        /// Documents the reranker scores per model call; lower bounds memory
+        /// with long chunks or a large --rerank-top (defaults to
+        /// search.rerank_batch_size from config)
+        #[arg(long, value_name = "N")]
+        rerank_batch_size: Option<usize>,
+
+        /// Rerank with cached reranker scores only, never loading the model;
+        /// uncached results keep their fusion score (implies --rerank)
+        #[arg(long)]
+        rerank_cache_only: bool,
+
+        /// Boost results under a path prefix by a score factor, e.g. "src/core:1.5"
+        /// (repeatable; a soft preference, unlike --filter-path)
+        #[arg(long, value_name = "PREFIX:FACTOR")]
+        boost: Vec<String>,
 
         /// Filter results to files under this path (e.g., "src/")
         #[arg(long)]
         filter_path: Option<String>,
 
-        /// Filter results by chunk kind (e.g., "Function", "Struct", "Trait", "Method")
+        /// Filter results by chunk kind (e.g., "Function", "Struct", "Trait", "Method";
+        /// `demongrep kinds` lists them all)
         #[arg(long)]
         kind: Option<String>,
 
+        /// Drop results of these chunk kinds, e.g. "Block,Other" (repeatable or
+        /// comma-separated; wins over --kind)
+        #[arg(long, value_delimiter = ',', value_name = "KIND")]
+        exclude_kind: Vec<String>,
+
+        /// Only show definitions (functions, methods, types, traits, impls,
+        /// modules) that have a signature
+        #[arg(long)]
+        definitions_only: bool,
+
+        /// Only show results from files in this language, by name or extension
+        /// (e.g. "rust", "python", "ts")
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Show the symbol path of each result, e.g. crate::auth::Session::verify
+        /// (enclosing modules, types and functions)
+        #[arg(long)]
+        highlight_context: bool,
+
         /// Optimized output for coding agents (combines --json --quiet --sync --content -m 10)
         #[arg(long)]
         agent: bool,
@@ -105,15 +202,63 @@ pub enum Commands {
         #[arg(long)]
         code: bool,
 
+        /// Embed the query exactly as typed, skipping model-specific query prefixes
+        /// (may reduce accuracy for instruction-tuned models like mxbai or jina)
+        #[arg(long, conflicts_with = "code")]
+        verbatim: bool,
+
         /// Show score breakdown per result (vector, FTS, RRF, rerank scores)
         #[arg(long)]
         explain: bool,
+
+        /// Location format for results: grep (path:line), vscode, or a custom
+        /// template using {path}, {line} and {end_line}
+        #[arg(long, default_value = "grep")]
+        open_format: String,
+
+        /// Don't highlight matched query terms in snippets (also disabled without colors)
+        #[arg(long)]
+        no_highlight: bool,
+
+        /// Bias results toward code related to this file (e.g. the one open in
+        /// an editor); the query stays dominant
+        #[arg(long, value_name = "PATH")]
+        context_file: Option<PathBuf>,
+
+        /// Return only the single best result, as fast as possible (no reranking,
+        /// small candidate pool); with --json, prints just that result object
+        #[arg(long)]
+        first: bool,
+
+        /// Result order: relevance, or recency (most recently modified files
+        /// first among the matches)
+        #[arg(long, default_value = "relevance", value_name = "ORDER")]
+        sort: String,
+
+        /// Lines of each result shown in the snippet (without --content)
+        #[arg(long, default_value = "3", value_name = "N")]
+        snippet_lines: usize,
+
+        /// Characters of the snippet shown before it's cut off (0 = no limit)
+        #[arg(long, default_value = "100", value_name = "N")]
+        snippet_chars: usize,
+
+        /// Don't warn when files changed since the index was built (the
+        /// warning suggests --sync)
+        #[arg(long)]
+        no_sync_warn: bool,
+
+        /// Print only the number of results and of files they're in (with
+        /// --json, as {"count", "files"})
+        #[arg(long, conflicts_with_all = ["compact", "first"])]
+        count: bool,
     },
 
     /// Index the repository
     Index {
-        /// Path to index (defaults to current directory)
-        path: Option<PathBuf>,
+        /// Path to index (defaults to current directory). Several paths index
+        /// just those roots of the current directory into one database
+        paths: Vec<PathBuf>,
 
         /// Show what would be indexed without actually indexing
         #[arg(long)]
@@ -126,6 +271,72 @@ pub enum Commands {
         /// Index to global database in home directory instead of local .demongrep/store
         #[arg(short = 'g', long)]
         global: bool,
+
+        /// Follow symbolic links while walking (cycles are detected and skipped)
+        #[arg(long)]
+        follow_symlinks: bool,
+
+        /// Index hidden files and directories (e.g. .github/, .config/);
+        /// gitignore and the built-in excludes still apply
+        #[arg(long)]
+        include_hidden: bool,
+
+        /// Only index files up to N directory levels below the root (1 = files
+        /// directly in it); deeper directories are skipped
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(usize).range(1..))]
+        max_depth: Option<usize>,
+
+        /// Vector index strategy: auto, exact (brute force), or approx (ANN trees).
+        /// Remembered per database; defaults to auto
+        #[arg(long)]
+        index_mode: Option<String>,
+
+        /// Truncate embeddings to N dimensions (Matryoshka models only, e.g. mxbai-xsmall).
+        /// Recorded in the index so searches use the same truncation
+        #[arg(long, value_name = "N")]
+        truncate_dims: Option<usize>,
+
+        /// Print a per-language summary of the chunks created in this run, plus
+        /// the largest files and the files with the most chunks
+        #[arg(long)]
+        stats: bool,
+
+        /// Skip building the full-text (BM25) index; searches use vector similarity only.
+        /// Remembered per database
+        #[arg(long)]
+        no_fts: bool,
+
+        /// Also index doc comments as separate `Doc` chunks linked to their
+        /// definition, for "how do I use X" queries. Remembered per database
+        #[arg(long)]
+        index_docs: bool,
+
+        /// Walk, chunk and embed into the embedding cache without building an
+        /// index (e.g. to warm a CI cache); a later `index` embeds from the cache
+        #[arg(long, conflicts_with = "dry_run")]
+        embed_only: bool,
+
+        /// Progress output: bar, plain (a line every 10%, for CI logs) or none.
+        /// Defaults to bar on a terminal and plain otherwise
+        #[arg(long, value_name = "MODE")]
+        progress: Option<String>,
+
+        /// Resource profile: memory (small embedding batches, a group of files
+        /// at a time with the in-memory embedding cache dropped after each;
+        /// slower) or speed (large batches; more memory). Only affects
+        /// indexing. --batch-size still wins
+        #[arg(long, value_name = "PROFILE")]
+        profile: Option<String>,
+    },
+
+    /// Re-index a single file (delete its chunks, re-chunk, re-embed) without a full sync
+    ReindexFile {
+        /// File to re-index
+        file: PathBuf,
+
+        /// Project path whose database holds the file (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
     },
 
     /// Run a background server with live file watching
@@ -136,6 +347,25 @@ pub enum Commands {
 
         /// Path to serve (defaults to current directory)
         path: Option<PathBuf>,
+
+        /// Index and watch hidden files and directories (e.g. .github/, .config/)
+        #[arg(long)]
+        include_hidden: bool,
+
+        /// File watcher debounce in milliseconds, overriding server.debounce_ms
+        /// from config (lower = snappier, higher = fewer batches during large checkouts)
+        #[arg(long, value_name = "N")]
+        debounce_ms: Option<u64>,
+
+        /// Index (or incrementally sync an existing index) before serving
+        /// searches; /search returns 503 until it's done
+        #[arg(long)]
+        reindex_on_start: bool,
+
+        /// Seconds a search may take before /search returns 504, overriding
+        /// server.request_timeout_secs from config
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
     },
 
     /// List all indexed repositories
@@ -145,6 +375,25 @@ pub enum Commands {
     Stats {
         /// Path to show stats for (defaults to current directory)
         path: Option<PathBuf>,
+
+        /// Output JSON for scripts and dashboards
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Cross-check the vector store, FTS index and file metadata for inconsistencies
+    Verify {
+        /// Project path (defaults to current directory)
+        path: Option<PathBuf>,
+
+        /// Rebuild the FTS and vector indexes from the stored chunks, dropping
+        /// files with missing chunks so the next sync re-indexes them
+        #[arg(long)]
+        fix: bool,
+
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Clear the vector database
@@ -156,7 +405,7 @@ pub enum Commands {
         #[arg(short = 'y', long)]
         yes: bool,
 
-        /// Project name or path to clear (looks up in global projects.json)
+        /// Project name or path to clear (resolved via the global projects.json registry)
         #[arg(short = 'p', long)]
         project: Option<String>,
     },
@@ -171,9 +420,44 @@ pub enum Commands {
         yes: bool,
     },
 
+    /// Show how the full-text tokenizer splits text into terms (debugging FTS matches)
+    #[command(hide = true)]
+    Tokenize {
+        /// Text to tokenize (a query or a snippet of indexed content)
+        text: String,
+
+        /// Project whose index term filters to apply (defaults to current directory)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Create or inspect the project configuration (.demongrep.toml)
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
     /// Check installation health
     Doctor,
 
+    /// List available embedding models
+    Models {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List the chunk kinds accepted by search --kind and --exclude-kind
+    Kinds {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Download embedding models
     Setup {
         /// Model to download (defaults to current default embedding model)
@@ -185,6 +469,15 @@ pub enum Commands {
     Mcp {
         /// Path to project (defaults to current directory)
         path: Option<PathBuf>,
+
+        /// Print the exposed tools and exit without starting the server
+        #[arg(long)]
+        list_tools: bool,
+
+        /// Seconds a search tool call may take before it fails, overriding
+        /// server.request_timeout_secs from config
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
     },
 
     /// Configure Claude Code MCP integration
@@ -226,9 +519,14 @@ pub enum Commands {
 
     /// Install demongrep coding-agent skills into .agents skill folders
     AddSkills {
-        /// Skill name to install
-        #[arg(long, default_value = "demongrep")]
-        skill: String,
+        /// Skill(s) to install (repeat or comma-separate for several)
+        #[arg(
+            long = "skill",
+            value_delimiter = ',',
+            default_value = "demongrep",
+            value_name = "NAME"
+        )]
+        skills: Vec<String>,
 
         /// Git ref (tag/branch) to install from (defaults to current demongrep version tag)
         #[arg(long = "ref")]
@@ -237,6 +535,10 @@ pub enum Commands {
         /// Destination skills directory (defaults to ~/.codex/skills)
         #[arg(long)]
         dest: Option<PathBuf>,
+
+        /// Install from a branch or pre-release tag without asking for confirmation
+        #[arg(long)]
+        allow_prerelease: bool,
     },
 
     /// Benchmark embedding models (compare performance, quality, and memory)
@@ -253,31 +555,116 @@ pub enum Commands {
         #[arg(long)]
         limit: Option<usize>,
 
-        /// Path to benchmark (defaults to current directory)
-        #[arg(long)]
+        /// Corpus directory to benchmark (defaults to current directory)
+        #[arg(long, visible_alias = "corpus")]
         path: Option<PathBuf>,
 
         /// Output file for markdown report (default: benchmark_results.md)
         #[arg(long)]
         output: Option<PathBuf>,
 
+        /// Also write one CSV row per model to this file (for spreadsheets)
+        #[arg(long, value_name = "PATH")]
+        csv: Option<PathBuf>,
+
         /// Output results as JSON instead of table
         #[arg(long)]
         json: bool,
+
+        /// JSON file of queries with expected path substrings (required outside the demongrep repo)
+        #[arg(long)]
+        queries: Option<PathBuf>,
+
+        /// Untimed runs of each timed step before measuring
+        #[arg(long, default_value = "0", value_name = "N")]
+        warmup_runs: usize,
+
+        /// Timed runs per step; timings are reported as mean ± standard deviation
+        #[arg(long, default_value = "1", value_name = "N")]
+        runs: usize,
+    },
+}
+
+impl Commands {
+    /// Project directory the command works on, if it names one
+    fn project_path(&self) -> Option<&Path> {
+        match self {
+            Commands::Search { path, .. }
+            | Commands::ReindexFile { path, .. }
+            | Commands::Serve { path, .. }
+            | Commands::Stats { path, .. }
+            | Commands::Verify { path, .. }
+            | Commands::Clear { path, .. }
+            | Commands::MigrateIndex { path, .. }
+            | Commands::Tokenize { path, .. }
+            | Commands::Mcp { path, .. } => path.as_deref(),
+            Commands::Index { paths, .. } if paths.len() == 1 => {
+                paths.first().map(PathBuf::as_path)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Write a commented .demongrep.toml with every setting at its default
+    Init {
+        /// Directory to write it to (defaults to current directory)
+        path: Option<PathBuf>,
+
+        /// Overwrite an existing .demongrep.toml
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Print the effective configuration and the file it was loaded from
+    Show {
+        /// Project path (defaults to current directory)
+        path: Option<PathBuf>,
+
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check a config file for unknown keys, type errors and invalid values
+    Validate {
+        /// Config file, or a directory containing .demongrep.toml (defaults
+        /// to the file `config show` would load)
+        path: Option<PathBuf>,
     },
 }
 
 pub async fn run() -> Result<()> {
     let cli = Cli::parse_from(rewrite_legacy_search_args(std::env::args().collect()));
 
-    // Parse model from CLI flag
-    let model_type = cli.model.as_ref().and_then(|m| ModelType::from_str(m));
+    // Configure colors before any command prints
+    let color = ColorChoice::parse(&cli.color).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown color mode: '{}'. Expected always, auto, or never",
+            cli.color
+        )
+    })?;
+    crate::output::set_color(color);
+
+    crate::embed::set_batch_size(cli.batch_size)?;
+    crate::embed::set_offline(cli.offline);
+    crate::embed::set_model_cache_dir(cli.model_cache_dir.clone())?;
+
+    // Resolve the project config once: model directory and [models] aliases
+    let project_config = crate::config::ProjectConfig::load(cli.command.project_path());
+    crate::embed::set_model_dir(project_config.embedding.model_dir.clone());
+
+    // Parse model from CLI flag, through the project config's [models] aliases
+    let models = project_config.models;
+    let model_type = cli.model.as_ref().and_then(|m| models.resolve(m));
     if cli.model.is_some() && model_type.is_none() {
-        return Err(anyhow::anyhow!(
-            "Unknown model: '{}'. Available models:\n  \
-             minilm-l6-q, jina-v5-nano, jina-code-1.5b, mixedbread-ai/mxbai-embed-xsmall-v1",
-            cli.model.as_ref().unwrap()
-        ));
+        return Err(crate::error::DemongrepError::UnknownModel {
+            model: cli.model.clone().unwrap_or_default(),
+            available: ModelType::available_names(),
+        }
+        .into());
     }
 
     // Set quiet mode if requested
@@ -288,6 +675,7 @@ pub async fn run() -> Result<()> {
     match cli.command {
         Commands::Search {
             query,
+            query_file,
             offset,
             max_results,
             per_file,
@@ -296,66 +684,304 @@ pub async fn run() -> Result<()> {
             compact,
             sync,
             json,
+            json_pretty,
             path,
+            local_only,
+            global_only,
             vector_only,
             rrf_k,
             rerank,
+            no_rerank,
             rerank_top,
+            rerank_weight,
+            rerank_model_path,
+            rerank_batch_size,
+            rerank_cache_only,
+            boost,
             filter_path,
             kind,
+            exclude_kind,
+            definitions_only,
+            language,
+            highlight_context,
             agent,
             code,
+            verbatim,
             explain,
+            open_format,
+            no_highlight,
+            context_file,
+            first,
+            sort,
+            snippet_lines,
+            snippet_chars,
+            no_sync_warn,
+            count,
         } => {
-            // --agent mode: override flags for optimized agent output
-            let (max_results, content, sync, json) = if agent {
+            let query = read_query(query, query_file, std::io::stdin().lock(), verbatim)?;
+            let sort = SortOrder::parse(&sort).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown sort order: '{}'. Expected relevance or recency",
+                    sort
+                )
+            })?;
+            let open_format = OpenFormat::parse(&open_format).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown open format: '{}'. Expected grep, vscode, or a template like '{{path}}:{{line}}'",
+                    open_format
+                )
+            })?;
+
+            let search_config = crate::config::ProjectConfig::load(path.as_deref()).search;
+            let rerank_weight = rerank_weight
+                .unwrap_or(search_config.rerank_weight)
+                .clamp(0.0, 1.0);
+            // A configured path is relative to the project root
+            let rerank_model_path = rerank_model_path.or_else(|| {
+                search_config
+                    .rerank_model_path
+                    .map(|p| path.as_deref().unwrap_or(std::path::Path::new(".")).join(p))
+            });
+            let rerank_batch_size = rerank_batch_size
+                .unwrap_or(search_config.rerank_batch_size)
+                .max(1);
+            let rerank = search_config.rerank(match (rerank, no_rerank) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            });
+            let rerank_top = rerank_top.unwrap_or(search_config.rerank_top);
+
+            let boosts = boost
+                .iter()
+                .map(|b| {
+                    PathBoost::parse(b).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Invalid boost: '{}'. Expected <prefix>:<factor>, e.g. 'src/core:1.5'",
+                            b
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let parse_kind = |k: &str| {
+                ChunkKind::from_str(k).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown chunk kind: '{}'. Expected one of: {} (see `demongrep kinds`)",
+                        k,
+                        ChunkKind::all()
+                            .iter()
+                            .map(ChunkKind::as_str)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })
+            };
+            let kind = kind.as_deref().map(parse_kind).transpose()?;
+            let exclude_kinds = exclude_kind
+                .iter()
+                .map(|k| parse_kind(k))
+                .collect::<Result<Vec<_>>>()?;
+
+            let language = language
+                .map(|l| {
+                    Language::parse(&l).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unknown language: '{}'. Expected one of: {}",
+                            l,
+                            Language::all()
+                                .iter()
+                                .map(|language| language.name().to_lowercase())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    })
+                })
+                .transpose()?;
+
+            let scope = if local_only {
+                DatabaseScope::Local
+            } else if global_only {
+                DatabaseScope::Global
+            } else {
+                DatabaseScope::All
+            };
+
+            // --agent mode: override flags for optimized agent output (compact JSON)
+            let (max_results, content, sync, json, json_pretty) = if agent {
                 crate::output::set_quiet(true);
-                (10, true, true, true)
+                (10, true, true, true, false)
             } else {
-                (max_results, content, sync, json)
+                (max_results, content, sync, json || json_pretty, json_pretty)
             };
 
-            // Auto-enable quiet mode for JSON output
-            if json {
+            // Auto-enable quiet mode for JSON and --count output
+            if json || count {
                 crate::output::set_quiet(true);
             }
-            crate::search::search(
-                &query,
+            let options = SearchOptions {
                 offset,
                 max_results,
                 per_file,
                 content,
                 scores,
                 compact,
-                sync,
                 json,
-                path,
+                json_pretty,
+                highlight_context,
+                explain,
+                open_format,
+                highlight: !no_highlight,
+                context_file,
+                first,
+                sort,
+                snippet: SnippetSize {
+                    lines: snippet_lines,
+                    max_chars: snippet_chars,
+                },
+                count,
+                sync,
+                sync_warn: !no_sync_warn,
+                scope,
                 filter_path,
-                model_type,
-                vector_only,
+                kind_filter: kind,
+                exclude_kinds,
+                definitions_only,
+                language_filter: language,
+                model_override: model_type,
+                vector_only_mode: vector_only,
                 rrf_k,
-                rerank,
+                rerank: rerank || rerank_cache_only,
                 rerank_top,
-                kind,
-                code,
-                explain,
-            )
-            .await
+                rerank_weight,
+                rerank_model_path,
+                rerank_batch_size,
+                rerank_cache_only,
+                boosts,
+                code_mode: code,
+                verbatim,
+            };
+            crate::search::search(&query, path, options).await
         }
         Commands::Index {
-            path,
+            paths,
             dry_run,
             force,
             global,
-        } => crate::index::index(path, dry_run, force, global, model_type).await,
-        Commands::Serve { port, path } => crate::server::serve(port, path).await,
+            follow_symlinks,
+            include_hidden,
+            max_depth,
+            index_mode,
+            truncate_dims,
+            stats,
+            no_fts,
+            index_docs,
+            embed_only,
+            progress,
+            profile,
+        } => {
+            if let Some(progress) = progress {
+                let mode = ProgressMode::parse(&progress).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown progress mode: '{}'. Expected one of: bar, plain, none",
+                        progress
+                    )
+                })?;
+                crate::output::set_progress(mode);
+            }
+            let index_mode = index_mode
+                .map(|m| {
+                    IndexMode::parse(&m).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unknown index mode: '{}'. Expected one of: auto, exact, approx",
+                            m
+                        )
+                    })
+                })
+                .transpose()?;
+            let profile = match profile {
+                Some(profile) => IndexProfile::parse(&profile).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown profile: '{}'. Expected one of: default, memory, speed",
+                        profile
+                    )
+                })?,
+                None => IndexProfile::default(),
+            };
+            if cli.batch_size.is_none() {
+                crate::embed::set_batch_size(profile.batch_size())?;
+            }
+            // Several paths are roots within the current directory's project
+            let (path, roots) = if paths.len() > 1 {
+                (None, paths)
+            } else {
+                (paths.into_iter().next(), Vec::new())
+            };
+            let options = IndexOptions {
+                roots,
+                dry_run,
+                force,
+                global,
+                follow_symlinks,
+                include_hidden,
+                max_depth,
+                index_mode,
+                model: model_type,
+                truncate_dims,
+                show_stats: stats,
+                no_fts,
+                index_docs,
+                embed_only,
+                profile,
+            };
+            crate::index::index(path, options).await
+        }
+        Commands::ReindexFile { file, path } => crate::index::reindex_file(file, path).await,
+        Commands::Serve {
+            port,
+            path,
+            include_hidden,
+            debounce_ms,
+            reindex_on_start,
+            timeout,
+        } => {
+            crate::server::serve(
+                port,
+                path,
+                include_hidden,
+                debounce_ms,
+                reindex_on_start,
+                timeout,
+            )
+            .await
+        }
         Commands::List => crate::index::list().await,
-        Commands::Stats { path } => crate::index::stats(path).await,
+        Commands::Stats { path, json } => crate::index::stats(path, json).await,
+        Commands::Verify { path, fix, json } => crate::index::verify(path, fix, json).await,
         Commands::Clear { path, yes, project } => crate::index::clear(path, yes, project).await,
         Commands::MigrateIndex { path, yes } => crate::index::migrate_index(path, yes).await,
+        Commands::Tokenize { text, path, json } => crate::cli::tokenize::run(&text, path, json),
+        Commands::Config { action } => match action {
+            ConfigAction::Init { path, force } => crate::cli::config::init(path, force),
+            ConfigAction::Show { path, json } => crate::cli::config::show(path, json),
+            ConfigAction::Validate { path } => crate::cli::config::validate(path),
+        },
         Commands::Doctor => crate::cli::doctor::run().await,
+        Commands::Models { json } => crate::cli::models::run(json).await,
+        Commands::Kinds { json } => crate::cli::kinds::run(json),
         Commands::Setup { model } => crate::cli::setup::run(model).await,
-        Commands::Mcp { path } => crate::mcp::run_mcp_server(path).await,
+        Commands::Mcp {
+            path,
+            list_tools,
+            timeout,
+        } => {
+            if list_tools {
+                crate::mcp::print_tools();
+                Ok(())
+            } else {
+                crate::mcp::run_mcp_server(path, timeout).await
+            }
+        }
         Commands::InstallClaudeCode {
             global,
             project_path,
@@ -370,21 +996,70 @@ pub async fn run() -> Result<()> {
             dry_run,
         } => crate::cli::install_opencode::run(project_path, dry_run),
         Commands::AddSkills {
-            skill,
+            skills,
             ref_name,
             dest,
-        } => crate::cli::add_skills::run(skill, ref_name, dest),
+            allow_prerelease,
+        } => crate::cli::add_skills::run(skills, ref_name, dest, allow_prerelease),
         Commands::Bench {
             models,
             profile,
             limit,
             path,
             output,
+            csv,
             json,
-        } => crate::bench::bench(models, profile, limit, path, output, json).await,
+            queries,
+            warmup_runs,
+            runs,
+        } => {
+            let runs = crate::bench::BenchRuns {
+                warmup: warmup_runs,
+                measured: runs,
+            };
+            crate::bench::bench(
+                models, profile, limit, path, output, csv, json, queries, runs,
+            )
+            .await
+        }
     }
 }
 
+/// Resolve the search query: the argument itself, stdin for "-", or --query-file
+///
+/// Text read from stdin or a file keeps its lines and indentation; only the
+/// surrounding whitespace is trimmed, or with --verbatim just the final line
+/// ending that shells and editors add. Empty queries are left to
+/// `search::validate_query`.
+fn read_query(
+    query: Option<String>,
+    query_file: Option<PathBuf>,
+    mut stdin: impl std::io::Read,
+    verbatim: bool,
+) -> Result<String> {
+    let text = match (query, query_file) {
+        (_, Some(file)) => std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read query file {}", file.display()))?,
+        (Some(query), None) if query == "-" => {
+            let mut text = String::new();
+            stdin
+                .read_to_string(&mut text)
+                .context("Failed to read query from stdin")?;
+            text
+        }
+        (Some(query), None) => return Ok(query),
+        (None, None) => anyhow::bail!("No search query given"),
+    };
+
+    let query = if verbatim {
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+        text.strip_suffix('\r').unwrap_or(text)
+    } else {
+        text.trim()
+    };
+    Ok(query.to_string())
+}
+
 fn is_known_subcommand(arg: &str) -> bool {
     matches!(
         arg,
@@ -393,9 +1068,15 @@ fn is_known_subcommand(arg: &str) -> bool {
             | "serve"
             | "list"
             | "stats"
+            | "verify"
             | "clear"
             | "migrate-index"
+            | "reindex-file"
+            | "tokenize"
+            | "config"
             | "doctor"
+            | "models"
+            | "kinds"
             | "setup"
             | "mcp"
             | "install-claude-code"
@@ -434,7 +1115,8 @@ fn rewrite_legacy_search_args(mut args: Vec<String>) -> Vec<String> {
 
 #[cfg(test)]
 mod cli_args_tests {
-    use super::rewrite_legacy_search_args;
+    use super::{read_query, rewrite_legacy_search_args};
+    use std::path::PathBuf;
 
     #[test]
     fn rewrites_legacy_query_only() {
@@ -465,6 +1147,54 @@ mod cli_args_tests {
         );
     }
 
+    #[test]
+    fn model_aliases_come_from_the_command_project() {
+        use clap::Parser;
+
+        let project = |args: &[&str]| {
+            super::Cli::try_parse_from(args)
+                .unwrap()
+                .command
+                .project_path()
+                .map(|path| path.to_path_buf())
+        };
+        assert_eq!(
+            project(&["demongrep", "search", "q", "--path", "/work/app"]),
+            Some(PathBuf::from("/work/app"))
+        );
+        assert_eq!(
+            project(&["demongrep", "index", "/work/app"]),
+            Some(PathBuf::from("/work/app"))
+        );
+        // Several index paths are roots of the current directory's project
+        assert_eq!(project(&["demongrep", "index", "src", "lib"]), None);
+        assert_eq!(project(&["demongrep", "doctor"]), None);
+    }
+
+    #[test]
+    fn verbatim_conflicts_with_code() {
+        use clap::Parser;
+
+        let parse = |args: &[&str]| super::Cli::try_parse_from(args).map(|_| ());
+        assert!(parse(&["demongrep", "search", "q", "--verbatim"]).is_ok());
+        assert!(parse(&["demongrep", "search", "q", "--code"]).is_ok());
+        assert!(parse(&["demongrep", "search", "q", "--code", "--verbatim"]).is_err());
+    }
+
+    #[test]
+    fn keeps_every_subcommand() {
+        use clap::CommandFactory;
+
+        let cli = super::Cli::command();
+        for command in cli.get_subcommands().filter(|c| c.get_name() != "help") {
+            let out = rewrite_legacy_search_args(vec![
+                "demongrep".to_string(),
+                command.get_name().to_string(),
+            ]);
+            assert_eq!(out[1], command.get_name());
+        }
+    }
+
     #[test]
     fn keeps_normal_subcommand() {
         let out = rewrite_legacy_search_args(vec![
@@ -481,12 +1211,63 @@ mod cli_args_tests {
             ]
         );
     }
+
+    #[test]
+    fn reads_multi_line_query_from_stdin() {
+        let piped = "error[E0382]: borrow of moved value: `config`\n  --> src/main.rs:12:5\n";
+        let stdin = || std::io::Cursor::new(piped);
+
+        let query = read_query(Some("-".to_string()), None, stdin(), false).unwrap();
+        assert_eq!(
+            query,
+            "error[E0382]: borrow of moved value: `config`\n  --> src/main.rs:12:5"
+        );
+
+        // --verbatim keeps everything but the final newline
+        let piped_indented = "    let x = 1;\n    let y = 2;  \n";
+        let query = read_query(
+            Some("-".to_string()),
+            None,
+            std::io::Cursor::new(piped_indented),
+            true,
+        )
+        .unwrap();
+        assert_eq!(query, "    let x = 1;\n    let y = 2;  ");
+
+        // A regular query argument never touches stdin
+        let query = read_query(Some("auth".to_string()), None, stdin(), false).unwrap();
+        assert_eq!(query, "auth");
+
+        // An empty query is read as is and rejected by the search itself
+        let query = read_query(
+            Some("-".to_string()),
+            None,
+            std::io::Cursor::new("\n"),
+            false,
+        )
+        .unwrap();
+        assert!(crate::search::validate_query(&query).is_err());
+    }
+
+    #[test]
+    fn reads_query_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("query.txt");
+        std::fs::write(&file, "fn verify(token: &str)\n").unwrap();
+
+        let query = read_query(None, Some(file), std::io::empty(), false).unwrap();
+        assert_eq!(query, "fn verify(token: &str)");
+    }
 }
 
 mod add_skills;
+mod config;
 mod doctor;
 mod install_claude_code;
 mod install_codex;
 mod install_common;
 mod install_opencode;
+mod kinds;
+mod models;
 mod setup;
+mod tokenize;
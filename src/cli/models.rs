@@ -0,0 +1,52 @@
+use crate::embed::{FastEmbedder, ModelType};
+use anyhow::Result;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct ModelEntry {
+    short_name: &'static str,
+    name: &'static str,
+    dimensions: usize,
+    quantized: bool,
+    downloaded: bool,
+    default: bool,
+}
+
+pub async fn run(json: bool) -> Result<()> {
+    let entries: Vec<ModelEntry> = ModelType::all()
+        .iter()
+        .map(|model| ModelEntry {
+            short_name: model.short_name(),
+            name: model.name(),
+            dimensions: model.dimensions(),
+            quantized: model.is_quantized(),
+            downloaded: FastEmbedder::is_cached(*model),
+            default: *model == ModelType::default(),
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    println!("Available embedding models:");
+    for entry in &entries {
+        println!(
+            "  {:<16} {:>5}d  {:<9} {:<14} {}{}",
+            entry.short_name,
+            entry.dimensions,
+            if entry.quantized { "quantized" } else { "" },
+            if entry.downloaded {
+                "downloaded"
+            } else {
+                "not downloaded"
+            },
+            entry.name,
+            if entry.default { " (default)" } else { "" }
+        );
+    }
+    println!();
+    println!("Use --model <short_name> with index/search, or `demongrep setup --model <short_name>` to download.");
+    Ok(())
+}
@@ -1,16 +1,24 @@
+use crate::config::ProjectConfig;
 use crate::embed::{EmbeddingService, ModelType};
 use anyhow::{anyhow, Result};
 
-pub async fn run(model: Option<String>) -> Result<()> {
-    let model_type = match model {
-        Some(name) => ModelType::from_str(&name).ok_or_else(|| {
+/// Model to set up: `--model`, else the one `index` would use from the config
+fn setup_model(config: &ProjectConfig, model: Option<String>) -> Result<ModelType> {
+    match model {
+        Some(name) => config.models.resolve(&name).ok_or_else(|| {
             anyhow!(
-                "Unknown model '{}'. Use --model with one of: minilm-l6-q, jina-v5-nano, jina-code-1.5b, mixedbread-ai/mxbai-embed-xsmall-v1",
-                name
+                "Unknown model '{}'. Use --model with one of: {}",
+                name,
+                ModelType::available_names()
             )
-        })?,
-        None => ModelType::default(),
-    };
+        }),
+        None => crate::index::configured_model(config),
+    }
+}
+
+pub async fn run(model: Option<String>) -> Result<()> {
+    let config = ProjectConfig::load(None);
+    let model_type = setup_model(&config, model)?;
 
     println!("Setting up demongrep model cache...");
     println!("  Model: {}", model_type.name());
@@ -34,3 +42,28 @@ pub async fn run(model: Option<String>) -> Result<()> {
     println!("    2) demongrep search \"where do we handle authentication?\"");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_model_drives_setup() {
+        let config: ProjectConfig =
+            toml::from_str("[embedding]\nmodel = \"jina-v5-nano\"\n").unwrap();
+        assert_eq!(
+            setup_model(&config, None).unwrap(),
+            ModelType::JinaEmbeddingsV5TextNano
+        );
+        // --model still wins
+        assert_eq!(
+            setup_model(&config, Some("minilm-l6-q".to_string())).unwrap(),
+            ModelType::AllMiniLML6V2Q
+        );
+        // No config file: the built-in default
+        assert_eq!(
+            setup_model(&ProjectConfig::default(), None).unwrap(),
+            ModelType::default()
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::config::ProjectConfig;
+use crate::fts::{CodeTokenizer, FtsStore};
+use anyhow::Result;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize, PartialEq)]
+struct TermEntry {
+    term: String,
+    position: usize,
+    offset_from: usize,
+    offset_to: usize,
+}
+
+fn tokenize_terms(tokenizer: &CodeTokenizer, text: &str) -> Vec<TermEntry> {
+    tokenizer
+        .tokenize(text)
+        .into_iter()
+        .map(|token| TermEntry {
+            term: token.text,
+            position: token.position,
+            offset_from: token.offset_from,
+            offset_to: token.offset_to,
+        })
+        .collect()
+}
+
+pub fn run(text: &str, path: Option<PathBuf>, json: bool) -> Result<()> {
+    // Use the term filters the project's index was built with; without an
+    // index, the [fts] filters from .demongrep.toml that `index` would apply
+    let config = crate::index::get_search_db_paths(path.clone())
+        .unwrap_or_default()
+        .iter()
+        .find_map(|db_path| FtsStore::tokenizer_config(db_path))
+        .unwrap_or_else(|| ProjectConfig::load(path.as_deref()).fts);
+    let tokenizer = CodeTokenizer::with_filters(&config);
+    let terms = tokenize_terms(&tokenizer, text);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&terms)?);
+        return Ok(());
+    }
+
+    if terms.is_empty() {
+        println!("No terms.");
+        return Ok(());
+    }
+
+    println!("{:>4}  {:<12} {:<24} term", "pos", "offsets", "source");
+    for entry in &terms {
+        println!(
+            "{:>4}  {:<12} {:<24} {}",
+            entry.position,
+            format!("{}..{}", entry.offset_from, entry.offset_to),
+            &text[entry.offset_from..entry.offset_to],
+            entry.term
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_splits_camel_and_snake_case() {
+        let terms = tokenize_terms(&CodeTokenizer::default(), "HTTPServer process_data");
+        let summary: Vec<(&str, usize, usize)> = terms
+            .iter()
+            .map(|t| (t.term.as_str(), t.offset_from, t.offset_to))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("http", 0, 4),
+                ("server", 4, 10),
+                ("process", 11, 18),
+                ("data", 19, 23),
+            ]
+        );
+        assert_eq!(
+            terms.iter().map(|t| t.position).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+    }
+}
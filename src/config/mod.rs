@@ -5,9 +5,11 @@
 //!
 //! Priority: CLI flags > env vars > config file > defaults
 
+use crate::embed::ModelType;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Project-level configuration loaded from .demongrep.toml
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -15,28 +17,64 @@ use std::path::Path;
 pub struct ProjectConfig {
     pub embedding: EmbeddingConfig,
     pub chunking: ChunkingConfig,
+    pub indexing: IndexingConfig,
     pub search: SearchConfig,
+    pub fts: FtsConfig,
     pub database: DatabaseConfig,
     pub server: ServerConfig,
+    pub mcp: McpConfig,
+    pub models: ModelsConfig,
+}
+
+/// Where embeddings are computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// Local ONNX models via fastembed
+    #[default]
+    Local,
+    /// An OpenAI-compatible `/v1/embeddings` endpoint
+    Remote,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct EmbeddingConfig {
-    /// Model name (e.g., "minilm-l6-q", "jina-v5-nano", "jina-code-1.5b")
+    /// Embedding backend ("local" or "remote")
+    pub provider: EmbeddingProvider,
+    /// Model name (e.g., "minilm-l6-q", "jina-v5-nano", "jina-code-1.5b"), or
+    /// the model sent to the remote endpoint (e.g., "text-embedding-3-small")
     pub model: String,
     /// Batch size for embedding
     pub batch_size: usize,
     /// Cache size in MB
     pub cache_size_mb: usize,
+    /// Base URL of the remote endpoint (e.g., "https://api.openai.com")
+    pub base_url: Option<String>,
+    /// Environment variable holding the remote API key
+    pub api_key_env: String,
+    /// Dimensionality of the remote model's embeddings
+    pub dimensions: Option<usize>,
+    /// Pin the local model to `model`: `index --model` with another model is
+    /// rejected and `search --model` warns
+    pub pinned: bool,
+    /// Directory of pre-downloaded models, laid out like the model cache;
+    /// models are loaded from it and never downloaded
+    pub model_dir: Option<PathBuf>,
 }
 
 impl Default for EmbeddingConfig {
     fn default() -> Self {
         Self {
+            provider: EmbeddingProvider::Local,
             model: "minilm-l6-q".to_string(),
             batch_size: 32,
             cache_size_mb: 512,
+            base_url: None,
+            api_key_env: "OPENAI_API_KEY".to_string(),
+            dimensions: None,
+            pinned: false,
+            model_dir: None,
         }
     }
 }
@@ -52,6 +90,12 @@ pub struct ChunkingConfig {
     pub overlap_lines: usize,
     /// Lines of surrounding context to include
     pub context_lines: usize,
+    /// Definitions shorter than this many lines are not indexed on their own
+    /// (0 = disabled); they stay searchable through their enclosing chunk
+    pub min_chunk_lines: usize,
+    /// Chunks with fewer non-whitespace-trimmed characters than this are
+    /// treated the same way (0 = disabled)
+    pub min_chunk_chars: usize,
 }
 
 impl Default for ChunkingConfig {
@@ -61,10 +105,20 @@ impl Default for ChunkingConfig {
             max_chars: 2000,
             overlap_lines: 10,
             context_lines: 3,
+            min_chunk_lines: 0,
+            min_chunk_chars: 0,
         }
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndexingConfig {
+    /// `.gitignore`-style patterns (relative to the project root) of files to
+    /// index even though they look generated or minified
+    pub allow_generated: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct SearchConfig {
@@ -74,6 +128,22 @@ pub struct SearchConfig {
     pub rerank_weight: f32,
     /// Default maximum results
     pub default_limit: usize,
+    /// Local ONNX cross-encoder to rerank with instead of the built-in model
+    /// (directory or `.onnx` file, relative to the project root)
+    pub rerank_model_path: Option<PathBuf>,
+    /// Documents the reranker scores per model call
+    pub rerank_batch_size: usize,
+    /// Rerank every search unless `--no-rerank` is given
+    pub rerank_enabled: bool,
+    /// Number of top results to rerank
+    pub rerank_top: usize,
+}
+
+impl SearchConfig {
+    /// Whether to rerank: `--rerank` / `--no-rerank` when given, else `rerank_enabled`
+    pub fn rerank(&self, flag: Option<bool>) -> bool {
+        flag.unwrap_or(self.rerank_enabled)
+    }
 }
 
 impl Default for SearchConfig {
@@ -82,6 +152,33 @@ impl Default for SearchConfig {
             rrf_k: 20.0,
             rerank_weight: 0.575,
             default_limit: 25,
+            rerank_model_path: None,
+            rerank_batch_size: 32,
+            rerank_enabled: false,
+            rerank_top: 50,
+        }
+    }
+}
+
+/// Term filters applied by the full-text tokenizer
+///
+/// Recorded in the FTS index when it is created, so changes only take effect
+/// after `demongrep clear` and a fresh `index`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FtsConfig {
+    /// Drop terms shorter than this many characters (e.g. 2 drops loop
+    /// variables like `i`; 1 keeps everything)
+    pub min_term_length: usize,
+    /// Drop common filler words and ubiquitous keywords (`the`, `a`, `let`, ...)
+    pub stop_words: bool,
+}
+
+impl Default for FtsConfig {
+    fn default() -> Self {
+        Self {
+            min_term_length: 1,
+            stop_words: false,
         }
     }
 }
@@ -106,6 +203,8 @@ pub struct ServerConfig {
     pub port: u16,
     /// File watcher debounce in milliseconds
     pub debounce_ms: u64,
+    /// Seconds a `serve` or `mcp` search may take before it is abandoned
+    pub request_timeout_secs: u64,
 }
 
 impl Default for ServerConfig {
@@ -113,39 +212,68 @@ impl Default for ServerConfig {
         Self {
             port: 4444,
             debounce_ms: 300,
+            request_timeout_secs: 30,
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct McpConfig {
+    /// Most results a single MCP search returns, whatever `limit` asks for
+    pub max_results: usize,
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self { max_results: 10 }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelsConfig {
+    /// Team names for embedding models, e.g. `"our-model" = "jina-code-1.5b"`
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl ModelsConfig {
+    /// Resolve a `--model` name, consulting the aliases before the built-in names
+    pub fn resolve(&self, name: &str) -> Option<ModelType> {
+        let target = self
+            .aliases
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+            .map_or(name, |(_, target)| target.as_str());
+        ModelType::from_str(target)
+    }
+}
+
 impl ProjectConfig {
     /// Load config with priority: project-local > CWD > global > defaults
     pub fn load(project_path: Option<&Path>) -> Self {
-        // 1. Try project-local .demongrep.toml
-        if let Some(path) = project_path {
-            let config_path = path.join(".demongrep.toml");
-            if let Ok(config) = Self::load_from_file(&config_path) {
-                return config;
-            }
-        }
-
-        // 2. Try CWD
-        if let Ok(cwd) = std::env::current_dir() {
-            let config_path = cwd.join(".demongrep.toml");
-            if let Ok(config) = Self::load_from_file(&config_path) {
-                return config;
-            }
-        }
+        Self::load_with_source(project_path).0
+    }
 
-        // 3. Try global ~/.demongrep/config.toml
-        if let Some(home) = dirs::home_dir() {
-            let config_path = home.join(".demongrep").join("config.toml");
+    /// Like [`ProjectConfig::load`], also returning the file the config came
+    /// from (None for the defaults)
+    pub fn load_with_source(project_path: Option<&Path>) -> (Self, Option<PathBuf>) {
+        // 1. Project-local .demongrep.toml, 2. CWD, 3. global ~/.demongrep/config.toml
+        let candidates = [
+            project_path.map(|path| path.join(".demongrep.toml")),
+            std::env::current_dir()
+                .ok()
+                .map(|cwd| cwd.join(".demongrep.toml")),
+            dirs::home_dir().map(|home| home.join(".demongrep").join("config.toml")),
+        ];
+        for config_path in candidates.into_iter().flatten() {
             if let Ok(config) = Self::load_from_file(&config_path) {
-                return config;
+                return (config, Some(config_path));
             }
         }
 
         // 4. Defaults
-        Self::default()
+        (Self::default(), None)
     }
 
     fn load_from_file(path: &Path) -> Result<Self> {
@@ -153,6 +281,260 @@ impl ProjectConfig {
         let config: ProjectConfig = toml::from_str(&content)?;
         Ok(config)
     }
+
+    /// Settings that parse but make no sense, as (dotted key, message)
+    fn range_problems(&self) -> Vec<(&'static str, String)> {
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, key: &'static str, message: String| {
+            if !ok {
+                problems.push((key, message));
+            }
+        };
+
+        let embedding = &self.embedding;
+        check(
+            !embedding.model.trim().is_empty(),
+            "embedding.model",
+            "must not be empty".to_string(),
+        );
+        check(
+            embedding.batch_size > 0,
+            "embedding.batch_size",
+            "must be greater than 0".to_string(),
+        );
+        check(
+            embedding.dimensions != Some(0),
+            "embedding.dimensions",
+            "must be greater than 0".to_string(),
+        );
+        if embedding.provider == EmbeddingProvider::Remote {
+            check(
+                embedding.base_url.is_some(),
+                "embedding.base_url",
+                "is required when provider = \"remote\"".to_string(),
+            );
+            check(
+                embedding.dimensions.is_some(),
+                "embedding.dimensions",
+                "is required when provider = \"remote\"".to_string(),
+            );
+        }
+
+        let chunking = &self.chunking;
+        check(
+            chunking.max_lines > 0,
+            "chunking.max_lines",
+            "must be greater than 0".to_string(),
+        );
+        check(
+            chunking.max_chars > 0,
+            "chunking.max_chars",
+            "must be greater than 0".to_string(),
+        );
+        check(
+            chunking.max_lines == 0 || chunking.overlap_lines < chunking.max_lines,
+            "chunking.overlap_lines",
+            format!(
+                "must be less than max_lines ({}), got {}",
+                chunking.max_lines, chunking.overlap_lines
+            ),
+        );
+
+        let search = &self.search;
+        check(
+            search.rrf_k.is_finite() && search.rrf_k > 0.0,
+            "search.rrf_k",
+            format!("must be greater than 0, got {}", search.rrf_k),
+        );
+        check(
+            (0.0..=1.0).contains(&search.rerank_weight),
+            "search.rerank_weight",
+            format!("must be between 0 and 1, got {}", search.rerank_weight),
+        );
+        check(
+            search.default_limit > 0,
+            "search.default_limit",
+            "must be greater than 0".to_string(),
+        );
+        check(
+            search.rerank_batch_size > 0,
+            "search.rerank_batch_size",
+            "must be greater than 0".to_string(),
+        );
+        check(
+            search.rerank_top > 0,
+            "search.rerank_top",
+            "must be greater than 0".to_string(),
+        );
+
+        check(
+            self.fts.min_term_length > 0,
+            "fts.min_term_length",
+            "must be at least 1".to_string(),
+        );
+        check(
+            self.database.max_size_gb > 0,
+            "database.max_size_gb",
+            "must be greater than 0".to_string(),
+        );
+        check(
+            self.server.port > 0,
+            "server.port",
+            "must be between 1 and 65535".to_string(),
+        );
+        check(
+            self.server.request_timeout_secs > 0,
+            "server.request_timeout_secs",
+            "must be greater than 0".to_string(),
+        );
+        check(
+            self.mcp.max_results > 0,
+            "mcp.max_results",
+            "must be greater than 0".to_string(),
+        );
+        for (alias, target) in &self.models.aliases {
+            check(
+                ModelType::from_str(target).is_some(),
+                "models.aliases",
+                format!(
+                    "'{}' points to unknown model '{}' (expected one of: {})",
+                    alias,
+                    target,
+                    ModelType::available_names()
+                ),
+            );
+        }
+
+        problems
+    }
+}
+
+/// A problem found by [`validate_config`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// Dotted key the problem is about (e.g. "search.rrf_k"), if known
+    pub key: Option<String>,
+    /// 1-based line in the file, if the problem can be located
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        match &self.key {
+            Some(key) => write!(f, "{}: {}", key, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Strictly check the contents of a config file
+///
+/// Unlike [`ProjectConfig::load`], which silently falls back to the defaults
+/// when a file doesn't parse and ignores keys it doesn't know, this reports
+/// unknown keys, type errors and out-of-range values. An empty result means
+/// the file is valid.
+pub fn validate_config(content: &str) -> Vec<ConfigProblem> {
+    let table: toml::Table = match content.parse() {
+        Ok(table) => table,
+        Err(e) => return vec![parse_problem(content, &e)],
+    };
+
+    let mut problems = unknown_keys(content, &table);
+    match toml::from_str::<ProjectConfig>(content) {
+        Ok(config) => {
+            for (key, message) in config.range_problems() {
+                let (section, name) = key.split_once('.').unwrap_or((key, ""));
+                problems.push(ConfigProblem {
+                    key: Some(key.to_string()),
+                    line: key_line(content, section, Some(name)),
+                    message,
+                });
+            }
+        }
+        Err(e) => problems.push(parse_problem(content, &e)),
+    }
+    problems
+}
+
+fn parse_problem(content: &str, error: &toml::de::Error) -> ConfigProblem {
+    ConfigProblem {
+        key: None,
+        line: error
+            .span()
+            .map(|span| content[..span.start].matches('\n').count() + 1),
+        message: error.message().to_string(),
+    }
+}
+
+/// Every key a config file may contain, including unset optional ones
+fn known_keys() -> toml::Table {
+    let mut config = ProjectConfig::default();
+    // Options are left out of the serialized defaults while unset
+    config.embedding.base_url = Some(String::new());
+    config.embedding.dimensions = Some(1);
+    config.embedding.model_dir = Some(PathBuf::new());
+    config.search.rerank_model_path = Some(PathBuf::new());
+    toml::Table::try_from(config).expect("config serializes to a table")
+}
+
+fn unknown_keys(content: &str, table: &toml::Table) -> Vec<ConfigProblem> {
+    let known = known_keys();
+    let mut problems = Vec::new();
+    for (section, value) in table {
+        let Some(known_section) = known.get(section).and_then(|v| v.as_table()) else {
+            problems.push(ConfigProblem {
+                key: Some(section.clone()),
+                line: key_line(content, section, None)
+                    .or_else(|| key_line(content, "", Some(section))),
+                message: "unknown section".to_string(),
+            });
+            continue;
+        };
+        let Some(settings) = value.as_table() else {
+            continue; // reported as a type error
+        };
+        for key in settings.keys() {
+            if !known_section.contains_key(key) {
+                problems.push(ConfigProblem {
+                    key: Some(format!("{}.{}", section, key)),
+                    line: key_line(content, section, Some(key)),
+                    message: format!(
+                        "unknown key (expected one of: {})",
+                        known_section.keys().cloned().collect::<Vec<_>>().join(", ")
+                    ),
+                });
+            }
+        }
+    }
+    problems
+}
+
+/// 1-based line of `[section]` (key None) or of `key = ...` inside it
+///
+/// A plain line scan: good enough for the flat tables a config file uses.
+fn key_line(content: &str, section: &str, key: Option<&str>) -> Option<usize> {
+    let mut current = "";
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[') {
+            current = header.split(']').next().unwrap_or("").trim();
+            if key.is_none() && current == section {
+                return Some(i + 1);
+            }
+        } else if let Some(key) = key {
+            let is_key = line
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='));
+            if current == section && is_key {
+                return Some(i + 1);
+            }
+        }
+    }
+    None
 }
 
 #[cfg(test)]
@@ -163,10 +545,50 @@ mod tests {
     fn test_default_config() {
         let config = ProjectConfig::default();
         assert_eq!(config.embedding.model, "minilm-l6-q");
+        assert_eq!(config.embedding.provider, EmbeddingProvider::Local);
         assert_eq!(config.search.rrf_k, 20.0);
         assert_eq!(config.server.port, 4444);
         assert_eq!(config.chunking.max_lines, 75);
+        assert_eq!(config.fts, FtsConfig::default());
         assert_eq!(config.database.max_size_gb, 10);
+        assert_eq!(config.mcp.max_results, 10);
+    }
+
+    #[test]
+    fn test_model_alias_resolves_to_model_type() {
+        let config: ProjectConfig = toml::from_str(
+            r#"
+[models]
+aliases = { "our-model" = "jina-code-1.5b", "minilm-l6-q" = "jina-v5-nano" }
+"#,
+        )
+        .unwrap();
+        let models = &config.models;
+        assert_eq!(
+            models.resolve("our-model"),
+            Some(ModelType::JinaCodeEmbeddings15B)
+        );
+        assert_eq!(
+            models.resolve("Our-Model"),
+            Some(ModelType::JinaCodeEmbeddings15B)
+        );
+        // Aliases take precedence over built-in names
+        assert_eq!(
+            models.resolve("minilm-l6-q"),
+            Some(ModelType::JinaEmbeddingsV5TextNano)
+        );
+        assert_eq!(
+            models.resolve("mxbai-xsmall"),
+            Some(ModelType::MxbaiEmbedXSmallV1)
+        );
+        assert_eq!(models.resolve("their-model"), None);
+        assert_eq!(ModelsConfig::default().resolve("our-model"), None);
+
+        let problems =
+            validate_config("[models]\naliases = { \"our-model\" = \"no-such-model\" }\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].key.as_deref(), Some("models.aliases"));
+        assert!(problems[0].message.contains("no-such-model"));
     }
 
     #[test]
@@ -184,6 +606,26 @@ default_limit = 15
         assert_eq!(config.server.port, 4444);
     }
 
+    #[test]
+    fn test_rerank_enabled_turns_reranking_on() {
+        let config: ProjectConfig = toml::from_str(
+            r#"
+[search]
+rerank_enabled = true
+rerank_top = 20
+"#,
+        )
+        .unwrap();
+        assert!(config.search.rerank(None));
+        assert_eq!(config.search.rerank_top, 20);
+        // --no-rerank still wins
+        assert!(!config.search.rerank(Some(false)));
+
+        let defaults = SearchConfig::default();
+        assert!(!defaults.rerank(None));
+        assert!(defaults.rerank(Some(true)));
+    }
+
     #[test]
     fn test_parse_full_toml() {
         let toml_str = r#"
@@ -197,12 +639,18 @@ max_lines = 100
 max_chars = 3000
 overlap_lines = 15
 context_lines = 5
+min_chunk_lines = 3
+min_chunk_chars = 40
 
 [search]
 rrf_k = 25.0
 rerank_weight = 0.6
 default_limit = 50
 
+[fts]
+min_term_length = 2
+stop_words = true
+
 [database]
 max_size_gb = 20
 
@@ -214,11 +662,87 @@ debounce_ms = 500
         assert_eq!(config.embedding.model, "jina-code-1.5b");
         assert_eq!(config.embedding.batch_size, 64);
         assert_eq!(config.chunking.max_lines, 100);
+        assert_eq!(config.chunking.min_chunk_lines, 3);
+        assert_eq!(config.chunking.min_chunk_chars, 40);
         assert_eq!(config.search.rrf_k, 25.0);
+        assert_eq!(config.fts.min_term_length, 2);
+        assert!(config.fts.stop_words);
         assert_eq!(config.database.max_size_gb, 20);
         assert_eq!(config.server.port, 8080);
     }
 
+    #[test]
+    fn test_parse_remote_embedding() {
+        let toml_str = r#"
+[embedding]
+provider = "remote"
+base_url = "http://localhost:8080"
+model = "text-embedding-3-small"
+api_key_env = "EMBEDDINGS_KEY"
+dimensions = 1536
+batch_size = 16
+"#;
+        let config: ProjectConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.embedding.provider, EmbeddingProvider::Remote);
+        assert_eq!(
+            config.embedding.base_url.as_deref(),
+            Some("http://localhost:8080")
+        );
+        assert_eq!(config.embedding.model, "text-embedding-3-small");
+        assert_eq!(config.embedding.api_key_env, "EMBEDDINGS_KEY");
+        assert_eq!(config.embedding.dimensions, Some(1536));
+        assert_eq!(config.embedding.batch_size, 16);
+    }
+
+    #[test]
+    fn test_validate_accepts_defaults() {
+        let defaults = toml::to_string(&ProjectConfig::default()).unwrap();
+        assert_eq!(validate_config(&defaults), vec![]);
+        assert_eq!(validate_config(""), vec![]);
+    }
+
+    #[test]
+    fn test_validate_reports_unknown_key() {
+        let toml_str = "[search]\nrrf_k = 30.0\ndefault_limt = 10\n";
+        // Loading ignores the typo...
+        let config: ProjectConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.search.default_limit, 25);
+
+        // ...validation points at it
+        let problems = validate_config(toml_str);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].key.as_deref(), Some("search.default_limt"));
+        assert_eq!(problems[0].line, Some(3));
+        assert!(problems[0].message.contains("default_limit"));
+
+        let problems = validate_config("[serch]\nrrf_k = 30.0\n");
+        assert_eq!(problems[0].key.as_deref(), Some("serch"));
+        assert_eq!(problems[0].line, Some(1));
+    }
+
+    #[test]
+    fn test_validate_reports_invalid_values() {
+        let toml_str = "[search]\nrrf_k = 0.0\n\n[server]\nport = 0\n";
+        let problems = validate_config(toml_str);
+        let found: Vec<_> = problems
+            .iter()
+            .map(|p| (p.key.as_deref(), p.line))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some("search.rrf_k"), Some(2)),
+                (Some("server.port"), Some(5))
+            ]
+        );
+
+        // Type errors come with their line
+        let problems = validate_config("[server]\nport = \"http\"\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].key, None);
+        assert_eq!(problems[0].line, Some(2));
+    }
+
     #[test]
     fn test_load_nonexistent_returns_defaults() {
         let config = ProjectConfig::load(Some(std::path::Path::new("/nonexistent/path")));
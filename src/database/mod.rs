@@ -4,9 +4,10 @@
 //! databases, eliminating code duplication across search, server, MCP, and index modules.
 
 use anyhow::{anyhow, Result};
-use std::path::PathBuf;
+use serde::{Deserialize, Deserializer};
+use std::path::{Path, PathBuf};
 
-use crate::embed::ModelType;
+use crate::embed::{EmbeddingService, ModelType};
 use crate::fts::FtsStore;
 use crate::index::{get_search_db_paths, is_local_db_path};
 use crate::rerank::rrf_fusion;
@@ -28,6 +29,52 @@ impl DatabaseType {
             DatabaseType::Global => "Global",
         }
     }
+
+    /// Lowercase tag shown next to results: "local" or "global"
+    pub fn tag(&self) -> &'static str {
+        match self {
+            DatabaseType::Local => "local",
+            DatabaseType::Global => "global",
+        }
+    }
+
+    /// Type of the database at `db_path`
+    pub fn of(db_path: &Path) -> Self {
+        if is_local_db_path(db_path) {
+            DatabaseType::Local
+        } else {
+            DatabaseType::Global
+        }
+    }
+}
+
+/// Which databases to search (`--local-only` / `--global-only`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DatabaseScope {
+    /// Local and global databases, merged
+    #[default]
+    All,
+    Local,
+    Global,
+}
+
+impl DatabaseScope {
+    pub fn includes(self, db_type: DatabaseType) -> bool {
+        match self {
+            DatabaseScope::All => true,
+            DatabaseScope::Local => db_type == DatabaseType::Local,
+            DatabaseScope::Global => db_type == DatabaseType::Global,
+        }
+    }
+
+    /// "local", "global" or "local or global", for messages
+    pub fn describe(self) -> &'static str {
+        match self {
+            DatabaseScope::All => "local or global",
+            DatabaseScope::Local => "local",
+            DatabaseScope::Global => "global",
+        }
+    }
 }
 
 /// A single database entry with metadata
@@ -39,9 +86,9 @@ pub struct Database {
 }
 
 impl Database {
-    /// Create a new database instance
+    /// Open a database instance for searching (read-only)
     pub fn new(path: PathBuf, db_type: DatabaseType, dimensions: usize) -> Result<Self> {
-        let store = VectorStore::new(&path, dimensions)?;
+        let store = VectorStore::open_readonly(&path, dimensions)?;
         // Try to open FTS store (optional - may not exist yet)
         let fts_store = FtsStore::open_readonly(&path).ok();
         Ok(Self {
@@ -57,17 +104,44 @@ impl Database {
         &self.store
     }
 
-    /// Get mutable vector store
-    pub fn store_mut(&mut self) -> &mut VectorStore {
-        &mut self.store
-    }
-
     /// Get the FTS store (if available)
     pub fn fts_store(&self) -> Option<&FtsStore> {
         self.fts_store.as_ref()
     }
 }
 
+/// How a database was indexed, as recorded in its `metadata.json`
+///
+/// Missing fields (and a missing or unreadable file) read as the defaults,
+/// which are what indexes written before the field existed were built with.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct IndexMetadata {
+    /// Whether there's a full-text index; false only for `index --no-fts`
+    pub fts: bool,
+    /// Whether doc comments were indexed as chunks of their own (`--index-docs`)
+    pub docs: bool,
+    /// Roots the database was indexed from, relative to the project root;
+    /// empty for indexes of the whole project
+    #[serde(deserialize_with = "null_as_empty")]
+    pub roots: Vec<PathBuf>,
+}
+
+impl Default for IndexMetadata {
+    fn default() -> Self {
+        Self {
+            fts: true,
+            docs: false,
+            roots: Vec::new(),
+        }
+    }
+}
+
+/// `"roots": null` is written for whole-project indexes
+fn null_as_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<PathBuf>, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
 /// Combined statistics from all databases
 #[derive(Debug, Clone, Default)]
 pub struct CombinedStats {
@@ -91,27 +165,41 @@ pub struct DatabaseManager {
 impl DatabaseManager {
     /// Load all available databases for a given path
     pub fn load(path: Option<PathBuf>) -> Result<Self> {
-        let db_paths = get_search_db_paths(path)?;
+        Self::load_scoped(path, DatabaseScope::All)
+    }
+
+    /// Load the available databases for a given path that `scope` includes
+    pub fn load_scoped(path: Option<PathBuf>, scope: DatabaseScope) -> Result<Self> {
+        let db_paths: Vec<(PathBuf, DatabaseType)> = get_search_db_paths(path)?
+            .into_iter()
+            .map(|db_path| {
+                let db_type = DatabaseType::of(&db_path);
+                (db_path, db_type)
+            })
+            .filter(|(_, db_type)| scope.includes(*db_type))
+            .collect();
 
         if db_paths.is_empty() {
-            return Err(anyhow!("No databases found"));
+            return Err(anyhow!("No {} databases found", scope.describe()));
         }
+        Self::open(db_paths)
+    }
+
+    /// Open the given databases; the model and dimensions come from the first
+    pub(crate) fn open(db_paths: Vec<(PathBuf, DatabaseType)>) -> Result<Self> {
+        let Some((first, _)) = db_paths.first() else {
+            return Err(anyhow!("No databases found"));
+        };
 
         // Read metadata from first database
-        let (model_type, dimensions) = Self::read_metadata(&db_paths[0]).unwrap_or_else(|| {
+        let (model_type, dimensions) = Self::read_metadata(first).unwrap_or_else(|| {
             let default_model = ModelType::default();
             (default_model, default_model.dimensions())
         });
 
         // Load all databases
         let mut databases = Vec::new();
-        for db_path in db_paths {
-            let db_type = if is_local_db_path(&db_path) {
-                DatabaseType::Local
-            } else {
-                DatabaseType::Global
-            };
-
+        for (db_path, db_type) in db_paths {
             match Database::new(db_path.clone(), db_type, dimensions) {
                 Ok(db) => databases.push(db),
                 Err(e) => {
@@ -136,6 +224,15 @@ impl DatabaseManager {
         })
     }
 
+    /// Keep only the databases `scope` includes
+    pub fn scoped(mut self, scope: DatabaseScope) -> Result<Self> {
+        self.databases.retain(|db| scope.includes(db.db_type));
+        if self.databases.is_empty() {
+            return Err(anyhow!("No {} databases found", scope.describe()));
+        }
+        Ok(self)
+    }
+
     /// Get model type
     pub fn model_type(&self) -> ModelType {
         self.model_type
@@ -206,8 +303,11 @@ impl DatabaseManager {
 
         for database in &self.databases {
             match database.store.search(query_embedding, retrieval_limit) {
-                Ok(mut results) => {
-                    all_results.append(&mut results);
+                Ok(results) => {
+                    all_results.extend(results.into_iter().map(|mut r| {
+                        r.database = Some(database.db_type);
+                        r
+                    }));
                 }
                 Err(e) => {
                     eprintln!(
@@ -294,6 +394,7 @@ impl DatabaseManager {
                     r.fts_score = fused.fts_score;
                     r.vector_rank = fused.vector_rank;
                     r.fts_rank = fused.fts_rank;
+                    r.database = Some(database.db_type);
                     all_results.push(r);
                 } else if let Ok(Some(mut result)) =
                     database.store.get_chunk_as_result(fused.chunk_id)
@@ -303,6 +404,7 @@ impl DatabaseManager {
                     result.fts_score = fused.fts_score;
                     result.vector_rank = fused.vector_rank;
                     result.fts_rank = fused.fts_rank;
+                    result.database = Some(database.db_type);
                     all_results.push(result);
                 }
             }
@@ -364,29 +466,18 @@ impl DatabaseManager {
     }
 
     /// Read metadata from a database
-    fn read_metadata(db_path: &PathBuf) -> Option<(ModelType, usize)> {
-        let metadata_path = db_path.join("metadata.json");
-
-        if !metadata_path.exists() {
-            return None;
-        }
-
-        let content = std::fs::read_to_string(&metadata_path).ok()?;
-        let json: serde_json::Value = serde_json::from_str(&content).ok()?;
-
-        let model_name = json
-            .get("model_short_name")
-            .and_then(|v| v.as_str())
-            .unwrap_or("minilm-l6");
-
-        let dimensions = json
-            .get("dimensions")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(384) as usize;
-
-        let model_type = ModelType::from_str(model_name).unwrap_or_default();
+    ///
+    /// None when the metadata is missing or names a model this build doesn't know.
+    pub(crate) fn read_metadata(db_path: &Path) -> Option<(ModelType, usize)> {
+        EmbeddingService::index_model(&db_path.join("metadata.json")).ok()
+    }
 
-        Some((model_type, dimensions))
+    /// How the database was indexed (full-text index, doc chunks, roots)
+    pub(crate) fn index_metadata(db_path: &Path) -> IndexMetadata {
+        std::fs::read_to_string(db_path.join("metadata.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
     }
 
     /// Print database information
@@ -457,11 +548,7 @@ impl DatabaseManagerBuilder {
         // Load all databases
         let mut databases = Vec::new();
         for db_path in self.db_paths {
-            let db_type = if is_local_db_path(&db_path) {
-                DatabaseType::Local
-            } else {
-                DatabaseType::Global
-            };
+            let db_type = DatabaseType::of(&db_path);
 
             match Database::new(db_path.clone(), db_type, dimensions) {
                 Ok(db) => databases.push(db),
@@ -497,11 +584,132 @@ impl Default for DatabaseManagerBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chunker::{Chunk, ChunkKind};
+    use crate::embed::EmbeddedChunk;
+
+    /// Build an index at `dir` holding one function chunk from `path`
+    fn store_with_function(dir: &Path, path: &str) {
+        let mut store = VectorStore::new(dir, 4).unwrap();
+        let chunk = Chunk::new(
+            format!("fn in_{}() {{}}", path),
+            0,
+            1,
+            ChunkKind::Function,
+            path.to_string(),
+        );
+        store
+            .insert_chunks(vec![EmbeddedChunk::new(chunk, vec![1.0, 0.0, 0.0, 0.0])])
+            .unwrap();
+        store.build_index().unwrap();
+    }
+
+    /// Open the databases at `local` and `global` together
+    fn local_and_global(local: &Path, global: &Path) -> DatabaseManager {
+        DatabaseManager::open(vec![
+            (local.to_path_buf(), DatabaseType::Local),
+            (global.to_path_buf(), DatabaseType::Global),
+        ])
+        .unwrap()
+    }
 
     #[test]
     fn test_database_type() {
         assert_eq!(DatabaseType::Local.name(), "Local");
         assert_eq!(DatabaseType::Global.name(), "Global");
+        assert_eq!(DatabaseType::Local.tag(), "local");
+        assert_eq!(DatabaseType::Global.tag(), "global");
+    }
+
+    #[test]
+    fn test_scoped_search_only_hits_selected_database() {
+        let local_dir = tempfile::tempdir().unwrap();
+        let global_dir = tempfile::tempdir().unwrap();
+        store_with_function(local_dir.path(), "local.rs");
+        store_with_function(global_dir.path(), "global.rs");
+
+        let open_both = || local_and_global(local_dir.path(), global_dir.path());
+        let paths = |manager: &DatabaseManager| -> Vec<String> {
+            let mut paths: Vec<String> = manager
+                .search_all(&[1.0, 0.0, 0.0, 0.0], 10, 0)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.path)
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(paths(&open_both()), vec!["global.rs", "local.rs"]);
+
+        let local = open_both().scoped(DatabaseScope::Local).unwrap();
+        assert_eq!(local.database_count(), 1);
+        assert_eq!(paths(&local), vec!["local.rs"]);
+
+        let global = open_both().scoped(DatabaseScope::Global).unwrap();
+        assert_eq!(paths(&global), vec!["global.rs"]);
+
+        let local_only =
+            DatabaseManager::open(vec![(local_dir.path().to_path_buf(), DatabaseType::Local)])
+                .unwrap();
+        assert!(local_only.scoped(DatabaseScope::Global).is_err());
+    }
+
+    #[test]
+    fn test_merged_results_carry_their_database() {
+        let local_dir = tempfile::tempdir().unwrap();
+        let global_dir = tempfile::tempdir().unwrap();
+        store_with_function(local_dir.path(), "local.rs");
+        store_with_function(global_dir.path(), "global.rs");
+        let manager = local_and_global(local_dir.path(), global_dir.path());
+
+        let query = [1.0, 0.0, 0.0, 0.0];
+        let vector = manager.search_all(&query, 10, 0).unwrap();
+        let hybrid = manager
+            .hybrid_search_all("in_local", &query, 10, 0, 20.0)
+            .unwrap();
+        for results in [vector, hybrid] {
+            assert_eq!(results.len(), 2);
+            for result in results {
+                let expected = match result.path.as_str() {
+                    "local.rs" => DatabaseType::Local,
+                    _ => DatabaseType::Global,
+                };
+                assert_eq!(result.database, Some(expected), "{}", result.path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_index_metadata_defaults_for_older_indexes() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            DatabaseManager::index_metadata(dir.path()),
+            IndexMetadata::default()
+        );
+
+        let metadata = dir.path().join("metadata.json");
+        std::fs::write(
+            &metadata,
+            r#"{"model_short_name": "minilm-l6-q", "roots": null}"#,
+        )
+        .unwrap();
+        let index = DatabaseManager::index_metadata(dir.path());
+        assert!(index.fts);
+        assert!(!index.docs);
+        assert!(index.roots.is_empty());
+
+        std::fs::write(
+            &metadata,
+            r#"{"fts": false, "docs": true, "roots": ["src", "lib"]}"#,
+        )
+        .unwrap();
+        let index = DatabaseManager::index_metadata(dir.path());
+        assert!(!index.fts);
+        assert!(index.docs);
+        assert_eq!(
+            index.roots,
+            vec![PathBuf::from("src"), PathBuf::from("lib")]
+        );
     }
 
     #[test]
@@ -1,4 +1,4 @@
-use super::embedder::{FastEmbedder, ModelType};
+use super::embedder::{Embedder, ModelType};
 use crate::chunker::Chunk;
 use anyhow::Result;
 use rayon::prelude::*;
@@ -67,13 +67,13 @@ impl EmbeddedChunk {
 
 /// Batch processor for embedding chunks efficiently
 pub struct BatchEmbedder {
-    pub embedder: Arc<Mutex<FastEmbedder>>,
+    pub embedder: Arc<Mutex<dyn Embedder>>,
     batch_size: usize,
 }
 
 impl BatchEmbedder {
     /// Create a new batch embedder
-    pub fn new(embedder: Arc<Mutex<FastEmbedder>>) -> Self {
+    pub fn new(embedder: Arc<Mutex<dyn Embedder>>) -> Self {
         Self {
             embedder,
             batch_size: 32, // Default batch size
@@ -81,7 +81,7 @@ impl BatchEmbedder {
     }
 
     /// Create with custom batch size
-    pub fn with_batch_size(embedder: Arc<Mutex<FastEmbedder>>, batch_size: usize) -> Self {
+    pub fn with_batch_size(embedder: Arc<Mutex<dyn Embedder>>, batch_size: usize) -> Self {
         Self {
             embedder,
             batch_size,
@@ -282,6 +282,7 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
 mod tests {
     use super::*;
     use crate::chunker::ChunkKind;
+    use crate::embed::FastEmbedder;
 
     #[test]
     fn test_embedding_stats() {
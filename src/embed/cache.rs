@@ -3,6 +3,7 @@ use crate::chunker::Chunk;
 use anyhow::Result;
 use dashmap::DashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 /// Cache for embeddings keyed by chunk hash
 ///
@@ -109,7 +110,7 @@ impl CacheStats {
 /// Cached batch embedder with L1 in-memory + optional L2 persistent disk cache
 pub struct CachedBatchEmbedder {
     pub batch_embedder: super::batch::BatchEmbedder,
-    cache: EmbeddingCache,
+    cache: Arc<EmbeddingCache>,
     persistent_cache: Option<super::persistent_cache::PersistentEmbeddingCache>,
 }
 
@@ -118,7 +119,7 @@ impl CachedBatchEmbedder {
     pub fn new(batch_embedder: super::batch::BatchEmbedder) -> Self {
         Self {
             batch_embedder,
-            cache: EmbeddingCache::new(),
+            cache: Arc::new(EmbeddingCache::new()),
             persistent_cache: None,
         }
     }
@@ -130,7 +131,7 @@ impl CachedBatchEmbedder {
     ) -> Self {
         Self {
             batch_embedder,
-            cache: EmbeddingCache::new(),
+            cache: Arc::new(EmbeddingCache::new()),
             persistent_cache: Some(persistent_cache),
         }
     }
@@ -257,6 +258,17 @@ impl CachedBatchEmbedder {
     pub fn cache(&self) -> &EmbeddingCache {
         &self.cache
     }
+
+    /// Handle on the in-memory cache, for reading its stats without
+    /// borrowing the embedder
+    pub fn shared_cache(&self) -> Arc<EmbeddingCache> {
+        Arc::clone(&self.cache)
+    }
+
+    /// Number of embeddings in the persistent cache, if there is one
+    pub fn persistent_cache_len(&self) -> Option<usize> {
+        self.persistent_cache.as_ref().and_then(|pc| pc.len().ok())
+    }
 }
 
 #[cfg(test)]
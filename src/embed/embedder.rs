@@ -5,7 +5,31 @@ use fastembed::{
     QuantizationMode, TextEmbedding, TokenizerFiles, UserDefinedEmbeddingModel,
 };
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Tokenizer files fastembed reads next to every model
+const TOKENIZER_FILES: &[&str] = &[
+    "tokenizer.json",
+    "config.json",
+    "special_tokens_map.json",
+    "tokenizer_config.json",
+];
+
+/// ONNX exports of Jina v5 Text Nano, tried in order, as (model, external data,
+/// initializer name)
+const JINA_V5_ONNX_CANDIDATES: &[(&str, &str, &str)] = &[
+    ("onnx/model.onnx", "onnx/model.onnx_data", "model.onnx_data"),
+    (
+        "onnx/model_fp16.onnx",
+        "onnx/model_fp16.onnx_data",
+        "model_fp16.onnx_data",
+    ),
+    (
+        "onnx/model_quantized.onnx",
+        "onnx/model_quantized.onnx_data",
+        "model_quantized.onnx_data",
+    ),
+];
 
 /// Available embedding models
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -78,6 +102,15 @@ impl ModelType {
         }
     }
 
+    /// Text actually embedded for a query: `format_query`, or the raw input when `verbatim`
+    pub fn query_text(&self, query: &str, verbatim: bool) -> String {
+        if verbatim {
+            query.to_string()
+        } else {
+            self.format_query(query)
+        }
+    }
+
     /// Format indexed passages according to model-specific recommendations.
     pub fn format_passage(&self, passage: &str) -> String {
         match self {
@@ -97,6 +130,38 @@ impl ModelType {
         )
     }
 
+    /// Whether the model was trained with Matryoshka representation learning,
+    /// so its embeddings can be truncated to fewer dimensions
+    pub fn supports_matryoshka(&self) -> bool {
+        matches!(
+            self,
+            Self::MxbaiEmbedXSmallV1 | Self::JinaEmbeddingsV5TextNano | Self::JinaCodeEmbeddings15B
+        )
+    }
+
+    /// Check a `--truncate-dims` value against this model
+    pub fn validate_truncate_dims(&self, dims: usize) -> Result<usize> {
+        if !self.supports_matryoshka() {
+            return Err(anyhow!(
+                "{} does not support Matryoshka truncation",
+                self.short_name()
+            ));
+        }
+        if dims == 0 || dims > self.dimensions() {
+            return Err(anyhow!(
+                "--truncate-dims must be between 1 and {} for {}",
+                self.dimensions(),
+                self.short_name()
+            ));
+        }
+        Ok(dims)
+    }
+
+    /// Truncation implied by a stored index dimension (None if full-size)
+    pub fn truncation_for(&self, stored_dimensions: usize) -> Option<usize> {
+        (stored_dimensions < self.dimensions()).then_some(stored_dimensions)
+    }
+
     /// Get a short identifier for the model (for filenames, etc.)
     pub fn short_name(&self) -> &'static str {
         match self {
@@ -117,6 +182,27 @@ impl ModelType {
         ]
     }
 
+    /// Comma-separated short names of all models (for help and error messages)
+    pub fn available_names() -> String {
+        Self::all()
+            .iter()
+            .map(|m| m.short_name())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Cache subdirectory for models loaded through fastembed's user-defined path
+    ///
+    /// Empty for built-in fastembed models, which use the hf-hub cache layout.
+    fn user_defined_cache_segments(&self) -> &'static [&'static str] {
+        match self {
+            Self::AllMiniLML6V2Q => &[],
+            Self::MxbaiEmbedXSmallV1 => &["mixedbread-ai", "mxbai-embed-xsmall-v1"],
+            Self::JinaEmbeddingsV5TextNano => &["jinaai", "jina-embeddings-v5-text-nano-retrieval"],
+            Self::JinaCodeEmbeddings15B => &["herMaster", "jina-code-embeddings-1.5b-ONNX"],
+        }
+    }
+
     /// Parse model from string (for CLI)
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
@@ -147,10 +233,67 @@ impl Default for ModelType {
     }
 }
 
+/// Scale a vector to unit L2 length in place (zero vectors are left untouched)
+pub fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 && norm.is_finite() {
+        for x in vector.iter_mut() {
+            *x /= norm;
+        }
+    }
+}
+
+/// Truncate a Matryoshka embedding to its first `dims` values and re-normalize
+pub fn truncate_embedding(vector: &mut Vec<f32>, dims: usize) {
+    vector.truncate(dims);
+    l2_normalize(vector);
+}
+
+/// Whether embeddings should be L2-normalized (disable with DEMONGREP_NORMALIZE_EMBEDDINGS=0)
+pub(crate) fn normalize_from_env() -> bool {
+    match std::env::var("DEMONGREP_NORMALIZE_EMBEDDINGS") {
+        Ok(value) => !matches!(value.to_lowercase().as_str(), "0" | "false" | "no" | "off"),
+        Err(_) => true,
+    }
+}
+
+/// Mini-batch size for embedding: an explicit override (`--batch-size`), then
+/// `DEMONGREP_BATCH_SIZE`, then an adaptive size based on model dimensions
+///
+/// The override was already validated by `set_batch_size`; an unparsable or
+/// zero `DEMONGREP_BATCH_SIZE` falls back to 256.
+pub(crate) fn resolve_batch_size(
+    override_size: Option<usize>,
+    env_size: Option<&str>,
+    dimensions: usize,
+) -> usize {
+    if let Some(size) = override_size {
+        return size;
+    }
+    if let Some(env_size) = env_size {
+        return env_size
+            .parse()
+            .ok()
+            .and_then(|size| super::check_batch_size(size).ok())
+            .unwrap_or(256);
+    }
+    // Adaptive batch size: smaller batches for larger models to avoid OOM
+    // Benchmarked on 12-core/24-thread CPU - batch size has minimal impact
+    // when CPU is saturated, but larger batches slightly more efficient
+    match dimensions {
+        d if d <= 384 => 256, // Small models: larger batches OK
+        d if d <= 768 => 128, // Medium models
+        _ => 64,              // Large models: smaller to avoid OOM
+    }
+}
+
 /// Fast embedding model using fastembed library
 pub struct FastEmbedder {
-    model: TextEmbedding,
+    /// `None` once `shutdown` has released the ONNX session
+    model: Option<TextEmbedding>,
     model_type: ModelType,
+    normalize: bool,
+    truncate_dims: Option<usize>,
 }
 
 impl FastEmbedder {
@@ -164,9 +307,15 @@ impl FastEmbedder {
         info_print!("📦 Loading embedding model: {}", model_type.name());
         info_print!("   Dimensions: {}", model_type.dimensions());
 
+        if super::is_offline() {
+            Self::ensure_cached(model_type, &super::cache_root())?;
+        }
+
         let model = match model_type.to_fastembed_model() {
             Some(fast_model) => TextEmbedding::try_new(
-                InitOptions::new(fast_model).with_show_download_progress(true),
+                InitOptions::new(fast_model)
+                    .with_cache_dir(super::cache_root())
+                    .with_show_download_progress(true),
             )
             .map_err(|e| anyhow!("Failed to initialize embedding model: {}", e))?,
             None => match model_type {
@@ -189,7 +338,42 @@ impl FastEmbedder {
 
         info_print!("✅ Model loaded successfully!");
 
-        Ok(Self { model, model_type })
+        Ok(Self {
+            model: Some(model),
+            model_type,
+            normalize: normalize_from_env(),
+            truncate_dims: None,
+        })
+    }
+
+    /// Check whether output embeddings are L2-normalized (see
+    /// `DEMONGREP_NORMALIZE_EMBEDDINGS`)
+    pub fn normalizes(&self) -> bool {
+        self.normalize
+    }
+
+    /// Truncate output embeddings to `dims` (Matryoshka), re-normalizing them
+    pub fn set_truncate_dims(&mut self, dims: Option<usize>) -> Result<()> {
+        if let Some(dims) = dims {
+            self.model_type.validate_truncate_dims(dims)?;
+        }
+        self.truncate_dims = dims;
+        Ok(())
+    }
+
+    /// Drop the ONNX session now instead of when the embedder goes out of scope
+    ///
+    /// Later embedding calls fail; use this before loading another model in a
+    /// long-running process so the two sessions never coexist in memory.
+    pub fn shutdown(&mut self) {
+        if self.model.take().is_some() {
+            tracing::debug!("Released embedding model: {}", self.model_type.name());
+        }
+    }
+
+    /// Check whether the model is loaded (i.e. `shutdown` hasn't been called)
+    pub fn is_loaded(&self) -> bool {
+        self.model.is_some()
     }
 
     fn huggingface_endpoint() -> String {
@@ -200,10 +384,10 @@ impl FastEmbedder {
     }
 
     fn user_defined_cache_dir(path_segments: &[&str]) -> PathBuf {
-        let cache_root = std::env::var("FASTEMBED_CACHE_DIR")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| PathBuf::from(fastembed::get_cache_dir()));
+        Self::user_defined_dir_in(&super::cache_root(), path_segments)
+    }
 
+    fn user_defined_dir_in(cache_root: &Path, path_segments: &[&str]) -> PathBuf {
         path_segments
             .iter()
             .fold(cache_root.join("user-defined"), |path, segment| {
@@ -211,6 +395,117 @@ impl FastEmbedder {
             })
     }
 
+    /// Fail with the files loading `model_type` would have to download
+    ///
+    /// Used in offline mode, so an uncached model errors up front instead of
+    /// attempting a network request.
+    fn ensure_cached(model_type: ModelType, cache_root: &Path) -> Result<()> {
+        let (model_dir, missing) = Self::missing_model_files(model_type, cache_root);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow!(
+            "Offline mode: model '{}' is not in the local cache.\n  \
+             Expected in: {}\n  \
+             Missing: {}\n\
+             Run `demongrep setup --model {}` on a machine with network access and copy \
+             its cache directory over, or point `embedding.model_dir` at a copy.",
+            model_type.short_name(),
+            model_dir.display(),
+            missing.join(", "),
+            model_type.short_name()
+        ))
+    }
+
+    /// Directory a model is loaded from, and the files in it (relative to it)
+    /// that are missing from the cache
+    fn missing_model_files(model_type: ModelType, cache_root: &Path) -> (PathBuf, Vec<String>) {
+        let missing_from = |dir: &Path, files: &[&str]| -> Vec<String> {
+            files
+                .iter()
+                .filter(|file| !dir.join(file).exists())
+                .map(|file| file.to_string())
+                .collect()
+        };
+
+        let Some(fast_model) = model_type.to_fastembed_model() else {
+            let dir =
+                Self::user_defined_dir_in(cache_root, model_type.user_defined_cache_segments());
+            let missing = match model_type {
+                ModelType::JinaEmbeddingsV5TextNano => {
+                    // special_tokens_map.json is synthesized when the repo lacks it
+                    let mut missing = missing_from(
+                        &dir,
+                        &["tokenizer.json", "config.json", "tokenizer_config.json"],
+                    );
+                    let has_export = JINA_V5_ONNX_CANDIDATES
+                        .iter()
+                        .any(|(onnx, data, _)| dir.join(onnx).exists() && dir.join(data).exists());
+                    if !has_export {
+                        let (onnx, data, _) = JINA_V5_ONNX_CANDIDATES[0];
+                        missing.extend([onnx.to_string(), data.to_string()]);
+                    }
+                    missing
+                }
+                ModelType::JinaCodeEmbeddings15B => missing_from(
+                    &dir,
+                    &[TOKENIZER_FILES, &["model.onnx", "model.onnx_data"]].concat(),
+                ),
+                _ => missing_from(&dir, &[TOKENIZER_FILES, &["onnx/model.onnx"]].concat()),
+            };
+            return (dir, missing);
+        };
+
+        // Hugging Face cache layout: refs/main names the snapshot holding the files
+        let Ok(info) = TextEmbedding::get_model_info(&fast_model) else {
+            return (
+                cache_root.to_path_buf(),
+                vec![model_type.name().to_string()],
+            );
+        };
+        let files = [&[info.model_file.as_str()][..], TOKENIZER_FILES].concat();
+        super::missing_snapshot_files(cache_root, &info.model_code, &files)
+    }
+
+    /// Check whether a model's ONNX weights are already in the local cache
+    pub fn is_cached(model_type: ModelType) -> bool {
+        match model_type.to_fastembed_model() {
+            Some(fast_model) => {
+                let Ok(info) = TextEmbedding::get_model_info(&fast_model) else {
+                    return false;
+                };
+                let snapshots = super::cache_root()
+                    .join(format!("models--{}", info.model_code.replace('/', "--")))
+                    .join("snapshots");
+                std::fs::read_dir(snapshots)
+                    .map(|entries| {
+                        entries
+                            .flatten()
+                            .any(|entry| entry.path().join(&info.model_file).exists())
+                    })
+                    .unwrap_or(false)
+            }
+            None => {
+                let dir = Self::user_defined_cache_dir(model_type.user_defined_cache_segments());
+                Self::contains_onnx_file(&dir, 2)
+            }
+        }
+    }
+
+    fn contains_onnx_file(dir: &std::path::Path, depth: usize) -> bool {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return false;
+        };
+        entries.flatten().any(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                depth > 0 && Self::contains_onnx_file(&path, depth - 1)
+            } else {
+                path.extension().is_some_and(|ext| ext == "onnx")
+            }
+        })
+    }
+
     fn read_hf_repo_file(
         model_cache: &std::path::Path,
         model_repo: &str,
@@ -223,6 +518,14 @@ impl FastEmbedder {
                 .with_context(|| format!("Failed to read cached file {}", local_path.display()));
         }
 
+        if super::is_offline() {
+            return Err(anyhow!(
+                "Offline mode: {} is not cached at {}",
+                name,
+                local_path.display()
+            ));
+        }
+
         if let Some(parent) = local_path.parent() {
             std::fs::create_dir_all(parent).with_context(|| {
                 format!("Failed to create cache directory {}", parent.display())
@@ -285,7 +588,9 @@ impl FastEmbedder {
         const TOKENIZER_CONFIG_JSON: &str = "tokenizer_config.json";
 
         let endpoint = Self::huggingface_endpoint();
-        let model_cache = Self::user_defined_cache_dir(&["mixedbread-ai", "mxbai-embed-xsmall-v1"]);
+        let model_cache = Self::user_defined_cache_dir(
+            ModelType::MxbaiEmbedXSmallV1.user_defined_cache_segments(),
+        );
 
         let tokenizer_files = TokenizerFiles {
             tokenizer_file: Self::read_hf_repo_file(
@@ -331,23 +636,11 @@ impl FastEmbedder {
         const CONFIG_JSON: &str = "config.json";
         const SPECIAL_TOKENS_MAP_JSON: &str = "special_tokens_map.json";
         const TOKENIZER_CONFIG_JSON: &str = "tokenizer_config.json";
-        const ONNX_CANDIDATES: &[(&str, &str, &str)] = &[
-            ("onnx/model.onnx", "onnx/model.onnx_data", "model.onnx_data"),
-            (
-                "onnx/model_fp16.onnx",
-                "onnx/model_fp16.onnx_data",
-                "model_fp16.onnx_data",
-            ),
-            (
-                "onnx/model_quantized.onnx",
-                "onnx/model_quantized.onnx_data",
-                "model_quantized.onnx_data",
-            ),
-        ];
 
         let endpoint = Self::huggingface_endpoint();
-        let model_cache =
-            Self::user_defined_cache_dir(&["jinaai", "jina-embeddings-v5-text-nano-retrieval"]);
+        let model_cache = Self::user_defined_cache_dir(
+            ModelType::JinaEmbeddingsV5TextNano.user_defined_cache_segments(),
+        );
 
         let tokenizer_config_file =
             Self::read_hf_repo_file(&model_cache, MODEL_REPO, &endpoint, TOKENIZER_CONFIG_JSON)?;
@@ -375,7 +668,7 @@ impl FastEmbedder {
 
         let mut onnx_file: Option<(Vec<u8>, String, Vec<u8>)> = None;
         let mut onnx_errors = Vec::new();
-        for (onnx_candidate, data_candidate, initializer_name) in ONNX_CANDIDATES {
+        for (onnx_candidate, data_candidate, initializer_name) in JINA_V5_ONNX_CANDIDATES {
             match (
                 Self::read_hf_repo_file(&model_cache, MODEL_REPO, &endpoint, onnx_candidate),
                 Self::read_hf_repo_file(&model_cache, MODEL_REPO, &endpoint, data_candidate),
@@ -424,8 +717,9 @@ impl FastEmbedder {
         const EXTERNAL_DATA_FILE: &str = "model.onnx_data";
 
         let endpoint = Self::huggingface_endpoint();
-        let model_cache =
-            Self::user_defined_cache_dir(&["herMaster", "jina-code-embeddings-1.5b-ONNX"]);
+        let model_cache = Self::user_defined_cache_dir(
+            ModelType::JinaCodeEmbeddings15B.user_defined_cache_segments(),
+        );
 
         let tokenizer_files = TokenizerFiles {
             tokenizer_file: Self::read_hf_repo_file(
@@ -470,19 +764,13 @@ impl FastEmbedder {
     }
 
     fn resolve_batch_size(&self) -> usize {
-        // Check for env var override (tune with DEMONGREP_BATCH_SIZE=N)
-        if let Ok(env_size) = std::env::var("DEMONGREP_BATCH_SIZE") {
-            env_size.parse().unwrap_or(256)
-        } else {
-            // Adaptive batch size: smaller batches for larger models to avoid OOM
-            // Benchmarked on 12-core/24-thread CPU - batch size has minimal impact
-            // when CPU is saturated, but larger batches slightly more efficient
-            match self.model_type.dimensions() {
-                d if d <= 384 => 256, // Small models: larger batches OK
-                d if d <= 768 => 128, // Medium models
-                _ => 64,              // Large models: smaller to avoid OOM
-            }
-        }
+        // Tune with --batch-size or DEMONGREP_BATCH_SIZE=N
+        let env_size = std::env::var("DEMONGREP_BATCH_SIZE").ok();
+        resolve_batch_size(
+            super::batch_size(),
+            env_size.as_deref(),
+            self.model_type.dimensions(),
+        )
     }
 
     /// Embed a batch of texts (processes in mini-batches to avoid OOM)
@@ -517,17 +805,32 @@ impl FastEmbedder {
             return Ok(Vec::new());
         }
 
+        let model = self.model.as_mut().ok_or_else(|| {
+            anyhow!(
+                "Embedding model {} has been shut down",
+                self.model_type.name()
+            )
+        })?;
         let mut all_embeddings = Vec::with_capacity(texts.len());
 
         // Process in mini-batches to avoid OOM with large models
         for chunk in texts.chunks(batch_size) {
             let text_refs: Vec<&str> = chunk.iter().map(|s| s.as_str()).collect();
 
-            let embeddings = self
-                .model
+            let mut embeddings = model
                 .embed(text_refs, None)
                 .map_err(|e| anyhow!("Failed to generate embeddings: {}", e))?;
 
+            if let Some(dims) = self.truncate_dims {
+                for embedding in &mut embeddings {
+                    truncate_embedding(embedding, dims);
+                }
+            } else if self.normalize {
+                for embedding in &mut embeddings {
+                    l2_normalize(embedding);
+                }
+            }
+
             all_embeddings.extend(embeddings);
         }
 
@@ -543,9 +846,10 @@ impl FastEmbedder {
             .ok_or_else(|| anyhow!("No embedding generated"))
     }
 
-    /// Get the dimensionality of embeddings
+    /// Get the dimensionality of embeddings (after any truncation)
     pub fn dimensions(&self) -> usize {
-        self.model_type.dimensions()
+        self.truncate_dims
+            .unwrap_or_else(|| self.model_type.dimensions())
     }
 
     /// Get the model name
@@ -559,6 +863,74 @@ impl FastEmbedder {
     }
 }
 
+/// An embedding backend used by `BatchEmbedder`
+///
+/// Implemented by `FastEmbedder` (local ONNX models) and `RemoteEmbedder`
+/// (OpenAI-compatible HTTP endpoints).
+pub trait Embedder: Send {
+    /// Embed a batch of texts, returning one vector per text in order
+    fn embed_batch_refs(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+
+    /// Embed a single text
+    fn embed_one(&mut self, text: &str) -> Result<Vec<f32>> {
+        self.embed_batch_refs(&[text.to_string()])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No embedding generated"))
+    }
+
+    /// Get the dimensionality of embeddings
+    fn dimensions(&self) -> usize;
+
+    /// Get the model name
+    fn model_name(&self) -> &str;
+
+    /// Get the model type whose query/passage formatting applies
+    fn model_type(&self) -> ModelType;
+
+    /// Check whether embeddings are L2-normalized
+    fn normalizes(&self) -> bool;
+
+    /// Release any resources held by the backend
+    fn shutdown(&mut self) {}
+}
+
+impl Embedder for FastEmbedder {
+    fn embed_batch_refs(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        FastEmbedder::embed_batch_refs(self, texts)
+    }
+
+    fn embed_one(&mut self, text: &str) -> Result<Vec<f32>> {
+        FastEmbedder::embed_one(self, text)
+    }
+
+    fn dimensions(&self) -> usize {
+        FastEmbedder::dimensions(self)
+    }
+
+    fn model_name(&self) -> &str {
+        FastEmbedder::model_name(self)
+    }
+
+    fn model_type(&self) -> ModelType {
+        FastEmbedder::model_type(self)
+    }
+
+    fn normalizes(&self) -> bool {
+        FastEmbedder::normalizes(self)
+    }
+
+    fn shutdown(&mut self) {
+        FastEmbedder::shutdown(self)
+    }
+}
+
+impl Drop for FastEmbedder {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
 // NOTE: Default impl removed - FastEmbedder::new() returns Result and must not
 // panic on model load failure. Use FastEmbedder::new() or ::with_model() instead.
 
@@ -630,6 +1002,13 @@ mod tests {
         assert_eq!(ModelType::from_str("unknown"), None);
     }
 
+    #[test]
+    fn test_short_name_round_trip() {
+        for model in ModelType::all() {
+            assert_eq!(ModelType::from_str(model.short_name()), Some(*model));
+        }
+    }
+
     #[test]
     fn test_is_quantized() {
         assert!(ModelType::AllMiniLML6V2Q.is_quantized());
@@ -638,6 +1017,18 @@ mod tests {
         assert!(!ModelType::MxbaiEmbedXSmallV1.is_quantized());
     }
 
+    #[test]
+    fn test_l2_normalize() {
+        let mut v = vec![3.0, 4.0];
+        l2_normalize(&mut v);
+        assert!((v[0] - 0.6).abs() < 1e-6);
+        assert!((v[1] - 0.8).abs() < 1e-6);
+
+        let mut zero = vec![0.0, 0.0];
+        l2_normalize(&mut zero);
+        assert_eq!(zero, vec![0.0, 0.0]);
+    }
+
     #[test]
     fn test_model_specific_formatting() {
         let query = "find auth";
@@ -673,6 +1064,24 @@ mod tests {
         assert_eq!(ModelType::AllMiniLML6V2Q.format_passage(passage), passage);
     }
 
+    #[test]
+    fn test_verbatim_query_text() {
+        let query = "fn parse_config(path: &Path)";
+        for model in [
+            ModelType::MxbaiEmbedXSmallV1,
+            ModelType::JinaEmbeddingsV5TextNano,
+            ModelType::JinaCodeEmbeddings15B,
+            ModelType::AllMiniLML6V2Q,
+        ] {
+            assert_eq!(model.query_text(query, true), query);
+            assert_eq!(model.query_text(query, false), model.format_query(query));
+        }
+        assert_ne!(
+            ModelType::MxbaiEmbedXSmallV1.query_text(query, false),
+            query
+        );
+    }
+
     #[test]
     #[ignore] // Requires downloading model
     fn test_embedder_creation() {
@@ -683,6 +1092,38 @@ mod tests {
         assert_eq!(embedder.dimensions(), 384);
     }
 
+    /// Resident set size of this process in MB (Linux only)
+    #[cfg(target_os = "linux")]
+    fn rss_mb() -> f64 {
+        std::fs::read_to_string("/proc/self/status")
+            .ok()
+            .and_then(|status| {
+                status
+                    .lines()
+                    .find(|line| line.starts_with("VmRSS:"))
+                    .and_then(|line| line.split_whitespace().nth(1))
+                    .and_then(|kb| kb.parse::<f64>().ok())
+            })
+            .map(|kb| kb / 1024.0)
+            .unwrap_or(0.0)
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[ignore] // Requires model
+    fn test_shutdown_releases_model_memory() {
+        let mut embedder = FastEmbedder::new().unwrap();
+        embedder.embed_one("warm up the session").unwrap();
+        let loaded = rss_mb();
+
+        embedder.shutdown();
+        let released = rss_mb();
+
+        assert!(!embedder.is_loaded());
+        assert!(embedder.embed_one("after shutdown").is_err());
+        assert!(released < loaded);
+    }
+
     #[test]
     #[ignore] // Requires model
     fn test_embed_single_text() {
@@ -735,6 +1176,140 @@ mod tests {
         assert!(sim_1_2 > 0.7); // Should be quite similar
     }
 
+    #[test]
+    fn test_batch_size_override() {
+        // Adaptive default by model size
+        assert_eq!(resolve_batch_size(None, None, 384), 256);
+        assert_eq!(resolve_batch_size(None, None, 768), 128);
+        assert_eq!(resolve_batch_size(None, None, 1536), 64);
+        assert_eq!(resolve_batch_size(None, Some("48"), 384), 48);
+        assert_eq!(resolve_batch_size(None, Some("0"), 384), 256);
+
+        // --batch-size wins over both the env var and the adaptive size
+        assert_eq!(resolve_batch_size(Some(8), None, 384), 8);
+        assert_eq!(resolve_batch_size(Some(8), Some("48"), 1536), 8);
+    }
+
+    #[test]
+    fn test_truncate_dims_validation() {
+        let mxbai = ModelType::MxbaiEmbedXSmallV1;
+        assert_eq!(mxbai.validate_truncate_dims(128).unwrap(), 128);
+        assert!(mxbai.validate_truncate_dims(0).is_err());
+        assert!(mxbai.validate_truncate_dims(512).is_err());
+        assert!(ModelType::AllMiniLML6V2Q
+            .validate_truncate_dims(128)
+            .is_err());
+
+        assert_eq!(mxbai.truncation_for(128), Some(128));
+        assert_eq!(mxbai.truncation_for(384), None);
+    }
+
+    #[test]
+    fn test_truncate_embedding_renormalizes() {
+        let mut vector = vec![3.0, 4.0, 12.0, 0.5, 0.25, 0.1];
+        truncate_embedding(&mut vector, 2);
+
+        assert_eq!(vector.len(), 2);
+        assert!((vector[0] - 0.6).abs() < 1e-6);
+        assert!((vector[1] - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_truncated_search_ranks_known_match_first() {
+        use crate::chunker::{Chunk, ChunkKind};
+        use crate::embed::EmbeddedChunk;
+        use crate::vectordb::VectorStore;
+
+        // Matryoshka-style vectors: the leading dimensions carry most of the signal
+        let full = [
+            ("auth.rs", vec![0.9, 0.1, 0.0, 0.1, 0.05, -0.05, 0.02, 0.01]),
+            ("math.rs", vec![0.1, 0.9, 0.1, 0.0, -0.05, 0.05, 0.01, 0.02]),
+            ("io.rs", vec![0.0, 0.1, 0.9, 0.1, 0.02, 0.01, -0.05, 0.05]),
+        ];
+        let mut query = vec![0.8, 0.2, 0.05, 0.05, 0.3, 0.3, 0.3, 0.3];
+        truncate_embedding(&mut query, 4);
+
+        let chunks: Vec<EmbeddedChunk> = full
+            .iter()
+            .map(|(path, embedding)| {
+                let mut embedding = embedding.clone();
+                truncate_embedding(&mut embedding, 4);
+                assert_eq!(embedding.len(), 4);
+                EmbeddedChunk::new(
+                    Chunk::new(
+                        format!("// {}", path),
+                        0,
+                        1,
+                        ChunkKind::Function,
+                        path.to_string(),
+                    ),
+                    embedding,
+                )
+            })
+            .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = VectorStore::new(dir.path(), 4).unwrap();
+        store.insert_chunks(chunks).unwrap();
+        store.build_index().unwrap();
+
+        let results = store.search(&query, 3).unwrap();
+        assert_eq!(results[0].path, "auth.rs");
+    }
+
+    #[test]
+    fn test_user_defined_models_live_under_the_cache_root() {
+        let cache = tempfile::tempdir().unwrap();
+        let root = cache.path().join("models");
+
+        // The directory the mxbai loader reads from and downloads into
+        let mxbai_dir = FastEmbedder::user_defined_dir_in(
+            &root,
+            ModelType::MxbaiEmbedXSmallV1.user_defined_cache_segments(),
+        );
+
+        assert_eq!(
+            mxbai_dir,
+            root.join("user-defined/mixedbread-ai/mxbai-embed-xsmall-v1")
+        );
+    }
+
+    #[test]
+    fn test_offline_errors_when_model_not_cached() {
+        let cache = tempfile::tempdir().unwrap();
+
+        let err = FastEmbedder::ensure_cached(ModelType::AllMiniLML6V2Q, cache.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Offline mode"), "{}", err);
+        assert!(err.contains(&cache.path().display().to_string()), "{}", err);
+        assert!(err.contains("refs/main"), "{}", err);
+        assert!(err.contains("tokenizer.json"), "{}", err);
+
+        // Partially downloaded user-defined model: only the missing files are listed
+        let model = ModelType::MxbaiEmbedXSmallV1;
+        let dir =
+            FastEmbedder::user_defined_dir_in(cache.path(), model.user_defined_cache_segments());
+        std::fs::create_dir_all(dir.join("onnx")).unwrap();
+        for file in ["tokenizer.json", "config.json", "onnx/model.onnx"] {
+            std::fs::write(dir.join(file), b"{}").unwrap();
+        }
+        let err = FastEmbedder::ensure_cached(model, cache.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&dir.display().to_string()), "{}", err);
+        assert!(
+            err.contains("Missing: special_tokens_map.json, tokenizer_config.json"),
+            "{}",
+            err
+        );
+
+        for file in ["special_tokens_map.json", "tokenizer_config.json"] {
+            std::fs::write(dir.join(file), b"{}").unwrap();
+        }
+        assert!(FastEmbedder::ensure_cached(model, cache.path()).is_ok());
+    }
+
     fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
         let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
         let mag_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
@@ -2,20 +2,160 @@ mod batch;
 mod cache;
 mod embedder;
 mod persistent_cache;
+mod remote;
+#[cfg(test)]
+pub(crate) mod testing;
 
 pub use batch::{BatchEmbedder, EmbeddedChunk};
-pub use cache::{CacheStats, CachedBatchEmbedder};
-pub use embedder::{FastEmbedder, ModelType};
+pub use cache::{CacheStats, CachedBatchEmbedder, EmbeddingCache};
+pub use embedder::{l2_normalize, truncate_embedding, Embedder, FastEmbedder, ModelType};
 pub use persistent_cache::PersistentEmbeddingCache;
+pub use remote::{RemoteEmbedder, REMOTE_MODEL_SHORT_NAME};
+
+use crate::config::{EmbeddingConfig, EmbeddingProvider};
+use crate::error::DemongrepError;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Process-wide embedding batch size from `--batch-size` (0 = adaptive)
+static BATCH_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+/// Override the embedding batch size for every service created afterwards
+pub fn set_batch_size(batch_size: Option<usize>) -> Result<()> {
+    let batch_size = batch_size.map(check_batch_size).transpose()?;
+    BATCH_SIZE.store(batch_size.unwrap_or(0), Ordering::SeqCst);
+    Ok(())
+}
+
+/// Validate a batch size from `--batch-size` or `DEMONGREP_BATCH_SIZE`
+pub(crate) fn check_batch_size(batch_size: usize) -> Result<usize> {
+    if batch_size == 0 {
+        return Err(anyhow::anyhow!("Batch size must be greater than 0"));
+    }
+    Ok(batch_size)
+}
+
+/// Embedding batch size set with `set_batch_size`, if any
+pub fn batch_size() -> Option<usize> {
+    match BATCH_SIZE.load(Ordering::SeqCst) {
+        0 => None,
+        size => Some(size),
+    }
+}
+
+/// Process-wide offline mode from `--offline`
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Refuse to download models for every embedder created afterwards
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::SeqCst);
+}
+
+/// Whether models must already be on disk: set by `--offline`,
+/// `DEMONGREP_OFFLINE=1` or a configured `embedding.model_dir`
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::SeqCst)
+        || offline_from_env(std::env::var("DEMONGREP_OFFLINE").ok().as_deref())
+        || model_dir().is_some()
+}
+
+fn offline_from_env(value: Option<&str>) -> bool {
+    value.is_some_and(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+}
+
+/// Process-wide model cache directory from `--model-cache-dir`
+static MODEL_CACHE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Cache models under `dir` for every embedder created afterwards, overriding
+/// `FASTEMBED_CACHE_DIR` and `embedding.model_dir`
+///
+/// The directory is created if needed and must be writable.
+pub fn set_model_cache_dir(dir: Option<PathBuf>) -> Result<()> {
+    if let Some(dir) = &dir {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create model cache dir {}", dir.display()))?;
+        let probe = dir.join(".demongrep-write-test");
+        std::fs::write(&probe, b"")
+            .with_context(|| format!("Model cache dir {} is not writable", dir.display()))?;
+        let _ = std::fs::remove_file(&probe);
+    }
+    *MODEL_CACHE_DIR.write().unwrap_or_else(|e| e.into_inner()) = dir;
+    Ok(())
+}
+
+/// Model cache directory set with `set_model_cache_dir`, if any
+pub fn model_cache_dir() -> Option<PathBuf> {
+    MODEL_CACHE_DIR
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Process-wide local model directory from the project's `embedding.model_dir`
+static MODEL_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Load models from `dir` for every embedder and reranker created afterwards
+///
+/// Resolved once at startup from the project config, so creating a model
+/// doesn't re-read it.
+pub fn set_model_dir(dir: Option<PathBuf>) {
+    *MODEL_DIR.write().unwrap_or_else(|e| e.into_inner()) = dir;
+}
+
+/// Local model directory set with `set_model_dir`, laid out like the model cache
+pub fn model_dir() -> Option<PathBuf> {
+    MODEL_DIR.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Model cache: `--model-cache-dir`, then `FASTEMBED_CACHE_DIR`, then
+/// `embedding.model_dir`, then fastembed's default
+pub fn cache_root() -> PathBuf {
+    model_cache_dir()
+        .or_else(|| std::env::var("FASTEMBED_CACHE_DIR").map(PathBuf::from).ok())
+        .or_else(model_dir)
+        .unwrap_or_else(|| PathBuf::from(fastembed::get_cache_dir()))
+}
 
-use anyhow::Result;
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+/// Directory a Hugging Face model is cached in, and which of `files` are
+/// missing from its `refs/main` snapshot (relative to that directory)
+pub(crate) fn missing_snapshot_files(
+    cache_root: &Path,
+    model_code: &str,
+    files: &[&str],
+) -> (PathBuf, Vec<String>) {
+    let dir = cache_root.join(format!("models--{}", model_code.replace('/', "--")));
+    let revision = std::fs::read_to_string(dir.join("refs").join("main"))
+        .ok()
+        .map(|revision| revision.trim().to_string())
+        .filter(|revision| !revision.is_empty());
+    let missing = match revision {
+        Some(revision) => {
+            let snapshot = Path::new("snapshots").join(revision);
+            files
+                .iter()
+                .filter(|file| !dir.join(&snapshot).join(file).exists())
+                .map(|file| snapshot.join(file).display().to_string())
+                .collect()
+        }
+        None => std::iter::once("refs/main".to_string())
+            .chain(
+                files
+                    .iter()
+                    .map(|file| format!("snapshots/<revision>/{}", file)),
+            )
+            .collect(),
+    };
+    (dir, missing)
+}
 
 /// High-level embedding service that combines all features
 pub struct EmbeddingService {
     cached_embedder: CachedBatchEmbedder,
     model_type: ModelType,
+    model_name: String,
+    remote: bool,
 }
 
 impl EmbeddingService {
@@ -26,30 +166,95 @@ impl EmbeddingService {
 
     /// Create a new embedding service with specified model (in-memory cache only)
     pub fn with_model(model_type: ModelType) -> Result<Self> {
-        let embedder = FastEmbedder::with_model(model_type)?;
-        let arc_embedder = Arc::new(Mutex::new(embedder));
-        let batch_embedder = BatchEmbedder::new(arc_embedder);
-        let cached_embedder = CachedBatchEmbedder::new(batch_embedder);
-
-        Ok(Self {
-            cached_embedder,
-            model_type,
-        })
+        Self::with_options(model_type, None, None)
     }
 
     /// Create a new embedding service with persistent disk cache
     pub fn with_model_and_db(model_type: ModelType, db_path: &Path) -> Result<Self> {
-        let embedder = FastEmbedder::with_model(model_type)?;
-        let arc_embedder = Arc::new(Mutex::new(embedder));
-        let batch_embedder = BatchEmbedder::new(arc_embedder);
+        Self::with_options(model_type, Some(db_path), None)
+    }
+
+    /// Create an embedding service, optionally with a persistent disk cache and
+    /// Matryoshka truncation to `truncate_dims`
+    ///
+    /// Truncated embeddings are cached under their own key so they never mix
+    /// with full-size vectors in the persistent cache.
+    pub fn with_options(
+        model_type: ModelType,
+        db_path: Option<&Path>,
+        truncate_dims: Option<usize>,
+    ) -> Result<Self> {
+        let mut embedder = FastEmbedder::with_model(model_type)?;
+        embedder.set_truncate_dims(truncate_dims)?;
+        let batch_embedder = match batch_size() {
+            Some(size) => BatchEmbedder::with_batch_size(Arc::new(Mutex::new(embedder)), size),
+            None => BatchEmbedder::new(Arc::new(Mutex::new(embedder))),
+        };
+
+        let cache_key = match truncate_dims {
+            Some(dims) => format!("{}@{}", model_type.short_name(), dims),
+            None => model_type.short_name().to_string(),
+        };
+        Self::with_batch_embedder(batch_embedder, db_path, &cache_key, model_type)
+    }
+
+    /// Create an embedding service for the `[embedding]` provider in the
+    /// project config: the local `model_type`, or a `RemoteEmbedder`
+    ///
+    /// `dimensions` is the size of the index being searched or written; for
+    /// local models anything below the model's size means Matryoshka truncation.
+    pub fn from_config(
+        config: &EmbeddingConfig,
+        model_type: ModelType,
+        db_path: Option<&Path>,
+        dimensions: usize,
+    ) -> Result<Self> {
+        match config.provider {
+            EmbeddingProvider::Local => {
+                Self::with_options(model_type, db_path, model_type.truncation_for(dimensions))
+            }
+            EmbeddingProvider::Remote => Self::remote(config, db_path, Some(dimensions)),
+        }
+    }
+
+    /// Create an embedding service backed by an OpenAI-compatible endpoint
+    pub fn remote(
+        config: &EmbeddingConfig,
+        db_path: Option<&Path>,
+        dimensions: Option<usize>,
+    ) -> Result<Self> {
+        let embedder = RemoteEmbedder::from_config(config, dimensions)?;
+        let cache_key = format!("remote-{}@{}", config.model, embedder.dimensions());
+        let model_type = Embedder::model_type(&embedder);
+        let batch_size = batch_size().unwrap_or(config.batch_size);
+        let batch_embedder =
+            BatchEmbedder::with_batch_size(Arc::new(Mutex::new(embedder)), batch_size);
+        let mut service =
+            Self::with_batch_embedder(batch_embedder, db_path, &cache_key, model_type)?;
+        service.remote = true;
+        Ok(service)
+    }
 
-        let persistent_cache = PersistentEmbeddingCache::new(db_path, model_type.short_name())?;
-        let cached_embedder =
-            CachedBatchEmbedder::with_persistent_cache(batch_embedder, persistent_cache);
+    pub(crate) fn with_batch_embedder(
+        batch_embedder: BatchEmbedder,
+        db_path: Option<&Path>,
+        cache_key: &str,
+        model_type: ModelType,
+    ) -> Result<Self> {
+        let model_name = batch_embedder.embedder_info().0;
+        let cached_embedder = match db_path {
+            Some(db_path) => {
+                let persistent_cache = PersistentEmbeddingCache::new(db_path, cache_key)?;
+                CachedBatchEmbedder::with_persistent_cache(batch_embedder, persistent_cache)
+            }
+            None => CachedBatchEmbedder::new(batch_embedder),
+        };
 
         Ok(Self {
             cached_embedder,
             model_type,
+            model_name,
+            remote: false,
         })
     }
 
@@ -68,12 +273,21 @@ impl EmbeddingService {
 
     /// Embed query text
     pub fn embed_query(&mut self, query: &str) -> Result<Vec<f32>> {
+        self.embed_query_text(query, false)
+    }
+
+    /// Embed query text exactly as given, skipping model-specific query prefixes
+    pub fn embed_query_verbatim(&mut self, query: &str) -> Result<Vec<f32>> {
+        self.embed_query_text(query, true)
+    }
+
+    fn embed_query_text(&mut self, query: &str, verbatim: bool) -> Result<Vec<f32>> {
         let embedder_arc = &self.cached_embedder.batch_embedder.embedder;
         let mut guard = embedder_arc
             .lock()
             .map_err(|e| anyhow::anyhow!("Embedding mutex poisoned: {}", e))?;
-        let formatted = guard.model_type().format_query(query);
-        guard.embed_one(&formatted)
+        let text = guard.model_type().query_text(query, verbatim);
+        guard.embed_one(&text)
     }
 
     /// Embed a code snippet as a passage (for code-to-code similarity search).
@@ -89,14 +303,90 @@ impl EmbeddingService {
         guard.embed_one(&formatted)
     }
 
+    /// Release the embedding model's ONNX session
+    ///
+    /// The service can't embed afterwards; dropping it has the same effect,
+    /// this just makes the release point explicit (e.g. before switching models).
+    pub fn shutdown(&mut self) {
+        if let Ok(mut guard) = self.cached_embedder.batch_embedder.embedder.lock() {
+            guard.shutdown();
+        }
+    }
+
+    /// Model and dimensions an index was built with, from its `metadata.json`
+    ///
+    /// Indexes written before the model was recorded are read as the default
+    /// model, and a missing `dimensions` as the model's full size. Remote
+    /// indexes read as [`RemoteEmbedder::TEXT_FORMAT`]; build their embedder
+    /// with [`EmbeddingService::from_config`].
+    pub fn index_model(metadata_path: &Path) -> Result<(ModelType, usize), DemongrepError> {
+        let path = metadata_path.display().to_string();
+        let content = match std::fs::read_to_string(metadata_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(DemongrepError::MetadataNotFound { path })
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| DemongrepError::InvalidMetadata {
+                path: path.clone(),
+                reason: e.to_string(),
+            })?;
+
+        let model_type = match json.get("model_short_name").and_then(|v| v.as_str()) {
+            Some(REMOTE_MODEL_SHORT_NAME) => RemoteEmbedder::TEXT_FORMAT,
+            Some(name) => {
+                ModelType::from_str(name).ok_or_else(|| DemongrepError::UnknownModel {
+                    model: name.to_string(),
+                    available: ModelType::available_names(),
+                })?
+            }
+            None => ModelType::default(),
+        };
+        let dimensions = json
+            .get("dimensions")
+            .and_then(|v| v.as_u64())
+            .map_or(model_type.dimensions(), |dims| dims as usize);
+
+        Ok((model_type, dimensions))
+    }
+
+    /// Like [`index_model`](Self::index_model), but fails with
+    /// [`DemongrepError::ModelMismatch`] when the index was built with a
+    /// different model than `requested`
+    pub fn model_compatible_with(
+        metadata_path: &Path,
+        requested: ModelType,
+    ) -> Result<(ModelType, usize), DemongrepError> {
+        let (model_type, dimensions) = Self::index_model(metadata_path)?;
+        if model_type != requested {
+            return Err(DemongrepError::ModelMismatch {
+                indexed: model_type.short_name().to_string(),
+                requested: requested.short_name().to_string(),
+            });
+        }
+        Ok((model_type, dimensions))
+    }
+
     /// Get embedding dimensions
     pub fn dimensions(&self) -> usize {
         self.cached_embedder.dimensions()
     }
 
+    /// Check whether the embedder L2-normalizes its output
+    pub fn normalizes_embeddings(&self) -> bool {
+        self.cached_embedder
+            .batch_embedder
+            .embedder
+            .lock()
+            .map(|guard| guard.normalizes())
+            .unwrap_or(false)
+    }
+
     /// Get model information
     pub fn model_name(&self) -> &str {
-        self.model_type.name()
+        &self.model_name
     }
 
     /// Get model type
@@ -106,7 +396,11 @@ impl EmbeddingService {
 
     /// Get model short name (for storage)
     pub fn model_short_name(&self) -> &str {
-        self.model_type.short_name()
+        if self.remote {
+            REMOTE_MODEL_SHORT_NAME
+        } else {
+            self.model_type.short_name()
+        }
     }
 
     /// Get cache statistics
@@ -114,6 +408,17 @@ impl EmbeddingService {
         self.cached_embedder.cache_stats()
     }
 
+    /// The in-memory cache, whose stats stay readable while the service is
+    /// busy embedding
+    pub fn shared_cache(&self) -> Arc<EmbeddingCache> {
+        self.cached_embedder.shared_cache()
+    }
+
+    /// Number of embeddings in the persistent (on-disk) cache, if there is one
+    pub fn persistent_cache_len(&self) -> Option<usize> {
+        self.cached_embedder.persistent_cache_len()
+    }
+
     /// Clear the cache
     pub fn clear_cache(&self) {
         self.cached_embedder.clear_cache();
@@ -156,6 +461,55 @@ mod tests {
         assert_eq!(model.dimensions(), 384);
     }
 
+    #[test]
+    fn test_offline_from_env() {
+        assert!(offline_from_env(Some("1")));
+        assert!(offline_from_env(Some("true")));
+        assert!(!offline_from_env(Some("0")));
+        assert!(!offline_from_env(None));
+    }
+
+    #[test]
+    fn test_index_model_from_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metadata.json");
+
+        std::fs::write(
+            &path,
+            r#"{"model_short_name": "jina-v5-nano", "dimensions": 256}"#,
+        )
+        .unwrap();
+        let model = ModelType::from_str("jina-v5-nano").unwrap();
+        assert_eq!(EmbeddingService::index_model(&path).unwrap(), (model, 256));
+        assert!(EmbeddingService::model_compatible_with(&path, model).is_ok());
+        assert!(matches!(
+            EmbeddingService::model_compatible_with(&path, ModelType::default()),
+            Err(DemongrepError::ModelMismatch { .. })
+        ));
+
+        std::fs::write(
+            &path,
+            r#"{"model_short_name": "remote", "provider": "remote", "dimensions": 1536}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            EmbeddingService::index_model(&path).unwrap(),
+            (RemoteEmbedder::TEXT_FORMAT, 1536)
+        );
+
+        std::fs::write(&path, r#"{"model_short_name": "no-such-model"}"#).unwrap();
+        assert!(matches!(
+            EmbeddingService::index_model(&path),
+            Err(DemongrepError::UnknownModel { model, .. }) if model == "no-such-model"
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            EmbeddingService::index_model(&path),
+            Err(DemongrepError::MetadataNotFound { .. })
+        ));
+    }
+
     #[test]
     #[ignore] // Requires model download
     fn test_embedding_service_creation() {
@@ -0,0 +1,347 @@
+use super::embedder::{l2_normalize, normalize_from_env, Embedder, ModelType};
+use crate::config::EmbeddingConfig;
+use crate::info_print;
+use anyhow::{anyhow, Context, Result};
+use std::time::Duration;
+
+/// Model name `metadata.json` records for indexes built with the remote provider
+pub const REMOTE_MODEL_SHORT_NAME: &str = "remote";
+
+/// Embedder backed by an OpenAI-compatible `/v1/embeddings` endpoint
+///
+/// Configured through the `[embedding]` section of `.demongrep.toml`:
+///
+/// ```toml
+/// [embedding]
+/// provider = "remote"
+/// base_url = "https://api.openai.com"
+/// model = "text-embedding-3-small"
+/// api_key_env = "OPENAI_API_KEY"
+/// dimensions = 1536
+/// ```
+pub struct RemoteEmbedder {
+    agent: ureq::Agent,
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+    dimensions: usize,
+    batch_size: usize,
+    normalize: bool,
+}
+
+impl RemoteEmbedder {
+    /// Local model whose text formatting remote models share: the texts go
+    /// unformatted, with no query or passage prefixes
+    pub const TEXT_FORMAT: ModelType = ModelType::AllMiniLML6V2Q;
+
+    /// Create a remote embedder from the `[embedding]` config
+    ///
+    /// `dimensions` overrides the configured dimensions (e.g. the size stored
+    /// in an existing index).
+    pub fn from_config(config: &EmbeddingConfig, dimensions: Option<usize>) -> Result<Self> {
+        let base_url = config
+            .base_url
+            .as_deref()
+            .ok_or_else(|| anyhow!("embedding.base_url is required for the remote provider"))?;
+        let dimensions = dimensions
+            .or(config.dimensions)
+            .ok_or_else(|| anyhow!("embedding.dimensions is required for the remote provider"))?;
+        if config.batch_size == 0 {
+            return Err(anyhow!("embedding.batch_size must be greater than 0"));
+        }
+        let api_key = std::env::var(&config.api_key_env)
+            .ok()
+            .filter(|key| !key.trim().is_empty());
+
+        info_print!("🌐 Using remote embedding model: {}", config.model);
+        info_print!("   Endpoint: {}", embeddings_endpoint(base_url));
+        info_print!("   Dimensions: {}", dimensions);
+
+        Ok(Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(120))
+                .build(),
+            endpoint: embeddings_endpoint(base_url),
+            model: config.model.clone(),
+            api_key,
+            dimensions,
+            batch_size: config.batch_size,
+            normalize: normalize_from_env(),
+        })
+    }
+
+    /// Embed up to `batch_size` texts with a single request
+    fn request_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "input": texts,
+        });
+
+        let mut request = self
+            .agent
+            .post(&self.endpoint)
+            .set("Content-Type", "application/json");
+        if let Some(key) = &self.api_key {
+            request = request.set("Authorization", &format!("Bearer {}", key));
+        }
+
+        let response = match request.send_string(&body.to_string()) {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, response)) => {
+                let detail = response.into_string().unwrap_or_default();
+                return Err(anyhow!(
+                    "Embedding request to {} failed with status {}: {}",
+                    self.endpoint,
+                    code,
+                    detail.trim()
+                ));
+            }
+            Err(e) => {
+                return Err(anyhow!(
+                    "Embedding request to {} failed: {}",
+                    self.endpoint,
+                    e
+                ))
+            }
+        };
+        let text = response
+            .into_string()
+            .with_context(|| format!("Failed to read response body from {}", self.endpoint))?;
+
+        parse_embeddings_response(&text, texts.len(), self.dimensions)
+    }
+}
+
+impl Embedder for RemoteEmbedder {
+    fn embed_batch_refs(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            embeddings.extend(self.request_batch(batch)?);
+        }
+        if self.normalize {
+            embeddings.iter_mut().for_each(|e| l2_normalize(e));
+        }
+        Ok(embeddings)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    /// Remote models get the texts unformatted (no query/passage prefixes)
+    fn model_type(&self) -> ModelType {
+        Self::TEXT_FORMAT
+    }
+
+    fn normalizes(&self) -> bool {
+        self.normalize
+    }
+}
+
+/// `{base_url}/v1/embeddings`, unless the base URL already ends in `/v1`
+fn embeddings_endpoint(base_url: &str) -> String {
+    let base = base_url.trim_end_matches('/');
+    if base.ends_with("/v1") {
+        format!("{}/embeddings", base)
+    } else {
+        format!("{}/v1/embeddings", base)
+    }
+}
+
+/// Extract the embeddings from a `/v1/embeddings` response, in input order
+fn parse_embeddings_response(
+    body: &str,
+    expected: usize,
+    dimensions: usize,
+) -> Result<Vec<Vec<f32>>> {
+    let json: serde_json::Value =
+        serde_json::from_str(body).context("Invalid JSON in embeddings response")?;
+    let data = json
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or_else(|| anyhow!("Embeddings response has no `data` array"))?;
+
+    let mut indexed = Vec::with_capacity(data.len());
+    for (position, item) in data.iter().enumerate() {
+        let index = item
+            .get("index")
+            .and_then(|i| i.as_u64())
+            .map(|i| i as usize)
+            .unwrap_or(position);
+        let embedding: Vec<f32> = item
+            .get("embedding")
+            .and_then(|e| e.as_array())
+            .ok_or_else(|| anyhow!("Embeddings response item {} has no `embedding`", index))?
+            .iter()
+            .map(|v| v.as_f64().map(|v| v as f32))
+            .collect::<Option<_>>()
+            .ok_or_else(|| anyhow!("Embedding {} contains a non-numeric value", index))?;
+        if embedding.len() != dimensions {
+            return Err(anyhow!(
+                "Remote model returned {} dimensions, expected {} (check embedding.dimensions)",
+                embedding.len(),
+                dimensions
+            ));
+        }
+        indexed.push((index, embedding));
+    }
+
+    if indexed.len() != expected {
+        return Err(anyhow!(
+            "Embeddings response has {} items for {} inputs",
+            indexed.len(),
+            expected
+        ));
+    }
+    indexed.sort_by_key(|(index, _)| *index);
+    Ok(indexed
+        .into_iter()
+        .map(|(_, embedding)| embedding)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EmbeddingProvider;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Serve `/v1/embeddings` on a local port, answering each request with the
+    /// vector `[i, 1, 0]` for input `i` (listed in reverse order) and reporting
+    /// the authorization header and input count of every request
+    fn mock_server(requests: usize) -> (String, mpsc::Receiver<(Option<String>, usize)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                assert!(request_line.starts_with("POST /v1/embeddings "));
+
+                let mut content_length = 0;
+                let mut authorization = None;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let (name, value) = line.split_once(':').unwrap();
+                    match name.to_lowercase().as_str() {
+                        "content-length" => content_length = value.trim().parse().unwrap(),
+                        "authorization" => authorization = Some(value.trim().to_string()),
+                        _ => {}
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                assert_eq!(request["model"], "test-embed");
+                let inputs = request["input"].as_array().unwrap().len();
+
+                let data: Vec<_> = (0..inputs)
+                    .rev()
+                    .map(|i| serde_json::json!({ "index": i, "embedding": [i as f32, 1.0, 0.0] }))
+                    .collect();
+                let response = serde_json::json!({ "object": "list", "data": data }).to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+                tx.send((authorization, inputs)).unwrap();
+            }
+        });
+
+        (url, rx)
+    }
+
+    fn test_config(base_url: &str, api_key_env: &str) -> EmbeddingConfig {
+        EmbeddingConfig {
+            provider: EmbeddingProvider::Remote,
+            model: "test-embed".to_string(),
+            batch_size: 2,
+            base_url: Some(base_url.to_string()),
+            api_key_env: api_key_env.to_string(),
+            dimensions: Some(3),
+            ..EmbeddingConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_remote_embedder_batches_and_orders_results() {
+        let (url, requests) = mock_server(2);
+        std::env::set_var("DEMONGREP_TEST_REMOTE_KEY", "secret");
+        let mut embedder =
+            RemoteEmbedder::from_config(&test_config(&url, "DEMONGREP_TEST_REMOTE_KEY"), None)
+                .unwrap();
+        embedder.normalize = false;
+
+        let texts: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let embeddings = embedder.embed_batch_refs(&texts).unwrap();
+
+        // Three texts with batch size 2: two requests, results back in input order
+        assert_eq!(
+            embeddings,
+            vec![
+                vec![0.0, 1.0, 0.0],
+                vec![1.0, 1.0, 0.0],
+                vec![0.0, 1.0, 0.0]
+            ]
+        );
+        let received: Vec<_> = requests.iter().collect();
+        assert_eq!(
+            received,
+            vec![
+                (Some("Bearer secret".to_string()), 2),
+                (Some("Bearer secret".to_string()), 1)
+            ]
+        );
+        assert_eq!(Embedder::dimensions(&embedder), 3);
+        assert_eq!(Embedder::model_name(&embedder), "test-embed");
+    }
+
+    #[test]
+    fn test_remote_embedder_normalizes_and_rejects_wrong_dimensions() {
+        let (url, _requests) = mock_server(2);
+        let config = test_config(&url, "DEMONGREP_TEST_UNSET_KEY");
+
+        let mut embedder = RemoteEmbedder::from_config(&config, None).unwrap();
+        embedder.normalize = true;
+        let texts = vec!["x".to_string(), "y".to_string()];
+        let embeddings = embedder.embed_batch_refs(&texts).unwrap();
+        let expected = 1.0 / 2f32.sqrt();
+        assert!((embeddings[1][0] - expected).abs() < 1e-6);
+        assert!((embeddings[1][1] - expected).abs() < 1e-6);
+
+        let mut embedder = RemoteEmbedder::from_config(&config, Some(4)).unwrap();
+        let err = embedder.embed_one("x").unwrap_err();
+        assert!(err.to_string().contains("expected 4"));
+    }
+
+    #[test]
+    fn test_embeddings_endpoint() {
+        assert_eq!(
+            embeddings_endpoint("https://api.openai.com"),
+            "https://api.openai.com/v1/embeddings"
+        );
+        assert_eq!(
+            embeddings_endpoint("http://localhost:8080/v1/"),
+            "http://localhost:8080/v1/embeddings"
+        );
+    }
+}
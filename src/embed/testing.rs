@@ -0,0 +1,39 @@
+use super::{EmbeddedChunk, Embedder, ModelType};
+use crate::chunker::Chunk;
+use anyhow::Result;
+
+/// The vector every stub embedding gets
+const STUB_VECTOR: [f32; 4] = [1.0, 0.0, 0.0, 0.0];
+
+/// Embeds every text as the same unit vector, without a model
+pub(crate) struct StubEmbedder;
+
+impl Embedder for StubEmbedder {
+    fn embed_batch_refs(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|_| STUB_VECTOR.to_vec()).collect())
+    }
+
+    fn dimensions(&self) -> usize {
+        STUB_VECTOR.len()
+    }
+
+    fn model_name(&self) -> &str {
+        "stub"
+    }
+
+    fn model_type(&self) -> ModelType {
+        ModelType::default()
+    }
+
+    fn normalizes(&self) -> bool {
+        true
+    }
+}
+
+/// Embed `chunks` the way [`StubEmbedder`] does, for code taking an embed function
+pub(crate) fn stub_embed(chunks: Vec<Chunk>) -> Result<Vec<EmbeddedChunk>> {
+    Ok(chunks
+        .into_iter()
+        .map(|chunk| EmbeddedChunk::new(chunk, STUB_VECTOR.to_vec()))
+        .collect())
+}
@@ -32,6 +32,18 @@ pub enum DemongrepError {
         source: anyhow::Error,
     },
 
+    #[error("No index metadata at {path}; run `demongrep index` to rebuild it")]
+    MetadataNotFound { path: String },
+
+    #[error("Invalid index metadata at {path}: {reason}")]
+    InvalidMetadata { path: String, reason: String },
+
+    #[error(
+        "Full-text index schema is v{found} but this version expects v{expected}; \
+         run `demongrep clear` and `demongrep index` to rebuild it"
+    )]
+    FtsSchemaMismatch { found: u32, expected: u32 },
+
     // === Search errors ===
     #[error("Search failed: {reason}")]
     SearchError { reason: String },
@@ -51,6 +63,15 @@ pub enum DemongrepError {
     #[error("Invalid configuration: {details}")]
     ConfigError { details: String },
 
+    #[error("Unknown model: '{model}'. Available models:\n  {available}")]
+    UnknownModel { model: String, available: String },
+
+    #[error(
+        "Index was built with '{indexed}' but '{requested}' was requested; \
+         re-index with `demongrep index --force --model {requested}`"
+    )]
+    ModelMismatch { indexed: String, requested: String },
+
     // === Concurrency errors ===
     #[error("Mutex lock failed (poisoned): {context}")]
     LockError { context: String },
@@ -70,3 +91,81 @@ pub enum DemongrepError {
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
+
+impl DemongrepError {
+    /// Stable machine-readable code for `--json` error output
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ModelLoadError { .. } => "model_load_failed",
+            Self::EmbeddingError { .. } => "embedding_failed",
+            Self::DatabaseNotFound { .. } => "database_not_found",
+            Self::DatabaseError { .. } => "database_error",
+            Self::MetadataNotFound { .. } => "metadata_not_found",
+            Self::InvalidMetadata { .. } => "invalid_metadata",
+            Self::FtsSchemaMismatch { .. } => "fts_schema_mismatch",
+            Self::SearchError { .. } => "search_failed",
+            Self::NoDatabases => "no_databases",
+            Self::ChunkingError { .. } => "chunking_failed",
+            Self::ConfigError { .. } => "invalid_config",
+            Self::UnknownModel { .. } => "unknown_model",
+            Self::ModelMismatch { .. } => "model_mismatch",
+            Self::LockError { .. } => "lock_poisoned",
+            Self::RerankError { .. } => "rerank_failed",
+            Self::Io(_) => "io_error",
+            Self::Other(_) => "internal_error",
+        }
+    }
+}
+
+/// Error object printed instead of human text in `--json` / `--agent` mode:
+/// `{"error": {"code": ..., "message": ...}}`
+///
+/// The code comes from the first `DemongrepError` in the error chain; other
+/// errors are `io_error` or `internal_error`.
+pub fn error_json(err: &anyhow::Error) -> serde_json::Value {
+    let code = err
+        .chain()
+        .find_map(|e| {
+            e.downcast_ref::<DemongrepError>()
+                .map(DemongrepError::code)
+                .or_else(|| e.downcast_ref::<std::io::Error>().map(|_| "io_error"))
+        })
+        .unwrap_or("internal_error");
+
+    serde_json::json!({
+        "error": {
+            "code": code,
+            "message": format!("{:#}", err),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_error_json_codes() {
+        let err = anyhow::Error::from(DemongrepError::DatabaseNotFound {
+            path: "/repo".to_string(),
+        });
+        let json = error_json(&err);
+        assert_eq!(json["error"]["code"], "database_not_found");
+        assert_eq!(json["error"]["message"], "Database not found: /repo");
+
+        // Context added on top keeps the underlying code
+        let err = Err::<(), _>(DemongrepError::NoDatabases)
+            .context("search failed")
+            .unwrap_err();
+        let json = error_json(&err);
+        assert_eq!(json["error"]["code"], "no_databases");
+        assert_eq!(
+            json["error"]["message"],
+            "search failed: No databases available for search"
+        );
+
+        let err = anyhow::anyhow!("something else");
+        assert_eq!(error_json(&err)["error"]["code"], "internal_error");
+    }
+}
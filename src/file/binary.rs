@@ -20,7 +20,7 @@ pub fn is_binary_file(path: &Path) -> bool {
 }
 
 /// Check if file has a known binary extension
-fn is_binary_by_extension(path: &Path) -> bool {
+pub(crate) fn is_binary_by_extension(path: &Path) -> bool {
     if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
         matches!(
             ext.to_lowercase().as_str(),
@@ -46,6 +46,9 @@ fn is_binary_by_extension(path: &Path) -> bool {
     }
 }
 
+/// Bytes at the start of a file the content check looks at (sufficient for detection)
+const BINARY_CHECK_SIZE: usize = 8192;
+
 /// Check if file content appears to be binary
 fn is_binary_by_content(path: &Path) -> bool {
     let mut file = match File::open(path) {
@@ -53,20 +56,26 @@ fn is_binary_by_content(path: &Path) -> bool {
         Err(_) => return false,
     };
 
-    // Read first 8KB (sufficient for detection)
-    let mut buffer = [0u8; 8192];
+    let mut buffer = [0u8; BINARY_CHECK_SIZE];
     let bytes_read = match file.read(&mut buffer) {
         Ok(n) => n,
         Err(_) => return false,
     };
 
+    is_binary_content(&buffer[..bytes_read])
+}
+
+/// Check if the start of a file's content (as read by [`super::read_file_head`])
+/// appears to be binary
+pub(crate) fn is_binary_content(head: &[u8]) -> bool {
+    let data = &head[..head.len().min(BINARY_CHECK_SIZE)];
+    let bytes_read = data.len();
+
     // Empty file is not binary
     if bytes_read == 0 {
         return false;
     }
 
-    let data = &buffer[..bytes_read];
-
     // Check 1: Null bytes are a strong indicator of binary content
     if data.contains(&0) {
         return true;
@@ -0,0 +1,163 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::Path;
+use tracing::warn;
+
+/// Average line length above which a file is treated as minified
+const MAX_AVERAGE_LINE_LENGTH: usize = 300;
+
+/// Files smaller than this are never flagged by their line lengths
+const MIN_MINIFIED_SIZE: usize = 1024;
+
+/// Check if a file is probably generated or minified
+///
+/// Two heuristics:
+/// 1. File name (`*.min.js`, `*.generated.rs`, protobuf output, ...)
+/// 2. Content: a "generated, do not edit" header, or lines far longer than
+///    anyone writes by hand (bundler and minifier output)
+pub fn is_generated_file(path: &Path) -> bool {
+    is_generated_by_name(path) || is_generated_content(&super::read_file_head(path))
+}
+
+/// [`is_generated_file`] with the start of the file already read (see
+/// [`super::read_file_head`]), so the walker reads each file once
+pub(crate) fn is_generated(path: &Path, head: &[u8]) -> bool {
+    is_generated_by_name(path) || is_generated_content(head)
+}
+
+/// Generated-looking files to index anyway (`indexing.allow_generated`)
+#[derive(Debug, Clone, Default)]
+pub struct AllowGenerated {
+    matcher: Option<Gitignore>,
+}
+
+impl AllowGenerated {
+    /// Allow files matching these `.gitignore`-style patterns (relative to `root`)
+    ///
+    /// Invalid patterns are logged and skipped.
+    pub fn new(root: &Path, patterns: &[String]) -> Self {
+        if patterns.is_empty() {
+            return Self::default();
+        }
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns {
+            if let Err(e) = builder.add_line(None, pattern) {
+                warn!(
+                    "Ignoring invalid allow_generated pattern '{}': {}",
+                    pattern, e
+                );
+            }
+        }
+        Self {
+            matcher: builder.build().ok(),
+        }
+    }
+
+    /// Whether `path` matches one of the patterns
+    pub fn allows(&self, path: &Path) -> bool {
+        self.matcher.as_ref().is_some_and(|allow| {
+            path.starts_with(allow.path())
+                && allow.matched_path_or_any_parents(path, false).is_ignore()
+        })
+    }
+}
+
+/// Check if the file name follows a known generated-code convention
+fn is_generated_by_name(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let name = name.to_lowercase();
+
+    const SUFFIXES: &[&str] = &[
+        // Minified and bundled web assets
+        ".min.js",
+        ".min.mjs",
+        ".min.css",
+        ".bundle.js",
+        // Protobuf / gRPC
+        ".pb.go",
+        ".pb.cc",
+        ".pb.h",
+        "_pb2.py",
+        "_pb2_grpc.py",
+        "_pb.js",
+        "_pb.d.ts",
+        // Dart build_runner, C# designers
+        ".g.dart",
+        ".freezed.dart",
+        ".designer.cs",
+    ];
+    SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+        // foo.generated.rs, api.generated.ts, ...
+        || name.contains(".generated.")
+}
+
+/// Check the start of the file for generated-code markers or minified lines
+fn is_generated_content(head: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(head);
+    has_generated_header(&text) || looks_minified(&text)
+}
+
+/// `// Code generated by protoc-gen-go. DO NOT EDIT.`, `@generated`, ...
+fn has_generated_header(text: &str) -> bool {
+    text.lines().take(5).any(|line| {
+        let line = line.to_lowercase();
+        line.contains("@generated") || (line.contains("generated") && line.contains("do not edit"))
+    })
+}
+
+fn looks_minified(text: &str) -> bool {
+    if text.len() < MIN_MINIFIED_SIZE {
+        return false;
+    }
+    let lines = text.lines().filter(|line| !line.trim().is_empty()).count();
+    lines == 0 || text.len() / lines > MAX_AVERAGE_LINE_LENGTH
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_generated_by_name() {
+        for name in [
+            "app.min.js",
+            "styles.min.css",
+            "service.pb.go",
+            "service_pb2.py",
+            "schema.generated.rs",
+            "model.g.dart",
+            "Form1.Designer.cs",
+        ] {
+            assert!(is_generated_by_name(Path::new(name)), "{}", name);
+        }
+        for name in ["main.rs", "minimal.js", "generator.py", "pb.rs"] {
+            assert!(!is_generated_by_name(Path::new(name)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_generated_by_content() {
+        let dir = TempDir::new().unwrap();
+
+        let header = dir.path().join("api.go");
+        fs::write(
+            &header,
+            "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n",
+        )
+        .unwrap();
+        assert!(is_generated_file(&header));
+
+        let minified = dir.path().join("vendor.js");
+        let body = "var a=function(b){return b*2};".repeat(100);
+        fs::write(&minified, format!("!function(){{{}}}();\n", body)).unwrap();
+        assert!(is_generated_file(&minified));
+
+        let source = dir.path().join("app.js");
+        let body = "function double(b) {\n  return b * 2;\n}\n".repeat(100);
+        fs::write(&source, body).unwrap();
+        assert!(!is_generated_file(&source));
+    }
+}
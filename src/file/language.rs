@@ -1,3 +1,5 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 /// Supported programming languages
@@ -16,7 +18,14 @@ pub enum Language {
     Php,
     Swift,
     Kotlin,
+    Dart,
+    Haskell,
     Shell,
+    Nix,
+    GraphQL,
+    Scala,
+    OCaml,
+    Jupyter,
     Markdown,
     Json,
     Yaml,
@@ -35,6 +44,120 @@ impl Language {
         Self::from_extension(extension)
     }
 
+    /// Detect language from the path, falling back to the content for files
+    /// whose extension is missing or unknown (e.g. scripts with a shebang)
+    pub fn detect(path: &Path, content: &str) -> Self {
+        match Self::from_path(path) {
+            Self::Unknown => Self::from_content(content).unwrap_or(Self::Unknown),
+            language => language,
+        }
+    }
+
+    /// Like `detect`, reading only the first line of the file when needed
+    pub fn detect_file(path: &Path) -> Self {
+        match Self::from_path(path) {
+            Self::Unknown => {
+                let mut first_line = String::new();
+                File::open(path)
+                    .map(BufReader::new)
+                    .and_then(|mut reader| reader.read_line(&mut first_line))
+                    .ok()
+                    .and_then(|_| Self::from_content(&first_line))
+                    .unwrap_or(Self::Unknown)
+            }
+            language => language,
+        }
+    }
+
+    /// Detect language from file content: a shebang or a well-known opening tag
+    pub fn from_content(content: &str) -> Option<Self> {
+        let first_line = content.lines().next()?.trim();
+        if let Some(shebang) = first_line.strip_prefix("#!") {
+            return Self::from_shebang(shebang);
+        }
+        if first_line.starts_with("<?php") {
+            return Some(Self::Php);
+        }
+        let lower = first_line.to_lowercase();
+        if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+            return Some(Self::Html);
+        }
+        None
+    }
+
+    /// Detect language from a shebang line (without the leading `#!`),
+    /// e.g. `/usr/bin/env python3` or `/bin/bash -e`
+    pub fn from_shebang(shebang: &str) -> Option<Self> {
+        let mut args = shebang.split_whitespace();
+        let mut interpreter = args.next()?.rsplit('/').next()?;
+        if interpreter == "env" {
+            // Skip env flags such as `-S`
+            interpreter = args.find(|arg| !arg.starts_with('-'))?;
+        }
+
+        // python3.11 -> python, node18 -> node
+        let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        match name {
+            "python" | "pypy" => Some(Self::Python),
+            "sh" | "bash" | "zsh" | "dash" | "ksh" | "ash" => Some(Self::Shell),
+            "node" | "nodejs" | "deno" | "bun" => Some(Self::JavaScript),
+            "ts-node" | "tsx" => Some(Self::TypeScript),
+            "ruby" => Some(Self::Ruby),
+            "php" => Some(Self::Php),
+            "runhaskell" | "runghc" => Some(Self::Haskell),
+            "dart" => Some(Self::Dart),
+            "scala" => Some(Self::Scala),
+            "ocaml" => Some(Self::OCaml),
+            "swift" => Some(Self::Swift),
+            _ => None,
+        }
+    }
+
+    /// Every known language (excluding `Unknown`)
+    pub fn all() -> &'static [Language] {
+        &[
+            Self::Rust,
+            Self::Python,
+            Self::JavaScript,
+            Self::TypeScript,
+            Self::Go,
+            Self::Java,
+            Self::C,
+            Self::Cpp,
+            Self::CSharp,
+            Self::Ruby,
+            Self::Php,
+            Self::Swift,
+            Self::Kotlin,
+            Self::Dart,
+            Self::Haskell,
+            Self::Shell,
+            Self::Nix,
+            Self::GraphQL,
+            Self::Scala,
+            Self::OCaml,
+            Self::Jupyter,
+            Self::Markdown,
+            Self::Json,
+            Self::Yaml,
+            Self::Toml,
+            Self::Sql,
+            Self::Html,
+            Self::Css,
+        ]
+    }
+
+    /// Parse a language by name or file extension, case-insensitively
+    /// (e.g. "rust", "Rust", "rs", "cpp")
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        Self::all()
+            .iter()
+            .copied()
+            .find(|language| language.name().eq_ignore_ascii_case(s))
+            .or_else(|| Some(Self::from_extension(s)).filter(|l| *l != Self::Unknown))
+    }
+
     /// Detect language from extension string
     pub fn from_extension(ext: &str) -> Self {
         match ext.to_lowercase().as_str() {
@@ -52,7 +175,14 @@ impl Language {
             "php" => Self::Php,
             "swift" => Self::Swift,
             "kt" | "kts" => Self::Kotlin,
+            "dart" => Self::Dart,
+            "hs" => Self::Haskell,
             "sh" | "bash" | "zsh" => Self::Shell,
+            "nix" => Self::Nix,
+            "graphql" | "gql" => Self::GraphQL,
+            "scala" | "sc" => Self::Scala,
+            "ml" | "mli" => Self::OCaml,
+            "ipynb" => Self::Jupyter,
             "md" | "markdown" | "txt" => Self::Markdown, // Treat txt as markdown-like
             "json" => Self::Json,
             "yaml" | "yml" => Self::Yaml,
@@ -79,7 +209,13 @@ impl Language {
                 | Self::Cpp
                 | Self::Ruby
                 | Self::Php
+                | Self::Dart
+                | Self::Haskell
                 | Self::Shell
+                | Self::Nix
+                | Self::GraphQL
+                | Self::Scala
+                | Self::OCaml
         )
     }
 
@@ -88,6 +224,14 @@ impl Language {
         !matches!(self, Self::Unknown)
     }
 
+    /// Separator between names in a symbol path (`auth::Session::verify`)
+    pub fn scope_separator(&self) -> &'static str {
+        match self {
+            Language::Rust | Language::Cpp | Language::Php | Language::Ruby => "::",
+            _ => ".",
+        }
+    }
+
     /// Get the language name as a string
     pub fn name(&self) -> &'static str {
         match self {
@@ -104,7 +248,14 @@ impl Language {
             Self::Php => "PHP",
             Self::Swift => "Swift",
             Self::Kotlin => "Kotlin",
+            Self::Dart => "Dart",
+            Self::Haskell => "Haskell",
             Self::Shell => "Shell",
+            Self::Nix => "Nix",
+            Self::GraphQL => "GraphQL",
+            Self::Scala => "Scala",
+            Self::OCaml => "OCaml",
+            Self::Jupyter => "Jupyter",
             Self::Markdown => "Markdown",
             Self::Json => "JSON",
             Self::Yaml => "YAML",
@@ -144,6 +295,19 @@ mod tests {
         assert_eq!(Language::from_extension("jsx"), Language::TypeScript);
     }
 
+    #[test]
+    fn test_haskell_detection() {
+        assert_eq!(Language::from_extension("hs"), Language::Haskell);
+        // The Haskell grammar can't parse literate (bird-track or LaTeX) source
+        assert_eq!(Language::from_extension("lhs"), Language::Unknown);
+    }
+
+    #[test]
+    fn test_ocaml_detection() {
+        assert_eq!(Language::from_extension("ml"), Language::OCaml);
+        assert_eq!(Language::from_extension("mli"), Language::OCaml);
+    }
+
     #[test]
     fn test_tree_sitter_support() {
         assert!(Language::Rust.supports_tree_sitter());
@@ -159,4 +323,59 @@ mod tests {
         assert!(Language::Markdown.is_indexable());
         assert!(!Language::Unknown.is_indexable());
     }
+
+    #[test]
+    fn test_shebang_detection() {
+        assert_eq!(
+            Language::from_shebang("/usr/bin/env python3"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::from_shebang("/usr/bin/python3.11 -u"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::from_shebang("/bin/bash -e"),
+            Some(Language::Shell)
+        );
+        assert_eq!(
+            Language::from_shebang("/usr/bin/env -S node --no-warnings"),
+            Some(Language::JavaScript)
+        );
+        assert_eq!(Language::from_shebang("/usr/bin/env perl"), None);
+    }
+
+    #[test]
+    fn test_detect_extensionless_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let python = dir.path().join("manage");
+        std::fs::write(&python, "#!/usr/bin/env python3\nprint('hi')\n").unwrap();
+        assert_eq!(Language::detect_file(&python), Language::Python);
+
+        let bash = dir.path().join("deploy");
+        std::fs::write(&bash, "#!/bin/bash\nset -e\necho deploying\n").unwrap();
+        assert_eq!(Language::detect_file(&bash), Language::Shell);
+
+        let plain = dir.path().join("LICENSE");
+        std::fs::write(&plain, "MIT License\n").unwrap();
+        assert_eq!(Language::detect_file(&plain), Language::Unknown);
+
+        // The extension still wins when it is known
+        assert_eq!(
+            Language::detect(Path::new("script.rb"), "#!/usr/bin/env python3\n"),
+            Language::Ruby
+        );
+    }
+
+    #[test]
+    fn test_parse_language() {
+        assert_eq!(Language::parse("rust"), Some(Language::Rust));
+        assert_eq!(Language::parse("TypeScript"), Some(Language::TypeScript));
+        assert_eq!(Language::parse("rs"), Some(Language::Rust));
+        assert_eq!(Language::parse("cpp"), Some(Language::Cpp));
+        assert_eq!(Language::parse("c#"), Some(Language::CSharp));
+        assert_eq!(Language::parse("unknown"), None);
+        assert_eq!(Language::parse("cobol"), None);
+    }
 }
@@ -1,15 +1,33 @@
 use anyhow::Result;
-use ignore::WalkBuilder;
-use std::collections::HashMap;
+use ignore::{ParallelVisitor, ParallelVisitorBuilder, WalkBuilder, WalkState};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use tracing::{debug, info, warn};
 
 mod binary;
+mod generated;
 mod language;
 
 pub use binary::is_binary_file;
+pub use generated::{is_generated_file, AllowGenerated};
 pub use language::Language;
 
+/// Bytes read from the start of a file for the binary and generated checks
+/// (64KB: enough lines to judge their length)
+const FILE_HEAD_SIZE: u64 = 64 * 1024;
+
+/// Read the start of a file once for the content checks (empty if unreadable)
+fn read_file_head(path: &Path) -> Vec<u8> {
+    use std::io::Read;
+
+    let mut head = Vec::new();
+    if let Ok(file) = std::fs::File::open(path) {
+        let _ = file.take(FILE_HEAD_SIZE).read_to_end(&mut head);
+    }
+    head
+}
+
 /// Information about a discovered file
 #[derive(Debug, Clone)]
 pub struct FileInfo {
@@ -18,6 +36,9 @@ pub struct FileInfo {
     pub size: u64,
 }
 
+/// Number of files listed in [`WalkStats::largest_files`]
+pub const TOP_FILES: usize = 10;
+
 /// Statistics about walked files
 #[derive(Debug, Default, Clone)]
 pub struct WalkStats {
@@ -25,8 +46,16 @@ pub struct WalkStats {
     pub indexable_files: usize,
     pub skipped_binary: usize,
     pub skipped_ignored: usize,
+    pub followed_symlinks: usize,
+    pub skipped_symlinks: usize,
+    /// Directories not descended into because they're at the maximum depth
+    pub skipped_depth: usize,
+    /// Probably generated or minified files (see [`is_generated_file`])
+    pub skipped_generated: usize,
     pub files_by_language: HashMap<Language, usize>,
     pub total_size_bytes: u64,
+    /// The [`TOP_FILES`] largest indexable files with their size in bytes, largest first
+    pub largest_files: Vec<(PathBuf, u64)>,
 }
 
 impl WalkStats {
@@ -38,12 +67,34 @@ impl WalkStats {
         self.indexable_files += 1;
         self.total_size_bytes += file.size;
         *self.files_by_language.entry(file.language).or_insert(0) += 1;
+
+        let rank = self
+            .largest_files
+            .partition_point(|(_, size)| *size >= file.size);
+        if rank < TOP_FILES {
+            self.largest_files
+                .insert(rank, (file.path.clone(), file.size));
+            self.largest_files.truncate(TOP_FILES);
+        }
     }
 
     pub fn add_skipped_binary(&mut self) {
         self.skipped_binary += 1;
     }
 
+    /// Add the skip and symlink counters of a walk over part of the tree
+    ///
+    /// Indexable file totals aren't merged; they come from [`Self::add_file`].
+    pub fn merge_counts(&mut self, other: &WalkStats) {
+        self.total_files += other.total_files;
+        self.skipped_binary += other.skipped_binary;
+        self.skipped_ignored += other.skipped_ignored;
+        self.followed_symlinks += other.followed_symlinks;
+        self.skipped_symlinks += other.skipped_symlinks;
+        self.skipped_depth += other.skipped_depth;
+        self.skipped_generated += other.skipped_generated;
+    }
+
     pub fn total_size_mb(&self) -> f64 {
         self.total_size_bytes as f64 / (1024.0 * 1024.0)
     }
@@ -53,6 +104,18 @@ impl WalkStats {
         info!("  Total files found: {}", self.total_files);
         info!("  Indexable files: {}", self.indexable_files);
         info!("  Binary/skipped: {}", self.skipped_binary);
+        if self.followed_symlinks > 0 || self.skipped_symlinks > 0 {
+            info!(
+                "  Symlinks: {} followed, {} skipped",
+                self.followed_symlinks, self.skipped_symlinks
+            );
+        }
+        if self.skipped_depth > 0 {
+            info!("  Directories beyond max depth: {}", self.skipped_depth);
+        }
+        if self.skipped_generated > 0 {
+            info!("  Generated/minified: {}", self.skipped_generated);
+        }
         info!("  Total size: {:.2} MB", self.total_size_mb());
 
         if !self.files_by_language.is_empty() {
@@ -63,22 +126,44 @@ impl WalkStats {
                 info!("    {}: {}", lang.name(), count);
             }
         }
+
+        if !self.largest_files.is_empty() {
+            info!("  Largest files:");
+            for (path, size) in &self.largest_files {
+                info!("    {} ({} bytes)", path.display(), size);
+            }
+        }
     }
 }
 
 /// Smart file walker that respects .gitignore and .demongrepignore
 pub struct FileWalker {
-    root: PathBuf,
+    roots: Vec<PathBuf>,
     respect_gitignore: bool,
     include_hidden: bool,
+    follow_symlinks: bool,
+    threads: usize,
+    max_depth: Option<usize>,
+    /// Generated-looking files to index anyway
+    allow_generated: AllowGenerated,
 }
 
 impl FileWalker {
     pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self::with_roots([root])
+    }
+
+    /// Walk several roots into one file list (e.g. `backend/` and `shared/`
+    /// of a monorepo); files reachable from more than one root are listed once
+    pub fn with_roots<P: Into<PathBuf>>(roots: impl IntoIterator<Item = P>) -> Self {
         Self {
-            root: root.into(),
+            roots: roots.into_iter().map(Into::into).collect(),
             respect_gitignore: true,
             include_hidden: false,
+            follow_symlinks: false,
+            threads: 0,
+            max_depth: None,
+            allow_generated: AllowGenerated::default(),
         }
     }
 
@@ -94,66 +179,91 @@ impl FileWalker {
         self
     }
 
+    /// Set whether to follow symbolic links (default: false)
+    ///
+    /// When enabled, symlink cycles are skipped, and a file reachable under
+    /// several paths (e.g. through a symlinked directory) is listed once,
+    /// under the first of its paths in sorted order.
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Set the number of threads walking the tree (default: 0, one per CPU)
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Only descend `depth` directory levels below each root (default: no limit)
+    ///
+    /// Files directly in a root are at depth 1, so a depth of 1 lists just those.
+    pub fn max_depth(mut self, depth: Option<usize>) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Index files matching these `.gitignore`-style patterns (relative to
+    /// `root`) even if they look generated or minified
+    ///
+    /// Invalid patterns are logged and skipped.
+    pub fn allow_generated(mut self, root: &Path, patterns: &[String]) -> Self {
+        self.allow_generated = AllowGenerated::new(root, patterns);
+        self
+    }
+
     /// Walk files, returning detailed file information
+    ///
+    /// Directories are read concurrently; files are returned sorted by path.
     pub fn walk(&self) -> Result<(Vec<FileInfo>, WalkStats)> {
-        let mut files = Vec::new();
-        let mut stats = WalkStats::new();
-
-        debug!("Starting file walk in: {}", self.root.display());
+        let Some((first_root, other_roots)) = self.roots.split_first() else {
+            return Ok((Vec::new(), WalkStats::new()));
+        };
+        for root in &self.roots {
+            debug!("Starting file walk in: {}", root.display());
+        }
 
-        let mut builder = WalkBuilder::new(&self.root);
+        let mut builder = WalkBuilder::new(first_root);
+        for root in other_roots {
+            builder.add(root);
+        }
         builder
             .git_ignore(self.respect_gitignore)
             .git_global(self.respect_gitignore)
             .git_exclude(self.respect_gitignore)
             .hidden(!self.include_hidden)
             .add_custom_ignore_filename(".demongrepignore")
-            .add_custom_ignore_filename(".osgrepignore"); // Compatibility with osgrep
-
-        for result in builder.build() {
-            match result {
-                Ok(entry) => {
-                    stats.total_files += 1;
-
-                    // Only process files (not directories)
-                    let file_type = entry.file_type();
-                    if file_type.is_none() || !file_type.unwrap().is_file() {
-                        continue;
-                    }
-
-                    let path = entry.path();
-
-                    // Check if file should be skipped
-                    if self.should_skip(path) {
-                        stats.add_skipped_binary();
-                        debug!("Skipping file: {}", path.display());
-                        continue;
-                    }
-
-                    // Get file info
-                    let language = Language::from_path(path);
-
-                    // Skip unknown/non-indexable files
-                    if !language.is_indexable() {
-                        stats.add_skipped_binary();
-                        continue;
-                    }
-
-                    let size = entry.metadata().ok().map(|m| m.len()).unwrap_or(0);
-
-                    let file_info = FileInfo {
-                        path: path.to_path_buf(),
-                        language,
-                        size,
-                    };
-
-                    stats.add_file(&file_info);
-                    files.push(file_info);
+            .add_custom_ignore_filename(".osgrepignore") // Compatibility with osgrep
+            .follow_links(self.follow_symlinks)
+            .threads(self.threads);
+
+        let shared = WalkResults::default();
+        builder.build_parallel().visit(&mut WalkVisitorBuilder {
+            walker: self,
+            shared: &shared,
+        });
+        let (mut files, mut stats) = shared.collected.into_inner().unwrap();
+
+        // Threads finish in any order; sort for a deterministic file list,
+        // and before deduplicating so the same alias of a file is always kept
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        if self.follow_symlinks {
+            let mut canonical_paths = HashSet::new();
+            files.retain(|file| {
+                let canonical = file
+                    .path
+                    .canonicalize()
+                    .unwrap_or_else(|_| file.path.clone());
+                let first = canonical_paths.insert(canonical);
+                if !first {
+                    stats.skipped_symlinks += 1;
+                    debug!("Skipping symlinked duplicate: {}", file.path.display());
                 }
-                Err(err) => {
-                    warn!("Error walking file: {}", err);
-                }
-            }
+                first
+            });
+        }
+        for file in &files {
+            stats.add_file(file);
         }
 
         stats.print_summary();
@@ -167,15 +277,10 @@ impl FileWalker {
         Ok(files.into_iter().map(|f| f.path).collect())
     }
 
-    /// Check if a file should be skipped
+    /// Check if a file should be skipped without reading it
     fn should_skip(&self, path: &Path) -> bool {
-        // Check for vendor/generated directories in path
-        if self.is_in_excluded_dir(path) {
-            return true;
-        }
-
-        // Check if file is binary
-        is_binary_file(path)
+        // Check for vendor/generated directories in path, then binary extensions
+        self.is_in_excluded_dir(path) || binary::is_binary_by_extension(path)
     }
 
     /// Check if path is in an excluded directory
@@ -187,12 +292,22 @@ impl FileWalker {
                 "node_modules" | "target" | "dist" | "build" | "out"
                 // Version control
                 | ".git" | ".svn" | ".hg"
+                // Our own databases (reachable with include_hidden)
+                | ".demongrep" | ".demongrep.db"
                 // Python
                 | "__pycache__" | ".pytest_cache" | ".tox" | "venv" | ".venv"
                 // Ruby
                 | "vendor" | ".bundle"
                 // Java
                 | ".gradle" | ".m2"
+                // Dart/Flutter
+                | ".dart_tool"
+                // Haskell
+                | ".stack-work" | "dist-newstyle"
+                // Scala
+                | ".bloop" | ".bsp" | ".metals"
+                // OCaml
+                | "_build" | "_opam"
                 // IDE
                 | ".idea" | ".vscode" | ".vs"
                 // Other
@@ -202,12 +317,185 @@ impl FileWalker {
     }
 }
 
+/// State shared by the threads of a parallel walk
+#[derive(Default)]
+struct WalkResults {
+    /// Files already listed, so overlapping roots don't yield duplicates
+    seen_files: Mutex<HashSet<PathBuf>>,
+    /// Files and skip counters gathered by finished threads
+    collected: Mutex<(Vec<FileInfo>, WalkStats)>,
+}
+
+struct WalkVisitorBuilder<'s> {
+    walker: &'s FileWalker,
+    shared: &'s WalkResults,
+}
+
+impl<'s> ParallelVisitorBuilder<'s> for WalkVisitorBuilder<'s> {
+    fn build(&mut self) -> Box<dyn ParallelVisitor + 's> {
+        Box::new(WalkVisitor {
+            walker: self.walker,
+            shared: self.shared,
+            files: Vec::new(),
+            stats: WalkStats::new(),
+        })
+    }
+}
+
+/// One walker thread; collects locally and hands its results over when dropped
+struct WalkVisitor<'s> {
+    walker: &'s FileWalker,
+    shared: &'s WalkResults,
+    files: Vec<FileInfo>,
+    stats: WalkStats,
+}
+
+impl ParallelVisitor for WalkVisitor<'_> {
+    fn visit(&mut self, result: Result<ignore::DirEntry, ignore::Error>) -> WalkState {
+        let entry = match result {
+            Ok(entry) => entry,
+            Err(err) if is_loop_error(&err) => {
+                self.stats.skipped_symlinks += 1;
+                debug!("Skipping symlink loop: {}", err);
+                return WalkState::Continue;
+            }
+            Err(err) => {
+                warn!("Error walking file: {}", err);
+                return WalkState::Continue;
+            }
+        };
+        self.stats.total_files += 1;
+
+        if entry.path_is_symlink() {
+            if !self.walker.follow_symlinks {
+                self.stats.skipped_symlinks += 1;
+                debug!("Skipping symlink: {}", entry.path().display());
+                return WalkState::Continue;
+            }
+            self.stats.followed_symlinks += 1;
+        }
+
+        // Directories at the depth limit would only yield files beyond it
+        if let Some(max_depth) = self.walker.max_depth {
+            if entry.depth() >= max_depth && entry.file_type().is_some_and(|t| t.is_dir()) {
+                self.stats.skipped_depth += 1;
+                debug!(
+                    "Skipping directory beyond max depth: {}",
+                    entry.path().display()
+                );
+                return WalkState::Skip;
+            }
+        }
+
+        // Only process files (not directories)
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            return WalkState::Continue;
+        }
+
+        let path = entry.path();
+        if !self
+            .shared
+            .seen_files
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf())
+        {
+            return WalkState::Continue;
+        }
+
+        // Check if file should be skipped; the binary and generated content
+        // checks share one read of the file's start
+        let head = (!self.walker.should_skip(path)).then(|| read_file_head(path));
+        let Some(head) = head.filter(|head| !binary::is_binary_content(head)) else {
+            self.stats.add_skipped_binary();
+            debug!("Skipping file: {}", path.display());
+            return WalkState::Continue;
+        };
+
+        // Skip unknown/non-indexable files
+        let language = Language::detect_file(path);
+        if !language.is_indexable() {
+            self.stats.add_skipped_binary();
+            return WalkState::Continue;
+        }
+
+        if generated::is_generated(path, &head) && !self.walker.allow_generated.allows(path) {
+            self.stats.skipped_generated += 1;
+            debug!("Skipping generated file: {}", path.display());
+            return WalkState::Continue;
+        }
+
+        let size = entry.metadata().ok().map(|m| m.len()).unwrap_or(0);
+        self.files.push(FileInfo {
+            path: path.to_path_buf(),
+            language,
+            size,
+        });
+        WalkState::Continue
+    }
+}
+
+impl Drop for WalkVisitor<'_> {
+    fn drop(&mut self) {
+        let mut collected = self
+            .shared
+            .collected
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        collected.0.append(&mut self.files);
+        collected.1.merge_counts(&self.stats);
+    }
+}
+
+/// Check whether a walk error is a filesystem loop, unwrapping path/depth context
+fn is_loop_error(err: &ignore::Error) -> bool {
+    match err {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_loop_error(err),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_minified_js_is_skipped() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("app.js"),
+            "function main() {\n  run();\n}\n",
+        )
+        .unwrap();
+        let minified = format!(
+            "!function(){{{}}}();",
+            "var a=function(b){return b*2},c=a(1);".repeat(200)
+        );
+        fs::write(dir.path().join("vendor.js"), &minified).unwrap();
+        fs::write(dir.path().join("app.min.js"), "var a=1;").unwrap();
+
+        let (files, stats) = FileWalker::new(dir.path()).walk().unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|f| f.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["app.js"]);
+        assert_eq!(stats.skipped_generated, 2);
+
+        // The allowlist brings them back
+        let (files, stats) = FileWalker::new(dir.path())
+            .allow_generated(dir.path(), &["vendor.js".to_string()])
+            .walk()
+            .unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(stats.skipped_generated, 1);
+    }
+
     #[test]
     fn test_file_walker_basic() {
         let dir = TempDir::new().unwrap();
@@ -224,6 +512,113 @@ mod tests {
         assert_eq!(stats.indexable_files, 3);
     }
 
+    #[test]
+    fn test_walk_stats_lists_largest_files_first() {
+        let dir = TempDir::new().unwrap();
+        for i in 0..TOP_FILES + 2 {
+            fs::write(dir.path().join(format!("small{}.rs", i)), "fn f() {}").unwrap();
+        }
+        let blob = "const DATA: &str = \"xxxxxxxxxxxxxxxx\";\n".repeat(2048);
+        fs::write(dir.path().join("large.rs"), &blob).unwrap();
+
+        let (_, stats) = FileWalker::new(dir.path()).walk().unwrap();
+        assert_eq!(stats.largest_files.len(), TOP_FILES);
+        let (path, size) = &stats.largest_files[0];
+        assert_eq!(path, &dir.path().join("large.rs"));
+        assert_eq!(*size, blob.len() as u64);
+        assert!(stats
+            .largest_files
+            .windows(2)
+            .all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn test_file_walker_multiple_roots() {
+        let dir = TempDir::new().unwrap();
+        for sub in ["backend", "shared", "frontend"] {
+            fs::create_dir_all(dir.path().join(sub)).unwrap();
+            fs::write(dir.path().join(sub).join("lib.rs"), "fn f() {}").unwrap();
+        }
+
+        let walker = FileWalker::with_roots([
+            dir.path().join("backend"),
+            dir.path().join("shared"),
+            // Overlaps with the first root, so its file isn't listed twice
+            dir.path().join("backend"),
+        ]);
+        let (files, stats) = walker.walk().unwrap();
+
+        let mut paths: Vec<_> = files
+            .iter()
+            .map(|f| f.path.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("backend/lib.rs"),
+                PathBuf::from("shared/lib.rs")
+            ]
+        );
+        assert_eq!(stats.indexable_files, 2);
+    }
+
+    #[test]
+    fn test_max_depth_skips_deep_files() {
+        let dir = TempDir::new().unwrap();
+        let mut level = dir.path().to_path_buf();
+        for depth in 1..=5 {
+            fs::write(level.join(format!("depth{}.rs", depth)), "fn f() {}").unwrap();
+            level = level.join(format!("level{}", depth));
+            fs::create_dir(&level).unwrap();
+        }
+
+        let (files, stats) = FileWalker::new(dir.path())
+            .max_depth(Some(2))
+            .walk()
+            .unwrap();
+        let names: Vec<String> = files
+            .iter()
+            .map(|f| f.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, ["depth1.rs", "depth2.rs"]);
+        assert_eq!(stats.skipped_depth, 1);
+
+        let (files, stats) = FileWalker::new(dir.path()).walk().unwrap();
+        assert_eq!(files.len(), 5);
+        assert_eq!(stats.skipped_depth, 0);
+    }
+
+    #[test]
+    fn test_parallel_walk_matches_serial_walk() {
+        // A deep, bushy synthetic tree: 4^5 leaf directories with 2 files each
+        let dir = TempDir::new().unwrap();
+        let mut dirs = vec![dir.path().to_path_buf()];
+        for _ in 0..5 {
+            dirs = dirs
+                .iter()
+                .flat_map(|parent| (0..4).map(move |i| parent.join(format!("d{}", i))))
+                .collect();
+        }
+        for leaf in &dirs {
+            fs::create_dir_all(leaf).unwrap();
+            fs::write(leaf.join("lib.rs"), "fn f() {}").unwrap();
+            fs::write(leaf.join("util.py"), "def g(): pass").unwrap();
+        }
+
+        let walk = |threads: usize| FileWalker::new(dir.path()).threads(threads).walk().unwrap();
+        let (serial, serial_stats) = walk(1);
+        let (parallel, parallel_stats) = walk(0);
+
+        assert_eq!(parallel.len(), 2 * 4usize.pow(5));
+        let paths = |files: &[FileInfo]| files.iter().map(|f| f.path.clone()).collect::<Vec<_>>();
+        assert_eq!(paths(&parallel), paths(&serial));
+        assert!(parallel.windows(2).all(|w| w[0].path < w[1].path));
+        assert_eq!(parallel_stats.total_files, serial_stats.total_files);
+        assert_eq!(parallel_stats.indexable_files, serial_stats.indexable_files);
+        assert_eq!(parallel_stats.largest_files, serial_stats.largest_files);
+    }
+
     #[test]
     fn test_skip_binary_files() {
         let dir = TempDir::new().unwrap();
@@ -259,6 +654,25 @@ mod tests {
         assert_eq!(stats.files_by_language.get(&Language::JavaScript), Some(&1));
     }
 
+    #[test]
+    fn test_extensionless_scripts_detected_by_shebang() {
+        let dir = TempDir::new().unwrap();
+
+        fs::write(
+            dir.path().join("manage"),
+            "#!/usr/bin/env python3\nimport sys\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("deploy"), "#!/bin/sh\necho deploy\n").unwrap();
+        fs::write(dir.path().join("NOTES"), "no shebang here\n").unwrap();
+
+        let (files, stats) = FileWalker::new(dir.path()).walk().unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(stats.files_by_language.get(&Language::Python), Some(&1));
+        assert_eq!(stats.files_by_language.get(&Language::Shell), Some(&1));
+    }
+
     #[test]
     fn test_excluded_directories() {
         let dir = TempDir::new().unwrap();
@@ -278,4 +692,99 @@ mod tests {
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].path.file_name().unwrap(), "index.js");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_not_followed_by_default() {
+        let dir = TempDir::new().unwrap();
+
+        let real = dir.path().join("real");
+        fs::create_dir(&real).unwrap();
+        fs::write(real.join("lib.rs"), "fn lib() {}").unwrap();
+        std::os::unix::fs::symlink(&real, dir.path().join("alias")).unwrap();
+
+        let walker = FileWalker::new(dir.path());
+        let (files, stats) = walker.walk().unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(stats.skipped_symlinks, 1);
+        assert_eq!(stats.followed_symlinks, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_cycle_terminates() {
+        let dir = TempDir::new().unwrap();
+
+        let nested = dir.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("main.rs"), "fn main() {}").unwrap();
+        // b/loop -> a creates a cycle a/b/loop/b/loop/...
+        std::os::unix::fs::symlink(dir.path().join("a"), nested.join("loop")).unwrap();
+
+        let walker = FileWalker::new(dir.path()).follow_symlinks(true);
+        let (files, stats) = walker.walk().unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert!(stats.skipped_symlinks >= 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_directory_keeps_first_sorted_alias() {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("lib.rs"), "fn lib() {}").unwrap();
+        std::os::unix::fs::symlink(&src, dir.path().join("alias")).unwrap();
+
+        for _ in 0..5 {
+            let (files, stats) = FileWalker::new(dir.path())
+                .follow_symlinks(true)
+                .walk()
+                .unwrap();
+            let paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+            assert_eq!(paths, [dir.path().join("alias").join("lib.rs")]);
+            assert_eq!(stats.skipped_symlinks, 1);
+        }
+    }
+
+    #[test]
+    fn test_include_hidden() {
+        let dir = TempDir::new().unwrap();
+
+        let config = dir.path().join(".config");
+        fs::create_dir(&config).unwrap();
+        fs::write(config.join("setup.sh"), "echo setup").unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+
+        // Ignored files and our own database stay excluded
+        fs::write(dir.path().join(".gitignore"), ".config/secret.sh\n").unwrap();
+        fs::write(config.join("secret.sh"), "echo secret").unwrap();
+        let store = dir.path().join(".demongrep").join("store");
+        fs::create_dir_all(&store).unwrap();
+        fs::write(store.join("metadata.json"), "{}").unwrap();
+
+        let (files, _) = FileWalker::new(dir.path()).walk().unwrap();
+        assert_eq!(files.len(), 1);
+
+        // git_ignore only applies inside a repository
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        let (files, _) = FileWalker::new(dir.path())
+            .include_hidden(true)
+            .walk()
+            .unwrap();
+        let mut names: Vec<_> = files
+            .iter()
+            .map(|f| {
+                f.path
+                    .strip_prefix(dir.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        names.sort();
+        assert_eq!(names, vec![".config/setup.sh", "main.rs"]);
+    }
 }
@@ -1,10 +1,24 @@
+use crate::config::FtsConfig;
 use tantivy::tokenizer::{BoxTokenStream, Token, TokenStream, Tokenizer};
 
+/// Terms dropped when `FtsConfig::stop_words` is enabled: English filler words
+/// that show up in comments and keywords present in nearly every chunk
+const CODE_STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "const", "def", "fn", "for", "from", "in",
+    "is", "it", "let", "mut", "of", "on", "or", "pub", "return", "self", "that", "the", "this",
+    "to", "var", "was", "with",
+];
+
 /// Tokenizer tuned for code identifiers.
 ///
 /// Splits on punctuation/separators and also on camelCase boundaries.
+/// Optionally drops short terms and stop words; dropped terms still take up
+/// a position, so phrase queries don't match across them.
 #[derive(Clone, Default)]
-pub struct CodeTokenizer;
+pub struct CodeTokenizer {
+    min_term_length: usize,
+    stop_words: bool,
+}
 
 #[derive(Clone)]
 struct CodeTokenStream {
@@ -13,11 +27,34 @@ struct CodeTokenStream {
     index: usize,
 }
 
+impl CodeTokenizer {
+    /// Tokenizer applying the term filters from `[fts]` in the project config
+    pub fn with_filters(config: &FtsConfig) -> Self {
+        Self {
+            min_term_length: config.min_term_length,
+            stop_words: config.stop_words,
+        }
+    }
+
+    /// Split text into lowercase terms with byte offsets, as indexed and queried
+    pub fn tokenize(&self, text: &str) -> Vec<Token> {
+        tokenize_code(text)
+            .into_iter()
+            .filter(|token| self.keeps(&token.text))
+            .collect()
+    }
+
+    fn keeps(&self, term: &str) -> bool {
+        term.chars().count() >= self.min_term_length
+            && !(self.stop_words && CODE_STOP_WORDS.contains(&term))
+    }
+}
+
 impl Tokenizer for CodeTokenizer {
     type TokenStream<'a> = BoxTokenStream<'a>;
 
     fn token_stream<'a>(&mut self, text: &'a str) -> Self::TokenStream<'a> {
-        let tokens = tokenize_code(text);
+        let tokens = self.tokenize(text);
         BoxTokenStream::new(CodeTokenStream {
             tokens,
             current: Token::default(),
@@ -160,7 +197,7 @@ mod tests {
 
     #[test]
     fn tokenizes_snake_and_camel() {
-        let mut tokenizer = CodeTokenizer;
+        let mut tokenizer = CodeTokenizer::default();
         let mut stream = tokenizer.token_stream("UserConfig process_data HTTPServer");
         let mut terms = Vec::new();
         while stream.advance() {
@@ -172,4 +209,43 @@ mod tests {
             vec!["user", "config", "process", "data", "http", "server"]
         );
     }
+
+    #[test]
+    fn drops_short_terms_and_stop_words_when_configured() {
+        let text = "for i in items { let the_count = a.len(); }";
+        let terms = |tokenizer: CodeTokenizer| {
+            tokenizer
+                .tokenize(text)
+                .into_iter()
+                .map(|t| (t.text, t.position))
+                .collect::<Vec<_>>()
+        };
+
+        // Default keeps every term: for i in items let the count a len
+        assert_eq!(terms(CodeTokenizer::default()).len(), 9);
+
+        let tokenizer = CodeTokenizer::with_filters(&FtsConfig {
+            min_term_length: 2,
+            stop_words: true,
+        });
+        assert_eq!(
+            terms(tokenizer),
+            vec![
+                ("items".to_string(), 3),
+                ("count".to_string(), 6),
+                ("len".to_string(), 8)
+            ]
+        );
+
+        // Short-term filtering alone keeps multi-letter keywords
+        let tokenizer = CodeTokenizer::with_filters(&FtsConfig {
+            min_term_length: 2,
+            stop_words: false,
+        });
+        let kept: Vec<_> = terms(tokenizer).into_iter().map(|(t, _)| t).collect();
+        assert_eq!(
+            kept,
+            vec!["for", "in", "items", "let", "the", "count", "len"]
+        );
+    }
 }
@@ -6,4 +6,5 @@
 mod code_tokenizer;
 mod tantivy_store;
 
+pub use code_tokenizer::CodeTokenizer;
 pub use tantivy_store::{FtsResult, FtsStore};
@@ -17,6 +17,21 @@ use tantivy::{
 };
 
 use super::code_tokenizer::CodeTokenizer;
+use crate::config::FtsConfig;
+use crate::error::DemongrepError;
+
+/// Version of the FTS schema written by this build
+///
+/// Bump whenever fields or tokenizers change. History:
+/// - 1: content/path/signature/kind (no version file)
+/// - 2: adds `string_literals` and the `code` tokenizer
+pub const FTS_SCHEMA_VERSION: u32 = 2;
+
+/// File inside the FTS directory recording the schema version
+const SCHEMA_VERSION_FILE: &str = "schema_version";
+
+/// File inside the FTS directory recording the tokenizer's term filters
+const TOKENIZER_CONFIG_FILE: &str = "tokenizer.json";
 
 /// Result from FTS search
 #[derive(Debug, Clone)]
@@ -44,15 +59,70 @@ pub struct FtsStore {
 }
 
 impl FtsStore {
-    fn register_tokenizers(index: &Index) {
-        let code_analyzer = TextAnalyzer::builder(CodeTokenizer)
+    fn register_tokenizers(index: &Index, config: &FtsConfig) {
+        let code_analyzer = TextAnalyzer::builder(CodeTokenizer::with_filters(config))
             .filter(LowerCaser)
             .build();
         index.tokenizers().register("code", code_analyzer);
     }
 
+    /// Term filters an existing index was built with (indexes written before
+    /// filters existed have none, which is the default)
+    fn stored_tokenizer_config(fts_path: &Path) -> FtsConfig {
+        std::fs::read_to_string(fts_path.join(TOKENIZER_CONFIG_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Term filters of the FTS index in `db_path`, or `None` when it has none
+    pub fn tokenizer_config(db_path: &Path) -> Option<FtsConfig> {
+        let fts_path = db_path.join("fts");
+        fts_path
+            .join("meta.json")
+            .exists()
+            .then(|| Self::stored_tokenizer_config(&fts_path))
+    }
+
+    /// Schema version of an existing index
+    ///
+    /// Indexes written before versioning have no version file, so infer it
+    /// from the fields present.
+    fn stored_schema_version(fts_path: &Path, index: &Index) -> u32 {
+        if let Ok(content) = std::fs::read_to_string(fts_path.join(SCHEMA_VERSION_FILE)) {
+            if let Ok(version) = content.trim().parse() {
+                return version;
+            }
+        }
+
+        if index.schema().get_field("string_literals").is_ok() {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn write_schema_version(fts_path: &Path) -> Result<()> {
+        std::fs::write(
+            fts_path.join(SCHEMA_VERSION_FILE),
+            FTS_SCHEMA_VERSION.to_string(),
+        )?;
+        Ok(())
+    }
+
     /// Create or open an FTS index at the given path
+    ///
+    /// Fails with [`DemongrepError::FtsSchemaMismatch`] when the existing index
+    /// was written with a different schema version and must be rebuilt.
     pub fn new(db_path: &Path) -> Result<Self> {
+        Self::with_config(db_path, &FtsConfig::default())
+    }
+
+    /// Like `new`, creating a missing index with the given term filters
+    ///
+    /// An existing index keeps the filters it was built with, so indexing and
+    /// querying always tokenize the same way.
+    pub fn with_config(db_path: &Path, config: &FtsConfig) -> Result<Self> {
         let fts_path = db_path.join("fts");
         std::fs::create_dir_all(&fts_path)?;
 
@@ -89,13 +159,28 @@ impl FtsStore {
         let schema = schema_builder.build();
 
         // Open or create index
-        let index = if fts_path.join("meta.json").exists() {
-            Index::open_in_dir(&fts_path)?
+        let (index, tokenizer_config) = if fts_path.join("meta.json").exists() {
+            let index = Index::open_in_dir(&fts_path)?;
+            let found = Self::stored_schema_version(&fts_path, &index);
+            if found != FTS_SCHEMA_VERSION {
+                return Err(DemongrepError::FtsSchemaMismatch {
+                    found,
+                    expected: FTS_SCHEMA_VERSION,
+                }
+                .into());
+            }
+            (index, Self::stored_tokenizer_config(&fts_path))
         } else {
             let dir = MmapDirectory::open(&fts_path)?;
-            Index::create(dir, schema.clone(), IndexSettings::default())?
+            let index = Index::create(dir, schema.clone(), IndexSettings::default())?;
+            std::fs::write(
+                fts_path.join(TOKENIZER_CONFIG_FILE),
+                serde_json::to_string(config)?,
+            )?;
+            (index, config.clone())
         };
-        Self::register_tokenizers(&index);
+        Self::write_schema_version(&fts_path)?;
+        Self::register_tokenizers(&index, &tokenizer_config);
 
         // Create reader for searching
         let reader = index.reader()?;
@@ -123,7 +208,7 @@ impl FtsStore {
         }
 
         let index = Index::open_in_dir(&fts_path)?;
-        Self::register_tokenizers(&index);
+        Self::register_tokenizers(&index, &Self::stored_tokenizer_config(&fts_path));
         let schema = index.schema();
 
         let chunk_id_field = schema
@@ -393,6 +478,87 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_fts_old_schema_requires_reindex() -> Result<()> {
+        let dir = tempdir()?;
+        let fts_path = dir.path().join("fts");
+        std::fs::create_dir_all(&fts_path)?;
+
+        // Simulate a v1 index: no string_literals field and no version file
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_u64_field("chunk_id", NumericOptions::default().set_indexed());
+        schema_builder.add_text_field("content", STRING);
+        schema_builder.add_text_field("path", STRING | STORED);
+        schema_builder.add_text_field("signature", STRING);
+        schema_builder.add_text_field("kind", STRING | STORED);
+        Index::create_in_dir(&fts_path, schema_builder.build())?;
+
+        let err = FtsStore::new(dir.path())
+            .err()
+            .expect("old schema must be rejected");
+        match err.downcast_ref::<DemongrepError>() {
+            Some(DemongrepError::FtsSchemaMismatch { found, expected }) => {
+                assert_eq!(*found, 1);
+                assert_eq!(*expected, FTS_SCHEMA_VERSION);
+            }
+            other => panic!("expected FtsSchemaMismatch, got {:?}", other),
+        }
+
+        // Search still works against the old index
+        assert!(FtsStore::open_readonly(dir.path()).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fts_schema_version_written() -> Result<()> {
+        let dir = tempdir()?;
+        let store = FtsStore::new(dir.path())?;
+        drop(store);
+
+        let version = std::fs::read_to_string(dir.path().join("fts").join(SCHEMA_VERSION_FILE))?;
+        assert_eq!(version.trim(), FTS_SCHEMA_VERSION.to_string());
+
+        // Reopening a current index is fine
+        FtsStore::new(dir.path())?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_fts_term_filters_persist_with_index() -> Result<()> {
+        let dir = tempdir()?;
+        let config = FtsConfig {
+            min_term_length: 2,
+            stop_words: true,
+        };
+
+        let mut store = FtsStore::with_config(dir.path(), &config)?;
+        store.add_chunk(
+            1,
+            "for i in items { total += i }",
+            "a.rs",
+            None,
+            "block",
+            &[],
+        )?;
+        store.commit()?;
+        drop(store);
+
+        // Reopening with defaults keeps the filters the index was built with
+        let store = FtsStore::new(dir.path())?;
+        assert!(store.search("i", 10)?.is_empty());
+        // Stop words are dropped from queries too, so they don't have to match
+        assert_eq!(store.search("the items", 10)?.len(), 1);
+        drop(store);
+
+        let store = FtsStore::open_readonly(dir.path())?;
+        assert!(store.search("i", 10)?.is_empty());
+        assert_eq!(store.search("total", 10)?.len(), 1);
+
+        assert_eq!(FtsStore::tokenizer_config(dir.path()), Some(config));
+        assert_eq!(FtsStore::tokenizer_config(&dir.path().join("none")), None);
+        Ok(())
+    }
+
     #[test]
     fn test_fts_delete() -> Result<()> {
         let dir = tempdir()?;
@@ -1,25 +1,34 @@
 use anyhow::Result;
 use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
+use serde::Serialize;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Instant;
 
-use crate::chunker::SemanticChunker;
+use crate::cache::FileMetaStore;
+use crate::chunker::{Chunk, SemanticChunker};
+use crate::config::{ChunkingConfig, EmbeddingProvider, ProjectConfig};
 use crate::database::DatabaseManager;
-use crate::embed::{EmbeddingService, ModelType};
-use crate::file::FileWalker;
+use crate::embed::{EmbeddedChunk, EmbeddingService, ModelType, RemoteEmbedder};
+use crate::error::DemongrepError;
+use crate::file::{FileInfo, FileWalker, Language, TOP_FILES};
 use crate::fts::FtsStore;
-use crate::vectordb::VectorStore;
+use crate::output::Progress;
+use crate::vectordb::{IndexMode, StoreStats, VectorStore};
+
+mod verify;
+
+pub use verify::{repair_database, verify, verify_database, RepairSummary, VerifyReport};
 
 const LOCAL_STATE_DIR: &str = ".demongrep";
 const LOCAL_STORE_DIR: &str = "store";
 const LEGACY_LOCAL_DB_DIR: &str = ".demongrep.db";
 
-fn local_db_path(project_root: &Path) -> PathBuf {
+pub(crate) fn local_db_path(project_root: &Path) -> PathBuf {
     project_root.join(LOCAL_STATE_DIR).join(LOCAL_STORE_DIR)
 }
 
@@ -27,6 +36,26 @@ fn legacy_local_db_path(project_root: &Path) -> PathBuf {
     project_root.join(LEGACY_LOCAL_DB_DIR)
 }
 
+/// Canonical form of a project path given on the command line
+///
+/// A path that doesn't exist (usually a mistyped `--path`) is a
+/// `ConfigError` naming it, instead of a bare "No such file or directory".
+pub fn canonical_project_path(path: &Path) -> Result<PathBuf> {
+    path.canonicalize().map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            DemongrepError::ConfigError {
+                details: format!(
+                    "path '{}' does not exist; check the spelling or create it first",
+                    path.display()
+                ),
+            }
+            .into()
+        } else {
+            anyhow::Error::new(e).context(format!("Failed to resolve path {}", path.display()))
+        }
+    })
+}
+
 pub fn is_local_db_path(db_path: &Path) -> bool {
     if db_path
         .file_name()
@@ -50,25 +79,34 @@ pub fn is_local_db_path(db_path: &Path) -> bool {
             .unwrap_or(false)
 }
 
+/// Global store for a project: `~/.demongrep/stores/<hash of canonical path>`
+///
+/// Each project gets its own store directory, so its vectors, FTS index and
+/// per-file metadata (used for incremental sync) never mix with other projects.
+fn global_db_path(home: &Path, canonical_path: &Path) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    canonical_path.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    home.join(".demongrep")
+        .join("stores")
+        .join(format!("{:x}", hash))
+}
+
 /// Get the database path for indexing
 fn get_index_db_path(path: Option<PathBuf>, global: bool) -> Result<PathBuf> {
     let project_path = path.unwrap_or_else(|| PathBuf::from("."));
-    let canonical_path = project_path.canonicalize()?;
+    let canonical_path = canonical_project_path(&project_path)?;
 
     if global {
         // Global mode: use home directory with project hash
         let home =
             dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
 
-        // Create hash of canonical path
-        let mut hasher = DefaultHasher::new();
-        canonical_path.hash(&mut hasher);
-        let hash = hasher.finish();
-
-        let global_base = home.join(".demongrep").join("stores");
-        std::fs::create_dir_all(&global_base)?;
-
-        let db_path = global_base.join(format!("{:x}", hash));
+        let db_path = global_db_path(&home, &canonical_path);
+        if let Some(global_base) = db_path.parent() {
+            std::fs::create_dir_all(global_base)?;
+        }
 
         // Save project mapping for later reference
         save_project_mapping(&canonical_path, &db_path)?;
@@ -81,34 +119,35 @@ fn get_index_db_path(path: Option<PathBuf>, global: bool) -> Result<PathBuf> {
 }
 
 /// Get all database paths to search (local + global)
+///
+/// Directories holding only an embedding cache (`index --embed-only`) aren't
+/// databases yet and are skipped.
 pub fn get_search_db_paths(path: Option<PathBuf>) -> Result<Vec<PathBuf>> {
+    project_db_dirs(path, has_vector_store)
+}
+
+/// Local and global database directories of a project that pass `is_database`
+fn project_db_dirs(path: Option<PathBuf>, is_database: fn(&Path) -> bool) -> Result<Vec<PathBuf>> {
     let mut paths = Vec::new();
 
     let project_path = path.unwrap_or_else(|| PathBuf::from("."));
-    let canonical_path = project_path.canonicalize()?;
+    let canonical_path = canonical_project_path(&project_path)?;
 
     // 1. Check local database (prefer new .demongrep/store layout)
     let local_db = local_db_path(&canonical_path);
-    if local_db.exists() {
+    if is_database(&local_db) {
         paths.push(local_db);
     } else {
         let legacy_local_db = legacy_local_db_path(&canonical_path);
-        if legacy_local_db.exists() {
+        if is_database(&legacy_local_db) {
             paths.push(legacy_local_db);
         }
     }
 
     // 2. Check global database
     if let Some(home) = dirs::home_dir() {
-        let mut hasher = DefaultHasher::new();
-        canonical_path.hash(&mut hasher);
-        let hash = hasher.finish();
-
-        let global_db = home
-            .join(".demongrep")
-            .join("stores")
-            .join(format!("{:x}", hash));
-        if global_db.exists() {
+        let global_db = global_db_path(&home, &canonical_path);
+        if is_database(&global_db) {
             paths.push(global_db);
         }
     }
@@ -116,22 +155,43 @@ pub fn get_search_db_paths(path: Option<PathBuf>) -> Result<Vec<PathBuf>> {
     Ok(paths)
 }
 
-/// Get only the local database path for search
-pub fn get_local_search_db_path(path: Option<PathBuf>) -> Result<Option<PathBuf>> {
-    let project_path = path.unwrap_or_else(|| PathBuf::from("."));
-    let canonical_path = project_path.canonicalize()?;
-    let local_db = local_db_path(&canonical_path);
-
-    if local_db.exists() {
-        Ok(Some(local_db))
+/// Walker over the whole project, or only the given roots (relative to it)
+///
+/// Generated files listed in `indexing.allow_generated` are walked too.
+pub(crate) fn project_walker(project_path: &Path, roots: &[PathBuf]) -> FileWalker {
+    let walker = if roots.is_empty() {
+        FileWalker::new(project_path)
     } else {
-        let legacy_local_db = legacy_local_db_path(&canonical_path);
-        if legacy_local_db.exists() {
-            Ok(Some(legacy_local_db))
-        } else {
-            Ok(None)
+        FileWalker::with_roots(roots.iter().map(|root| project_path.join(root)))
+    };
+    let indexing = ProjectConfig::load(Some(project_path)).indexing;
+    walker.allow_generated(project_path, &indexing.allow_generated)
+}
+
+/// Index roots relative to the project root, sorted and deduplicated
+///
+/// Empty when one of them is the project root itself (index everything).
+fn relative_roots(project_root: &Path, roots: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut relative = Vec::new();
+    for root in roots {
+        let canonical = root
+            .canonicalize()
+            .map_err(|e| anyhow::anyhow!("Cannot index {}: {}", root.display(), e))?;
+        let root_relative = canonical.strip_prefix(project_root).map_err(|_| {
+            anyhow::anyhow!(
+                "{} is outside the project root {}",
+                root.display(),
+                project_root.display()
+            )
+        })?;
+        if root_relative.as_os_str().is_empty() {
+            return Ok(Vec::new());
         }
+        relative.push(root_relative.to_path_buf());
     }
+    relative.sort();
+    relative.dedup();
+    Ok(relative)
 }
 
 /// Save project -> database mapping
@@ -161,49 +221,82 @@ fn save_project_mapping(project_path: &Path, db_path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Find databases for a project by name (searches in projects.json)
-fn find_project_databases(project_name: &str) -> Result<Vec<PathBuf>> {
+/// Load the project path -> global database mapping from projects.json
+fn load_project_mappings() -> Result<std::collections::HashMap<String, String>> {
     let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
     let mapping_file = home.join(".demongrep").join("projects.json");
 
     if !mapping_file.exists() {
-        return Ok(Vec::new());
+        return Ok(std::collections::HashMap::new());
     }
 
     let content = std::fs::read_to_string(&mapping_file)?;
-    let mappings: std::collections::HashMap<String, String> = serde_json::from_str(&content)?;
+    Ok(serde_json::from_str(&content)?)
+}
 
-    let mut found_paths = Vec::new();
+/// Resolve a project name or path to its `(project_path, db_path)` registry entry
+///
+/// A canonical project path wins; otherwise the name must match exactly one
+/// project directory name. Unknown and ambiguous names are errors.
+fn resolve_project<'a>(
+    mappings: &'a std::collections::HashMap<String, String>,
+    project: &str,
+) -> Result<(&'a String, &'a String)> {
+    let as_path = PathBuf::from(project);
+    let canonical = as_path.canonicalize().unwrap_or(as_path);
+    if let Some(entry) = mappings.get_key_value(canonical.to_string_lossy().as_ref()) {
+        return Ok(entry);
+    }
 
-    // Search for matching project (by name or full path)
-    for (project_path, db_path_str) in mappings {
-        // Match by full path or by directory name
-        let matches = project_path.contains(project_name)
-            || PathBuf::from(&project_path)
+    let mut matches: Vec<_> = mappings
+        .iter()
+        .filter(|(project_path, _)| {
+            Path::new(project_path.as_str())
                 .file_name()
                 .and_then(|n| n.to_str())
-                .map(|n| n.contains(project_name))
-                .unwrap_or(false);
+                == Some(project)
+        })
+        .collect();
 
-        if matches {
-            let db_path = PathBuf::from(&db_path_str);
-            if db_path.exists() {
-                found_paths.push(db_path);
-            }
+    match matches.len() {
+        0 => Err(anyhow::anyhow!(
+            "Project '{}' not found in global registry (run `demongrep list` to see indexed projects)",
+            project
+        )),
+        1 => Ok(matches.remove(0)),
+        _ => {
+            let mut candidates: Vec<_> = matches.iter().map(|(path, _)| path.as_str()).collect();
+            candidates.sort_unstable();
+            Err(anyhow::anyhow!(
+                "Project name '{}' is ambiguous, pass the full path instead:\n   {}",
+                project,
+                candidates.join("\n   ")
+            ))
+        }
+    }
+}
 
-            // Also check for local database at project path
-            let project_pb = PathBuf::from(&project_path);
-            if project_pb.exists() {
-                let local_db = local_db_path(&project_pb);
-                if local_db.exists() {
-                    found_paths.push(local_db);
-                } else {
-                    let legacy_local_db = legacy_local_db_path(&project_pb);
-                    if legacy_local_db.exists() {
-                        found_paths.push(legacy_local_db);
-                    }
-                }
-            }
+/// Find databases for a project by name or path (looked up in projects.json)
+fn find_project_databases(project: &str) -> Result<Vec<PathBuf>> {
+    let mappings = load_project_mappings()?;
+    let (project_path, db_path_str) = resolve_project(&mappings, project)?;
+
+    let mut found_paths = Vec::new();
+
+    let db_path = PathBuf::from(db_path_str);
+    if db_path.exists() {
+        found_paths.push(db_path);
+    }
+
+    // Also check for local database at project path
+    let project_pb = PathBuf::from(project_path);
+    let local_db = local_db_path(&project_pb);
+    if local_db.exists() {
+        found_paths.push(local_db);
+    } else {
+        let legacy_local_db = legacy_local_db_path(&project_pb);
+        if legacy_local_db.exists() {
+            found_paths.push(legacy_local_db);
         }
     }
 
@@ -268,42 +361,323 @@ fn remove_from_project_mapping(project_name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Insert chunks into the vector store and FTS index in a single pass
+///
+/// Vectors are written in one LMDB transaction and FTS documents are
+/// committed once at the end. The two commits aren't atomic: the vectors are
+/// committed first, so a failed FTS commit leaves them without full-text
+/// entries, and if adding an FTS document fails, those added for earlier
+/// chunks stay queued in the FTS writer. Without an FTS index
+/// (`index --no-fts`) only the vectors are written. Returned IDs follow the
+/// input order.
+pub fn ingest_chunks(
+    store: &mut VectorStore,
+    mut fts_store: Option<&mut FtsStore>,
+    chunks: &[EmbeddedChunk],
+) -> Result<Vec<u32>> {
+    let ids = store.insert_chunks_with(chunks, |id, chunk| match fts_store.as_deref_mut() {
+        Some(fts_store) => fts_store.add_chunk(
+            id,
+            &chunk.chunk.content,
+            &chunk.chunk.path,
+            chunk.chunk.signature.as_deref(),
+            chunk.chunk.kind.as_str(),
+            &chunk.chunk.string_literals,
+        ),
+        None => Ok(()),
+    })?;
+    if let Some(fts_store) = fts_store {
+        fts_store.commit()?;
+    }
+    Ok(ids)
+}
+
+/// Outcome of reindexing a single file
+#[derive(Debug, Default)]
+pub struct ReindexedFile {
+    /// Path the file is tracked under in the store
+    pub path: PathBuf,
+    /// Chunks removed from the previous version
+    pub removed: usize,
+    /// IDs of the chunks inserted for the current version
+    pub chunk_ids: Vec<u32>,
+}
+
+/// Embed a changed file's chunks, reusing `stored` embeddings for unchanged ones
+///
+/// `stored` maps content hashes to the vectors of the file's previous chunks
+/// (see [`VectorStore::embeddings_by_hash`]); only chunks whose content isn't
+/// among them go to `embed`, so editing one function re-embeds just that one.
+pub(crate) fn embed_changed_chunks<F>(
+    chunks: Vec<Chunk>,
+    stored: &HashMap<String, Vec<f32>>,
+    embed: F,
+) -> Result<Vec<EmbeddedChunk>>
+where
+    F: FnOnce(Vec<Chunk>) -> Result<Vec<EmbeddedChunk>>,
+{
+    let (unchanged, changed): (Vec<Chunk>, Vec<Chunk>) = chunks
+        .into_iter()
+        .partition(|chunk| stored.contains_key(&chunk.hash));
+
+    let mut embedded: Vec<EmbeddedChunk> = unchanged
+        .into_iter()
+        .map(|chunk| {
+            let embedding = stored[&chunk.hash].clone();
+            EmbeddedChunk::new(chunk, embedding)
+        })
+        .collect();
+    if !changed.is_empty() {
+        embedded.extend(embed(changed)?);
+    }
+    Ok(embedded)
+}
+
+/// Semantic chunker for indexing, with the `[chunking]` minimum sizes from the project config
+pub(crate) fn indexing_chunker(chunking: &ChunkingConfig) -> SemanticChunker {
+    SemanticChunker::new(100, 2000, 10)
+        .with_min_chunk_size(chunking.min_chunk_lines, chunking.min_chunk_chars)
+}
+
+/// Reindex a single file in an open vector store and FTS index
+///
+/// Deletes the file's previous chunks, re-chunks it, embeds the chunks whose
+/// content changed with `embed`, inserts them and rebuilds the vector index.
+/// A file that no longer exists is just removed. This is the primitive behind
+/// `reindex-file`.
+///
+/// The previous chunk IDs come from `file_meta` when it tracks the file, since
+/// `search --sync` and `serve` only record their changes there; otherwise from
+/// the store's own file metadata, or by path when neither tracks it. Both
+/// metadata records are updated with the new IDs.
+pub fn reindex_file_in_store<F>(
+    store: &mut VectorStore,
+    mut fts_store: Option<&mut FtsStore>,
+    file_meta: &mut FileMetaStore,
+    chunker: &mut SemanticChunker,
+    file: &Path,
+    embed: F,
+) -> Result<ReindexedFile>
+where
+    F: FnOnce(Vec<Chunk>) -> Result<Vec<EmbeddedChunk>>,
+{
+    // Keep the key the file was indexed under so chunk paths stay consistent
+    let tracked = match file_meta.file_chunk_ids(file) {
+        Some(tracked) => Some(tracked),
+        None => store.file_chunk_ids(file)?,
+    };
+    let (path, old_chunk_ids, stored) = match tracked {
+        Some((key, ids)) => {
+            let stored = store.embeddings_by_hash(&ids)?;
+            store.delete_chunks(&ids)?;
+            (PathBuf::from(key), ids, stored)
+        }
+        None => {
+            let ids = store.delete_file_chunks(&file.to_string_lossy())?;
+            (file.to_path_buf(), ids, HashMap::new())
+        }
+    };
+
+    if let Some(fts_store) = fts_store.as_deref_mut() {
+        for &chunk_id in &old_chunk_ids {
+            fts_store.delete_chunk(chunk_id)?;
+        }
+    }
+
+    let mut outcome = ReindexedFile {
+        path: path.clone(),
+        removed: old_chunk_ids.len(),
+        chunk_ids: vec![],
+    };
+
+    if !path.exists() {
+        if let Some(fts_store) = fts_store {
+            fts_store.commit()?;
+        }
+        store.remove_file_metadata(&path)?;
+        file_meta.remove_file(&path);
+        store.build_index()?;
+        return Ok(outcome);
+    }
+
+    let source_code = std::fs::read_to_string(&path)?;
+    let chunks =
+        chunker.chunk_semantic(Language::detect(&path, &source_code), &path, &source_code)?;
+
+    outcome.chunk_ids = if chunks.is_empty() {
+        if let Some(fts_store) = fts_store {
+            fts_store.commit()?;
+        }
+        vec![]
+    } else {
+        let embedded_chunks = embed_changed_chunks(chunks, &stored, embed)?;
+        ingest_chunks(store, fts_store, &embedded_chunks)?
+    };
+
+    store.build_index()?;
+    store.update_file_metadata(&path, outcome.chunk_ids.clone())?;
+    file_meta.update_file(&path, outcome.chunk_ids.clone())?;
+
+    Ok(outcome)
+}
+
+/// Reindex a single file without a full sync
+pub async fn reindex_file(file: PathBuf, path: Option<PathBuf>) -> Result<()> {
+    let db_path = get_search_db_paths(path)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            anyhow::anyhow!("No index found. Run `demongrep index` first to create one.")
+        })?;
+
+    let (model_type, dimensions) = DatabaseManager::read_metadata(&db_path)
+        .unwrap_or_else(|| (ModelType::default(), ModelType::default().dimensions()));
+
+    println!("📝 Re-indexing: {}", file.display());
+    println!("💾 Database: {}", db_path.display());
+
+    let index_metadata = DatabaseManager::index_metadata(&db_path);
+    let mut store = VectorStore::new(&db_path, dimensions)?;
+    let mut fts_store = index_metadata
+        .fts
+        .then(|| FtsStore::new(&db_path))
+        .transpose()?;
+    let project_root = path.clone().unwrap_or_else(|| PathBuf::from("."));
+    let config = ProjectConfig::load(Some(&project_root));
+    let mut embedding_service =
+        EmbeddingService::from_config(&config.embedding, model_type, Some(&db_path), dimensions)?;
+
+    let start = Instant::now();
+    let mut chunker = indexing_chunker(&config.chunking).with_doc_chunks(index_metadata.docs);
+    let mut file_meta =
+        FileMetaStore::load_or_create(&db_path, model_type.short_name(), dimensions)?;
+    let outcome = reindex_file_in_store(
+        &mut store,
+        fts_store.as_mut(),
+        &mut file_meta,
+        &mut chunker,
+        &file,
+        |chunks| embedding_service.embed_chunks(chunks),
+    )?;
+    file_meta.save(&db_path)?;
+    mark_indexed_now(&db_path)?;
+
+    println!(
+        "✅ Replaced {} chunk(s) with {} in {:?}",
+        outcome.removed,
+        outcome.chunk_ids.len(),
+        start.elapsed()
+    );
+
+    Ok(())
+}
+
+/// The local model pinned with `[embedding] pinned = true`, if any
+pub(crate) fn pinned_model(config: &ProjectConfig) -> Result<Option<ModelType>> {
+    let embedding = &config.embedding;
+    if !embedding.pinned || embedding.provider == EmbeddingProvider::Remote {
+        return Ok(None);
+    }
+    configured_model(config).map(Some)
+}
+
+/// The local model `[embedding] model` names (a `[models]` alias or a
+/// built-in name), used when no `--model` is given
+///
+/// Without a config file this is the default model. The remote provider's
+/// model isn't a local one; it reads as [`RemoteEmbedder::TEXT_FORMAT`], like
+/// remote indexes do.
+pub(crate) fn configured_model(config: &ProjectConfig) -> Result<ModelType> {
+    let embedding = &config.embedding;
+    if embedding.provider == EmbeddingProvider::Remote {
+        return Ok(RemoteEmbedder::TEXT_FORMAT);
+    }
+    config.models.resolve(&embedding.model).ok_or_else(|| {
+        DemongrepError::UnknownModel {
+            model: embedding.model.clone(),
+            available: ModelType::available_names(),
+        }
+        .into()
+    })
+}
+
+/// Model to index with: `--model`, else the pinned or configured model
+///
+/// A `--model` that conflicts with the pinned model is an error.
+fn index_model(config: &ProjectConfig, requested: Option<ModelType>) -> Result<ModelType> {
+    match (pinned_model(config)?, requested) {
+        (Some(pinned), Some(requested)) if requested != pinned => Err(anyhow::anyhow!(
+            "--model {} conflicts with the model pinned in .demongrep.toml ({}); \
+             drop --model or unpin [embedding] first",
+            requested.short_name(),
+            pinned.short_name()
+        )),
+        (Some(pinned), _) => Ok(pinned),
+        (None, Some(requested)) => Ok(requested),
+        (None, None) => configured_model(config),
+    }
+}
+
+/// Flags of a `demongrep index` run, besides the project path
+#[derive(Debug, Clone, Default)]
+pub struct IndexOptions {
+    /// Directories within the project to index instead of all of it
+    pub roots: Vec<PathBuf>,
+    pub dry_run: bool,
+    /// Rebuild from scratch, keeping only the embedding cache
+    pub force: bool,
+    pub global: bool,
+    pub follow_symlinks: bool,
+    pub include_hidden: bool,
+    pub max_depth: Option<usize>,
+    pub index_mode: Option<IndexMode>,
+    pub model: Option<ModelType>,
+    pub truncate_dims: Option<usize>,
+    pub show_stats: bool,
+    pub no_fts: bool,
+    pub index_docs: bool,
+    /// Only fill the embedding cache, without building an index
+    pub embed_only: bool,
+    pub profile: IndexProfile,
+}
+
 /// Index a repository
-pub async fn index(
-    path: Option<PathBuf>,
-    dry_run: bool,
-    _force: bool,
-    global: bool,
-    model: Option<ModelType>,
-) -> Result<()> {
+pub async fn index(path: Option<PathBuf>, options: IndexOptions) -> Result<()> {
+    let IndexOptions {
+        roots,
+        dry_run,
+        force,
+        global,
+        follow_symlinks,
+        include_hidden,
+        max_depth,
+        index_mode,
+        model,
+        truncate_dims,
+        show_stats,
+        no_fts,
+        index_docs,
+        embed_only,
+        profile,
+    } = options;
     let project_path = path.clone().unwrap_or_else(|| PathBuf::from("."));
-    let canonical_path = project_path.canonicalize()?;
+    let canonical_path = canonical_project_path(&project_path)?;
 
-    // Check for existing databases (local and global)
+    // Check for existing databases (local and global); an embedding cache
+    // alone doesn't count
     let local_db_path = local_db_path(&canonical_path);
     let legacy_local_db_path = legacy_local_db_path(&canonical_path);
-    let existing_local_db = if local_db_path.exists() {
+    let existing_local_db = if has_vector_store(&local_db_path) {
         Some(local_db_path.clone())
-    } else if legacy_local_db_path.exists() {
+    } else if has_vector_store(&legacy_local_db_path) {
         Some(legacy_local_db_path.clone())
     } else {
         None
     };
-    let global_db_path = if let Some(home) = dirs::home_dir() {
-        let mut hasher = DefaultHasher::new();
-        canonical_path.hash(&mut hasher);
-        let hash = hasher.finish();
-        Some(
-            home.join(".demongrep")
-                .join("stores")
-                .join(format!("{:x}", hash)),
-        )
-    } else {
-        None
-    };
+    let global_db_path = dirs::home_dir().map(|home| global_db_path(&home, &canonical_path));
 
     let local_exists = existing_local_db.is_some();
-    let global_exists = global_db_path.as_ref().map(|p| p.exists()).unwrap_or(false);
+    let global_exists = global_db_path.as_deref().is_some_and(has_vector_store);
 
     // Enforce exclusivity: can't have both local AND global
     if local_exists && global_exists {
@@ -365,7 +739,68 @@ pub async fn index(
     }
 
     let db_path = get_index_db_path(Some(canonical_path.clone()), global)?;
-    let model_type = model.unwrap_or_default();
+    let config = ProjectConfig::load(Some(&canonical_path));
+    let model_type = index_model(&config, model)?;
+    let remote = config.embedding.provider == EmbeddingProvider::Remote;
+    if remote && (model.is_some() || truncate_dims.is_some()) {
+        return Err(anyhow::anyhow!(
+            "--model and --truncate-dims only apply to local models; \
+             the remote model is configured in .demongrep.toml"
+        ));
+    }
+
+    // --force: rebuild from scratch, so nothing below sees the old index or
+    // its remembered settings
+    if force && !dry_run && !embed_only && has_vector_store(&db_path) {
+        println!("🗑️  --force: deleting the existing index");
+        remove_index(&db_path)?;
+    }
+
+    // Matryoshka truncation: the explicit flag, or whatever an existing index was built with
+    let truncate_dims = match truncate_dims {
+        Some(dims) => Some(model_type.validate_truncate_dims(dims)?),
+        None if remote => None,
+        None => DatabaseManager::read_metadata(&db_path)
+            .filter(|(stored_model, _)| *stored_model == model_type)
+            .and_then(|(_, dims)| model_type.truncation_for(dims)),
+    }
+    .filter(|&dims| dims < model_type.dimensions());
+    let (model_name, dimensions) = if remote {
+        let dimensions = config.embedding.dimensions.ok_or_else(|| {
+            anyhow::anyhow!("embedding.dimensions is required for the remote provider")
+        })?;
+        (config.embedding.model.clone(), dimensions)
+    } else {
+        (
+            model_type.name().to_string(),
+            truncate_dims.unwrap_or_else(|| model_type.dimensions()),
+        )
+    };
+
+    // The database directory may only hold the embedding cache (index --embed-only)
+    let index_exists = has_vector_store(&db_path);
+
+    let index_metadata = DatabaseManager::index_metadata(&db_path);
+    // Vector-only: the explicit flag, or an existing index built without FTS
+    let no_fts = no_fts || (index_exists && !index_metadata.fts);
+    // Doc chunks: likewise sticky once an index was built with them
+    let index_docs = index_docs || (index_exists && index_metadata.docs);
+
+    // Roots: the explicit paths, or whatever an existing index was built with
+    let stored_roots = index_metadata.roots;
+    let roots = if roots.is_empty() {
+        stored_roots
+    } else {
+        let roots = relative_roots(&canonical_path, &roots)?;
+        if index_exists && roots != stored_roots {
+            println!("\n{}", "⚠️  Index roots changed!".yellow());
+            println!("\n   Run {} first", "demongrep clear".bright_cyan());
+            return Err(anyhow::anyhow!(
+                "Index roots mismatch - clear database first"
+            ));
+        }
+        roots
+    };
 
     println!("{}", "🚀 Demongrep Indexer".bright_cyan().bold());
     println!("{}", "=".repeat(60));
@@ -376,20 +811,42 @@ pub async fn index(
     } else {
         println!("📍 Mode: Local (project-specific)");
     }
-    println!(
-        "🧠 Model: {} ({} dims)",
-        model_type.name(),
-        model_type.dimensions()
-    );
+    match truncate_dims {
+        _ if remote => println!("🧠 Model: {} ({} dims, remote)", model_name, dimensions),
+        Some(dims) => println!(
+            "🧠 Model: {} ({} dims, truncated from {})",
+            model_type.name(),
+            dims,
+            model_type.dimensions()
+        ),
+        None => println!(
+            "🧠 Model: {} ({} dims)",
+            model_type.name(),
+            model_type.dimensions()
+        ),
+    }
+
+    if !roots.is_empty() {
+        let roots: Vec<_> = roots.iter().map(|r| r.display().to_string()).collect();
+        println!("📁 Roots: {}", roots.join(", "));
+    }
+    if no_fts {
+        println!("🔎 Full-text index: disabled (vector-only search)");
+    }
+    if index_docs {
+        println!("📖 Doc comments: indexed as separate chunks");
+    }
 
     if dry_run {
         println!("\n{}", "🔍 DRY RUN MODE".bright_yellow());
     }
 
     // Check if this is incremental or full index
-    let is_incremental = db_path.exists();
+    let is_incremental = index_exists;
 
-    if is_incremental {
+    if embed_only {
+        println!("🧊 Mode: Embed only (filling the embedding cache, no index written)");
+    } else if is_incremental {
         println!("🔄 Mode: Incremental (updating existing database)");
     } else {
         println!("🆕 Mode: Full (creating new database)");
@@ -400,7 +857,10 @@ pub async fn index(
     println!("{}", "-".repeat(60));
 
     let start = Instant::now();
-    let walker = FileWalker::new(project_path.clone());
+    let walker = project_walker(&project_path, &roots)
+        .follow_symlinks(follow_symlinks)
+        .include_hidden(include_hidden)
+        .max_depth(max_depth);
     let (files, stats) = walker.walk()?;
     let discovery_duration = start.elapsed();
 
@@ -411,6 +871,24 @@ pub async fn index(
     );
     println!("   Total files scanned: {}", stats.total_files);
     println!("   Binary/skipped: {}", stats.skipped_binary);
+    if stats.followed_symlinks > 0 || stats.skipped_symlinks > 0 {
+        println!(
+            "   Symlinks: {} followed, {} skipped",
+            stats.followed_symlinks, stats.skipped_symlinks
+        );
+    }
+    if stats.skipped_depth > 0 {
+        println!(
+            "   Beyond --max-depth: {} directories skipped",
+            stats.skipped_depth
+        );
+    }
+    if stats.skipped_generated > 0 {
+        println!(
+            "   Generated/minified: {} skipped (see indexing.allow_generated)",
+            stats.skipped_generated
+        );
+    }
     println!("   Total size: {:.2} MB", stats.total_size_mb());
 
     if files.is_empty() {
@@ -423,14 +901,54 @@ pub async fn index(
         return Ok(());
     }
 
+    if embed_only {
+        println!("\n{}", "Phase 2: Chunking and Embedding".bright_cyan());
+        println!("{}", "-".repeat(60));
+        let start = Instant::now();
+        let mut embedding_service = EmbeddingService::from_config(
+            &config.embedding,
+            model_type,
+            Some(&db_path),
+            dimensions,
+        )?;
+        let fill = fill_embedding_cache(
+            &files,
+            &config.chunking,
+            index_docs,
+            profile,
+            &mut embedding_service,
+        )?;
+
+        println!("\n{}", "📊 Embedding Cache".bright_green().bold());
+        println!("{}", "=".repeat(60));
+        println!("   Chunks: {}", fill.chunks);
+        println!("   Already cached: {}", fill.chunks - fill.embedded());
+        println!("   Newly embedded: {}", fill.embedded());
+        println!(
+            "   Cache entries: {} -> {}",
+            fill.cached_before, fill.cached_after
+        );
+        println!("   Cache: {}", db_path.join("embedding_cache").display());
+        println!("   Time: {:?}", start.elapsed());
+        println!(
+            "\n{}",
+            "✨ Embedding cache filled! Run `demongrep index` to build the index from it."
+                .bright_green()
+                .bold()
+        );
+        return Ok(());
+    }
+
     // Open or create database
-    let mut store = VectorStore::new(&db_path, model_type.dimensions())?;
+    let mut store = VectorStore::new(&db_path, dimensions)?;
+    if let Some(mode) = index_mode {
+        store.set_index_mode(mode);
+    }
 
     // Check database metadata for model changes
     if is_incremental {
-        let db_meta = store.get_db_metadata(model_type.name(), model_type.dimensions())?;
-        if db_meta.model_name != model_type.name() || db_meta.dimensions != model_type.dimensions()
-        {
+        let db_meta = store.get_db_metadata(&model_name, dimensions)?;
+        if db_meta.model_name != model_name || db_meta.dimensions != dimensions {
             println!(
                 "\n{}",
                 "⚠️  Model changed! Full re-index required.".yellow()
@@ -439,13 +957,14 @@ pub async fn index(
                 "   Old: {} ({} dims)",
                 db_meta.model_name, db_meta.dimensions
             );
+            println!("   New: {} ({} dims)", model_name, dimensions);
             println!(
-                "   New: {} ({} dims)",
-                model_type.name(),
-                model_type.dimensions()
+                "\n   Rebuild with {}",
+                "demongrep index --force".bright_cyan()
             );
-            println!("\n   Run {} first", "demongrep clear".bright_cyan());
-            return Err(anyhow::anyhow!("Model mismatch - clear database first"));
+            return Err(anyhow::anyhow!(
+                "Model mismatch - rebuild with `demongrep index --force`"
+            ));
         }
     }
 
@@ -497,110 +1016,8 @@ pub async fn index(
         files_to_index = files.iter().map(|f| (f.clone(), vec![])).collect();
     }
 
-    // Phase 2: Semantic Chunking
-    println!("\n{}", "Phase 2: Semantic Chunking".bright_cyan());
-    println!("{}", "-".repeat(60));
-
-    let start = Instant::now();
-
-    let pb = ProgressBar::new(files_to_index.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("█▓▒░ "),
-    );
-
-    let skipped_files = AtomicUsize::new(0);
-    let all_chunks: Vec<crate::chunker::Chunk> = files_to_index
-        .par_iter()
-        .flat_map(|(file, _old_chunk_ids)| {
-            pb.inc(1);
-
-            // Each thread gets its own chunker (tree-sitter parser has internal state)
-            let mut chunker = SemanticChunker::new(100, 2000, 10);
-
-            // Skip files that aren't valid UTF-8
-            let source_code = match std::fs::read_to_string(&file.path) {
-                Ok(content) => content,
-                Err(_) => {
-                    skipped_files.fetch_add(1, Ordering::Relaxed);
-                    return vec![];
-                }
-            };
-
-            chunker
-                .chunk_semantic(file.language, &file.path, &source_code)
-                .unwrap_or_default()
-        })
-        .collect();
-
-    let skipped_count = skipped_files.load(Ordering::Relaxed);
-    if skipped_count > 0 {
-        println!("   ⚠️  Skipped {} files (invalid UTF-8)", skipped_count);
-    }
-
-    pb.finish_with_message("Done!");
-    let chunking_duration = start.elapsed();
-
-    println!(
-        "✅ Created {} chunks in {:?}",
-        all_chunks.len(),
-        chunking_duration
-    );
-
-    // Phase 3: Embedding Generation
-    println!("\n{}", "Phase 3: Embedding Generation".bright_cyan());
-    println!("{}", "-".repeat(60));
-
-    let start = Instant::now();
-    println!("🔄 Initializing embedding model...");
-
-    let mut embedding_service = EmbeddingService::with_model_and_db(model_type, &db_path)?;
-    println!(
-        "✅ Model loaded: {} ({} dims)",
-        embedding_service.model_name(),
-        embedding_service.dimensions()
-    );
-
-    let embedded_chunks = if all_chunks.is_empty() {
-        vec![]
-    } else {
-        println!(
-            "\n🔄 Generating embeddings for {} chunks...",
-            all_chunks.len()
-        );
-        let chunks = embedding_service.embed_chunks(all_chunks)?;
-        println!(
-            "✅ Generated {} embeddings in {:?}",
-            chunks.len(),
-            start.elapsed()
-        );
-        println!(
-            "   Average: {:?} per chunk",
-            start.elapsed() / chunks.len() as u32
-        );
-
-        // Show cache stats
-        let cache_stats = embedding_service.cache_stats();
-        println!("   Cache hit rate: {:.1}%", cache_stats.hit_rate() * 100.0);
-
-        chunks
-    };
-    let embedding_duration = start.elapsed();
-
-    // Phase 4: Vector Storage
-    println!("\n{}", "Phase 4: Vector Storage".bright_cyan());
-    println!("{}", "-".repeat(60));
-
-    let start = Instant::now();
-
-    // Database already opened earlier - just print status
-    if !is_incremental {
-        println!("✅ Database ready (newly created)");
-    }
-
-    // Delete old chunks from changed/deleted files
+    // Drop the old chunks of changed and deleted files; their replacements
+    // are inserted below
     if is_incremental {
         let mut chunks_to_delete = Vec::new();
 
@@ -621,83 +1038,240 @@ pub async fn index(
         }
     }
 
-    // Insert new chunks
-    let chunk_ids = if !embedded_chunks.is_empty() {
-        println!("\n🔄 Inserting {} chunks...", embedded_chunks.len());
-        let ids = store.insert_chunks_with_ids(embedded_chunks.clone())?;
-        println!("✅ Inserted {} chunks into vector store", ids.len());
-        ids
+    // Open FTS index and queue deletions for changed/deleted files
+    let mut fts_store = if no_fts {
+        // Drop a full-text index left over from before --no-fts so it can't go stale
+        let fts_path = db_path.join("fts");
+        if fts_path.exists() {
+            std::fs::remove_dir_all(&fts_path)?;
+            println!("🗑️  Removed existing full-text index");
+        }
+        None
     } else {
-        vec![]
+        Some(FtsStore::with_config(&db_path, &config.fts)?)
     };
-
-    println!("\n🔄 Building vector index...");
-    store.build_index()?;
-
-    // Phase 4b: FTS Index
-    println!("\n🔄 Updating full-text search index...");
-    let mut fts_store = FtsStore::new(&db_path)?;
-
-    // Delete old FTS entries
-    if is_incremental {
-        let mut fts_chunks_to_delete: Vec<u32> = Vec::new();
+    if let Some(fts_store) = fts_store.as_mut().filter(|_| is_incremental) {
         for (_file, old_chunk_ids) in &files_to_index {
-            fts_chunks_to_delete.extend(old_chunk_ids);
+            for &chunk_id in old_chunk_ids {
+                let _ = fts_store.delete_chunk(chunk_id);
+            }
         }
         for (_path, old_chunk_ids) in &files_to_delete {
-            fts_chunks_to_delete.extend(old_chunk_ids);
-        }
-
-        if !fts_chunks_to_delete.is_empty() {
-            for chunk_id in fts_chunks_to_delete {
+            for &chunk_id in old_chunk_ids {
                 let _ = fts_store.delete_chunk(chunk_id);
             }
-            // Commit deletions before adding new entries
-            fts_store.commit()?;
         }
     }
 
-    // Add new FTS entries
-    for (chunk, chunk_id) in embedded_chunks.iter().zip(chunk_ids.iter()) {
-        fts_store.add_chunk(
-            *chunk_id,
-            &chunk.chunk.content,
-            &chunk.chunk.path,
-            chunk.chunk.signature.as_deref(),
-            &format!("{:?}", chunk.chunk.kind),
-            &chunk.chunk.string_literals,
-        )?;
+    let files_to_chunk: Vec<&FileInfo> = files_to_index.iter().map(|(file, _)| file).collect();
+    let mut ingested = IngestedChunks::default();
+
+    let (embedding_service, chunking_duration, embedding_duration, storage_duration) =
+        match profile.file_group_size() {
+            Some(file_group_size) => {
+                // Phases 2-4 a group of files at a time, so only one group's
+                // chunks and embeddings are ever held in memory
+                println!(
+                    "\n{}",
+                    "Phase 2: Chunking, Embedding & Storage (memory profile)".bright_cyan()
+                );
+                println!("{}", "-".repeat(60));
+
+                println!("🔄 Initializing embedding model...");
+                let mut embedding_service = EmbeddingService::from_config(
+                    &config.embedding,
+                    model_type,
+                    Some(&db_path),
+                    dimensions,
+                )?;
+                println!(
+                    "✅ Model loaded: {} ({} dims)",
+                    embedding_service.model_name(),
+                    embedding_service.dimensions()
+                );
+
+                let mut chunking_duration = std::time::Duration::ZERO;
+                let mut embedding_duration = std::time::Duration::ZERO;
+                let mut storage_duration = std::time::Duration::ZERO;
+                let progress = Progress::new(files_to_chunk.len() as u64, "Indexing");
+                let mut skipped_count = 0;
+                for group in files_to_chunk.chunks(file_group_size) {
+                    let start = Instant::now();
+                    let (chunks, skipped) =
+                        chunk_file_group(group, &config.chunking, index_docs, &progress);
+                    skipped_count += skipped;
+                    chunking_duration += start.elapsed();
+                    if chunks.is_empty() {
+                        continue;
+                    }
+
+                    let start = Instant::now();
+                    let embedded = embed_chunks_grouped(
+                        &mut embedding_service,
+                        chunks,
+                        profile.embed_group_size(),
+                    )?;
+                    embedding_duration += start.elapsed();
+
+                    let start = Instant::now();
+                    let ids = ingest_chunks(&mut store, fts_store.as_mut(), &embedded)?;
+                    ingested.record(&embedded, &ids);
+                    storage_duration += start.elapsed();
+                }
+                progress.finish();
+                if skipped_count > 0 {
+                    println!("   ⚠️  Skipped {} files (invalid UTF-8)", skipped_count);
+                }
+                if let Some(fts_store) = fts_store.as_mut().filter(|_| ingested.chunks == 0) {
+                    fts_store.commit()?;
+                }
+
+                println!(
+                    "✅ Chunked, embedded and inserted {} chunks in {:?}",
+                    ingested.chunks,
+                    chunking_duration + embedding_duration + storage_duration
+                );
+
+                (
+                    embedding_service,
+                    chunking_duration,
+                    embedding_duration,
+                    storage_duration,
+                )
+            }
+            None => {
+                // Phase 2: Semantic Chunking
+                println!("\n{}", "Phase 2: Semantic Chunking".bright_cyan());
+                println!("{}", "-".repeat(60));
+
+                let start = Instant::now();
+
+                let all_chunks = chunk_files(&files_to_chunk, &config.chunking, index_docs);
+                let chunking_duration = start.elapsed();
+
+                println!(
+                    "✅ Created {} chunks in {:?}",
+                    all_chunks.len(),
+                    chunking_duration
+                );
+
+                // Phase 3: Embedding Generation
+                println!("\n{}", "Phase 3: Embedding Generation".bright_cyan());
+                println!("{}", "-".repeat(60));
+
+                let start = Instant::now();
+                println!("🔄 Initializing embedding model...");
+
+                let mut embedding_service = EmbeddingService::from_config(
+                    &config.embedding,
+                    model_type,
+                    Some(&db_path),
+                    dimensions,
+                )?;
+                println!(
+                    "✅ Model loaded: {} ({} dims)",
+                    embedding_service.model_name(),
+                    embedding_service.dimensions()
+                );
+
+                let embedded_chunks = if all_chunks.is_empty() {
+                    vec![]
+                } else {
+                    println!(
+                        "\n🔄 Generating embeddings for {} chunks...",
+                        all_chunks.len()
+                    );
+                    let chunks = embedding_service.embed_chunks(all_chunks)?;
+                    println!(
+                        "✅ Generated {} embeddings in {:?}",
+                        chunks.len(),
+                        start.elapsed()
+                    );
+                    println!(
+                        "   Average: {:?} per chunk",
+                        start.elapsed() / chunks.len() as u32
+                    );
+
+                    // Show cache stats
+                    let cache_stats = embedding_service.cache_stats();
+                    println!("   Cache hit rate: {:.1}%", cache_stats.hit_rate() * 100.0);
+
+                    chunks
+                };
+                let embedding_duration = start.elapsed();
+
+                // Phase 4: Vector Storage
+                println!("\n{}", "Phase 4: Vector Storage".bright_cyan());
+                println!("{}", "-".repeat(60));
+
+                let start = Instant::now();
+
+                // Database already opened earlier - just print status
+                if !is_incremental {
+                    println!("✅ Database ready (newly created)");
+                }
+
+                // Insert new chunks into the vector store and FTS index in one pass
+                if !embedded_chunks.is_empty() {
+                    println!("\n🔄 Inserting {} chunks...", embedded_chunks.len());
+                    let ids = ingest_chunks(&mut store, fts_store.as_mut(), &embedded_chunks)?;
+                    ingested.record(&embedded_chunks, &ids);
+                    if fts_store.is_some() {
+                        println!(
+                            "✅ Inserted {} chunks into vector store and full-text index",
+                            ids.len()
+                        );
+                    } else {
+                        println!("✅ Inserted {} chunks into vector store", ids.len());
+                    }
+                } else if let Some(fts_store) = fts_store.as_mut() {
+                    fts_store.commit()?;
+                }
+
+                (
+                    embedding_service,
+                    chunking_duration,
+                    embedding_duration,
+                    start.elapsed(),
+                )
+            }
+        };
+
+    let language_counts = show_stats.then(|| {
+        chunks_by_language(
+            &ingested.chunks_per_file,
+            files_to_index.iter().map(|(file, _)| file),
+        )
+    });
+    let most_chunked_files =
+        show_stats.then(|| files_by_chunk_count(&ingested.chunks_per_file, TOP_FILES));
+
+    let start = Instant::now();
+    println!("\n🔄 Building vector index...");
+    store.build_index()?;
+
+    if let Some(fts_store) = &fts_store {
+        let fts_stats = fts_store.stats()?;
+        println!(
+            "✅ FTS index updated ({} documents)",
+            fts_stats.num_documents
+        );
     }
-    fts_store.commit()?;
 
-    let fts_stats = fts_store.stats()?;
-    println!(
-        "✅ FTS index updated ({} documents)",
-        fts_stats.num_documents
-    );
-
-    let storage_duration = start.elapsed();
+    let storage_duration = storage_duration + start.elapsed();
 
     println!("✅ Index updated in {:?}", storage_duration);
 
     // Update file metadata in VectorStore
     println!("\n🔄 Updating file metadata...");
 
-    // Group chunks by file
-    use std::collections::HashMap;
-    let mut file_chunks: HashMap<PathBuf, Vec<u32>> = HashMap::new();
-
-    for (i, chunk) in embedded_chunks.iter().enumerate() {
-        let path = PathBuf::from(&chunk.chunk.path);
-        file_chunks
-            .entry(path)
-            .or_insert_with(Vec::new)
-            .push(chunk_ids[i]);
-    }
-
     // Update metadata for changed files
     for (file, _) in &files_to_index {
-        let chunk_ids_for_file = file_chunks.get(&file.path).cloned().unwrap_or_default();
+        let chunk_ids_for_file = ingested
+            .file_chunks
+            .get(&file.path)
+            .cloned()
+            .unwrap_or_default();
         store.update_file_metadata(&file.path, chunk_ids_for_file)?;
     }
 
@@ -720,6 +1294,12 @@ pub async fn index(
         "model_short_name": embedding_service.model_short_name(),
         "model_name": embedding_service.model_name(),
         "dimensions": embedding_service.dimensions(),
+        "truncate_dims": truncate_dims,
+        "provider": config.embedding.provider,
+        "normalized": store.is_normalized(),
+        "fts": !no_fts,
+        "docs": index_docs,
+        "roots": (!roots.is_empty()).then_some(&roots),
         "indexed_at": chrono::Utc::now().to_rfc3339(),
     });
     std::fs::write(
@@ -744,6 +1324,37 @@ pub async fn index(
     );
     println!("   Dimensions: {}", db_stats.dimensions);
 
+    if let Some(language_counts) = language_counts {
+        println!("\n{}", "🗂️  Chunks by Language (this run)".bright_green());
+        println!("{}", "-".repeat(60));
+        for (language, count) in &language_counts {
+            println!("   {:<12} {:>8} chunks", language.name(), count);
+        }
+
+        println!("\n{}", "📏 Largest Files".bright_green());
+        println!("{}", "-".repeat(60));
+        for (path, size) in &stats.largest_files {
+            println!(
+                "   {:>10.1} KB  {}",
+                *size as f64 / 1024.0,
+                path.strip_prefix(&project_path).unwrap_or(path).display()
+            );
+        }
+    }
+
+    if let Some(most_chunked_files) = most_chunked_files {
+        println!("\n{}", "🧩 Most-Chunked Files (this run)".bright_green());
+        println!("{}", "-".repeat(60));
+        for (path, count) in &most_chunked_files {
+            let path = Path::new(path);
+            println!(
+                "   {:>8} chunks  {}",
+                count,
+                path.strip_prefix(&project_path).unwrap_or(path).display()
+            );
+        }
+    }
+
     // Calculate database size
     let mut total_size = 0u64;
     for entry in std::fs::read_dir(&db_path)? {
@@ -778,6 +1389,316 @@ pub async fn index(
     Ok(())
 }
 
+/// Number of files in the index at `db_path` modified or deleted since they
+/// were last indexed or synced
+///
+/// Each tracked file's mtime is compared with the one recorded for it (in the
+/// store or the sync metadata, whichever is newer); nothing is walked or read,
+/// so this is cheap enough to run before every search. Files added since
+/// aren't counted, and an index that doesn't record when it was built
+/// (`indexed_at` in `metadata.json`) reports 0.
+pub(crate) fn files_changed_since_index(db_path: &Path) -> Result<usize> {
+    if indexed_at(db_path).is_none() {
+        return Ok(0);
+    }
+
+    let mut recorded: HashMap<String, u64> = HashMap::new();
+    let mut record = |path: &str, mtime: u64| {
+        let newest = recorded.entry(path.to_string()).or_insert(mtime);
+        *newest = (*newest).max(mtime);
+    };
+    if has_vector_store(db_path) {
+        let store = VectorStore::open_readonly(db_path, stored_dimensions(db_path))?;
+        for (path, mtime) in store.tracked_file_mtimes()? {
+            record(&path, mtime);
+        }
+    }
+    if let Some(file_meta) = FileMetaStore::load(db_path) {
+        for (path, mtime) in file_meta.file_mtimes() {
+            record(path, mtime);
+        }
+    }
+
+    Ok(recorded
+        .iter()
+        .filter(|(path, &mtime)| {
+            std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .map(|modified| {
+                    modified
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |since| since.as_secs())
+                        > mtime
+                })
+                // Deleted files still have chunks in the index
+                .unwrap_or(true)
+        })
+        .count())
+}
+
+/// When the index at `db_path` was last built or synced
+fn indexed_at(db_path: &Path) -> Option<std::time::SystemTime> {
+    let content = std::fs::read_to_string(db_path.join("metadata.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let indexed_at =
+        chrono::DateTime::parse_from_rfc3339(json.get("indexed_at")?.as_str()?).ok()?;
+    Some(indexed_at.into())
+}
+
+/// Record that the index at `db_path` was updated now (after a sync, a
+/// `serve` watcher batch or `reindex-file`)
+pub(crate) fn mark_indexed_now(db_path: &Path) -> Result<()> {
+    let path = db_path.join("metadata.json");
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let mut json: serde_json::Value = serde_json::from_str(&content)?;
+    if let Some(metadata) = json.as_object_mut() {
+        metadata.insert(
+            "indexed_at".to_string(),
+            chrono::Utc::now().to_rfc3339().into(),
+        );
+        std::fs::write(&path, serde_json::to_string_pretty(&json)?)?;
+    }
+    Ok(())
+}
+
+/// Whether `db_path` holds a vector store, rather than being missing or
+/// holding only the embedding cache
+fn has_vector_store(db_path: &Path) -> bool {
+    db_path.join("data.mdb").exists()
+}
+
+/// Delete everything in `db_path` but the embedding cache (`index --force`)
+///
+/// Cached embeddings are keyed by model and content, so they stay valid and
+/// spare the rebuild from re-embedding unchanged chunks.
+fn remove_index(db_path: &Path) -> Result<()> {
+    for entry in std::fs::read_dir(db_path)? {
+        let path = entry?.path();
+        if path.file_name() == Some("embedding_cache".as_ref()) {
+            continue;
+        }
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Chunk files in parallel, skipping those that aren't valid UTF-8
+fn chunk_files(files: &[&FileInfo], chunking: &ChunkingConfig, index_docs: bool) -> Vec<Chunk> {
+    let progress = Progress::new(files.len() as u64, "Chunking");
+    let (chunks, skipped_count) = chunk_file_group(files, chunking, index_docs, &progress);
+    if skipped_count > 0 {
+        println!("   ⚠️  Skipped {} files (invalid UTF-8)", skipped_count);
+    }
+
+    progress.finish();
+    chunks
+}
+
+/// Chunk files in parallel, ticking `progress` per file; also returns how
+/// many files were skipped for not being valid UTF-8
+fn chunk_file_group(
+    files: &[&FileInfo],
+    chunking: &ChunkingConfig,
+    index_docs: bool,
+    progress: &Progress,
+) -> (Vec<Chunk>, usize) {
+    let skipped_files = AtomicUsize::new(0);
+    let chunks: Vec<Chunk> = files
+        .par_iter()
+        .flat_map(|file| {
+            progress.inc();
+
+            // Each thread gets its own chunker (tree-sitter parser has internal state)
+            let mut chunker = indexing_chunker(chunking).with_doc_chunks(index_docs);
+
+            // Skip files that aren't valid UTF-8
+            let source_code = match std::fs::read_to_string(&file.path) {
+                Ok(content) => content,
+                Err(_) => {
+                    skipped_files.fetch_add(1, Ordering::Relaxed);
+                    return vec![];
+                }
+            };
+
+            chunker
+                .chunk_semantic(file.language, &file.path, &source_code)
+                .unwrap_or_default()
+        })
+        .collect();
+
+    (chunks, skipped_files.load(Ordering::Relaxed))
+}
+
+/// Speed/memory trade-off chosen with `index --profile`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexProfile {
+    /// Adaptive batch size, all chunks embedded in one pass
+    #[default]
+    Default,
+    /// Small batches; files are chunked, embedded and inserted a group at
+    /// a time, with the in-memory embedding cache dropped after each group
+    ///
+    /// Indexing only: vector storage, the persistent embedding cache and
+    /// reranking are configured for searches as usual.
+    Memory,
+    /// Large batches in one pass; fastest, highest peak memory
+    Speed,
+}
+
+/// Embedding batch size of `index --profile memory`
+pub const MEMORY_PROFILE_BATCH_SIZE: usize = 4;
+/// Embedding batch size of `index --profile speed`
+pub const SPEED_PROFILE_BATCH_SIZE: usize = 128;
+/// Chunks `index --profile memory` embeds before dropping the in-memory cache
+const MEMORY_PROFILE_EMBED_GROUP: usize = 512;
+/// Files `index --profile memory` chunks, embeds and inserts at a time
+const MEMORY_PROFILE_FILE_GROUP: usize = 64;
+
+impl IndexProfile {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "default" => Some(IndexProfile::Default),
+            "memory" => Some(IndexProfile::Memory),
+            "speed" => Some(IndexProfile::Speed),
+            _ => None,
+        }
+    }
+
+    /// Embedding batch size the profile forces (None = adaptive)
+    pub fn batch_size(self) -> Option<usize> {
+        match self {
+            IndexProfile::Default => None,
+            IndexProfile::Memory => Some(MEMORY_PROFILE_BATCH_SIZE),
+            IndexProfile::Speed => Some(SPEED_PROFILE_BATCH_SIZE),
+        }
+    }
+
+    /// Chunks embedded per group (None = all at once)
+    fn embed_group_size(self) -> Option<usize> {
+        match self {
+            IndexProfile::Memory => Some(MEMORY_PROFILE_EMBED_GROUP),
+            IndexProfile::Default | IndexProfile::Speed => None,
+        }
+    }
+
+    /// Files chunked and embedded per group (None = all at once)
+    fn file_group_size(self) -> Option<usize> {
+        match self {
+            IndexProfile::Memory => Some(MEMORY_PROFILE_FILE_GROUP),
+            IndexProfile::Default | IndexProfile::Speed => None,
+        }
+    }
+}
+
+/// Chunk IDs inserted by one `index` run, by file
+#[derive(Debug, Default)]
+struct IngestedChunks {
+    chunks: usize,
+    file_chunks: HashMap<PathBuf, Vec<u32>>,
+    /// Chunk counts keyed by the chunks' paths (for `index --stats`)
+    chunks_per_file: HashMap<String, usize>,
+}
+
+impl IngestedChunks {
+    /// Record inserted `chunks` under their `ids` (in the same order)
+    fn record(&mut self, chunks: &[EmbeddedChunk], ids: &[u32]) {
+        self.chunks += ids.len();
+        for (chunk, &id) in chunks.iter().zip(ids) {
+            self.file_chunks
+                .entry(PathBuf::from(&chunk.chunk.path))
+                .or_default()
+                .push(id);
+            *self
+                .chunks_per_file
+                .entry(chunk.chunk.path.clone())
+                .or_insert(0) += 1;
+        }
+    }
+}
+
+/// Embed `chunks`, `group_size` at a time when set
+///
+/// Each group is written to the persistent cache as it finishes, and the
+/// in-memory cache is dropped before the next one so it can't grow with the
+/// repository.
+fn embed_chunks_grouped(
+    embedding_service: &mut EmbeddingService,
+    chunks: Vec<Chunk>,
+    group_size: Option<usize>,
+) -> Result<Vec<EmbeddedChunk>> {
+    let Some(group_size) = group_size.filter(|&size| size < chunks.len()) else {
+        return embedding_service.embed_chunks(chunks);
+    };
+
+    let mut embedded = Vec::with_capacity(chunks.len());
+    let mut chunks = chunks.into_iter().peekable();
+    while chunks.peek().is_some() {
+        let group: Vec<Chunk> = chunks.by_ref().take(group_size).collect();
+        embedded.extend(embedding_service.embed_chunks(group)?);
+        embedding_service.clear_cache();
+    }
+    Ok(embedded)
+}
+
+/// Embedding cache counts from `index --embed-only`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CacheFill {
+    chunks: usize,
+    cached_before: usize,
+    cached_after: usize,
+}
+
+impl CacheFill {
+    /// Chunks that weren't cached yet (identical chunks share an entry)
+    fn embedded(&self) -> usize {
+        self.cached_after.saturating_sub(self.cached_before)
+    }
+}
+
+/// Chunk and embed `files` into the persistent embedding cache only
+///
+/// Backs `index --embed-only` (e.g. warming a CI cache): no vector store,
+/// full-text index or metadata is written, so a later `index` builds the
+/// index from the cache without re-embedding.
+fn fill_embedding_cache(
+    files: &[FileInfo],
+    chunking: &ChunkingConfig,
+    index_docs: bool,
+    profile: IndexProfile,
+    embedding_service: &mut EmbeddingService,
+) -> Result<CacheFill> {
+    let cached_before = embedding_service.persistent_cache_len().unwrap_or(0);
+
+    let files: Vec<&FileInfo> = files.iter().collect();
+    let progress = Progress::new(files.len() as u64, "Chunking");
+    let mut chunk_count = 0;
+    let mut skipped_count = 0;
+    for group in files.chunks(profile.file_group_size().unwrap_or(files.len().max(1))) {
+        let (chunks, skipped) = chunk_file_group(group, chunking, index_docs, &progress);
+        skipped_count += skipped;
+        chunk_count += chunks.len();
+        if !chunks.is_empty() {
+            embed_chunks_grouped(embedding_service, chunks, profile.embed_group_size())?;
+        }
+    }
+    progress.finish();
+    if skipped_count > 0 {
+        println!("   ⚠️  Skipped {} files (invalid UTF-8)", skipped_count);
+    }
+
+    Ok(CacheFill {
+        chunks: chunk_count,
+        cached_before,
+        cached_after: embedding_service.persistent_cache_len().unwrap_or(0),
+    })
+}
+
 /// List all indexed repositories
 pub async fn list() -> Result<()> {
     println!("{}", "📚 Indexed Repositories".bright_cyan().bold());
@@ -830,11 +1751,73 @@ pub async fn list() -> Result<()> {
     Ok(())
 }
 
+/// JSON output for `demongrep stats --json`
+#[derive(Debug, Serialize)]
+struct StatsJson {
+    model: String,
+    total_chunks: usize,
+    total_files: usize,
+    indexed: bool,
+    dimensions: usize,
+    size_bytes: u64,
+    databases: Vec<DatabaseStatsJson>,
+}
+
+#[derive(Debug, Serialize)]
+struct DatabaseStatsJson {
+    path: String,
+    #[serde(rename = "type")]
+    db_type: String,
+    #[serde(flatten)]
+    stats: StoreStats,
+    size_bytes: u64,
+}
+
+/// Total on-disk size of a database directory
+fn database_size(db_path: &Path) -> u64 {
+    walkdir::WalkDir::new(db_path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn build_stats_json(db_manager: &DatabaseManager) -> Result<StatsJson> {
+    let combined = db_manager.combined_stats()?;
+
+    let mut databases = Vec::new();
+    for database in db_manager.databases() {
+        databases.push(DatabaseStatsJson {
+            path: database.path.display().to_string(),
+            db_type: database.db_type.name().to_lowercase(),
+            stats: database.store().stats()?,
+            size_bytes: database_size(&database.path),
+        });
+    }
+
+    Ok(StatsJson {
+        model: db_manager.model_type().short_name().to_string(),
+        total_chunks: combined.total_chunks,
+        total_files: combined.total_files,
+        indexed: combined.indexed,
+        dimensions: combined.dimensions,
+        size_bytes: databases.iter().map(|db| db.size_bytes).sum(),
+        databases,
+    })
+}
+
 /// Show statistics about the vector database - REFACTORED to use DatabaseManager
-pub async fn stats(path: Option<PathBuf>) -> Result<()> {
+pub async fn stats(path: Option<PathBuf>, json: bool) -> Result<()> {
+    if json {
+        crate::output::set_quiet(true);
+    }
+
     // Load all databases using DatabaseManager
     let db_manager = match DatabaseManager::load(path) {
         Ok(manager) => manager,
+        Err(_) if json => return Err(anyhow::anyhow!("No database found")),
         Err(_) => {
             println!("{}", "❌ No database found!".red());
             println!(
@@ -846,6 +1829,12 @@ pub async fn stats(path: Option<PathBuf>) -> Result<()> {
         }
     };
 
+    if json {
+        let stats = build_stats_json(&db_manager)?;
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
     // Show database info
     db_manager.print_info();
     println!();
@@ -887,13 +1876,11 @@ pub async fn stats(path: Option<PathBuf>) -> Result<()> {
     }
 
     // Calculate total database size
-    let mut total_size = 0u64;
-    for db_path in db_manager.database_paths() {
-        for entry in std::fs::read_dir(db_path)? {
-            let entry = entry?;
-            total_size += entry.metadata()?.len();
-        }
-    }
+    let total_size: u64 = db_manager
+        .database_paths()
+        .into_iter()
+        .map(|db_path| database_size(db_path))
+        .sum();
 
     println!("\n{}", "Storage:".bright_green());
     println!(
@@ -916,18 +1903,15 @@ pub async fn clear(path: Option<PathBuf>, yes: bool, project: Option<String>) ->
         // Look up project in projects.json
         find_project_databases(project_name)?
     } else {
-        // Use current directory
-        get_search_db_paths(path)?
+        // Use current directory, including a directory holding only an
+        // embedding cache
+        project_db_dirs(path, |db_path| db_path.exists())?
     };
 
     if db_paths.is_empty() {
         println!("{}", "❌ No database found!".red());
         if let Some(proj) = &project {
-            println!("   Project '{}' not found in global registry", proj);
-            println!(
-                "   Run {} to see all indexed projects",
-                "demongrep list".bright_cyan()
-            );
+            println!("   Project '{}' has no database on disk", proj);
         }
         return Ok(());
     }
@@ -1002,10 +1986,22 @@ pub async fn clear(path: Option<PathBuf>, yes: bool, project: Option<String>) ->
     Ok(())
 }
 
+/// Embedding dimensions recorded in a database's metadata.json
+fn stored_dimensions(db_path: &Path) -> usize {
+    DatabaseManager::read_metadata(db_path)
+        .map(|(_, dimensions)| dimensions)
+        .unwrap_or_else(|| ModelType::default().dimensions())
+}
+
+/// Open a store using the dimensions recorded in its metadata.json
+fn open_store_with_stored_dimensions(db_path: &Path) -> Result<VectorStore> {
+    VectorStore::new(db_path, stored_dimensions(db_path))
+}
+
 /// Helper to print repository stats
 fn print_repo_stats(_repo_path: &Path, db_path: &Path) -> Result<()> {
     // Try to load stats
-    match VectorStore::new(db_path, 384) {
+    match open_store_with_stored_dimensions(db_path) {
         Ok(store) => match store.stats() {
             Ok(stats) => {
                 println!(
@@ -1028,7 +2024,7 @@ fn print_repo_stats(_repo_path: &Path, db_path: &Path) -> Result<()> {
 /// Migrate legacy local index layout (.demongrep.db) to new layout (.demongrep/store)
 pub async fn migrate_index(path: Option<PathBuf>, yes: bool) -> Result<()> {
     let project_path = path.unwrap_or_else(|| PathBuf::from("."));
-    let canonical_path = project_path.canonicalize()?;
+    let canonical_path = canonical_project_path(&project_path)?;
 
     let new_local = local_db_path(&canonical_path);
     let legacy_local = legacy_local_db_path(&canonical_path);
@@ -1114,3 +2110,682 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
     }
     Ok(())
 }
+
+/// The `n` files with the most chunks, most first (ties by path)
+fn files_by_chunk_count(
+    chunks_per_file: &HashMap<String, usize>,
+    n: usize,
+) -> Vec<(String, usize)> {
+    let mut counts: Vec<(&str, usize)> = chunks_per_file
+        .iter()
+        .map(|(path, &count)| (path.as_str(), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    counts
+        .into_iter()
+        .take(n)
+        .map(|(path, count)| (path.to_string(), count))
+        .collect()
+}
+
+/// Tally chunks by the language of their source file, largest first
+///
+/// Languages come from the walked files (so shebang-detected scripts count
+/// correctly), falling back to the chunk path's extension.
+fn chunks_by_language<'a>(
+    chunks_per_file: &HashMap<String, usize>,
+    files: impl IntoIterator<Item = &'a FileInfo>,
+) -> Vec<(Language, usize)> {
+    let languages: HashMap<String, Language> = files
+        .into_iter()
+        .map(|f| (f.path.to_string_lossy().to_string(), f.language))
+        .collect();
+
+    let mut counts: HashMap<Language, usize> = HashMap::new();
+    for (path, &count) in chunks_per_file {
+        let language = languages
+            .get(path)
+            .copied()
+            .unwrap_or_else(|| Language::from_path(Path::new(path)));
+        *counts.entry(language).or_insert(0) += count;
+    }
+
+    let mut counts: Vec<(Language, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name().cmp(b.0.name())));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EmbeddingConfig;
+    use crate::embed::testing::{stub_embed, StubEmbedder};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_memory_profile_uses_small_batches() {
+        let memory = IndexProfile::parse("memory").unwrap();
+        assert_eq!(memory.batch_size(), Some(MEMORY_PROFILE_BATCH_SIZE));
+        assert!(MEMORY_PROFILE_BATCH_SIZE <= 8);
+        assert_eq!(memory.embed_group_size(), Some(MEMORY_PROFILE_EMBED_GROUP));
+
+        let speed = IndexProfile::parse("Speed").unwrap();
+        assert_eq!(speed.batch_size(), Some(SPEED_PROFILE_BATCH_SIZE));
+        assert_eq!(speed.embed_group_size(), None);
+        assert_eq!(IndexProfile::default().batch_size(), None);
+        assert_eq!(IndexProfile::parse("tiny"), None);
+
+        // Grouped embedding returns every chunk, in order, and leaves the
+        // in-memory cache empty
+        let db = tempdir().unwrap();
+        let batch_embedder = crate::embed::BatchEmbedder::new(std::sync::Arc::new(
+            std::sync::Mutex::new(StubEmbedder),
+        ));
+        let mut service = EmbeddingService::with_batch_embedder(
+            batch_embedder,
+            Some(db.path()),
+            "stub",
+            ModelType::default(),
+        )
+        .unwrap();
+        let chunks: Vec<Chunk> = (0..5)
+            .map(|i| {
+                Chunk::new(
+                    format!("fn f{}() {{}}", i),
+                    i,
+                    i + 1,
+                    crate::chunker::ChunkKind::Function,
+                    "lib.rs".to_string(),
+                )
+            })
+            .collect();
+        let embedded = embed_chunks_grouped(&mut service, chunks, Some(2)).unwrap();
+        let lines: Vec<usize> = embedded.iter().map(|e| e.chunk.start_line).collect();
+        assert_eq!(lines, vec![0, 1, 2, 3, 4]);
+        assert_eq!(service.cache_stats().size, 0);
+        assert_eq!(service.persistent_cache_len(), Some(5));
+    }
+
+    #[test]
+    fn test_file_touched_after_indexing_is_stale() {
+        let project = tempdir().unwrap();
+        let file = project.path().join("lib.rs");
+        std::fs::write(&file, "fn login() -> bool {\n    true\n}\n").unwrap();
+        let db_path = local_db_path(project.path());
+        std::fs::create_dir_all(&db_path).unwrap();
+
+        let mut file_meta = FileMetaStore::new("stub".to_string(), 4);
+        file_meta.update_file(&file, vec![]).unwrap();
+        file_meta.save(&db_path).unwrap();
+
+        // No recorded index time: never reported as stale
+        assert_eq!(files_changed_since_index(&db_path).unwrap(), 0);
+
+        std::fs::write(
+            db_path.join("metadata.json"),
+            serde_json::json!({ "indexed_at": chrono::Utc::now().to_rfc3339() }).to_string(),
+        )
+        .unwrap();
+        assert_eq!(files_changed_since_index(&db_path).unwrap(), 0);
+
+        // Files that were never indexed aren't counted
+        std::fs::write(project.path().join("new.rs"), "fn new() {}\n").unwrap();
+        assert_eq!(files_changed_since_index(&db_path).unwrap(), 0);
+
+        // Edited after indexing
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert_eq!(files_changed_since_index(&db_path).unwrap(), 1);
+
+        // Re-syncing the file records its new mtime
+        file_meta.update_file(&file, vec![]).unwrap();
+        file_meta.save(&db_path).unwrap();
+        assert_eq!(files_changed_since_index(&db_path).unwrap(), 0);
+
+        // A deleted file still has chunks in the index
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(files_changed_since_index(&db_path).unwrap(), 1);
+
+        // A sync records a new index time
+        let before = indexed_at(&db_path).unwrap();
+        mark_indexed_now(&db_path).unwrap();
+        assert!(indexed_at(&db_path).unwrap() > before);
+    }
+
+    #[test]
+    fn test_force_removes_the_index_but_keeps_the_embedding_cache() {
+        let db = tempfile::tempdir().unwrap();
+        drop(VectorStore::new(db.path(), 4).unwrap());
+        std::fs::create_dir_all(db.path().join("fts")).unwrap();
+        std::fs::create_dir_all(db.path().join("embedding_cache")).unwrap();
+        std::fs::write(db.path().join("embedding_cache/data.mdb"), b"cached").unwrap();
+        std::fs::write(
+            db.path().join("metadata.json"),
+            r#"{"model_short_name": "minilm-l6-q", "dimensions": 4}"#,
+        )
+        .unwrap();
+        assert!(has_vector_store(db.path()));
+
+        remove_index(db.path()).unwrap();
+
+        let left: Vec<_> = std::fs::read_dir(db.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(left, vec![std::ffi::OsString::from("embedding_cache")]);
+        assert!(db.path().join("embedding_cache/data.mdb").exists());
+        assert!(!has_vector_store(db.path()));
+        assert!(DatabaseManager::read_metadata(db.path()).is_none());
+    }
+
+    #[test]
+    fn test_embed_only_fills_cache_without_an_index() {
+        let project = tempdir().unwrap();
+        std::fs::write(
+            project.path().join("lib.rs"),
+            "fn login() -> bool {\n    true\n}\n\nfn logout() {\n    println!(\"bye\");\n}\n",
+        )
+        .unwrap();
+        let db_path = local_db_path(project.path());
+
+        let (files, _) = FileWalker::new(project.path()).walk().unwrap();
+        let batch_embedder = crate::embed::BatchEmbedder::new(std::sync::Arc::new(
+            std::sync::Mutex::new(StubEmbedder),
+        ));
+        let mut service = EmbeddingService::with_batch_embedder(
+            batch_embedder,
+            Some(&db_path),
+            "stub",
+            ModelType::default(),
+        )
+        .unwrap();
+
+        let fill = fill_embedding_cache(
+            &files,
+            &ChunkingConfig::default(),
+            false,
+            IndexProfile::Default,
+            &mut service,
+        )
+        .unwrap();
+
+        assert!(fill.chunks > 0);
+        assert_eq!(fill.cached_before, 0);
+        assert!(fill.cached_after > 0);
+        assert_eq!(service.persistent_cache_len(), Some(fill.cached_after));
+
+        // Only the cache was written: no vector store, FTS index or metadata
+        assert!(db_path.join("embedding_cache").exists());
+        assert!(!has_vector_store(&db_path));
+        assert!(!db_path.join("fts").exists());
+        assert!(!db_path.join("metadata.json").exists());
+        assert!(get_search_db_paths(Some(project.path().to_path_buf()))
+            .unwrap()
+            .is_empty());
+
+        // A second run embeds nothing new
+        let again = fill_embedding_cache(
+            &files,
+            &ChunkingConfig::default(),
+            false,
+            IndexProfile::Default,
+            &mut service,
+        )
+        .unwrap();
+        assert_eq!(again.embedded(), 0);
+    }
+
+    #[test]
+    fn test_pinned_model_rejects_conflicting_index_model() {
+        let with_embedding = |embedding: EmbeddingConfig| ProjectConfig {
+            embedding,
+            ..ProjectConfig::default()
+        };
+        let pinned_embedding = EmbeddingConfig {
+            model: "jina-v5-nano".to_string(),
+            pinned: true,
+            ..EmbeddingConfig::default()
+        };
+        let pinned = with_embedding(pinned_embedding.clone());
+        let err = index_model(&pinned, Some(ModelType::AllMiniLML6V2Q)).unwrap_err();
+        assert!(err.to_string().contains("pinned"));
+        assert_eq!(
+            index_model(&pinned, Some(ModelType::JinaEmbeddingsV5TextNano)).unwrap(),
+            ModelType::JinaEmbeddingsV5TextNano
+        );
+        // Without --model the pinned model is used rather than the default
+        assert_eq!(
+            index_model(&pinned, None).unwrap(),
+            ModelType::JinaEmbeddingsV5TextNano
+        );
+
+        let unpinned = with_embedding(EmbeddingConfig {
+            pinned: false,
+            ..pinned_embedding
+        });
+        assert_eq!(
+            index_model(&unpinned, Some(ModelType::AllMiniLML6V2Q)).unwrap(),
+            ModelType::AllMiniLML6V2Q
+        );
+        // Unpinned, the configured model is still the default
+        assert_eq!(
+            index_model(&unpinned, None).unwrap(),
+            ModelType::JinaEmbeddingsV5TextNano
+        );
+        assert_eq!(
+            index_model(&ProjectConfig::default(), None).unwrap(),
+            ModelType::default()
+        );
+
+        // The configured model may be a [models] alias
+        let mut aliased = with_embedding(EmbeddingConfig {
+            model: "ours".to_string(),
+            pinned: true,
+            ..EmbeddingConfig::default()
+        });
+        aliased
+            .models
+            .aliases
+            .insert("ours".to_string(), "jina-v5-nano".to_string());
+        assert_eq!(
+            pinned_model(&aliased).unwrap(),
+            Some(ModelType::JinaEmbeddingsV5TextNano)
+        );
+        assert_eq!(
+            index_model(&aliased, None).unwrap(),
+            ModelType::JinaEmbeddingsV5TextNano
+        );
+    }
+
+    #[test]
+    fn test_chunks_by_language() {
+        use crate::chunker::ChunkKind;
+
+        let file = |path: &str, language: Language| FileInfo {
+            path: PathBuf::from(path),
+            language,
+            size: 0,
+        };
+        let chunk = |path: &str| {
+            EmbeddedChunk::new(
+                Chunk::new(String::new(), 0, 1, ChunkKind::Block, path.into()),
+                vec![],
+            )
+        };
+
+        let files = vec![
+            file("src/main.rs", Language::Rust),
+            file("src/lib.rs", Language::Rust),
+            file("scripts/build.py", Language::Python),
+            file("bin/manage", Language::Python),
+            file("README.md", Language::Markdown),
+        ];
+        let chunks = vec![
+            chunk("src/main.rs"),
+            chunk("src/main.rs"),
+            chunk("src/lib.rs"),
+            chunk("scripts/build.py"),
+            chunk("bin/manage"),
+            chunk("README.md"),
+            // Not among the walked files: falls back to the extension
+            chunk("docs/guide.md"),
+        ];
+        let mut ingested = IngestedChunks::default();
+        ingested.record(&chunks, &[0, 1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(
+            chunks_by_language(&ingested.chunks_per_file, &files),
+            vec![
+                (Language::Rust, 3),
+                (Language::Markdown, 2),
+                (Language::Python, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_files_by_chunk_count() {
+        use crate::chunker::ChunkKind;
+
+        let chunk = |path: &str| {
+            EmbeddedChunk::new(
+                Chunk::new(String::new(), 0, 1, ChunkKind::Block, path.into()),
+                vec![],
+            )
+        };
+        let chunks: Vec<EmbeddedChunk> = ["b.rs", "a.rs", "huge.rs", "huge.rs", "huge.rs", "a.rs"]
+            .iter()
+            .map(|&path| chunk(path))
+            .collect();
+        let mut ingested = IngestedChunks::default();
+        ingested.record(&chunks, &[0, 1, 2, 3, 4, 5]);
+        assert_eq!(ingested.file_chunks[Path::new("huge.rs")], vec![2, 3, 4]);
+
+        assert_eq!(
+            files_by_chunk_count(&ingested.chunks_per_file, 2),
+            vec![("huge.rs".to_string(), 3), ("a.rs".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_stats_json_keys() {
+        let dir = tempdir().unwrap();
+        let db_path = local_db_path(dir.path());
+        std::fs::create_dir_all(&db_path).unwrap();
+        VectorStore::new(&db_path, 384).unwrap();
+
+        let db_manager = DatabaseManager::load(Some(dir.path().to_path_buf())).unwrap();
+        let json = serde_json::to_value(build_stats_json(&db_manager).unwrap()).unwrap();
+
+        for key in [
+            "model",
+            "total_chunks",
+            "total_files",
+            "indexed",
+            "dimensions",
+            "size_bytes",
+            "databases",
+        ] {
+            assert!(json.get(key).is_some(), "missing key: {}", key);
+        }
+
+        let local = &json["databases"][0];
+        assert_eq!(local["type"], "local");
+        assert_eq!(local["total_chunks"], 0);
+        assert!(local["size_bytes"].as_u64().unwrap() > 0);
+    }
+
+    fn synthetic_chunks(count: usize) -> Vec<EmbeddedChunk> {
+        (0..count)
+            .map(|i| {
+                let t = i as f32 * 0.1;
+                EmbeddedChunk::new(
+                    crate::chunker::Chunk::new(
+                        format!("fn handler_{}() {{ process(\"item {}\") }}", i, i),
+                        i,
+                        i + 1,
+                        crate::chunker::ChunkKind::Function,
+                        format!("src/file_{}.rs", i % 100),
+                    ),
+                    vec![t.sin(), t.cos(), 1.0, (t * 0.5).sin()],
+                )
+            })
+            .collect()
+    }
+
+    /// Ingest `chunks` the way `index` did before `ingest_chunks`: a vector
+    /// insert, then a separate FTS loop
+    fn ingest_separately(dir: &Path, chunks: &[EmbeddedChunk]) -> (Vec<u32>, FtsStore) {
+        let mut store = VectorStore::new(dir, 4).unwrap();
+        let mut fts_store = FtsStore::new(dir).unwrap();
+        let ids = store.insert_chunks_with_ids(chunks.to_vec()).unwrap();
+        for (chunk, id) in chunks.iter().zip(&ids) {
+            fts_store
+                .add_chunk(
+                    *id,
+                    &chunk.chunk.content,
+                    &chunk.chunk.path,
+                    chunk.chunk.signature.as_deref(),
+                    chunk.chunk.kind.as_str(),
+                    &chunk.chunk.string_literals,
+                )
+                .unwrap();
+        }
+        fts_store.commit().unwrap();
+        (ids, fts_store)
+    }
+
+    #[test]
+    fn test_ingest_chunks_matches_separate_inserts() {
+        let chunks = synthetic_chunks(500);
+
+        let dir = tempdir().unwrap();
+        let (separate_ids, _) = ingest_separately(dir.path(), &chunks);
+
+        let dir = tempdir().unwrap();
+        let mut store = VectorStore::new(dir.path(), 4).unwrap();
+        let mut fts_store = FtsStore::new(dir.path()).unwrap();
+        let combined_ids = ingest_chunks(&mut store, Some(&mut fts_store), &chunks).unwrap();
+
+        assert_eq!(combined_ids, separate_ids);
+        assert_eq!(combined_ids, (0..500).collect::<Vec<u32>>());
+        assert_eq!(store.stats().unwrap().total_chunks, 500);
+        assert_eq!(fts_store.stats().unwrap().num_documents, 500);
+
+        let hits = fts_store.search("handler_42", 5).unwrap();
+        assert!(hits.iter().any(|hit| hit.chunk_id == 42));
+    }
+
+    #[test]
+    #[ignore] // Timing comparison; run with --ignored --nocapture
+    fn test_ingest_chunks_vs_separate_inserts_timing() {
+        let chunks = synthetic_chunks(10_000);
+
+        let dir = tempdir().unwrap();
+        let start = Instant::now();
+        ingest_separately(dir.path(), &chunks);
+        let separate = start.elapsed();
+
+        let dir = tempdir().unwrap();
+        let mut store = VectorStore::new(dir.path(), 4).unwrap();
+        let mut fts_store = FtsStore::new(dir.path()).unwrap();
+        let start = Instant::now();
+        ingest_chunks(&mut store, Some(&mut fts_store), &chunks).unwrap();
+        let combined = start.elapsed();
+
+        println!(
+            "ingesting {} chunks: separate passes {:?}, ingest_chunks {:?}",
+            chunks.len(),
+            separate,
+            combined
+        );
+    }
+
+    #[test]
+    fn test_reindex_file_after_sync_replaces_synced_chunks() {
+        let dir = tempdir().unwrap();
+        let db = dir.path().join("db");
+        let file = dir.path().join("lib.rs");
+        let mut store = VectorStore::new(&db, 4).unwrap();
+        let mut file_meta = FileMetaStore::new("stub".to_string(), 4);
+        let mut chunker = indexing_chunker(&ChunkingConfig::default());
+        let embed = stub_embed;
+        let chunk_file = |chunker: &mut SemanticChunker, source: &str| {
+            std::fs::write(&file, source).unwrap();
+            chunker
+                .chunk_semantic(Language::Rust, &file, source)
+                .unwrap()
+        };
+
+        // `index` records the first version in the store's file metadata
+        let chunks = chunk_file(&mut chunker, "fn first() {\n    one();\n}\n");
+        let indexed_ids = store
+            .insert_chunks_with_ids(embed(chunks).unwrap())
+            .unwrap();
+        store
+            .update_file_metadata(&file, indexed_ids.clone())
+            .unwrap();
+
+        // A sync replaces it and records the new IDs only in the file metadata store
+        let chunks = chunk_file(&mut chunker, "fn second() {\n    two();\n}\n");
+        store.delete_chunks(&indexed_ids).unwrap();
+        let synced_ids = store
+            .insert_chunks_with_ids(embed(chunks).unwrap())
+            .unwrap();
+        file_meta.update_file(&file, synced_ids.clone()).unwrap();
+
+        chunk_file(&mut chunker, "fn third() {\n    three();\n}\n");
+        let outcome =
+            reindex_file_in_store(&mut store, None, &mut file_meta, &mut chunker, &file, embed)
+                .unwrap();
+
+        let remaining = store.chunks_for_path(&file.to_string_lossy()).unwrap();
+        assert_eq!(remaining.len(), outcome.chunk_ids.len());
+        assert!(remaining
+            .iter()
+            .all(|(_, chunk)| chunk.content.contains("third")));
+        let key = file.to_string_lossy().to_string();
+        assert_eq!(
+            file_meta.file_chunk_ids(&file),
+            Some((key.clone(), outcome.chunk_ids.clone()))
+        );
+        assert_eq!(
+            store.file_chunk_ids(&file).unwrap(),
+            Some((key, outcome.chunk_ids))
+        );
+    }
+
+    #[test]
+    fn test_resolve_project_by_name_and_path() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("webapp");
+        std::fs::create_dir_all(&project).unwrap();
+        let project = project.canonicalize().unwrap();
+        let project_str = project.to_string_lossy().to_string();
+
+        let mut mappings = std::collections::HashMap::new();
+        mappings.insert(project_str.clone(), "/stores/a".to_string());
+        mappings.insert("/work/webapp-old".to_string(), "/stores/b".to_string());
+
+        let (path, db) = resolve_project(&mappings, "webapp").unwrap();
+        assert_eq!(path, &project_str);
+        assert_eq!(db, "/stores/a");
+
+        let (path, _) = resolve_project(&mappings, &project_str).unwrap();
+        assert_eq!(path, &project_str);
+
+        assert!(resolve_project(&mappings, "missing").is_err());
+    }
+
+    #[test]
+    fn test_resolve_project_ambiguous() {
+        let mut mappings = std::collections::HashMap::new();
+        mappings.insert("/work/a/api".to_string(), "/stores/a".to_string());
+        mappings.insert("/work/b/api".to_string(), "/stores/b".to_string());
+
+        let err = resolve_project(&mappings, "api").unwrap_err().to_string();
+        assert!(err.contains("ambiguous"));
+        assert!(err.contains("/work/a/api") && err.contains("/work/b/api"));
+
+        let (_, db) = resolve_project(&mappings, "/work/b/api").unwrap();
+        assert_eq!(db, "/stores/b");
+    }
+
+    #[test]
+    fn test_stats_reads_stored_dimensions() {
+        let dir = tempdir().unwrap();
+        let db_path = local_db_path(dir.path());
+        std::fs::create_dir_all(&db_path).unwrap();
+        VectorStore::new(&db_path, 768).unwrap();
+        std::fs::write(
+            db_path.join("metadata.json"),
+            serde_json::json!({ "model_short_name": "jina-v5-nano", "dimensions": 768 })
+                .to_string(),
+        )
+        .unwrap();
+
+        let db_manager = DatabaseManager::load(Some(dir.path().to_path_buf())).unwrap();
+        let stats = build_stats_json(&db_manager).unwrap();
+        assert_eq!(stats.dimensions, 768);
+
+        let store = open_store_with_stored_dimensions(&db_path).unwrap();
+        assert_eq!(store.stats().unwrap().dimensions, 768);
+    }
+
+    #[test]
+    fn test_global_stores_isolate_projects() {
+        let home = tempdir().unwrap();
+        let workspace = tempdir().unwrap();
+        let project_a = workspace.path().join("alpha");
+        let project_b = workspace.path().join("beta");
+        std::fs::create_dir_all(&project_a).unwrap();
+        std::fs::create_dir_all(&project_b).unwrap();
+        let file_a = project_a.join("lib.rs");
+        let file_b = project_b.join("lib.rs");
+        std::fs::write(&file_a, "fn alpha() {}").unwrap();
+        std::fs::write(&file_b, "fn beta() {}").unwrap();
+
+        let db_a = global_db_path(home.path(), &project_a.canonicalize().unwrap());
+        let db_b = global_db_path(home.path(), &project_b.canonicalize().unwrap());
+        assert_ne!(db_a, db_b);
+        assert_eq!(db_a.parent(), db_b.parent());
+
+        let embed = stub_embed;
+        let mut chunker = indexing_chunker(&ChunkingConfig::default());
+        let open = |db_path: &Path| {
+            std::fs::create_dir_all(db_path).unwrap();
+            let store = VectorStore::new(db_path, 4).unwrap();
+            let file_meta = FileMetaStore::load_or_create(db_path, "stub", 4).unwrap();
+            (store, file_meta)
+        };
+        let (mut store_a, mut meta_a) = open(&db_a);
+        let (mut store_b, mut meta_b) = open(&db_b);
+
+        // Index both projects into the shared global stores directory
+        reindex_file_in_store(
+            &mut store_a,
+            None,
+            &mut meta_a,
+            &mut chunker,
+            &file_a,
+            embed,
+        )
+        .unwrap();
+        let indexed_b = reindex_file_in_store(
+            &mut store_b,
+            None,
+            &mut meta_b,
+            &mut chunker,
+            &file_b,
+            embed,
+        )
+        .unwrap();
+        let chunks_b = store_b.chunk_ids().unwrap();
+
+        // Reindexing and deleting project A's file leaves B's store untouched
+        std::fs::write(&file_a, "fn alpha() { changed() }").unwrap();
+        reindex_file_in_store(
+            &mut store_a,
+            None,
+            &mut meta_a,
+            &mut chunker,
+            &file_a,
+            embed,
+        )
+        .unwrap();
+        assert!(store_a
+            .chunks_for_path(&file_a.to_string_lossy())
+            .unwrap()
+            .iter()
+            .all(|(_, chunk)| chunk.content.contains("changed")));
+        std::fs::remove_file(&file_a).unwrap();
+        let removed = reindex_file_in_store(
+            &mut store_a,
+            None,
+            &mut meta_a,
+            &mut chunker,
+            &file_a,
+            embed,
+        )
+        .unwrap();
+        assert!(removed.removed > 0);
+        assert!(store_a.chunk_ids().unwrap().is_empty());
+        assert_eq!(store_a.file_chunk_ids(&file_a).unwrap(), None);
+
+        assert_eq!(store_b.chunk_ids().unwrap(), chunks_b);
+        assert!(store_b
+            .chunks_for_path(&file_a.to_string_lossy())
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            meta_b.file_chunk_ids(&file_b).map(|(_, ids)| ids),
+            Some(indexed_b.chunk_ids)
+        );
+        assert!(store_b.find_deleted_files().unwrap().is_empty());
+    }
+}
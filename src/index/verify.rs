@@ -0,0 +1,339 @@
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::{get_search_db_paths, stored_dimensions};
+use crate::chunker::Chunk;
+use crate::config::{FtsConfig, ProjectConfig};
+use crate::database::DatabaseManager;
+use crate::error::DemongrepError;
+use crate::fts::FtsStore;
+use crate::vectordb::VectorStore;
+
+/// Result of cross-checking one database
+#[derive(Debug, Serialize)]
+pub struct VerifyReport {
+    pub path: String,
+    /// Chunks in the vector store
+    pub vector_chunks: usize,
+    /// Documents in the FTS index (None without one, or when it can't be read)
+    pub fts_documents: Option<usize>,
+    /// Files in the incremental-indexing file metadata
+    pub tracked_files: usize,
+    /// Chunk IDs the file metadata refers to
+    pub tracked_chunks: usize,
+    /// Chunk IDs in the file metadata that are missing from the vector store
+    pub missing_chunks: usize,
+    /// Chunks in the vector store that no tracked file refers to
+    pub untracked_chunks: usize,
+    pub issues: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// What `verify --fix` rebuilt
+#[derive(Debug, Default, Serialize)]
+pub struct RepairSummary {
+    /// Documents in the rebuilt FTS index (None without one)
+    pub fts_documents: Option<usize>,
+    /// Files dropped from the file metadata (re-indexed on the next sync)
+    pub pruned_files: usize,
+}
+
+/// Cross-check the vector store, FTS index and file metadata of a database
+pub fn verify_database(db_path: &Path) -> Result<VerifyReport> {
+    let store = VectorStore::open_readonly(db_path, stored_dimensions(db_path))?;
+    let stats = store.stats()?;
+
+    let chunk_ids: HashSet<u32> = store.chunk_ids()?.into_iter().collect();
+    let tracked = store.tracked_files()?;
+    let tracked_ids: HashSet<u32> = tracked
+        .iter()
+        .flat_map(|(_, ids)| ids.iter().copied())
+        .collect();
+
+    let mut report = VerifyReport {
+        path: db_path.display().to_string(),
+        vector_chunks: stats.total_chunks,
+        fts_documents: None,
+        tracked_files: tracked.len(),
+        tracked_chunks: tracked_ids.len(),
+        missing_chunks: tracked_ids.difference(&chunk_ids).count(),
+        untracked_chunks: chunk_ids.difference(&tracked_ids).count(),
+        issues: Vec::new(),
+    };
+
+    if DatabaseManager::index_metadata(db_path).fts {
+        match FtsStore::open_readonly(db_path).and_then(|fts| fts.stats()) {
+            Ok(fts_stats) => {
+                if fts_stats.num_documents != stats.total_chunks {
+                    report.issues.push(format!(
+                        "FTS index has {} documents for {} chunks",
+                        fts_stats.num_documents, stats.total_chunks
+                    ));
+                }
+                report.fts_documents = Some(fts_stats.num_documents);
+            }
+            Err(e) => report
+                .issues
+                .push(format!("FTS index could not be read: {}", e)),
+        }
+    }
+
+    if stats.total_chunks > 0 && !stats.indexed {
+        report
+            .issues
+            .push("Vector index has not been built".to_string());
+    }
+    if report.missing_chunks > 0 {
+        report.issues.push(format!(
+            "File metadata refers to {} chunks missing from the vector store",
+            report.missing_chunks
+        ));
+    }
+    if report.untracked_chunks > 0 {
+        report.issues.push(format!(
+            "{} chunks in the vector store belong to no tracked file",
+            report.untracked_chunks
+        ));
+    }
+
+    Ok(report)
+}
+
+/// Rebuild the derived indexes of a database from its vector store
+///
+/// Drops file metadata (and the remaining chunks) of files with missing
+/// chunks so the next sync re-indexes them, rewrites the FTS index from the
+/// stored chunks and rebuilds the vector index. An FTS index that can't be
+/// opened is recreated with `fts_config`.
+pub fn repair_database(db_path: &Path, fts_config: &FtsConfig) -> Result<RepairSummary> {
+    let mut store = VectorStore::new(db_path, stored_dimensions(db_path))?;
+    let mut summary = RepairSummary::default();
+
+    let chunk_ids: HashSet<u32> = store.chunk_ids()?.into_iter().collect();
+    for (path, ids) in store.tracked_files()? {
+        if ids.iter().any(|id| !chunk_ids.contains(id)) {
+            store.delete_chunks(&ids)?;
+            store.remove_file_metadata(Path::new(&path))?;
+            summary.pruned_files += 1;
+        }
+    }
+
+    if DatabaseManager::index_metadata(db_path).fts {
+        let mut fts = match FtsStore::new(db_path) {
+            Ok(fts) => fts,
+            Err(_) => {
+                std::fs::remove_dir_all(db_path.join("fts"))?;
+                FtsStore::with_config(db_path, fts_config)?
+            }
+        };
+        fts.clear()?;
+        for id in store.chunk_ids()? {
+            if let Some(chunk) = store.get_chunk(id)? {
+                fts.add_chunk(
+                    id,
+                    &chunk.content,
+                    &chunk.path,
+                    chunk.signature.as_deref(),
+                    &chunk.kind,
+                    &Chunk::extract_string_literals(&chunk.content),
+                )?;
+            }
+        }
+        fts.commit()?;
+        summary.fts_documents = Some(fts.stats()?.num_documents);
+    }
+
+    store.build_index()?;
+
+    Ok(summary)
+}
+
+fn print_report(report: &VerifyReport) {
+    println!("\n📦 {}", report.path.bright_cyan());
+    println!("   Vector chunks: {}", report.vector_chunks);
+    match report.fts_documents {
+        Some(documents) => println!("   FTS documents: {}", documents),
+        None => println!("   FTS documents: {}", "-".dimmed()),
+    }
+    println!(
+        "   File metadata: {} chunks in {} files",
+        report.tracked_chunks, report.tracked_files
+    );
+
+    if report.is_consistent() {
+        println!("   {}", "✅ Consistent".green());
+        return;
+    }
+    for issue in &report.issues {
+        println!("   {} {}", "❌".red(), issue);
+    }
+}
+
+/// Check every database of a project for inconsistencies, optionally repairing them
+pub async fn verify(path: Option<PathBuf>, fix: bool, json: bool) -> Result<()> {
+    if json {
+        crate::output::set_quiet(true);
+    }
+
+    let project_path = path.clone().unwrap_or_else(|| PathBuf::from("."));
+    let db_paths = get_search_db_paths(path)?;
+    if db_paths.is_empty() {
+        return Err(DemongrepError::DatabaseNotFound {
+            path: project_path.display().to_string(),
+        }
+        .into());
+    }
+
+    let fts_config = ProjectConfig::load(Some(&project_path)).fts;
+    let mut reports = Vec::new();
+    for db_path in &db_paths {
+        let mut report = verify_database(db_path)?;
+        if fix && !report.is_consistent() {
+            let summary = repair_database(db_path, &fts_config)?;
+            if !json {
+                print_report(&report);
+                println!(
+                    "   🔧 Rebuilt FTS and vector indexes, dropped {} files for re-indexing",
+                    summary.pruned_files
+                );
+            }
+            report = verify_database(db_path)?;
+        }
+        reports.push(report);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+        return Ok(());
+    }
+
+    println!("{}", "🔍 Index Verification".bright_cyan().bold());
+    println!("{}", "=".repeat(60));
+    for report in &reports {
+        print_report(report);
+    }
+
+    if reports.iter().any(|report| !report.is_consistent()) {
+        println!(
+            "\n💡 Run {} to rebuild the FTS and vector indexes from the stored chunks,",
+            "demongrep verify --fix".bright_cyan()
+        );
+        println!(
+            "   or {} then {} for a full reindex",
+            "demongrep clear".bright_cyan(),
+            "demongrep index".bright_cyan()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunker::ChunkKind;
+    use crate::embed::EmbeddedChunk;
+    use crate::index::ingest_chunks;
+    use tempfile::tempdir;
+
+    /// Index two files into a fresh database with an FTS index
+    fn indexed_db(root: &Path) -> (PathBuf, Vec<u32>) {
+        let db_path = root.join("db");
+        let mut store = VectorStore::new(&db_path, 4).unwrap();
+        let mut fts = FtsStore::new(&db_path).unwrap();
+
+        let mut all_ids = Vec::new();
+        for (i, name) in ["auth.rs", "view.rs"].iter().enumerate() {
+            let file = root.join(name);
+            let content = format!("fn f{}() {{}}", i);
+            std::fs::write(&file, &content).unwrap();
+            let mut vector = vec![0.0; 4];
+            vector[i] = 1.0;
+            let chunk = EmbeddedChunk::new(
+                Chunk::new(
+                    content,
+                    0,
+                    1,
+                    ChunkKind::Function,
+                    file.display().to_string(),
+                ),
+                vector,
+            );
+            let ids = ingest_chunks(&mut store, Some(&mut fts), &[chunk]).unwrap();
+            store.update_file_metadata(&file, ids.clone()).unwrap();
+            all_ids.extend(ids);
+        }
+        store.build_index().unwrap();
+
+        (db_path, all_ids)
+    }
+
+    #[test]
+    fn test_verify_consistent_database() {
+        let dir = tempdir().unwrap();
+        let (db_path, _) = indexed_db(dir.path());
+
+        let report = verify_database(&db_path).unwrap();
+        assert!(report.is_consistent(), "{:?}", report.issues);
+        assert_eq!(report.vector_chunks, 2);
+        assert_eq!(report.fts_documents, Some(2));
+        assert_eq!(report.tracked_files, 2);
+    }
+
+    #[test]
+    fn test_verify_flags_and_fixes_fts_desync() {
+        let dir = tempdir().unwrap();
+        let (db_path, ids) = indexed_db(dir.path());
+
+        // Lose one FTS document, as after a crash between the two writes
+        {
+            let mut fts = FtsStore::new(&db_path).unwrap();
+            fts.delete_chunk(ids[0]).unwrap();
+            fts.commit().unwrap();
+        }
+
+        let report = verify_database(&db_path).unwrap();
+        assert!(!report.is_consistent());
+        assert_eq!(report.vector_chunks, 2);
+        assert_eq!(report.fts_documents, Some(1));
+        assert!(report.issues.iter().any(|issue| issue.contains("FTS")));
+
+        let summary = repair_database(&db_path, &FtsConfig::default()).unwrap();
+        assert_eq!(summary.fts_documents, Some(2));
+        assert_eq!(summary.pruned_files, 0);
+        assert!(verify_database(&db_path).unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_verify_flags_missing_chunks() {
+        let dir = tempdir().unwrap();
+        let (db_path, ids) = indexed_db(dir.path());
+
+        // Delete a chunk from the vector store (and FTS) but not the file metadata
+        {
+            let mut store = VectorStore::new(&db_path, 4).unwrap();
+            store.delete_chunks(&ids[..1]).unwrap();
+            let mut fts = FtsStore::new(&db_path).unwrap();
+            fts.delete_chunk(ids[0]).unwrap();
+            fts.commit().unwrap();
+        }
+
+        let report = verify_database(&db_path).unwrap();
+        assert_eq!(report.missing_chunks, 1);
+        assert_eq!(report.untracked_chunks, 0);
+
+        let summary = repair_database(&db_path, &FtsConfig::default()).unwrap();
+        assert_eq!(summary.pruned_files, 1);
+        let report = verify_database(&db_path).unwrap();
+        assert!(report.is_consistent(), "{:?}", report.issues);
+        assert_eq!(report.tracked_files, 1);
+    }
+}
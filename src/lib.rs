@@ -24,9 +24,10 @@ pub use chunker::{Chunk, ChunkKind, Chunker};
 pub use config::ProjectConfig;
 pub use database::{CombinedStats, Database, DatabaseManager, DatabaseType};
 pub use embed::{
-    CacheStats, EmbeddedChunk, EmbeddingService, FastEmbedder, ModelType, PersistentEmbeddingCache,
+    CacheStats, EmbeddedChunk, Embedder, EmbeddingService, FastEmbedder, ModelType,
+    PersistentEmbeddingCache, RemoteEmbedder,
 };
 pub use error::DemongrepError;
 pub use file::{FileInfo, FileWalker, Language, WalkStats};
 pub use fts::{FtsResult, FtsStore};
-pub use vectordb::{SearchResult, StoreStats, VectorStore};
+pub use vectordb::{IndexMode, SearchResult, StoreStats, VectorStore};
@@ -80,5 +80,12 @@ async fn main() -> Result<()> {
     }
 
     // Parse CLI and execute command
-    cli::run().await
+    match cli::run().await {
+        // JSON consumers get a structured error on stdout instead of human text
+        Err(err) if is_json || is_agent => {
+            println!("{}", error::error_json(&err));
+            std::process::exit(1);
+        }
+        result => result,
+    }
 }
@@ -14,32 +14,52 @@ use rmcp::{
     tool, tool_handler, tool_router, ErrorData as McpError, ServerHandler,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 
+use crate::cache::{index_state_hash, SearchResultCache};
+use crate::chunker::ChunkKind;
+use crate::config::{EmbeddingConfig, ProjectConfig, SearchConfig};
 use crate::database::DatabaseManager; // NEW: Use DatabaseManager
-use crate::embed::EmbeddingService;
+use crate::embed::{EmbeddingService, ModelType};
+use crate::error::DemongrepError;
 use crate::index::is_local_db_path;
-use crate::rerank::NeuralReranker;
+use crate::rerank::{match_sources, NeuralReranker};
+use crate::search::with_timeout;
+use crate::vectordb::ChunkMetadata;
 
 const MCP_DEFAULT_LIMIT: usize = 4;
-const MCP_MAX_LIMIT: usize = 10;
 const MCP_CONTENT_CHAR_LIMIT: usize = 250;
 const MCP_DEFAULT_RERANK_TOP: usize = 20;
 const MCP_MAX_RERANK_TOP: usize = 50;
 const MCP_DEFAULT_PER_FILE: usize = 1;
 const MCP_MAX_PER_FILE: usize = 3;
 const MCP_MAX_CANDIDATE_LIMIT: usize = 50;
+const MCP_DEFAULT_FILE_CHUNKS_LIMIT: usize = 50;
+const MCP_MAX_FILE_CHUNKS_LIMIT: usize = 200;
+const MCP_RESULT_CACHE_ENTRIES: u64 = 256;
+const MCP_MAX_EMBEDDINGS_LIMIT: usize = 100;
 
 /// Demongrep MCP service with dual-database support via DatabaseManager
 pub struct DemongrepService {
     tool_router: ToolRouter<DemongrepService>,
     db_manager: DatabaseManager,
-    // Lazily initialized on first use
-    embedding_service: Mutex<Option<EmbeddingService>>,
-    reranker: Mutex<Option<NeuralReranker>>,
+    // Lazily initialized on first use, on a blocking thread
+    embedding_service: Arc<Mutex<Option<EmbeddingService>>>,
+    // `[embedding]` provider settings the embedding service is created with
+    embedding_config: EmbeddingConfig,
+    reranker: Arc<Mutex<Option<NeuralReranker>>>,
+    // `[search]` settings the reranker is created with
+    search_config: SearchConfig,
+    // Serialized search responses, keyed by request and index state
+    result_cache: SearchResultCache<String>,
+    // Hard cap on search `limit` ([mcp] max_results)
+    max_results: usize,
+    // How long a search may run before it fails ([server] request_timeout_secs)
+    request_timeout: Duration,
 }
 
 impl std::fmt::Debug for DemongrepService {
@@ -57,9 +77,10 @@ pub struct SemanticSearchRequest {
     /// The search query (natural language or code snippet)
     pub query: String,
 
-    /// Maximum number of results to return (default: 4)
+    /// Maximum number of results to return (default: 4, capped by the server;
+    /// the response reports the limit applied)
     pub limit: Option<usize>,
-    /// Offset for pagination (default: 0)
+    /// Offset for pagination (default: 0); page with `offset + limit`
     pub offset: Option<usize>,
     /// Maximum matches per file (default: 1, max: 3)
     pub per_file: Option<usize>,
@@ -70,7 +91,42 @@ pub struct SemanticSearchRequest {
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetFileChunksRequest {
     /// Path to the file (relative to project root)
+    pub path: Option<String>,
+    /// Several files at once; returns a map of path to page
+    pub paths: Option<Vec<String>>,
+    /// Maximum number of chunks to return per file (default: 50, max: 200)
+    pub limit: Option<usize>,
+    /// Offset for pagination (default: 0); use `next_offset` from the previous page
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetEmbeddingsRequest {
+    /// Chunk IDs to fetch (as stored in each database)
+    pub ids: Option<Vec<u32>>,
+    /// Fetch every chunk of this file instead (relative to project root)
+    pub path: Option<String>,
+    /// Maximum number of embeddings to return (required, max: 100)
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmbeddingItem {
+    pub id: u32,
     pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub database: String,
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmbeddingsResponse {
+    pub model: String,
+    pub dimensions: usize,
+    pub embeddings: Vec<EmbeddingItem>,
+    /// Whether more chunks matched than `limit` allowed
+    pub truncated: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -79,6 +135,8 @@ pub struct SearchResultItem {
     pub start_line: usize,
     pub end_line: usize,
     pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
     pub content: String,
     pub score: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -99,6 +157,92 @@ pub struct SearchResultItem {
     pub fts_rank: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rerank_score: Option<f32>,
+    /// Retrieval sources that matched the chunk ("vector", "fts")
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<&'static str>,
+}
+
+/// One page of a file's chunks, ordered by start line
+#[derive(Debug, Serialize)]
+pub struct FileChunksPage {
+    pub chunks: Vec<SearchResultItem>,
+    /// Total number of chunks for the file across all databases
+    pub total: usize,
+    pub offset: usize,
+    /// Offset of the next page, absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+}
+
+impl FileChunksPage {
+    fn paginate(chunks: Vec<SearchResultItem>, offset: usize, limit: usize) -> Self {
+        let total = chunks.len();
+        let chunks: Vec<SearchResultItem> = chunks.into_iter().skip(offset).take(limit).collect();
+        let end = offset.saturating_add(chunks.len());
+        Self {
+            chunks,
+            total,
+            offset,
+            next_offset: (end < total).then_some(end),
+        }
+    }
+}
+
+/// Group chunks under the requested path they belong to, each group ordered by start line
+///
+/// Paths match with or without a leading `./`; requested paths without chunks map to an
+/// empty list.
+fn group_file_chunks(
+    paths: &[String],
+    chunks: impl IntoIterator<Item = (&'static str, ChunkMetadata)>,
+) -> BTreeMap<String, Vec<SearchResultItem>> {
+    let mut groups: BTreeMap<String, Vec<SearchResultItem>> =
+        paths.iter().map(|p| (p.clone(), Vec::new())).collect();
+    let wanted: HashMap<&str, &String> = paths
+        .iter()
+        .map(|p| (p.trim_start_matches("./"), p))
+        .collect();
+
+    for (db_type, chunk) in chunks {
+        let Some(&requested) = wanted.get(chunk.path.trim_start_matches("./")) else {
+            continue;
+        };
+        if let Some(group) = groups.get_mut(requested) {
+            group.push(SearchResultItem {
+                path: chunk.path,
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                kind: chunk.kind,
+                language: chunk.language,
+                content: chunk.content,
+                score: 1.0,
+                signature: chunk.signature,
+                context_prev: chunk.context_prev,
+                context_next: chunk.context_next,
+                database: Some(db_type.to_string()),
+                vector_score: None,
+                fts_score: None,
+                vector_rank: None,
+                fts_rank: None,
+                rerank_score: None,
+                sources: Vec::new(),
+            });
+        }
+    }
+
+    for group in groups.values_mut() {
+        group.sort_by_key(|c| c.start_line);
+    }
+    groups
+}
+
+/// One page of `semantic_search` results
+#[derive(Debug, Serialize)]
+pub struct SearchPage<T> {
+    pub results: Vec<T>,
+    /// Limit actually applied, after clamping to the server's cap
+    pub limit: usize,
+    pub offset: usize,
 }
 
 /// Compact search result for minimal token usage
@@ -114,7 +258,7 @@ pub struct CompactResultItem {
 pub struct HybridSearchRequest {
     /// The search query (natural language or code snippet)
     pub query: String,
-    /// Maximum number of results to return (default: 4)
+    /// Maximum number of results to return (default: 4, capped by the server)
     pub limit: Option<usize>,
     /// Offset for pagination (default: 0)
     pub offset: Option<usize>,
@@ -167,42 +311,124 @@ pub struct IndexStatusResponse {
 
 #[tool_router]
 impl DemongrepService {
-    /// Create a new DemongrepService with DatabaseManager
-    pub fn new(db_manager: DatabaseManager) -> Result<Self> {
+    /// Create a new DemongrepService with DatabaseManager, configured from the
+    /// `.demongrep.toml` of `project_root`
+    ///
+    /// Fails when that config pins a model that a database wasn't indexed with.
+    pub fn new(db_manager: DatabaseManager, project_root: &Path) -> Result<Self> {
+        let mut config = ProjectConfig::load(Some(project_root));
+        // A configured reranker path is relative to the project root, as for `search`
+        config.search.rerank_model_path = config
+            .search
+            .rerank_model_path
+            .map(|path| project_root.join(path));
+        if let Some(pinned) = crate::index::pinned_model(&config)? {
+            for database in db_manager.databases() {
+                let metadata_path = database.path.join("metadata.json");
+                if let Err(e @ DemongrepError::ModelMismatch { .. }) =
+                    EmbeddingService::model_compatible_with(&metadata_path, pinned)
+                {
+                    return Err(e.into());
+                }
+            }
+        }
         Ok(Self {
             tool_router: Self::tool_router(),
             db_manager,
-            embedding_service: Mutex::new(None),
-            reranker: Mutex::new(None),
+            embedding_service: Arc::new(Mutex::new(None)),
+            embedding_config: config.embedding,
+            reranker: Arc::new(Mutex::new(None)),
+            search_config: config.search,
+            result_cache: SearchResultCache::new(MCP_RESULT_CACHE_ENTRIES),
+            max_results: config.mcp.max_results,
+            request_timeout: Duration::from_secs(config.server.request_timeout_secs),
         })
     }
 
+    /// Override the configured search timeout
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Hash of the on-disk state of every database being searched
+    fn index_state(&self) -> u64 {
+        index_state_hash(
+            self.db_manager
+                .databases()
+                .iter()
+                .map(|db| db.path.as_path()),
+        )
+    }
+
+    /// Cache a search response and wrap it as a tool result
+    fn cache_response(&self, key: &str, state: u64, text: String) -> CallToolResult {
+        self.result_cache.insert(key, state, text.clone());
+        CallToolResult::success(vec![Content::text(text)])
+    }
+
     /// Get or initialize the embedding service
-    fn get_embedding_service(&self) -> Result<std::sync::MutexGuard<'_, Option<EmbeddingService>>> {
-        let mut guard = self
-            .embedding_service
+    fn get_embedding_service<'a>(
+        slot: &'a Mutex<Option<EmbeddingService>>,
+        config: &EmbeddingConfig,
+        model_type: ModelType,
+        dimensions: usize,
+    ) -> Result<MutexGuard<'a, Option<EmbeddingService>>> {
+        let mut guard = slot
             .lock()
             .map_err(|e| anyhow::anyhow!("MCP embedding mutex poisoned: {}", e))?;
         if guard.is_none() {
-            *guard = Some(EmbeddingService::with_model(self.db_manager.model_type())?);
+            *guard = Some(EmbeddingService::from_config(
+                config, model_type, None, dimensions,
+            )?);
         }
         Ok(guard)
     }
 
+    /// Embed a query on a blocking thread, so the search timeout can still
+    /// fire while the model loads or embeds
+    async fn embed_query(&self, query: &str) -> std::result::Result<Vec<f32>, String> {
+        crate::search::validate_query(query).map_err(|e| e.to_string())?;
+        let slot = self.embedding_service.clone();
+        let config = self.embedding_config.clone();
+        let model_type = self.db_manager.model_type();
+        let dimensions = self.db_manager.dimensions();
+        let query = query.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let mut guard = Self::get_embedding_service(&slot, &config, model_type, dimensions)
+                .map_err(|e| format!("Error initializing embedding service: {}", e))?;
+            let service = guard.as_mut().unwrap();
+            service
+                .embed_query(&query)
+                .map_err(|e| format!("Error embedding query: {}", e))
+        })
+        .await
+        .map_err(|e| format!("Error embedding query: {}", e))?
+    }
+
     /// Get or initialize the reranker (lazy loading)
-    fn get_reranker(&self) -> Result<std::sync::MutexGuard<'_, Option<NeuralReranker>>> {
-        let mut guard = self
-            .reranker
+    fn get_reranker<'a>(
+        slot: &'a Mutex<Option<NeuralReranker>>,
+        search_config: &SearchConfig,
+    ) -> Result<MutexGuard<'a, Option<NeuralReranker>>> {
+        let mut guard = slot
             .lock()
             .map_err(|e| anyhow::anyhow!("MCP reranker mutex poisoned: {}", e))?;
         if guard.is_none() {
-            *guard = Some(NeuralReranker::new()?);
+            *guard = Some(
+                NeuralReranker::load(search_config.rerank_model_path.as_deref())?
+                    .with_batch_size(search_config.rerank_batch_size),
+            );
         }
         Ok(guard)
     }
 
-    fn normalize_limit(limit: Option<usize>) -> usize {
-        limit.unwrap_or(MCP_DEFAULT_LIMIT).clamp(1, MCP_MAX_LIMIT)
+    /// Requested limit (or the default), clamped to 1..=`max_results`
+    fn normalize_limit(limit: Option<usize>, max_results: usize) -> usize {
+        limit
+            .unwrap_or(MCP_DEFAULT_LIMIT)
+            .clamp(1, max_results.max(1))
     }
 
     fn compact_content(content: &str) -> String {
@@ -230,7 +456,7 @@ impl DemongrepService {
         limit
             .saturating_mul(per_file)
             .saturating_mul(2)
-            .clamp(limit, MCP_MAX_CANDIDATE_LIMIT)
+            .clamp(limit, MCP_MAX_CANDIDATE_LIMIT.max(limit))
     }
 
     fn limit_results_per_file(
@@ -261,7 +487,7 @@ impl DemongrepService {
         filtered
     }
 
-    fn apply_optional_rerank(
+    async fn apply_optional_rerank(
         &self,
         query: &str,
         mut results: Vec<crate::vectordb::SearchResult>,
@@ -280,30 +506,32 @@ impl DemongrepService {
             .collect::<Vec<_>>();
         let rrf_scores = top_results.iter().map(|r| r.score).collect::<Vec<_>>();
 
-        let mut guard = match self.get_reranker() {
-            Ok(g) => g,
-            Err(e) => {
-                eprintln!("Warning: Could not load reranker: {}", e);
-                let mut original = top_results;
-                original.extend(results);
-                return original;
-            }
-        };
-
-        let reranker = match guard.as_mut() {
-            Some(r) => r,
-            None => {
-                eprintln!("Warning: Reranker not available");
-                let mut original = top_results;
-                original.extend(results);
-                return original;
-            }
-        };
+        // Model loading and inference block, so they run off the async thread
+        let slot = self.reranker.clone();
+        let search_config = self.search_config.clone();
+        let query = query.to_string();
+        let blended = tokio::task::spawn_blocking(move || {
+            let mut guard = Self::get_reranker(&slot, &search_config)
+                .map_err(|e| format!("Could not load reranker: {}", e))?;
+            let reranker = guard
+                .as_mut()
+                .ok_or_else(|| "Reranker not available".to_string())?;
+            reranker
+                .rerank_and_blend(
+                    &query,
+                    &documents,
+                    &rrf_scores,
+                    crate::rerank::RERANK_WEIGHT,
+                )
+                .map_err(|e| format!("Reranking failed: {}", e))
+        })
+        .await
+        .unwrap_or_else(|e| Err(format!("Reranking failed: {}", e)));
 
-        let blended = match reranker.rerank_and_blend(query, &documents, &rrf_scores) {
+        let blended = match blended {
             Ok(scores) => scores,
             Err(e) => {
-                eprintln!("Warning: Reranking failed: {}", e);
+                eprintln!("Warning: {}", e);
                 let mut original = top_results;
                 original.extend(results);
                 return original;
@@ -323,37 +551,36 @@ impl DemongrepService {
     }
 
     #[tool(
-        description = "Search the codebase using semantic similarity. Searches both local and global databases. Returns code chunks that are semantically similar to the query."
+        description = "Search the codebase using semantic similarity. Searches both local and global databases. Returns code chunks that are semantically similar to the query, with the limit applied (oversized limits are capped) and the offset; page with offset + limit."
     )]
     async fn semantic_search(
         &self,
         Parameters(request): Parameters<SemanticSearchRequest>,
     ) -> Result<CallToolResult, McpError> {
-        let limit = Self::normalize_limit(request.limit);
+        search_with_timeout(self.request_timeout, self.run_semantic_search(request)).await
+    }
+
+    async fn run_semantic_search(
+        &self,
+        request: SemanticSearchRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let cache_key = format!("semantic_search:{:?}", request);
+        let index_state = self.index_state();
+        if let Some(cached) = self.result_cache.get(&cache_key, index_state) {
+            return Ok(CallToolResult::success(vec![Content::text(
+                cached.as_str(),
+            )]));
+        }
+
+        let limit = Self::normalize_limit(request.limit, self.max_results);
         let offset = request.offset.unwrap_or(0);
         let per_file = Self::normalize_per_file(request.per_file);
         let candidate_limit = Self::normalize_candidate_limit(limit, per_file);
 
-        // Get embedding service and embed query
-        let mut service_guard = match self.get_embedding_service() {
-            Ok(g) => g,
-            Err(e) => {
-                return Ok(CallToolResult::success(vec![Content::text(format!(
-                    "Error initializing embedding service: {}",
-                    e
-                ))]));
-            }
-        };
-
-        let service = service_guard.as_mut().unwrap();
-        let query_embedding = match service.embed_query(&request.query) {
+        // Embed query
+        let query_embedding = match self.embed_query(&request.query).await {
             Ok(e) => e,
-            Err(e) => {
-                return Ok(CallToolResult::success(vec![Content::text(format!(
-                    "Error embedding query: {}",
-                    e
-                ))]));
-            }
+            Err(e) => return Ok(CallToolResult::success(vec![Content::text(e)])),
         };
 
         // Search across all databases using DatabaseManager
@@ -371,9 +598,11 @@ impl DemongrepService {
         };
 
         if results.is_empty() {
-            return Ok(CallToolResult::success(vec![Content::text(
-                "No results found for the query.",
-            )]));
+            return Ok(self.cache_response(
+                &cache_key,
+                index_state,
+                "No results found for the query.".to_string(),
+            ));
         }
 
         // Keep ranked diversity so broad queries are not dominated by a single file.
@@ -389,29 +618,26 @@ impl DemongrepService {
                     score: r.score,
                 })
                 .collect();
-            let json = serde_json::to_string(&items).unwrap_or_else(|_| "[]".to_string());
-            return Ok(CallToolResult::success(vec![Content::text(json)]));
+            let page = SearchPage {
+                results: items,
+                limit,
+                offset,
+            };
+            let json = serde_json::to_string(&page).unwrap_or_else(|_| "{}".to_string());
+            return Ok(self.cache_response(&cache_key, index_state, json));
         }
 
         let items: Vec<SearchResultItem> = diversified
             .into_iter()
             .map(|r| {
-                // Determine which database this came from based on path
-                let database = self
-                    .db_manager
-                    .databases()
-                    .iter()
-                    .find(|db| r.path.starts_with(db.path.to_str().unwrap_or("")))
-                    .map(|db| match db.db_type {
-                        crate::database::DatabaseType::Local => "local".to_string(),
-                        crate::database::DatabaseType::Global => "global".to_string(),
-                    });
+                let database = r.database.map(|database| database.tag().to_string());
 
                 SearchResultItem {
                     path: r.path,
                     start_line: r.start_line,
                     end_line: r.end_line,
                     kind: r.kind,
+                    language: r.language,
                     content: Self::compact_content(&r.content),
                     score: r.score,
                     signature: None,
@@ -423,77 +649,152 @@ impl DemongrepService {
                     vector_rank: r.vector_rank,
                     fts_rank: r.fts_rank,
                     rerank_score: r.rerank_score,
+                    sources: match_sources(r.vector_rank, r.fts_rank),
                 }
             })
             .collect();
 
-        let json = serde_json::to_string_pretty(&items).unwrap_or_else(|_| "[]".to_string());
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        let page = SearchPage {
+            results: items,
+            limit,
+            offset,
+        };
+        let json = serde_json::to_string(&page).unwrap_or_else(|_| "{}".to_string());
+        Ok(self.cache_response(&cache_key, index_state, json))
+    }
+
+    /// Chunks of each requested file across all databases, read through each store's path index
+    fn collect_file_chunks(&self, paths: &[String]) -> BTreeMap<String, Vec<SearchResultItem>> {
+        let mut chunks = Vec::new();
+        for database in self.db_manager.databases() {
+            let db_type = database.db_type.tag();
+            for path in paths {
+                if let Ok(found) = database.store().chunks_for_path(path) {
+                    chunks.extend(found.into_iter().map(|(_, chunk)| (db_type, chunk)));
+                }
+            }
+        }
+        group_file_chunks(paths, chunks)
     }
 
-    #[allow(dead_code)]
+    #[tool(
+        description = "Get the indexed chunks of a file (`path`) or of several files at once (`paths`, returns a map of path to page). Paginated with `limit` (default 50, max 200) and `offset`; continue from `next_offset`."
+    )]
     async fn get_file_chunks(
         &self,
         Parameters(request): Parameters<GetFileChunksRequest>,
     ) -> Result<CallToolResult, McpError> {
-        let mut all_file_chunks: Vec<SearchResultItem> = Vec::new();
+        let limit = request
+            .limit
+            .unwrap_or(MCP_DEFAULT_FILE_CHUNKS_LIMIT)
+            .clamp(1, MCP_MAX_FILE_CHUNKS_LIMIT);
+        let offset = request.offset.unwrap_or(0);
+        if request.path.is_some() == request.paths.is_some() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Provide exactly one of `path` or `paths`".to_string(),
+            )]));
+        }
 
-        // Search across all databases
-        for database in self.db_manager.databases() {
+        if let Some(paths) = request.paths {
+            let pages: BTreeMap<String, FileChunksPage> = self
+                .collect_file_chunks(&paths)
+                .into_iter()
+                .map(|(path, chunks)| (path, FileChunksPage::paginate(chunks, offset, limit)))
+                .collect();
+            let json = serde_json::to_string(&pages).unwrap_or_else(|_| "{}".to_string());
+            return Ok(CallToolResult::success(vec![Content::text(json)]));
+        }
+
+        let path = request.path.unwrap_or_default();
+        let all_file_chunks = self
+            .collect_file_chunks(std::slice::from_ref(&path))
+            .remove(&path)
+            .unwrap_or_default();
+
+        if all_file_chunks.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No chunks found for file: {}",
+                path
+            ))]));
+        }
+
+        let page = FileChunksPage::paginate(all_file_chunks, offset, limit);
+        let json = serde_json::to_string(&page).unwrap_or_else(|_| "{}".to_string());
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(
+        description = "Get raw embedding vectors (L2-normalized) for chunks, by chunk ids or by file path, for clustering or visualization. `limit` is required (max 100)."
+    )]
+    async fn get_embeddings(
+        &self,
+        Parameters(request): Parameters<GetEmbeddingsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if request.limit == 0 || request.limit > MCP_MAX_EMBEDDINGS_LIMIT {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "limit must be between 1 and {}",
+                MCP_MAX_EMBEDDINGS_LIMIT
+            ))]));
+        }
+        if request.ids.is_some() == request.path.is_some() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "Provide exactly one of `ids` or `path`".to_string(),
+            )]));
+        }
+
+        let req_path = request.path.as_deref().map(|p| p.trim_start_matches("./"));
+        let mut embeddings: Vec<EmbeddingItem> = Vec::new();
+        let mut truncated = false;
+
+        'databases: for database in self.db_manager.databases() {
             let store = database.store();
 
-            let stats = match store.stats() {
-                Ok(s) => s,
-                Err(_) => continue,
+            // Chunk IDs can be sparse after deletions, so look a path's chunks up directly
+            let chunks: Vec<(u32, ChunkMetadata)> = match (&request.ids, req_path) {
+                (Some(ids), _) => ids
+                    .iter()
+                    .filter_map(|&id| store.get_chunk(id).ok().flatten().map(|chunk| (id, chunk)))
+                    .collect(),
+                (None, Some(req_path)) => match store.chunks_for_path(req_path) {
+                    Ok(chunks) => chunks,
+                    Err(_) => continue,
+                },
+                (None, None) => continue,
             };
 
-            // Collect chunks for the requested file
-            for id in 0..stats.total_chunks as u32 {
-                if let Ok(Some(chunk)) = store.get_chunk(id) {
-                    // Normalize paths for comparison
-                    let chunk_path = chunk.path.trim_start_matches("./");
-                    let req_path = request.path.trim_start_matches("./");
-
-                    if chunk_path == req_path || chunk.path == request.path {
-                        let db_type = match database.db_type {
-                            crate::database::DatabaseType::Local => "local",
-                            crate::database::DatabaseType::Global => "global",
-                        };
-
-                        all_file_chunks.push(SearchResultItem {
-                            path: chunk.path,
-                            start_line: chunk.start_line,
-                            end_line: chunk.end_line,
-                            kind: chunk.kind,
-                            content: chunk.content,
-                            score: 1.0,
-                            signature: chunk.signature,
-                            context_prev: chunk.context_prev,
-                            context_next: chunk.context_next,
-                            database: Some(db_type.to_string()),
-                            vector_score: None,
-                            fts_score: None,
-                            vector_rank: None,
-                            fts_rank: None,
-                            rerank_score: None,
-                        });
-                    }
+            for (id, chunk) in chunks {
+                let Ok(Some(embedding)) = store.get_embedding(id) else {
+                    continue;
+                };
+
+                if embeddings.len() >= request.limit {
+                    truncated = true;
+                    break 'databases;
                 }
+                embeddings.push(EmbeddingItem {
+                    id,
+                    path: chunk.path,
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    database: database.db_type.name().to_lowercase(),
+                    embedding,
+                });
             }
         }
 
-        // Sort by start line
-        all_file_chunks.sort_by_key(|c| c.start_line);
-
-        if all_file_chunks.is_empty() {
-            return Ok(CallToolResult::success(vec![Content::text(format!(
-                "No chunks found for file: {}",
-                request.path
-            ))]));
+        if embeddings.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No matching chunks found.".to_string(),
+            )]));
         }
 
-        let json =
-            serde_json::to_string_pretty(&all_file_chunks).unwrap_or_else(|_| "[]".to_string());
+        let response = EmbeddingsResponse {
+            model: self.db_manager.model_type().short_name().to_string(),
+            dimensions: self.db_manager.dimensions(),
+            embeddings,
+            truncated,
+        };
+        let json = serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string());
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
@@ -504,7 +805,22 @@ impl DemongrepService {
         &self,
         Parameters(request): Parameters<HybridSearchRequest>,
     ) -> Result<CallToolResult, McpError> {
-        let limit = Self::normalize_limit(request.limit);
+        search_with_timeout(self.request_timeout, self.run_hybrid_search(request)).await
+    }
+
+    async fn run_hybrid_search(
+        &self,
+        request: HybridSearchRequest,
+    ) -> Result<CallToolResult, McpError> {
+        let cache_key = format!("hybrid_search:{:?}", request);
+        let index_state = self.index_state();
+        if let Some(cached) = self.result_cache.get(&cache_key, index_state) {
+            return Ok(CallToolResult::success(vec![Content::text(
+                cached.as_str(),
+            )]));
+        }
+
+        let limit = Self::normalize_limit(request.limit, self.max_results);
         let offset = request.offset.unwrap_or(0);
         let rrf_k = request.rrf_k.unwrap_or(20.0);
         let rerank = request.rerank.unwrap_or(false);
@@ -512,26 +828,10 @@ impl DemongrepService {
         let per_file = Self::normalize_per_file(request.per_file);
         let candidate_limit = Self::normalize_candidate_limit(limit, per_file);
 
-        // Get embedding service and embed query
-        let mut service_guard = match self.get_embedding_service() {
-            Ok(g) => g,
-            Err(e) => {
-                return Ok(CallToolResult::success(vec![Content::text(format!(
-                    "Error initializing embedding service: {}",
-                    e
-                ))]));
-            }
-        };
-
-        let service = service_guard.as_mut().unwrap();
-        let query_embedding = match service.embed_query(&request.query) {
+        // Embed query
+        let query_embedding = match self.embed_query(&request.query).await {
             Ok(e) => e,
-            Err(e) => {
-                return Ok(CallToolResult::success(vec![Content::text(format!(
-                    "Error embedding query: {}",
-                    e
-                ))]));
-            }
+            Err(e) => return Ok(CallToolResult::success(vec![Content::text(e)])),
         };
 
         // Use hybrid search (vector + FTS + RRF)
@@ -560,13 +860,17 @@ impl DemongrepService {
             });
         }
 
-        results = self.apply_optional_rerank(&request.query, results, rerank, rerank_top);
+        results = self
+            .apply_optional_rerank(&request.query, results, rerank, rerank_top)
+            .await;
         results = Self::limit_results_per_file(results, per_file, limit);
 
         if results.is_empty() {
-            return Ok(CallToolResult::success(vec![Content::text(
-                "No results found for the query.",
-            )]));
+            return Ok(self.cache_response(
+                &cache_key,
+                index_state,
+                "No results found for the query.".to_string(),
+            ));
         }
 
         // Check for compact mode
@@ -580,7 +884,7 @@ impl DemongrepService {
                 })
                 .collect();
             let json = serde_json::to_string(&items).unwrap_or_else(|_| "[]".to_string());
-            return Ok(CallToolResult::success(vec![Content::text(json)]));
+            return Ok(self.cache_response(&cache_key, index_state, json));
         }
 
         let items: Vec<SearchResultItem> = results
@@ -590,25 +894,29 @@ impl DemongrepService {
                 start_line: r.start_line,
                 end_line: r.end_line,
                 kind: r.kind.clone(),
+                language: r.language.clone(),
                 content: Self::compact_content(&r.content),
                 score: r.score,
                 signature: None,
                 context_prev: None,
                 context_next: None,
-                database: None,
+                database: r.database.map(|database| database.tag().to_string()),
                 vector_score: r.vector_score,
                 fts_score: r.fts_score,
                 vector_rank: r.vector_rank,
                 fts_rank: r.fts_rank,
                 rerank_score: r.rerank_score,
+                sources: match_sources(r.vector_rank, r.fts_rank),
             })
             .collect();
 
-        let json = serde_json::to_string_pretty(&items).unwrap_or_else(|_| "[]".to_string());
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        let json = serde_json::to_string(&items).unwrap_or_else(|_| "[]".to_string());
+        Ok(self.cache_response(&cache_key, index_state, json))
     }
 
-    #[allow(dead_code)]
+    #[tool(
+        description = "Incrementally re-index the local database(s): re-embeds changed files and drops deleted ones. Use after editing files when search results look stale."
+    )]
     async fn reindex(
         &self,
         Parameters(_request): Parameters<ReindexRequest>,
@@ -656,41 +964,63 @@ impl DemongrepService {
             }
         }
 
+        self.result_cache.invalidate_all();
+
         Ok(CallToolResult::success(vec![Content::text(format!(
             "Reindex complete. Checked {} database(s).",
             total_changes
         ))]))
     }
 
-    #[allow(dead_code)]
+    #[tool(
+        description = "List definitions (functions, structs, classes, ...) sorted by path and line, optionally filtered by a name `pattern` and a `kind`. Cheaper than a search when you know what you're looking for."
+    )]
     async fn find_definitions(
         &self,
         Parameters(request): Parameters<FindDefinitionsRequest>,
     ) -> Result<CallToolResult, McpError> {
         let limit = request.limit.unwrap_or(20);
+        let kind_filter = match request.kind.as_deref().map(ChunkKind::from_str) {
+            Some(None) => {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Unknown kind '{}'. Expected one of: {}",
+                    request.kind.as_deref().unwrap_or_default(),
+                    ChunkKind::all()
+                        .iter()
+                        .map(|kind| kind.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))]));
+            }
+            Some(kind) => kind,
+            None => None,
+        };
         let mut definitions: Vec<SearchResultItem> = Vec::new();
 
         for database in self.db_manager.databases() {
             let store = database.store();
 
-            let stats = match store.stats() {
-                Ok(s) => s,
+            let ids = match store.chunk_ids() {
+                Ok(ids) => ids,
                 Err(_) => continue,
             };
 
-            for id in 0..stats.total_chunks as u32 {
+            for id in ids {
                 if let Ok(Some(chunk)) = store.get_chunk(id) {
                     // Skip non-definition kinds (Block, Anchor, Other)
-                    let kind_lower = chunk.kind.to_lowercase();
-                    if kind_lower == "block" || kind_lower == "anchor" || kind_lower == "other" {
+                    let Some(kind) = ChunkKind::from_str(&chunk.kind) else {
+                        continue;
+                    };
+                    if matches!(
+                        kind,
+                        ChunkKind::Block | ChunkKind::Anchor | ChunkKind::Other
+                    ) {
                         continue;
                     }
 
                     // Filter by kind if specified
-                    if let Some(ref kind_filter) = request.kind {
-                        if !kind_lower.contains(&kind_filter.to_lowercase()) {
-                            continue;
-                        }
+                    if kind_filter.is_some_and(|kind_filter| kind_filter != kind) {
+                        continue;
                     }
 
                     // Filter by pattern if specified (matches signature or content)
@@ -708,16 +1038,14 @@ impl DemongrepService {
                         }
                     }
 
-                    let db_type = match database.db_type {
-                        crate::database::DatabaseType::Local => "local",
-                        crate::database::DatabaseType::Global => "global",
-                    };
+                    let db_type = database.db_type.tag();
 
                     definitions.push(SearchResultItem {
                         path: chunk.path,
                         start_line: chunk.start_line,
                         end_line: chunk.end_line,
                         kind: chunk.kind,
+                        language: chunk.language,
                         content: Self::compact_content(&chunk.content),
                         score: 1.0,
                         signature: None,
@@ -729,6 +1057,7 @@ impl DemongrepService {
                         vector_rank: None,
                         fts_rank: None,
                         rerank_score: None,
+                        sources: Vec::new(),
                     });
 
                     if definitions.len() >= limit {
@@ -755,7 +1084,7 @@ impl DemongrepService {
             return Ok(CallToolResult::success(vec![Content::text(msg)]));
         }
 
-        let json = serde_json::to_string_pretty(&definitions).unwrap_or_else(|_| "[]".to_string());
+        let json = serde_json::to_string(&definitions).unwrap_or_else(|_| "[]".to_string());
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
@@ -793,7 +1122,7 @@ impl DemongrepService {
             databases_available: self.db_manager.database_count(),
         };
 
-        let json = serde_json::to_string_pretty(&response).unwrap_or_else(|_| "{}".to_string());
+        let json = serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string());
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 }
@@ -822,10 +1151,56 @@ impl ServerHandler for DemongrepService {
     }
 }
 
+/// Names and descriptions of the tools registered on the MCP tool router
+pub fn list_tools() -> Vec<(String, String)> {
+    let mut tools: Vec<(String, String)> = DemongrepService::tool_router()
+        .list_all()
+        .into_iter()
+        .map(|tool| {
+            (
+                tool.name.to_string(),
+                tool.description.map(|d| d.to_string()).unwrap_or_default(),
+            )
+        })
+        .collect();
+    tools.sort();
+    tools
+}
+
+/// Print the exposed tools without starting the stdio server
+pub fn print_tools() {
+    println!("demongrep MCP tools:");
+    for (name, description) in list_tools() {
+        println!("  {:<16} {}", name, description);
+    }
+}
+
+/// A search's result, or an error result once it has run for `timeout`
+///
+/// Blocking steps are handed to `spawn_blocking`, so the timer keeps running
+/// on this task; see [`with_timeout`] for what happens to them on timeout.
+async fn search_with_timeout(
+    timeout: Duration,
+    search: impl std::future::Future<Output = Result<CallToolResult, McpError>>,
+) -> Result<CallToolResult, McpError> {
+    with_timeout(timeout, search)
+        .await
+        .unwrap_or_else(|timed_out| {
+            Ok(CallToolResult::success(vec![Content::text(format!(
+                "Error searching: {}",
+                timed_out
+            ))]))
+        })
+}
+
 /// Run the MCP server using stdio transport with DatabaseManager
-pub async fn run_mcp_server(path: Option<PathBuf>) -> Result<()> {
+pub async fn run_mcp_server(path: Option<PathBuf>, timeout: Option<u64>) -> Result<()> {
     use rmcp::{transport::stdio, ServiceExt};
 
+    // Project whose config the server uses, wherever it was started from
+    let project_root =
+        crate::index::canonical_project_path(path.as_deref().unwrap_or(Path::new(".")))?;
+
     // Use DatabaseManager to load all databases
     let db_manager = match DatabaseManager::load(path) {
         Ok(manager) => manager,
@@ -849,7 +1224,11 @@ pub async fn run_mcp_server(path: Option<PathBuf>) -> Result<()> {
         );
     }
 
-    let service = DemongrepService::new(db_manager)?;
+    let mut service = DemongrepService::new(db_manager, &project_root)?;
+    // --timeout wins over server.request_timeout_secs from config
+    if let Some(timeout) = timeout {
+        service = service.with_request_timeout(Duration::from_secs(timeout));
+    }
 
     // Serve using stdio transport
     let server = service.serve(stdio()).await?;
@@ -861,3 +1240,403 @@ pub async fn run_mcp_server(path: Option<PathBuf>) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_tools() {
+        let tools = list_tools();
+        let names: Vec<&str> = tools.iter().map(|(name, _)| name.as_str()).collect();
+
+        for expected in [
+            "hybrid_search",
+            "semantic_search",
+            "index_status",
+            "get_embeddings",
+            "get_file_chunks",
+            "reindex",
+            "find_definitions",
+        ] {
+            assert!(names.contains(&expected), "missing tool: {}", expected);
+        }
+        assert!(tools.iter().all(|(_, description)| !description.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_slow_search_times_out() {
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(CallToolResult::success(vec![Content::text("results")]))
+        };
+        let result = search_with_timeout(Duration::from_millis(20), slow)
+            .await
+            .unwrap();
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains("timed out after 20ms"), "{}", json);
+
+        let fast = async { Ok(CallToolResult::success(vec![Content::text("results")])) };
+        let result = search_with_timeout(Duration::from_secs(5), fast)
+            .await
+            .unwrap();
+        assert!(serde_json::to_string(&result).unwrap().contains("results"));
+    }
+
+    #[test]
+    fn test_pinned_model_comes_from_the_project_config() {
+        let db_dir = tempfile::tempdir().unwrap();
+        drop(crate::vectordb::VectorStore::new(db_dir.path(), 4).unwrap());
+        std::fs::write(
+            db_dir.path().join("metadata.json"),
+            r#"{"model_short_name": "minilm-l6-q", "dimensions": 4}"#,
+        )
+        .unwrap();
+        let manager = || {
+            DatabaseManager::open(vec![(
+                db_dir.path().to_path_buf(),
+                crate::database::DatabaseType::Local,
+            )])
+            .unwrap()
+        };
+
+        let unpinned = tempfile::tempdir().unwrap();
+        assert!(DemongrepService::new(manager(), unpinned.path()).is_ok());
+
+        let pinned = tempfile::tempdir().unwrap();
+        std::fs::write(
+            pinned.path().join(".demongrep.toml"),
+            "[embedding]\nmodel = \"jina-v5-nano\"\npinned = true\n",
+        )
+        .unwrap();
+        let err = DemongrepService::new(manager(), pinned.path()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DemongrepError>(),
+            Some(DemongrepError::ModelMismatch { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_embeddings_by_path_after_deletions() {
+        use crate::chunker::Chunk;
+        use crate::embed::EmbeddedChunk;
+
+        let db_dir = tempfile::tempdir().unwrap();
+        {
+            let mut store = crate::vectordb::VectorStore::new(db_dir.path(), 4).unwrap();
+            let chunks = ["a.rs", "a.rs", "b.rs"]
+                .iter()
+                .map(|&path| {
+                    EmbeddedChunk::new(
+                        Chunk::new(format!("// {}", path), 0, 1, ChunkKind::Block, path.into()),
+                        vec![1.0, 0.0, 0.0, 0.0],
+                    )
+                })
+                .collect();
+            let ids = store.insert_chunks_with_ids(chunks).unwrap();
+            // b.rs keeps an ID past the remaining chunk count
+            store.delete_chunks(&ids[..2]).unwrap();
+            store.build_index().unwrap();
+        }
+        std::fs::write(
+            db_dir.path().join("metadata.json"),
+            r#"{"model_short_name": "minilm-l6-q", "dimensions": 4}"#,
+        )
+        .unwrap();
+        let manager = DatabaseManager::open(vec![(
+            db_dir.path().to_path_buf(),
+            crate::database::DatabaseType::Local,
+        )])
+        .unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let service = DemongrepService::new(manager, project.path()).unwrap();
+
+        let result = service
+            .get_embeddings(Parameters(GetEmbeddingsRequest {
+                ids: None,
+                path: Some("./b.rs".to_string()),
+                limit: 10,
+            }))
+            .await
+            .unwrap();
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains(r#"\"id\":2"#), "{}", json);
+    }
+
+    /// A service over one local database holding a chunk per path, kept
+    /// alive by the returned tempdirs
+    fn indexed_service(paths: &[&str]) -> (tempfile::TempDir, tempfile::TempDir, DemongrepService) {
+        use crate::chunker::Chunk;
+        use crate::embed::EmbeddedChunk;
+
+        let db_dir = tempfile::tempdir().unwrap();
+        {
+            let mut store = crate::vectordb::VectorStore::new(db_dir.path(), 4).unwrap();
+            let chunks = paths
+                .iter()
+                .map(|&path| {
+                    EmbeddedChunk::new(
+                        Chunk::new(
+                            format!("// {}", path),
+                            0,
+                            1,
+                            ChunkKind::Function,
+                            path.into(),
+                        ),
+                        vec![1.0, 0.0, 0.0, 0.0],
+                    )
+                })
+                .collect();
+            store.insert_chunks_with_ids(chunks).unwrap();
+            store.build_index().unwrap();
+        }
+        std::fs::write(
+            db_dir.path().join("metadata.json"),
+            r#"{"model_short_name": "minilm-l6-q", "dimensions": 4}"#,
+        )
+        .unwrap();
+        let manager = DatabaseManager::open(vec![(
+            db_dir.path().to_path_buf(),
+            crate::database::DatabaseType::Local,
+        )])
+        .unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let service = DemongrepService::new(manager, project.path()).unwrap();
+        (db_dir, project, service)
+    }
+
+    /// Call `tool` the way a client does: serve `service` over an in-memory
+    /// stdio pipe, initialize, and send a `tools/call` request. Returns the
+    /// `result` of the call.
+    async fn call_tool(
+        service: DemongrepService,
+        tool: &str,
+        arguments: serde_json::Value,
+    ) -> serde_json::Value {
+        use rmcp::ServiceExt;
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let (client, server) = tokio::io::duplex(1 << 16);
+        let server = tokio::spawn(async move {
+            let running = service.serve(tokio::io::split(server)).await.unwrap();
+            running.waiting().await.unwrap();
+        });
+        let (reader, mut writer) = tokio::io::split(client);
+        let mut lines = BufReader::new(reader).lines();
+
+        let messages = [
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {},
+                    "clientInfo": {"name": "test", "version": "0"}
+                }
+            }),
+            serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {"name": tool, "arguments": arguments}
+            }),
+        ];
+        for message in messages {
+            writer
+                .write_all(format!("{}\n", message).as_bytes())
+                .await
+                .unwrap();
+        }
+
+        let result = loop {
+            let line = lines.next_line().await.unwrap().expect("server closed");
+            let response: serde_json::Value = serde_json::from_str(&line).unwrap();
+            if response["id"] == 2 {
+                break response["result"].clone();
+            }
+        };
+        drop(writer);
+        server.abort();
+        result
+    }
+
+    /// Text of the first content item of a `tools/call` result
+    fn tool_text(result: &serde_json::Value) -> &str {
+        result["content"][0]["text"].as_str().unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_get_file_chunks_through_the_router() {
+        let (_db_dir, _project, service) = indexed_service(&["src/a.rs", "src/b.rs"]);
+
+        let result = call_tool(
+            service,
+            "get_file_chunks",
+            serde_json::json!({"path": "src/a.rs"}),
+        )
+        .await;
+        assert_ne!(result["isError"], true, "{}", result);
+        let page: serde_json::Value = serde_json::from_str(tool_text(&result)).unwrap();
+        assert_eq!(page["total"], 1);
+        assert_eq!(page["chunks"][0]["path"], "src/a.rs");
+    }
+
+    #[tokio::test]
+    async fn test_batched_file_chunks_through_the_router() {
+        let (_db_dir, _project, service) =
+            indexed_service(&["src/a.rs", "src/b.rs", "src/b.rs", "src/c.rs"]);
+
+        let result = call_tool(
+            service,
+            "get_file_chunks",
+            serde_json::json!({"paths": ["src/a.rs", "src/b.rs", "src/missing.rs"]}),
+        )
+        .await;
+        assert_ne!(result["isError"], true, "{}", result);
+        let pages: serde_json::Value = serde_json::from_str(tool_text(&result)).unwrap();
+        assert_eq!(pages["src/a.rs"]["total"], 1);
+        assert_eq!(pages["src/b.rs"]["total"], 2);
+        assert_eq!(pages["src/missing.rs"]["total"], 0);
+        assert!(pages.get("src/c.rs").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_find_definitions_through_the_router() {
+        let (_db_dir, _project, service) = indexed_service(&["src/a.rs", "src/b.rs"]);
+
+        let result = call_tool(
+            service,
+            "find_definitions",
+            serde_json::json!({"pattern": "b.rs", "kind": "Function"}),
+        )
+        .await;
+        assert_ne!(result["isError"], true, "{}", result);
+        let definitions: serde_json::Value = serde_json::from_str(tool_text(&result)).unwrap();
+        assert_eq!(definitions.as_array().unwrap().len(), 1, "{}", definitions);
+        assert_eq!(definitions[0]["path"], "src/b.rs");
+    }
+
+    #[test]
+    fn test_oversized_limit_is_clamped() {
+        let max_results = crate::config::McpConfig::default().max_results;
+        assert_eq!(
+            DemongrepService::normalize_limit(Some(5000), max_results),
+            max_results
+        );
+        assert_eq!(
+            DemongrepService::normalize_limit(None, max_results),
+            MCP_DEFAULT_LIMIT
+        );
+        assert_eq!(DemongrepService::normalize_limit(Some(0), max_results), 1);
+
+        // A configured cap above the candidate pool still searches enough candidates
+        let config: crate::config::ProjectConfig =
+            toml::from_str("[mcp]\nmax_results = 100\n").unwrap();
+        let limit = DemongrepService::normalize_limit(Some(5000), config.mcp.max_results);
+        assert_eq!(limit, 100);
+        assert_eq!(DemongrepService::normalize_candidate_limit(limit, 1), 100);
+
+        let page = SearchPage {
+            results: vec![1, 2],
+            limit,
+            offset: 20,
+        };
+        let json = serde_json::to_value(&page).unwrap();
+        assert_eq!(json["limit"], 100);
+        assert_eq!(json["offset"], 20);
+    }
+
+    #[test]
+    fn test_file_chunks_pagination() {
+        let chunks = |n: usize| -> Vec<SearchResultItem> {
+            (0..n)
+                .map(|i| SearchResultItem {
+                    path: "src/big.rs".to_string(),
+                    start_line: i * 10 + 1,
+                    end_line: i * 10 + 10,
+                    kind: "Function".to_string(),
+                    language: None,
+                    content: format!("fn f{}() {{}}", i),
+                    score: 1.0,
+                    signature: None,
+                    context_prev: None,
+                    context_next: None,
+                    database: Some("local".to_string()),
+                    vector_score: None,
+                    fts_score: None,
+                    vector_rank: None,
+                    fts_rank: None,
+                    rerank_score: None,
+                    sources: Vec::new(),
+                })
+                .collect()
+        };
+
+        // Walk all pages of a 120-chunk file
+        let mut offset = 0;
+        let mut seen = Vec::new();
+        loop {
+            let page = FileChunksPage::paginate(chunks(120), offset, 50);
+            assert_eq!(page.total, 120);
+            assert!(page.chunks.len() <= 50);
+            seen.extend(page.chunks.iter().map(|c| c.start_line));
+            match page.next_offset {
+                Some(next) => offset = next,
+                None => break,
+            }
+        }
+        assert_eq!(seen, (0..120).map(|i| i * 10 + 1).collect::<Vec<_>>());
+
+        let last = FileChunksPage::paginate(chunks(120), 100, 50);
+        assert_eq!(last.chunks.len(), 20);
+        assert_eq!(last.next_offset, None);
+
+        let past_end = FileChunksPage::paginate(chunks(3), 10, 50);
+        assert!(past_end.chunks.is_empty());
+        assert_eq!(past_end.next_offset, None);
+    }
+
+    #[test]
+    fn test_file_chunks_grouped_by_path() {
+        let chunk = |path: &str, start_line: usize| ChunkMetadata {
+            content: format!("// {}:{}", path, start_line),
+            path: path.to_string(),
+            start_line,
+            end_line: start_line + 5,
+            kind: "Function".to_string(),
+            signature: None,
+            docstring: None,
+            context: None,
+            hash: String::new(),
+            context_prev: None,
+            context_next: None,
+            language: Some("Rust".to_string()),
+            scope: None,
+        };
+        let chunks = vec![
+            ("local", chunk("./src/b.rs", 30)),
+            ("local", chunk("src/a.rs", 20)),
+            ("local", chunk("src/other.rs", 1)),
+            ("global", chunk("src/b.rs", 1)),
+            ("local", chunk("src/a.rs", 1)),
+        ];
+        let paths = vec![
+            "src/a.rs".to_string(),
+            "src/b.rs".to_string(),
+            "src/missing.rs".to_string(),
+        ];
+
+        let groups = group_file_chunks(&paths, chunks);
+        assert_eq!(groups.len(), 3);
+
+        let lines =
+            |path: &str| -> Vec<usize> { groups[path].iter().map(|c| c.start_line).collect() };
+        assert_eq!(lines("src/a.rs"), vec![1, 20]);
+        assert_eq!(lines("src/b.rs"), vec![1, 30]);
+        assert!(groups["src/a.rs"].iter().all(|c| c.path == "src/a.rs"));
+        assert_eq!(groups["src/b.rs"][0].database.as_deref(), Some("global"));
+        assert!(groups["src/missing.rs"].is_empty());
+    }
+}
@@ -1,8 +1,12 @@
-//! Output control for quiet mode and JSON output
+//! Output control for quiet mode, colors, progress and JSON output
 //!
-//! Provides a global quiet mode flag to suppress non-essential output.
+//! Provides a global quiet mode flag to suppress non-essential output, the
+//! `--color` setting applied to `colored` for the whole process, and the
+//! `--progress` style used by long-running phases.
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
 
 /// Global quiet mode flag
 static QUIET_MODE: AtomicBool = AtomicBool::new(false);
@@ -17,6 +21,186 @@ pub fn is_quiet() -> bool {
     QUIET_MODE.load(Ordering::SeqCst)
 }
 
+/// When to emit ANSI colors (`--color`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    Always,
+    /// Color only when stdout is a terminal and NO_COLOR is unset
+    #[default]
+    Auto,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "always" => Some(Self::Always),
+            "auto" => Some(Self::Auto),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+
+    /// Resolve the choice given the NO_COLOR convention and whether stdout is a TTY
+    pub fn enabled(self, no_color: bool, stdout_is_tty: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => !no_color && stdout_is_tty,
+        }
+    }
+}
+
+/// Configure `colored` for the rest of the process
+pub fn set_color(choice: ColorChoice) {
+    // Per no-color.org, NO_COLOR only counts when set to a non-empty value
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let enabled = choice.enabled(no_color, std::io::stdout().is_terminal());
+    colored::control::set_override(enabled);
+}
+
+/// Check whether colored output is currently enabled
+pub fn color_enabled() -> bool {
+    colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+/// How long-running phases report progress (`--progress`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// Redrawn progress bar, for terminals
+    Bar,
+    /// A line at every 10%, for CI logs and other non-TTY output
+    Plain,
+    None,
+}
+
+/// Progress mode set with `set_progress` (0 = not set)
+static PROGRESS_MODE: AtomicU8 = AtomicU8::new(0);
+
+impl ProgressMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "bar" => Some(Self::Bar),
+            "plain" => Some(Self::Plain),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    /// The requested mode, else a bar on a terminal and plain lines otherwise
+    pub fn resolve(requested: Option<Self>, stdout_is_tty: bool) -> Self {
+        match requested {
+            Some(mode) => mode,
+            None if stdout_is_tty => Self::Bar,
+            None => Self::Plain,
+        }
+    }
+}
+
+/// Set the progress mode for the rest of the process
+pub fn set_progress(mode: ProgressMode) {
+    let value = match mode {
+        ProgressMode::Bar => 1,
+        ProgressMode::Plain => 2,
+        ProgressMode::None => 3,
+    };
+    PROGRESS_MODE.store(value, Ordering::SeqCst);
+}
+
+/// The progress mode set with `set_progress`, or the default for stdout
+pub fn progress_mode() -> ProgressMode {
+    let requested = match PROGRESS_MODE.load(Ordering::SeqCst) {
+        1 => Some(ProgressMode::Bar),
+        2 => Some(ProgressMode::Plain),
+        3 => Some(ProgressMode::None),
+        _ => None,
+    };
+    ProgressMode::resolve(requested, std::io::stdout().is_terminal())
+}
+
+/// Progress over `total` items, rendered according to a [`ProgressMode`]
+///
+/// Can be advanced from several threads (e.g. inside a rayon iterator).
+pub struct Progress {
+    bar: Option<ProgressBar>,
+    plain: bool,
+    label: &'static str,
+    total: u64,
+    done: AtomicU64,
+}
+
+impl Progress {
+    /// Progress in the process-wide mode (see [`progress_mode`])
+    pub fn new(total: u64, label: &'static str) -> Self {
+        Self::with_mode(progress_mode(), total, label)
+    }
+
+    pub fn with_mode(mode: ProgressMode, total: u64, label: &'static str) -> Self {
+        let bar = (mode == ProgressMode::Bar).then(|| {
+            let bar = ProgressBar::new(total);
+            bar.set_style(
+                ProgressStyle::default_bar()
+                    .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
+                    .unwrap()
+                    .progress_chars("█▓▒░ "),
+            );
+            bar
+        });
+        Self {
+            bar,
+            plain: mode == ProgressMode::Plain,
+            label,
+            total,
+            done: AtomicU64::new(0),
+        }
+    }
+
+    /// Count one more item done
+    pub fn inc(&self) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(bar) = &self.bar {
+            bar.inc(1);
+        }
+        if let Some(line) = self.plain_line(done) {
+            println!("{}", line);
+        }
+    }
+
+    pub fn finish(&self) {
+        if let Some(bar) = &self.bar {
+            bar.finish_with_message("Done!");
+        }
+    }
+
+    /// Line to print in plain mode once `done` items are done, at each 10% step
+    fn plain_line(&self, done: u64) -> Option<String> {
+        if !self.plain || self.total == 0 || done == 0 {
+            return None;
+        }
+        let tenths = |n: u64| n.min(self.total) * 10 / self.total;
+        (tenths(done) > tenths(done - 1)).then(|| {
+            format!(
+                "   {}: {}% ({}/{})",
+                self.label,
+                tenths(done) * 10,
+                done.min(self.total),
+                self.total
+            )
+        })
+    }
+}
+
+/// Cut `text` to its first `max_chars` characters, marking the cut with `...`
+///
+/// Counts characters rather than bytes, so the cut never lands inside a
+/// multi-byte UTF-8 sequence.
+pub fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
 /// Print a message only if not in quiet mode
 #[macro_export]
 macro_rules! info_print {
@@ -36,3 +220,64 @@ macro_rules! warn_print {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_choice() {
+        assert_eq!(ColorChoice::parse("NEVER"), Some(ColorChoice::Never));
+        assert_eq!(ColorChoice::parse("rainbow"), None);
+
+        assert!(ColorChoice::Always.enabled(true, false));
+        assert!(!ColorChoice::Never.enabled(false, true));
+        assert!(ColorChoice::Auto.enabled(false, true));
+        assert!(!ColorChoice::Auto.enabled(false, false));
+        assert!(!ColorChoice::Auto.enabled(true, true));
+    }
+
+    #[test]
+    fn test_truncate_chars_never_splits_a_char() {
+        // "é" and "🦀" are multi-byte, so many byte offsets fall inside a char
+        let text = "caféé 🦀🦀 rust";
+        for max_chars in 0..=text.chars().count() + 1 {
+            let truncated = truncate_chars(text, max_chars);
+            let kept = truncated.trim_end_matches("...");
+            assert!(text.starts_with(kept));
+            assert!(kept.chars().count() <= max_chars);
+        }
+        assert_eq!(truncate_chars(text, 5), "caféé...");
+        assert_eq!(truncate_chars(text, 7), "caféé 🦀...");
+        assert_eq!(truncate_chars("short", 10), "short");
+    }
+
+    #[test]
+    fn test_progress_mode_resolution() {
+        assert_eq!(ProgressMode::parse("PLAIN"), Some(ProgressMode::Plain));
+        assert_eq!(ProgressMode::parse("dots"), None);
+
+        assert_eq!(ProgressMode::resolve(None, true), ProgressMode::Bar);
+        assert_eq!(ProgressMode::resolve(None, false), ProgressMode::Plain);
+        assert_eq!(
+            ProgressMode::resolve(Some(ProgressMode::Bar), false),
+            ProgressMode::Bar
+        );
+    }
+
+    #[test]
+    fn test_progress_none_renders_nothing() {
+        let progress = Progress::with_mode(ProgressMode::None, 50, "Chunking");
+        assert!(progress.bar.is_none());
+        assert!((1..=50).all(|done| progress.plain_line(done).is_none()));
+
+        let progress = Progress::with_mode(ProgressMode::Plain, 50, "Chunking");
+        assert!(progress.bar.is_none());
+        let lines: Vec<String> = (1..=50)
+            .filter_map(|done| progress.plain_line(done))
+            .collect();
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[0], "   Chunking: 10% (5/50)");
+        assert_eq!(lines[9], "   Chunking: 100% (50/50)");
+    }
+}
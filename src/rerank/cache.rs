@@ -0,0 +1,247 @@
+use anyhow::Result;
+use heed::types::{Bytes, Str};
+use heed::{Database, EnvOpenOptions};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Scores kept before the cache is emptied (~100 bytes each, well within the map size)
+const MAX_ENTRIES: u64 = 500_000;
+
+/// Persistent cache of raw cross-encoder scores backed by LMDB
+///
+/// Key: SHA-256(model_name + query + document)
+/// Value: the score as little-endian f32 bytes
+///
+/// Holds at most [`MAX_ENTRIES`] scores: a write that would go past it
+/// empties the cache first (scores are cheap to recompute, and this keeps
+/// the fixed-size LMDB map from filling up).
+pub struct RerankScoreCache {
+    env: heed::Env,
+    db: Database<Str, Bytes>,
+    model_name: String,
+    max_entries: u64,
+}
+
+impl RerankScoreCache {
+    /// Open or create the cache at `db_path/rerank_cache/`
+    pub fn new(db_path: &Path, model_name: &str) -> Result<Self> {
+        let cache_path = db_path.join("rerank_cache");
+        std::fs::create_dir_all(&cache_path)?;
+
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(256 * 1024 * 1024) // 256MB max
+                .max_dbs(1)
+                .open(&cache_path)?
+        };
+
+        let mut wtxn = env.write_txn()?;
+        let db: Database<Str, Bytes> = env.create_database(&mut wtxn, Some("scores"))?;
+        wtxn.commit()?;
+
+        Ok(Self {
+            env,
+            db,
+            model_name: model_name.to_string(),
+            max_entries: MAX_ENTRIES,
+        })
+    }
+
+    #[cfg(test)]
+    fn with_max_entries(mut self, max_entries: u64) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> Result<u64> {
+        let rtxn = self.env.read_txn()?;
+        Ok(self.db.len(&rtxn)?)
+    }
+
+    fn cache_key(model_name: &str, query: &str, document: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(model_name.as_bytes());
+        hasher.update(b":");
+        hasher.update(query.as_bytes());
+        hasher.update(b":");
+        hasher.update(document.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Cached score of `document` for `query`
+    pub fn get(&self, query: &str, document: &str) -> Option<f32> {
+        let rtxn = self.env.read_txn().ok()?;
+        let bytes = self
+            .db
+            .get(&rtxn, &Self::cache_key(&self.model_name, query, document))
+            .ok()??;
+        Some(f32::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Store scores of documents for `query` (single transaction)
+    pub fn put_batch(&self, query: &str, scores: &[(&str, f32)]) -> Result<()> {
+        self.put_batch_for(&self.model_name, query, scores)
+    }
+
+    /// Store scores `model_name` gave documents for `query`
+    fn put_batch_for(&self, model_name: &str, query: &str, scores: &[(&str, f32)]) -> Result<()> {
+        if scores.is_empty() {
+            return Ok(());
+        }
+        let mut wtxn = self.env.write_txn()?;
+        if self.db.len(&wtxn)? + scores.len() as u64 > self.max_entries {
+            self.db.clear(&mut wtxn)?;
+        }
+        for (document, score) in scores {
+            let key = Self::cache_key(model_name, query, document);
+            self.db.put(&mut wtxn, &key, &score.to_le_bytes())?;
+        }
+        wtxn.commit()?;
+        Ok(())
+    }
+
+    /// Raw scores for `documents`, taken from the cache where possible
+    ///
+    /// Only uncached documents are passed to `score`, which returns the name
+    /// of the model it ran and (index within them, score) pairs, and isn't
+    /// called at all when every document is cached. New scores are written
+    /// back under the model that produced them, so scores of a fallback
+    /// model never answer for this one. With `cache_only`, uncached
+    /// documents are left out instead. Returns (original_index, score) pairs
+    /// in index order.
+    pub fn scores(
+        &self,
+        query: &str,
+        documents: &[String],
+        cache_only: bool,
+        score: impl FnOnce(&[String]) -> Result<(String, Vec<(usize, f32)>)>,
+    ) -> Result<Vec<(usize, f32)>> {
+        let mut scores = Vec::with_capacity(documents.len());
+        let mut uncached = Vec::new();
+        for (idx, document) in documents.iter().enumerate() {
+            match self.get(query, document) {
+                Some(cached) => scores.push((idx, cached)),
+                None => uncached.push(idx),
+            }
+        }
+
+        if !cache_only && !uncached.is_empty() {
+            let misses: Vec<String> = uncached.iter().map(|&i| documents[i].clone()).collect();
+            let (model_name, fresh) = score(&misses)?;
+            let fresh: Vec<(usize, f32)> =
+                fresh.into_iter().map(|(i, s)| (uncached[i], s)).collect();
+            let entries: Vec<(&str, f32)> = fresh
+                .iter()
+                .map(|&(i, s)| (documents[i].as_str(), s))
+                .collect();
+            if let Err(e) = self.put_batch_for(&model_name, query, &entries) {
+                crate::warn_print!("Warning: Could not write rerank cache: {}", e);
+            }
+            scores.extend(fresh);
+        }
+
+        scores.sort_by_key(|&(idx, _)| idx);
+        Ok(scores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn documents() -> Vec<String> {
+        ["fn login()", "fn logout()", "struct Session"]
+            .iter()
+            .map(|d| d.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_warm_cache_skips_the_model() {
+        let temp = tempdir().unwrap();
+        let cache = RerankScoreCache::new(temp.path(), "test-reranker").unwrap();
+        let documents = documents();
+
+        let scores = cache
+            .scores("auth", &documents, false, |docs| {
+                Ok((
+                    "test-reranker".to_string(),
+                    docs.iter()
+                        .enumerate()
+                        .map(|(i, _)| (i, i as f32))
+                        .collect(),
+                ))
+            })
+            .unwrap();
+        assert_eq!(scores, vec![(0, 0.0), (1, 1.0), (2, 2.0)]);
+
+        // Every document is cached now, so the model is never loaded
+        let cached = cache
+            .scores("auth", &documents, false, |_| {
+                panic!("reranker loaded with a warm cache")
+            })
+            .unwrap();
+        assert_eq!(cached, scores);
+
+        // Scores are per model and per query
+        let other = RerankScoreCache::new(temp.path(), "other-reranker").unwrap();
+        assert!(other.get("auth", "fn login()").is_none());
+        assert!(cache.get("session", "fn login()").is_none());
+    }
+
+    #[test]
+    fn test_fallback_scores_are_cached_under_the_model_that_ran() {
+        let temp = tempdir().unwrap();
+        let custom = RerankScoreCache::new(temp.path(), "custom.onnx").unwrap();
+        let documents = documents();
+
+        let scores = custom
+            .scores("auth", &documents, false, |docs| {
+                Ok((
+                    "builtin-reranker".to_string(),
+                    docs.iter().enumerate().map(|(i, _)| (i, 1.0)).collect(),
+                ))
+            })
+            .unwrap();
+        assert_eq!(scores.len(), 3);
+
+        assert!(custom.get("auth", "fn login()").is_none());
+        let builtin = RerankScoreCache::new(temp.path(), "builtin-reranker").unwrap();
+        assert_eq!(builtin.get("auth", "fn login()"), Some(1.0));
+    }
+
+    #[test]
+    fn test_full_cache_is_emptied_before_writing() {
+        let temp = tempdir().unwrap();
+        let cache = RerankScoreCache::new(temp.path(), "test-reranker")
+            .unwrap()
+            .with_max_entries(3);
+        cache
+            .put_batch("auth", &[("fn login()", 0.1), ("fn logout()", 0.2)])
+            .unwrap();
+        assert_eq!(cache.len().unwrap(), 2);
+
+        cache
+            .put_batch("session", &[("struct Session", 0.3), ("fn refresh()", 0.4)])
+            .unwrap();
+        assert_eq!(cache.len().unwrap(), 2);
+        assert!(cache.get("auth", "fn login()").is_none());
+        assert_eq!(cache.get("session", "fn refresh()"), Some(0.4));
+    }
+
+    #[test]
+    fn test_cache_only_leaves_uncached_documents_unscored() {
+        let temp = tempdir().unwrap();
+        let cache = RerankScoreCache::new(temp.path(), "test-reranker").unwrap();
+        cache.put_batch("auth", &[("fn logout()", 0.5)]).unwrap();
+
+        let scores = cache
+            .scores("auth", &documents(), true, |_| {
+                panic!("reranker loaded in cache-only mode")
+            })
+            .unwrap();
+        assert_eq!(scores, vec![(1, 0.5)]);
+    }
+}
@@ -3,6 +3,7 @@
 //! Provides RRF (Reciprocal Rank Fusion) for combining vector and FTS results,
 //! and neural reranking using cross-encoder models for improved accuracy.
 
+mod cache;
 mod neural;
 
 use std::collections::HashMap;
@@ -10,7 +11,9 @@ use std::collections::HashMap;
 use crate::fts::FtsResult;
 use crate::vectordb::SearchResult;
 
-pub use neural::NeuralReranker;
+pub use cache::RerankScoreCache;
+pub(crate) use neural::blend_scores;
+pub use neural::{NeuralReranker, RERANK_WEIGHT};
 
 /// Default RRF k parameter (per osgrep reference)
 pub const DEFAULT_RRF_K: f32 = 20.0;
@@ -32,6 +35,25 @@ pub struct FusedResult {
     pub fts_rank: Option<usize>,
 }
 
+impl FusedResult {
+    /// Retrieval sources that produced this chunk: "vector", "fts" or both
+    pub fn sources(&self) -> Vec<&'static str> {
+        match_sources(self.vector_rank, self.fts_rank)
+    }
+}
+
+/// Names of the retrieval sources a result was ranked by, in a fixed order
+pub fn match_sources(vector_rank: Option<usize>, fts_rank: Option<usize>) -> Vec<&'static str> {
+    let mut sources = Vec::new();
+    if vector_rank.is_some() {
+        sources.push("vector");
+    }
+    if fts_rank.is_some() {
+        sources.push("fts");
+    }
+    sources
+}
+
 /// Reciprocal Rank Fusion (RRF) for combining search results
 ///
 /// RRF formula: score = sum(1 / (k + rank)) for each ranking list
@@ -127,6 +149,8 @@ mod tests {
             start_line: 1,
             end_line: 10,
             kind: "function".to_string(),
+            language: None,
+            scope: None,
             signature: None,
             docstring: None,
             context: None,
@@ -140,6 +164,8 @@ mod tests {
             vector_rank: None,
             fts_rank: None,
             rerank_score: None,
+            mtime: None,
+            database: None,
         }
     }
 
@@ -186,6 +212,20 @@ mod tests {
         assert!(id4.fts_rank.is_some());
     }
 
+    #[test]
+    fn test_fused_sources() {
+        let vector_results = vec![make_vector_result(1, 0.9), make_vector_result(2, 0.8)];
+        let fts_results = vec![make_fts_result(2, 10.0), make_fts_result(3, 6.0)];
+
+        let fused = rrf_fusion(&vector_results, &fts_results, 20.0);
+        let sources = |id: u32| fused.iter().find(|r| r.chunk_id == id).unwrap().sources();
+
+        assert_eq!(sources(1), vec!["vector"]);
+        assert_eq!(sources(2), vec!["vector", "fts"]);
+        assert_eq!(sources(3), vec!["fts"]);
+        assert_eq!(vector_only(&vector_results)[0].sources(), vec!["vector"]);
+    }
+
     #[test]
     fn test_rrf_score_calculation() {
         // With k=20:
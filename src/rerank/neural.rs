@@ -4,22 +4,113 @@
 //! with the Jina Reranker v1 Turbo model for improved accuracy.
 
 use crate::info_print;
-use anyhow::Result;
-use fastembed::{RerankInitOptions, RerankerModel, TextRerank};
+use anyhow::{anyhow, bail, Context, Result};
+use fastembed::{
+    RerankInitOptions, RerankInitOptionsUserDefined, RerankerModel, TextRerank, TokenizerFiles,
+    UserDefinedRerankingModel,
+};
+use std::path::{Path, PathBuf};
 
 #[allow(dead_code)]
 /// Default number of top results to rerank
 pub const DEFAULT_RERANK_TOP: usize = 50;
 
-/// Score blending weights (per osgrep pattern)
+/// Default score blending weight (per osgrep pattern)
 /// 57.5% rerank + 42.5% RRF
 pub const RERANK_WEIGHT: f32 = 0.575;
-pub const RRF_WEIGHT: f32 = 0.425;
+
+/// Default number of documents the cross-encoder scores at once
+pub const DEFAULT_RERANK_BATCH_SIZE: usize = 32;
+
+/// Tokenizer files a user-supplied cross-encoder needs, as in a Hugging Face export
+const TOKENIZER_FILES: [&str; 4] = [
+    "tokenizer.json",
+    "config.json",
+    "special_tokens_map.json",
+    "tokenizer_config.json",
+];
+
+/// Files of a user-supplied ONNX cross-encoder (`--rerank-model-path`)
+#[derive(Debug, PartialEq)]
+struct RerankModelFiles {
+    onnx: PathBuf,
+    /// Directory holding [`TOKENIZER_FILES`]
+    tokenizer_dir: PathBuf,
+}
+
+impl RerankModelFiles {
+    /// Locate the model and tokenizer files
+    ///
+    /// `path` is either the `.onnx` file or a directory with `model.onnx` (or
+    /// `onnx/model.onnx`, the Hugging Face export layout). The tokenizer files
+    /// are looked up next to the model, then one directory up.
+    fn resolve(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            bail!("Reranker model path does not exist: {}", path.display());
+        }
+        let onnx = if path.is_file() {
+            path.to_path_buf()
+        } else {
+            ["model.onnx", "onnx/model.onnx"]
+                .iter()
+                .map(|file| path.join(file))
+                .find(|file| file.is_file())
+                .ok_or_else(|| anyhow!("No model.onnx or onnx/model.onnx in {}", path.display()))?
+        };
+
+        let model_dir = onnx.parent().unwrap_or(Path::new("."));
+        let tokenizer_dir = [Some(model_dir), model_dir.parent()]
+            .into_iter()
+            .flatten()
+            .find(|dir| dir.join(TOKENIZER_FILES[0]).is_file())
+            .ok_or_else(|| anyhow!("No tokenizer.json next to {}", onnx.display()))?
+            .to_path_buf();
+        if let Some(missing) = TOKENIZER_FILES
+            .iter()
+            .find(|file| !tokenizer_dir.join(file).is_file())
+        {
+            bail!(
+                "Reranker tokenizer file {} is missing from {}",
+                missing,
+                tokenizer_dir.display()
+            );
+        }
+
+        Ok(Self {
+            onnx,
+            tokenizer_dir,
+        })
+    }
+
+    /// Identity of the model for cached scores: the `.onnx` path plus its
+    /// size and modification time, so replacing the file invalidates them
+    fn model_name(&self) -> String {
+        let stamp = std::fs::metadata(&self.onnx).ok().map(|metadata| {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|age| age.as_nanos())
+                .unwrap_or_default();
+            format!("{}-{}", metadata.len(), modified)
+        });
+        match stamp {
+            Some(stamp) => format!("{}@{}", self.onnx.display(), stamp),
+            None => self.onnx.display().to_string(),
+        }
+    }
+
+    fn read_tokenizer_file(&self, name: &str) -> Result<Vec<u8>> {
+        let path = self.tokenizer_dir.join(name);
+        std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))
+    }
+}
 
 /// Neural reranker using cross-encoder model
 pub struct NeuralReranker {
     reranker: TextRerank,
     model_name: String,
+    batch_size: usize,
 }
 
 impl NeuralReranker {
@@ -33,8 +124,14 @@ impl NeuralReranker {
         let model_name = model.to_string();
         info_print!("Loading reranker model: {}", model_name);
 
+        let cache_root = crate::embed::cache_root();
+        if crate::embed::is_offline() {
+            ensure_cached(&model, &cache_root)?;
+        }
+
         let mut options = RerankInitOptions::default();
         options.model_name = model;
+        options.cache_dir = cache_root;
         options.show_download_progress = true;
 
         let reranker = TextRerank::try_new(options)?;
@@ -44,9 +141,78 @@ impl NeuralReranker {
         Ok(Self {
             reranker,
             model_name,
+            batch_size: DEFAULT_RERANK_BATCH_SIZE,
         })
     }
 
+    /// Create a neural reranker from a local ONNX cross-encoder
+    ///
+    /// Fails if the files are missing or the model doesn't produce one finite
+    /// score per document.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let files = RerankModelFiles::resolve(path)?;
+        let model_name = files.model_name();
+        let onnx_name = files.onnx.display().to_string();
+        info_print!("Loading reranker model: {}", onnx_name);
+
+        let tokenizer_files = TokenizerFiles {
+            tokenizer_file: files.read_tokenizer_file("tokenizer.json")?,
+            config_file: files.read_tokenizer_file("config.json")?,
+            special_tokens_map_file: files.read_tokenizer_file("special_tokens_map.json")?,
+            tokenizer_config_file: files.read_tokenizer_file("tokenizer_config.json")?,
+        };
+        let onnx_file = std::fs::read(&files.onnx)
+            .with_context(|| format!("Failed to read {}", files.onnx.display()))?;
+        let model = UserDefinedRerankingModel::new(onnx_file, tokenizer_files);
+        let reranker =
+            TextRerank::try_new_from_user_defined(model, RerankInitOptionsUserDefined::default())
+                .map_err(|e| anyhow!("Failed to initialize reranker {}: {}", onnx_name, e))?;
+
+        let mut reranker = Self {
+            reranker,
+            model_name,
+            batch_size: DEFAULT_RERANK_BATCH_SIZE,
+        };
+        let probe = reranker.rerank(
+            "query",
+            &["first document".to_string(), "second document".to_string()],
+        )?;
+        if probe.len() != 2 || probe.iter().any(|(_, score)| !score.is_finite()) {
+            bail!(
+                "Reranker {} does not produce one scalar score per document",
+                onnx_name
+            );
+        }
+
+        info_print!("Reranker model loaded successfully!");
+        Ok(reranker)
+    }
+
+    /// Load the reranker at `custom_path` (if any), falling back to the
+    /// built-in model when it can't be loaded
+    pub fn load(custom_path: Option<&Path>) -> Result<Self> {
+        with_fallback(custom_path, Self::from_path, Self::new)
+    }
+
+    /// Name of the model `load(custom_path)` is expected to use, without
+    /// loading it
+    ///
+    /// A custom model that resolves but then fails to load falls back to the
+    /// built-in one, so compare with [`Self::model_name`] of the loaded model.
+    pub fn model_name_for(custom_path: Option<&Path>) -> String {
+        custom_path
+            .and_then(|path| RerankModelFiles::resolve(path).ok())
+            .map(|files| files.model_name())
+            .unwrap_or_else(|| RerankerModel::JINARerankerV1TurboEn.to_string())
+    }
+
+    /// Score at most `batch_size` documents per model call, bounding memory
+    /// for large `--rerank-top` values (clamped to at least 1)
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
     /// Get the model name
     pub fn model_name(&self) -> &str {
         &self.model_name
@@ -60,27 +226,34 @@ impl NeuralReranker {
             return Ok(vec![]);
         }
 
-        // Convert to &str references for fastembed API
-        let doc_refs: Vec<&str> = documents.iter().map(|s| s.as_str()).collect();
-
-        // Rerank using the cross-encoder
-        let results = self.reranker.rerank(
-            query, doc_refs, false, // Don't return documents (we have them)
-            None,  // Use default batch size
-        )?;
-
-        // Convert to (index, score) pairs
-        Ok(results.into_iter().map(|r| (r.index, r.score)).collect())
+        let reranker = &mut self.reranker;
+        score_in_batches(documents, self.batch_size, |batch| {
+            // Convert to &str references for fastembed API
+            let doc_refs: Vec<&str> = batch.iter().map(|s| s.as_str()).collect();
+
+            // Rerank using the cross-encoder
+            let results = reranker.rerank(
+                query,
+                doc_refs,
+                false, // Don't return documents (we have them)
+                Some(batch.len()),
+            )?;
+
+            // Convert to (index, score) pairs
+            Ok(results.into_iter().map(|r| (r.index, r.score)).collect())
+        })
     }
 
     /// Rerank and blend scores with existing RRF scores
     ///
-    /// Uses weighted blending: final_score = RERANK_WEIGHT * rerank_score + RRF_WEIGHT * rrf_score
+    /// Uses weighted blending: final_score = rerank_weight * rerank_score + (1 - rerank_weight) * rrf_score.
+    /// `rerank_weight` is clamped to [0, 1]: 0.0 keeps the RRF order, 1.0 uses the reranker order.
     pub fn rerank_and_blend(
         &mut self,
         query: &str,
         documents: &[String],
         rrf_scores: &[f32],
+        rerank_weight: f32,
     ) -> Result<Vec<(usize, f32)>> {
         if documents.is_empty() {
             return Ok(vec![]);
@@ -97,32 +270,109 @@ impl NeuralReranker {
         // Get rerank scores
         let rerank_results = self.rerank(query, documents)?;
 
-        // Normalize rerank scores to [0, 1] using sigmoid (scores can be negative)
-        let normalized: Vec<(usize, f32)> = rerank_results
-            .iter()
-            .map(|(idx, score)| (*idx, sigmoid(*score)))
-            .collect();
+        Ok(blend_scores(&rerank_results, rrf_scores, rerank_weight))
+    }
+}
 
-        // Normalize RRF scores to [0, 1] (they're already positive, just need min-max)
-        let rrf_min = rrf_scores.iter().cloned().fold(f32::INFINITY, f32::min);
-        let rrf_max = rrf_scores.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
-        let rrf_range = (rrf_max - rrf_min).max(0.0001); // Avoid division by zero
+/// Score `documents` in mini-batches of `batch_size`
+///
+/// `score_batch` returns (index within the batch, score) pairs. Returns
+/// (original_index, score) for every document, sorted by score descending.
+fn score_in_batches(
+    documents: &[String],
+    batch_size: usize,
+    mut score_batch: impl FnMut(&[String]) -> Result<Vec<(usize, f32)>>,
+) -> Result<Vec<(usize, f32)>> {
+    let batch_size = batch_size.max(1);
+    let mut scores = Vec::with_capacity(documents.len());
+    for (batch_index, batch) in documents.chunks(batch_size).enumerate() {
+        let offset = batch_index * batch_size;
+        scores.extend(
+            score_batch(batch)?
+                .into_iter()
+                .map(|(idx, score)| (offset + idx, score)),
+        );
+    }
 
-        // Blend scores
-        let mut blended: Vec<(usize, f32)> = normalized
-            .into_iter()
-            .map(|(idx, rerank_norm)| {
-                let rrf_norm = (rrf_scores[idx] - rrf_min) / rrf_range;
-                let blended_score = RERANK_WEIGHT * rerank_norm + RRF_WEIGHT * rrf_norm;
-                (idx, blended_score)
-            })
-            .collect();
+    scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(scores)
+}
 
-        // Sort by blended score descending
-        blended.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+/// Fail with the files loading `model` would have to download (offline mode)
+fn ensure_cached(model: &RerankerModel, cache_root: &Path) -> Result<()> {
+    let info = TextRerank::list_supported_models()
+        .into_iter()
+        .find(|info| &info.model == model)
+        .ok_or_else(|| anyhow!("Unknown reranker model: {}", model))?;
+    let files = [&[info.model_file.as_str()][..], &TOKENIZER_FILES].concat();
+    let (dir, missing) = crate::embed::missing_snapshot_files(cache_root, &info.model_code, &files);
+    if missing.is_empty() {
+        return Ok(());
+    }
+    bail!(
+        "Offline mode: reranker model '{}' is not in the local cache.\n  \
+         Expected in: {}\n  \
+         Missing: {}\n\
+         Run a `--rerank` search on a machine with network access and copy its cache \
+         directory over, or point --rerank-model-path at a local copy.",
+        model,
+        dir.display(),
+        missing.join(", ")
+    )
+}
 
-        Ok(blended)
+/// Try `load_custom` on `custom_path`, warning and using `load_default` if it fails
+fn with_fallback<T>(
+    custom_path: Option<&Path>,
+    load_custom: impl FnOnce(&Path) -> Result<T>,
+    load_default: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    if let Some(path) = custom_path {
+        match load_custom(path) {
+            Ok(reranker) => return Ok(reranker),
+            Err(e) => eprintln!(
+                "Warning: Could not load reranker from {}: {:#}; using the built-in model",
+                path.display(),
+                e
+            ),
+        }
     }
+    load_default()
+}
+
+/// Blend raw reranker scores with RRF scores
+///
+/// `rerank_results` are (original_index, raw_rerank_score) pairs; `rrf_scores`
+/// is indexed by original_index. Returns (original_index, blended_score)
+/// sorted by blended score descending.
+pub(crate) fn blend_scores(
+    rerank_results: &[(usize, f32)],
+    rrf_scores: &[f32],
+    rerank_weight: f32,
+) -> Vec<(usize, f32)> {
+    let rerank_weight = rerank_weight.clamp(0.0, 1.0);
+    let rrf_weight = 1.0 - rerank_weight;
+
+    // Normalize RRF scores to [0, 1] (they're already positive, just need min-max)
+    let rrf_min = rrf_scores.iter().cloned().fold(f32::INFINITY, f32::min);
+    let rrf_max = rrf_scores.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let rrf_range = (rrf_max - rrf_min).max(0.0001); // Avoid division by zero
+
+    // Normalize rerank scores to [0, 1] using sigmoid (scores can be negative) and blend
+    let mut blended: Vec<(usize, f32)> = rerank_results
+        .iter()
+        .map(|(idx, score)| {
+            let rerank_norm = sigmoid(*score);
+            let rrf_norm = (rrf_scores[*idx] - rrf_min) / rrf_range;
+            let blended_score = rerank_weight * rerank_norm + rrf_weight * rrf_norm;
+            (*idx, blended_score)
+        })
+        .collect();
+
+    // Sort by blended score descending
+    blended.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    blended
 }
 
 /// Sigmoid function to normalize scores to [0, 1]
@@ -141,6 +391,164 @@ mod tests {
         assert!(sigmoid(-10.0) < 0.01);
     }
 
+    // Reranker prefers documents in reverse RRF order
+    fn conflicting_scores() -> (Vec<(usize, f32)>, Vec<f32>) {
+        let rerank = vec![(2, 3.0), (1, 0.5), (0, -2.0)];
+        let rrf = vec![0.05, 0.03, 0.01];
+        (rerank, rrf)
+    }
+
+    #[test]
+    fn test_blend_weight_zero_preserves_rrf_order() {
+        let (rerank, rrf) = conflicting_scores();
+        let order: Vec<usize> = blend_scores(&rerank, &rrf, 0.0)
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(order, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_blend_weight_one_uses_reranker_order() {
+        let (rerank, rrf) = conflicting_scores();
+        let order: Vec<usize> = blend_scores(&rerank, &rrf, 1.0)
+            .into_iter()
+            .map(|(idx, _)| idx)
+            .collect();
+        assert_eq!(order, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_blend_weight_is_clamped() {
+        let (rerank, rrf) = conflicting_scores();
+        assert_eq!(
+            blend_scores(&rerank, &rrf, 5.0),
+            blend_scores(&rerank, &rrf, 1.0)
+        );
+        assert_eq!(
+            blend_scores(&rerank, &rrf, -1.0),
+            blend_scores(&rerank, &rrf, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_batched_scores_match_single_batch() {
+        let documents: Vec<String> = [
+            "fn authenticate(user: &str) -> bool",
+            "fn add(a: i32, b: i32) -> i32",
+            "struct Session { token: String }",
+            "impl Drop for Session",
+            "fn login(credentials: Credentials) -> Result<Token>",
+        ]
+        .iter()
+        .map(|d| d.to_string())
+        .collect();
+        // Stand-in cross-encoder: scores depend only on the document
+        let score = |batch: &[String]| -> Result<Vec<(usize, f32)>> {
+            Ok(batch
+                .iter()
+                .enumerate()
+                .map(|(i, doc)| (i, doc.len() as f32 / 10.0 - 3.0))
+                .collect())
+        };
+
+        let single = score_in_batches(&documents, documents.len(), score).unwrap();
+        assert_eq!(single.len(), documents.len());
+        for batch_size in [1, 2, 3, 100] {
+            let mut calls = 0;
+            let batched = score_in_batches(&documents, batch_size, |batch| {
+                assert!(batch.len() <= batch_size);
+                calls += 1;
+                score(batch)
+            })
+            .unwrap();
+            assert_eq!(batched, single, "batch size {}", batch_size);
+            assert_eq!(calls, documents.len().div_ceil(batch_size));
+        }
+    }
+
+    /// Write a Hugging Face style export: `onnx/model.onnx` plus tokenizer files
+    fn write_export(dir: &Path) {
+        std::fs::create_dir_all(dir.join("onnx")).unwrap();
+        std::fs::write(dir.join("onnx/model.onnx"), b"onnx").unwrap();
+        for file in TOKENIZER_FILES {
+            std::fs::write(dir.join(file), b"{}").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_resolve_rerank_model_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write_export(dir.path());
+        let expected = RerankModelFiles {
+            onnx: dir.path().join("onnx/model.onnx"),
+            tokenizer_dir: dir.path().to_path_buf(),
+        };
+
+        // The export directory or the model file itself
+        assert_eq!(RerankModelFiles::resolve(dir.path()).unwrap(), expected);
+        assert_eq!(
+            RerankModelFiles::resolve(&dir.path().join("onnx/model.onnx")).unwrap(),
+            expected
+        );
+
+        // A flat directory keeps everything next to model.onnx
+        let flat = tempfile::tempdir().unwrap();
+        std::fs::write(flat.path().join("model.onnx"), b"onnx").unwrap();
+        for file in TOKENIZER_FILES {
+            std::fs::write(flat.path().join(file), b"{}").unwrap();
+        }
+        let files = RerankModelFiles::resolve(flat.path()).unwrap();
+        assert_eq!(files.tokenizer_dir, flat.path());
+
+        std::fs::remove_file(dir.path().join("special_tokens_map.json")).unwrap();
+        let err = RerankModelFiles::resolve(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("special_tokens_map.json"));
+
+        let err = RerankModelFiles::resolve(&dir.path().join("missing")).unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+    }
+
+    #[test]
+    fn test_replacing_the_model_file_changes_its_name() {
+        let dir = tempfile::tempdir().unwrap();
+        write_export(dir.path());
+        let before = NeuralReranker::model_name_for(Some(dir.path()));
+        assert!(before.starts_with(&dir.path().join("onnx/model.onnx").display().to_string()));
+        assert_eq!(NeuralReranker::model_name_for(Some(dir.path())), before);
+
+        std::fs::write(dir.path().join("onnx/model.onnx"), b"retrained onnx").unwrap();
+        assert_ne!(NeuralReranker::model_name_for(Some(dir.path())), before);
+    }
+
+    #[test]
+    fn test_offline_errors_when_reranker_not_cached() {
+        let cache = tempfile::tempdir().unwrap();
+        let err = ensure_cached(&RerankerModel::JINARerankerV1TurboEn, cache.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Offline mode"), "{}", err);
+        assert!(err.contains("refs/main"), "{}", err);
+    }
+
+    #[test]
+    fn test_custom_reranker_falls_back_to_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        let custom = |path: &Path| RerankModelFiles::resolve(path).map(|_| "custom");
+        let builtin = || Ok("builtin");
+
+        assert_eq!(with_fallback(None, custom, builtin).unwrap(), "builtin");
+        assert_eq!(
+            with_fallback(Some(dir.path()), custom, builtin).unwrap(),
+            "builtin"
+        );
+        write_export(dir.path());
+        assert_eq!(
+            with_fallback(Some(dir.path()), custom, builtin).unwrap(),
+            "custom"
+        );
+    }
+
     #[test]
     #[ignore] // Requires model download
     fn test_reranker_creation() {
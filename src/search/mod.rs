@@ -1,16 +1,24 @@
 use anyhow::Result;
 use colored::Colorize;
 use serde::Serialize;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
+use tantivy::tokenizer::{TokenStream, Tokenizer};
 
 use crate::cache::FileMetaStore;
-use crate::chunker::SemanticChunker;
-use crate::embed::{EmbeddingService, ModelType};
-use crate::file::FileWalker;
-use crate::fts::FtsStore;
-use crate::index::get_local_search_db_path;
-use crate::rerank::{rrf_fusion, vector_only, FusedResult, NeuralReranker};
+use crate::chunker::{Chunk, ChunkKind, SemanticChunker};
+use crate::database::{DatabaseManager, DatabaseScope, DatabaseType};
+use crate::embed::{l2_normalize, EmbeddedChunk, EmbeddingService, ModelType};
+use crate::error::DemongrepError;
+use crate::file::Language;
+use crate::fts::{CodeTokenizer, FtsStore};
+use crate::index::{
+    canonical_project_path, files_changed_since_index, get_search_db_paths, is_local_db_path,
+};
+use crate::rerank::{
+    blend_scores, rrf_fusion, vector_only, FusedResult, NeuralReranker, RerankScoreCache,
+};
 use crate::vectordb::VectorStore;
 
 /// JSON output format for search results
@@ -22,6 +30,41 @@ struct JsonOutput {
     has_more: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     timing: Option<JsonTiming>,
+    /// Files modified since the index was built (search with --sync to update it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale_files: Option<usize>,
+}
+
+/// `--count` output: the results a search would list and the files they're in
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct MatchCount {
+    count: usize,
+    files: usize,
+}
+
+impl MatchCount {
+    fn of(results: &[crate::vectordb::SearchResult]) -> Self {
+        let files: std::collections::HashSet<&str> =
+            results.iter().map(|r| r.path.as_str()).collect();
+        Self {
+            count: results.len(),
+            files: files.len(),
+        }
+    }
+}
+
+impl std::fmt::Display for MatchCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        write!(
+            f,
+            "{} result{} in {} file{}",
+            self.count,
+            plural(self.count),
+            self.files,
+            plural(self.files)
+        )
+    }
 }
 
 #[derive(Serialize)]
@@ -30,10 +73,15 @@ struct JsonResult {
     start_line: usize,
     end_line: usize,
     kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
     content: String,
     score: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
     signature: Option<String>,
+    /// Symbol path of the chunk, with --highlight-context
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     context_prev: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -48,6 +96,38 @@ struct JsonResult {
     fts_rank: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     rerank_score: Option<f32>,
+    /// Retrieval sources that matched the chunk ("vector", "fts")
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    sources: Vec<&'static str>,
+    /// "local" or "global" when both databases were searched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    database: Option<&'static str>,
+}
+
+impl JsonResult {
+    /// Score breakdown fields are only included with --explain
+    fn new(r: &crate::vectordb::SearchResult, explain: bool) -> Self {
+        Self {
+            path: r.path.clone(),
+            start_line: r.start_line,
+            end_line: r.end_line,
+            kind: r.kind.clone(),
+            language: r.language.clone(),
+            content: r.content.clone(),
+            score: r.score,
+            signature: r.signature.clone(),
+            scope: r.scope.clone(),
+            context_prev: r.context_prev.clone(),
+            context_next: r.context_next.clone(),
+            vector_score: if explain { r.vector_score } else { None },
+            fts_score: if explain { r.fts_score } else { None },
+            vector_rank: if explain { r.vector_rank } else { None },
+            fts_rank: if explain { r.fts_rank } else { None },
+            rerank_score: if explain { r.rerank_score } else { None },
+            sources: crate::rerank::match_sources(r.vector_rank, r.fts_rank),
+            database: r.database.map(|database| database.tag()),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -59,17 +139,366 @@ struct JsonTiming {
     rerank_ms: Option<u64>,
 }
 
-/// Read model metadata from database
-fn read_metadata(db_path: &PathBuf) -> Option<(String, usize)> {
-    let metadata_path = db_path.join("metadata.json");
-    if let Ok(content) = std::fs::read_to_string(&metadata_path) {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-            let model = json.get("model_short_name")?.as_str()?.to_string();
-            let dims = json.get("dimensions")?.as_u64()? as usize;
-            return Some((model, dims));
+/// How a result's location is rendered so terminals/editors can link to it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum OpenFormat {
+    /// `path:line` (grep-style)
+    #[default]
+    Grep,
+    /// `vscode://file/path:line`
+    VsCode,
+    /// Custom template with `{path}`, `{line}` and `{end_line}` placeholders
+    Template(String),
+}
+
+impl OpenFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "grep" | "path" => Some(OpenFormat::Grep),
+            "vscode" | "code" => Some(OpenFormat::VsCode),
+            _ if s.contains('{') => Some(OpenFormat::Template(s.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Render the location of a result spanning `start_line..=end_line`
+    pub fn render(&self, path: &str, start_line: usize, end_line: usize) -> String {
+        match self {
+            OpenFormat::Grep => format!("{}:{}", path, start_line),
+            OpenFormat::VsCode => {
+                format!(
+                    "vscode://file/{}:{}",
+                    path.trim_start_matches('/'),
+                    start_line
+                )
+            }
+            OpenFormat::Template(template) => template
+                .replace("{path}", path)
+                .replace("{end_line}", &end_line.to_string())
+                .replace("{line}", &start_line.to_string()),
+        }
+    }
+}
+
+/// Size of the snippet shown for each result without `--content`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnippetSize {
+    /// Leading lines of the chunk joined into the snippet
+    pub lines: usize,
+    /// Characters kept before truncating with `...` (0 = no limit)
+    pub max_chars: usize,
+}
+
+impl Default for SnippetSize {
+    fn default() -> Self {
+        Self {
+            lines: 3,
+            max_chars: 100,
         }
     }
-    None
+}
+
+impl SnippetSize {
+    /// First `lines` lines of `content` on one line, cut at `max_chars` characters
+    fn render(&self, content: &str) -> String {
+        let snippet = content
+            .lines()
+            .take(self.lines)
+            .collect::<Vec<_>>()
+            .join(" ");
+        if self.max_chars == 0 {
+            return snippet;
+        }
+        crate::output::truncate_chars(&snippet, self.max_chars)
+    }
+}
+
+/// Order results are listed in (`--sort`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Best match first
+    #[default]
+    Relevance,
+    /// Most recently modified file first, best match breaking ties
+    Recency,
+}
+
+impl SortOrder {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "relevance" | "score" => Some(SortOrder::Relevance),
+            "recency" | "recent" => Some(SortOrder::Recency),
+            _ => None,
+        }
+    }
+}
+
+/// Soft preference for results under a path prefix (`--boost src/core:1.5`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathBoost {
+    pub prefix: String,
+    pub factor: f32,
+}
+
+impl PathBoost {
+    /// Parse `prefix:factor`; the factor must be a non-negative number
+    pub fn parse(s: &str) -> Option<Self> {
+        let (prefix, factor) = s.rsplit_once(':')?;
+        let factor: f32 = factor.trim().parse().ok()?;
+        if prefix.is_empty() || !factor.is_finite() || factor < 0.0 {
+            return None;
+        }
+        Some(PathBoost {
+            prefix: prefix.trim_start_matches("./").to_string(),
+            factor,
+        })
+    }
+
+    /// Whether `path` (absolute, or relative to `project_root`) lies under the prefix
+    ///
+    /// Matches whole path components, so `src/core` doesn't match `src/core_utils`.
+    fn matches(&self, path: &str, project_root: &Path) -> bool {
+        let prefix = Path::new(&self.prefix);
+        let relative = Path::new(path)
+            .strip_prefix(project_root)
+            .unwrap_or_else(|_| Path::new(path.trim_start_matches("./")));
+        relative.starts_with(prefix) || Path::new(path).starts_with(prefix)
+    }
+}
+
+/// Keep results matching `--kind` and drop those of an `--exclude-kind`
+/// (exclusion wins when both name the same kind)
+fn apply_kind_filters(
+    results: &mut Vec<crate::vectordb::SearchResult>,
+    kind: Option<ChunkKind>,
+    exclude: &[ChunkKind],
+) {
+    if let Some(kind) = kind {
+        results.retain(|r| r.kind == kind.as_str());
+    }
+    if !exclude.is_empty() {
+        let excluded: HashSet<&str> = exclude.iter().map(ChunkKind::as_str).collect();
+        results.retain(|r| !excluded.contains(r.kind.as_str()));
+    }
+}
+
+/// Keep only definitions with a signature for `--definitions-only`
+fn retain_definitions(results: &mut Vec<crate::vectordb::SearchResult>) {
+    results.retain(|r| {
+        ChunkKind::from_str(&r.kind).is_some_and(|kind| kind.is_definition())
+            && r.signature.as_deref().is_some_and(|s| !s.trim().is_empty())
+    });
+}
+
+/// Whether a result is in `language` for `--language`: the language its chunk
+/// was indexed as, or its file's (notebook cells are indexed in the kernel
+/// language or as Markdown, yet `--language jupyter` should find them)
+fn matches_language(result: &crate::vectordb::SearchResult, language: Language) -> bool {
+    result.language.as_deref() == Some(language.name())
+        || Language::from_path(Path::new(&result.path)) == language
+}
+
+/// Re-sort score-ordered results for `--sort recency`
+///
+/// Results whose file has no recorded modification time sort last.
+fn sort_results(results: &mut [crate::vectordb::SearchResult], order: SortOrder) {
+    if order == SortOrder::Recency {
+        results.sort_by(|a, b| {
+            b.mtime.cmp(&a.mtime).then_with(|| {
+                b.score
+                    .partial_cmp(&a.score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+        });
+    }
+}
+
+/// Candidates retrieved from each index in `--first` mode
+const FIRST_RETRIEVAL_LIMIT: usize = 20;
+
+/// How many candidates to retrieve and how many results to return
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Retrieval {
+    max_results: usize,
+    offset: usize,
+    retrieval_limit: usize,
+    rerank: bool,
+}
+
+impl Retrieval {
+    /// `--first` trades recall for latency: the single best result, no
+    /// reranking and a small candidate pool from each index
+    fn new(
+        max_results: usize,
+        offset: usize,
+        rerank: bool,
+        vector_only: bool,
+        first: bool,
+    ) -> Self {
+        if first {
+            return Self {
+                max_results: 1,
+                offset: 0,
+                retrieval_limit: FIRST_RETRIEVAL_LIMIT,
+                rerank: false,
+            };
+        }
+        Self {
+            max_results,
+            offset,
+            retrieval_limit: if vector_only { max_results } else { 200 },
+            rerank,
+        }
+    }
+}
+
+/// Weight of the `--context-file` embedding in the blended query embedding
+const CONTEXT_FILE_WEIGHT: f32 = 0.2;
+
+/// Mean of the stored chunk embeddings of `path`, or None if it isn't indexed
+fn context_file_embedding(store: &VectorStore, path: &Path) -> Result<Option<Vec<f32>>> {
+    let Some((_, chunk_ids)) = store.file_chunk_ids(path)? else {
+        return Ok(None);
+    };
+
+    let mut sum: Option<Vec<f32>> = None;
+    let mut count = 0;
+    for id in chunk_ids {
+        let Some(embedding) = store.get_embedding(id)? else {
+            continue;
+        };
+        match sum.as_mut() {
+            Some(sum) => sum.iter_mut().zip(&embedding).for_each(|(s, e)| *s += e),
+            None => sum = Some(embedding),
+        }
+        count += 1;
+    }
+
+    Ok(sum.map(|mut sum| {
+        sum.iter_mut().for_each(|s| *s /= count as f32);
+        sum
+    }))
+}
+
+/// `(1 - weight) * query + weight * context`, L2-normalized
+///
+/// Both inputs are normalized first so a small weight keeps the query dominant
+/// regardless of the embeddings' scale.
+fn blend_context(query: &[f32], context: &[f32], weight: f32) -> Vec<f32> {
+    let mut query = query.to_vec();
+    let mut context = context.to_vec();
+    l2_normalize(&mut query);
+    l2_normalize(&mut context);
+
+    let mut blended: Vec<f32> = query
+        .iter()
+        .zip(&context)
+        .map(|(q, c)| (1.0 - weight) * q + weight * c)
+        .collect();
+    l2_normalize(&mut blended);
+    blended
+}
+
+/// Multiply the score of results matching each boost and re-sort by score
+///
+/// Boosts compound when several prefixes match the same result.
+fn apply_path_boosts(
+    results: &mut [crate::vectordb::SearchResult],
+    boosts: &[PathBoost],
+    project_root: &Path,
+) {
+    if boosts.is_empty() {
+        return;
+    }
+    for result in results.iter_mut() {
+        for boost in boosts {
+            if boost.matches(&result.path, project_root) {
+                result.score *= boost.factor;
+            }
+        }
+    }
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+/// Byte ranges of identifier segments in `text` that match a query term
+///
+/// Both sides go through `CodeTokenizer`, so `parseConfig` in the content
+/// matches a query for `config` just like the FTS index does.
+fn highlight_ranges(text: &str, terms: &HashSet<String>) -> Vec<(usize, usize)> {
+    let mut tokenizer = CodeTokenizer::default();
+    let mut stream = tokenizer.token_stream(text);
+    let mut ranges = Vec::new();
+    while stream.advance() {
+        let token = stream.token();
+        if terms.contains(&token.text) {
+            ranges.push((token.offset_from, token.offset_to));
+        }
+    }
+    ranges
+}
+
+/// Lowercased identifier segments of the query, used for highlighting
+fn query_terms(query: &str) -> HashSet<String> {
+    let mut tokenizer = CodeTokenizer::default();
+    let mut stream = tokenizer.token_stream(query);
+    let mut terms = HashSet::new();
+    while stream.advance() {
+        terms.insert(stream.token().text.clone());
+    }
+    terms
+}
+
+/// Render `text` with matched ranges passed through `mark` and the rest through `plain`
+fn render_highlighted(
+    text: &str,
+    ranges: &[(usize, usize)],
+    mark: impl Fn(&str) -> String,
+    plain: impl Fn(&str) -> String,
+) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for &(start, end) in ranges {
+        if start > last {
+            out.push_str(&plain(&text[last..start]));
+        }
+        out.push_str(&mark(&text[start..end]));
+        last = end;
+    }
+    if last < text.len() {
+        out.push_str(&plain(&text[last..]));
+    }
+    out
+}
+
+/// Dim a line of output, highlighting query terms when enabled
+///
+/// `color` is the resolved `--color` setting; without it the text is returned as is.
+fn highlight_line(text: &str, terms: Option<&HashSet<String>>, color: bool) -> String {
+    if !color {
+        return text.to_string();
+    }
+    match terms {
+        Some(terms) => render_highlighted(
+            text,
+            &highlight_ranges(text, terms),
+            |s| s.bold().bright_yellow().to_string(),
+            |s| s.dimmed().to_string(),
+        ),
+        None => text.dimmed().to_string(),
+    }
+}
+
+/// Serialize `--json` output: one line by default, indented with `--json-pretty`
+fn to_json<T: Serialize>(value: &T, pretty: bool) -> Result<String> {
+    Ok(if pretty {
+        serde_json::to_string_pretty(value)?
+    } else {
+        serde_json::to_string(value)?
+    })
 }
 
 /// Normalize a stored result path to an absolute canonical path when possible.
@@ -88,42 +517,158 @@ fn normalize_result_path(path: &str, project_root: &Path) -> String {
         .to_string()
 }
 
+/// `search`'s output, or a "timed out after ..." error if it takes longer
+/// than `timeout`
+///
+/// `search` is dropped on timeout, which cancels it at its next `.await`.
+/// Blocking work already running (loading the model, embedding the query
+/// under the embedding service lock, reranking) can't be interrupted: it runs
+/// to completion in the background and its result is discarded. A search
+/// that blocks inline also stalls the timer, so spawn it first.
+pub(crate) async fn with_timeout<F: std::future::Future>(
+    timeout: Duration,
+    search: F,
+) -> Result<F::Output, String> {
+    tokio::time::timeout(timeout, search)
+        .await
+        .map_err(|_| format!("timed out after {:?}", timeout))
+}
+
+/// Reject a query with nothing to search for
+///
+/// An empty embedding is about equally close to every chunk, so its nearest
+/// neighbors are meaningless.
+pub(crate) fn validate_query(query: &str) -> Result<(), DemongrepError> {
+    if query.trim().is_empty() {
+        return Err(DemongrepError::SearchError {
+            reason: "the query is empty; give some text to search for".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Flags of a `demongrep search` run, besides the query and project path
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    // Paging and output
+    pub offset: usize,
+    pub max_results: usize,
+    /// Results kept per file (0 = unlimited)
+    pub per_file: usize,
+    pub content: bool,
+    pub scores: bool,
+    pub compact: bool,
+    pub json: bool,
+    pub json_pretty: bool,
+    pub highlight_context: bool,
+    pub explain: bool,
+    pub open_format: OpenFormat,
+    pub highlight: bool,
+    pub context_file: Option<PathBuf>,
+    pub first: bool,
+    pub sort: SortOrder,
+    pub snippet: SnippetSize,
+    pub count: bool,
+
+    // Index freshness
+    pub sync: bool,
+    pub sync_warn: bool,
+
+    // Which databases and chunks are searched
+    pub scope: DatabaseScope,
+    pub filter_path: Option<String>,
+    pub kind_filter: Option<ChunkKind>,
+    pub exclude_kinds: Vec<ChunkKind>,
+    pub definitions_only: bool,
+    pub language_filter: Option<Language>,
+
+    // Retrieval and ranking
+    pub model_override: Option<ModelType>,
+    pub vector_only_mode: bool,
+    pub rrf_k: f32,
+    pub rerank: bool,
+    pub rerank_top: usize,
+    pub rerank_weight: f32,
+    pub rerank_model_path: Option<PathBuf>,
+    pub rerank_batch_size: usize,
+    pub rerank_cache_only: bool,
+    pub boosts: Vec<PathBoost>,
+    pub code_mode: bool,
+    pub verbatim: bool,
+}
+
 /// Search the codebase (local database only)
-#[allow(clippy::too_many_arguments)]
-pub async fn search(
-    query: &str,
-    offset: usize,
-    max_results: usize,
-    per_file: usize,
-    content: bool,
-    scores: bool,
-    compact: bool,
-    sync: bool,
-    json: bool,
-    path: Option<PathBuf>,
-    filter_path: Option<String>,
-    model_override: Option<ModelType>,
-    vector_only_mode: bool,
-    rrf_k: f32,
-    rerank: bool,
-    rerank_top: usize,
-    kind_filter: Option<String>,
-    code_mode: bool,
-    explain: bool,
-) -> Result<()> {
+pub async fn search(query: &str, path: Option<PathBuf>, options: SearchOptions) -> Result<()> {
+    let SearchOptions {
+        offset,
+        max_results,
+        per_file,
+        content,
+        scores,
+        compact,
+        json,
+        json_pretty,
+        highlight_context,
+        explain,
+        open_format,
+        highlight,
+        context_file,
+        first,
+        sort,
+        snippet,
+        count,
+        sync,
+        sync_warn,
+        scope,
+        filter_path,
+        kind_filter,
+        exclude_kinds,
+        definitions_only,
+        language_filter,
+        model_override,
+        vector_only_mode,
+        rrf_k,
+        rerank,
+        rerank_top,
+        rerank_weight,
+        rerank_model_path,
+        rerank_batch_size,
+        rerank_cache_only,
+        boosts,
+        code_mode,
+        verbatim,
+    } = options;
+    validate_query(query)?;
+
     // --explain implies --scores
     let scores = scores || explain;
-
-    // Use local project database only
-    let db_path = get_local_search_db_path(path.clone())?;
-    let project_root = path
-        .clone()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .canonicalize()?;
-
-    let Some(db_path) = db_path else {
+    // Progress messages would get in the way of machine-readable output
+    let progress = !json && !count;
+
+    // Highlight matched query terms unless disabled (--no-highlight, --color never or NO_COLOR)
+    let highlight_terms = (highlight && crate::output::color_enabled()).then(|| query_terms(query));
+
+    // The project's local and/or global database, local first
+    let db_paths: Vec<PathBuf> = get_search_db_paths(path.clone())?
+        .into_iter()
+        .filter(|db_path| scope.includes(DatabaseType::of(db_path)))
+        .collect();
+    let project_root = canonical_project_path(path.as_deref().unwrap_or(Path::new(".")))?;
+
+    let Some(db_path) = db_paths.first().cloned() else {
+        if json {
+            return Err(DemongrepError::DatabaseNotFound {
+                path: project_root.display().to_string(),
+            }
+            .into());
+        }
         println!("{}", "❌ No database found!".red());
-        println!("   Run {} first", "demongrep index".bright_cyan());
+        match scope {
+            DatabaseScope::Global => {
+                println!("   Run {} first", "demongrep index --global".bright_cyan())
+            }
+            _ => println!("   Run {} first", "demongrep index".bright_cyan()),
+        }
         return Ok(());
     };
 
@@ -134,122 +679,221 @@ pub async fn search(
     let mut total_load_duration = Duration::ZERO;
     let model_load_duration: Duration;
 
-    // We'll use the first database's model/dimensions, or override
-    let (model_type, dimensions) = if let Some(override_model) = model_override {
-        (override_model, override_model.dimensions())
-    } else if let Some((model_name, dims)) = read_metadata(&db_path) {
-        if let Some(mt) = ModelType::from_str(&model_name) {
-            (mt, dims)
-        } else {
+    let project_config = crate::config::ProjectConfig::load(Some(&project_root));
+    if let (Some(requested), Ok(Some(pinned))) =
+        (model_override, crate::index::pinned_model(&project_config))
+    {
+        if requested != pinned {
             eprintln!(
                 "{}",
-                "⚠️  Unknown model in metadata, using default".yellow()
+                format!(
+                    "⚠️  --model {} differs from the model pinned in .demongrep.toml ({}); \
+                     results will not match the index",
+                    requested.short_name(),
+                    pinned.short_name()
+                )
+                .red()
+                .bold()
             );
-            let default_model = ModelType::default();
-            (default_model, default_model.dimensions())
         }
+    }
+
+    // We'll use the first database's model/dimensions, or override
+    let (model_type, dimensions) = if let Some(override_model) = model_override {
+        (override_model, override_model.dimensions())
     } else {
-        let default_model = ModelType::default();
-        (default_model, default_model.dimensions())
+        match EmbeddingService::index_model(&db_path.join("metadata.json")) {
+            Ok(model) => model,
+            Err(e) => {
+                if matches!(e, DemongrepError::UnknownModel { .. }) {
+                    eprintln!(
+                        "{}",
+                        "⚠️  Unknown model in metadata, using default".yellow()
+                    );
+                }
+                let default_model = ModelType::default();
+                (default_model, default_model.dimensions())
+            }
+        }
     };
 
     // Initialize embedding service once (shared across all databases)
     // Use persistent disk cache for faster re-indexing
     let start = Instant::now();
-    let mut embedding_service = EmbeddingService::with_model_and_db(model_type, &db_path)?;
+    let mut embedding_service = EmbeddingService::from_config(
+        &project_config.embedding,
+        model_type,
+        Some(&db_path),
+        dimensions,
+    )?;
     model_load_duration = start.elapsed();
 
-    // Embed query once (code mode uses passage embedding for code-to-code search)
+    // Embed query once (code mode uses passage embedding for code-to-code search,
+    // verbatim mode embeds the raw text with no model-specific formatting)
     let start = Instant::now();
-    let query_embedding = if code_mode {
+    let query_embedding = if verbatim {
+        embedding_service.embed_query_verbatim(query)?
+    } else if code_mode {
         embedding_service.embed_code_snippet(query)?
     } else {
         embedding_service.embed_query(query)?
     };
     total_embed_duration = start.elapsed();
 
-    // Perform sync if requested
-    if sync {
-        if !json {
+    // Perform sync if requested (only the local database tracks project files)
+    if sync && is_local_db_path(&db_path) {
+        if progress {
             println!("{}", "🔄 Syncing local database...".yellow());
         }
         sync_database(&db_path, &project_root, model_type)?;
     }
 
-    // Load local database
-    let start = Instant::now();
-    let mut store = VectorStore::new(&db_path, dimensions)?;
-    let stats = store.stats()?;
-    if stats.total_chunks == 0 {
-        return Ok(());
-    }
-    if !stats.indexed {
-        if !json {
-            println!(
-                "{}",
-                "⚠️  Vector index missing, rebuilding automatically...".yellow()
-            );
+    // Files edited since the index was built are missing from the results
+    let stale_files = if sync || !sync_warn {
+        0
+    } else {
+        files_changed_since_index(&db_path).unwrap_or(0)
+    };
+
+    // Indexes built with --no-fts have no full-text index to fuse with
+    let all_vector_only = vector_only_mode
+        || db_paths
+            .iter()
+            .all(|db_path| !DatabaseManager::index_metadata(db_path).fts);
+
+    let Retrieval {
+        max_results,
+        offset,
+        retrieval_limit,
+        rerank,
+    } = Retrieval::new(max_results, offset, rerank, all_vector_only, first);
+
+    // Results are only tagged with their database when local and global are merged
+    let tag_databases = db_paths.len() > 1;
+    for (i, db_path) in db_paths.iter().enumerate() {
+        // The first database picks the model; others built with a different
+        // one can't be searched with the same query embedding
+        if i > 0 {
+            if let Some(mismatch) = model_mismatch(db_path, model_type, dimensions) {
+                if progress {
+                    eprintln!("{}", format!("⚠️  {}", mismatch).yellow());
+                }
+                continue;
+            }
         }
-        store.build_index()?;
-    }
-    total_load_duration += start.elapsed();
+        let start = Instant::now();
+        let mut store = VectorStore::open_readonly(db_path, dimensions)?;
+        let stats = store.stats()?;
+        if stats.total_chunks == 0 {
+            continue;
+        }
+        if !stats.indexed {
+            if progress {
+                println!(
+                    "{}",
+                    "⚠️  Vector index missing, rebuilding automatically...".yellow()
+                );
+            }
+            // Rebuilding needs the write lock; reopen writable just for this
+            drop(store);
+            let mut writable = VectorStore::new(db_path, dimensions)?;
+            writable.build_index()?;
+            drop(writable);
+            store = VectorStore::open_readonly(db_path, dimensions)?;
+        }
+        total_load_duration += start.elapsed();
+
+        // Nudge vector results toward code related to the --context-file
+        let query_embedding = match &context_file {
+            Some(context_file) => match context_file_embedding(&store, context_file)? {
+                Some(context) => blend_context(&query_embedding, &context, CONTEXT_FILE_WEIGHT),
+                None => {
+                    if progress {
+                        eprintln!(
+                            "{}",
+                            format!(
+                                "⚠️  Context file {} is not indexed in the {} database, ignoring it",
+                                context_file.display(),
+                                DatabaseType::of(db_path).name().to_lowercase()
+                            )
+                            .yellow()
+                        );
+                    }
+                    query_embedding.clone()
+                }
+            },
+            None => query_embedding.clone(),
+        };
 
-    // Search local database
-    let start = Instant::now();
-    let retrieval_limit = if vector_only_mode { max_results } else { 200 };
-    let vector_results = store.search(&query_embedding, retrieval_limit)?;
+        let vector_only_mode = vector_only_mode || !DatabaseManager::index_metadata(db_path).fts;
 
-    let fused_results: Vec<FusedResult> = if vector_only_mode {
-        vector_only(&vector_results)
-    } else {
-        match FtsStore::open_readonly(&db_path) {
-            Ok(fts_store) => {
-                let fts_results = fts_store.search(query, retrieval_limit)?;
-                rrf_fusion(&vector_results, &fts_results, rrf_k)
-            }
-            Err(_) => {
-                if !json {
-                    eprintln!(
-                        "{}",
-                        "⚠️  FTS index not found, using vector-only search".yellow()
-                    );
+        let start = Instant::now();
+        let vector_results = store.search(&query_embedding, retrieval_limit)?;
+
+        let fused_results: Vec<FusedResult> = if vector_only_mode {
+            vector_only(&vector_results)
+        } else {
+            match FtsStore::open_readonly(db_path) {
+                Ok(fts_store) => {
+                    let fts_results = fts_store.search(query, retrieval_limit)?;
+                    rrf_fusion(&vector_results, &fts_results, rrf_k)
+                }
+                Err(_) => {
+                    if progress {
+                        eprintln!(
+                            "{}",
+                            "⚠️  FTS index not found, using vector-only search".yellow()
+                        );
+                    }
+                    vector_only(&vector_results)
                 }
-                vector_only(&vector_results)
             }
-        }
-    };
+        };
 
-    // Map fused results back to full SearchResult
-    let chunk_id_to_result: std::collections::HashMap<u32, &crate::vectordb::SearchResult> =
-        vector_results.iter().map(|r| (r.id, r)).collect();
+        // Map fused results back to full SearchResult
+        let chunk_id_to_result: std::collections::HashMap<u32, &crate::vectordb::SearchResult> =
+            vector_results.iter().map(|r| (r.id, r)).collect();
 
-    let requested_count = max_results.saturating_add(offset);
-    let take_count = if rerank {
-        rerank_top.max(requested_count).min(fused_results.len())
-    } else {
-        requested_count.min(fused_results.len())
-    };
+        let requested_count = max_results.saturating_add(offset);
+        let take_count = if rerank {
+            rerank_top.max(requested_count).min(fused_results.len())
+        } else {
+            requested_count.min(fused_results.len())
+        };
 
-    for fused in fused_results.iter().take(take_count) {
-        if let Some(result) = chunk_id_to_result.get(&fused.chunk_id) {
-            let mut r = (*result).clone();
-            r.score = fused.rrf_score;
-            r.vector_score = fused.vector_score;
-            r.fts_score = fused.fts_score;
-            r.vector_rank = fused.vector_rank;
-            r.fts_rank = fused.fts_rank;
-            all_results.push(r);
-        } else if let Ok(Some(mut result)) = store.get_chunk_as_result(fused.chunk_id) {
-            result.score = fused.rrf_score;
-            result.vector_score = fused.vector_score;
-            result.fts_score = fused.fts_score;
-            result.vector_rank = fused.vector_rank;
-            result.fts_rank = fused.fts_rank;
-            all_results.push(result);
+        let db_results_start = all_results.len();
+        for fused in fused_results.iter().take(take_count) {
+            if let Some(result) = chunk_id_to_result.get(&fused.chunk_id) {
+                let mut r = (*result).clone();
+                r.score = fused.rrf_score;
+                r.vector_score = fused.vector_score;
+                r.fts_score = fused.fts_score;
+                r.vector_rank = fused.vector_rank;
+                r.fts_rank = fused.fts_rank;
+                all_results.push(r);
+            } else if let Ok(Some(mut result)) = store.get_chunk_as_result(fused.chunk_id) {
+                result.score = fused.rrf_score;
+                result.vector_score = fused.vector_score;
+                result.fts_score = fused.fts_score;
+                result.vector_rank = fused.vector_rank;
+                result.fts_rank = fused.fts_rank;
+                all_results.push(result);
+            }
         }
-    }
 
-    total_search_duration += start.elapsed();
+        if tag_databases {
+            for result in &mut all_results[db_results_start..] {
+                result.database = Some(DatabaseType::of(db_path));
+            }
+        }
+        if sort == SortOrder::Recency {
+            for result in &mut all_results[db_results_start..] {
+                result.mtime = store.file_mtime(&result.path)?;
+            }
+        }
+
+        total_search_duration += start.elapsed();
+    }
 
     // Deduplicate results by (path, start_line, end_line) and keep highest score
     let mut seen: std::collections::HashMap<(String, usize, usize), usize> =
@@ -282,41 +926,47 @@ pub async fn search(
     let mut rerank_duration = Duration::ZERO;
     if rerank && !results.is_empty() {
         let start = Instant::now();
-        match NeuralReranker::new() {
-            Ok(mut reranker) => {
-                let documents: Vec<String> = results.iter().map(|r| r.content.clone()).collect();
-                let rrf_scores: Vec<f32> = results.iter().map(|r| r.score).collect();
-                match reranker.rerank_and_blend(query, &documents, &rrf_scores) {
-                    Ok(reranked) => {
-                        let mut reordered: Vec<crate::vectordb::SearchResult> =
-                            Vec::with_capacity(results.len());
-                        for (idx, score) in reranked {
-                            let mut result = results[idx].clone();
-                            result.rerank_score = Some(score);
-                            result.score = score;
-                            reordered.push(result);
-                        }
-                        results = reordered;
-                        if !json {
-                            println!("{}", "✅ Neural reranking applied".green());
-                        }
-                    }
-                    Err(e) => {
-                        if !json {
-                            eprintln!("{}", format!("⚠️  Reranking failed: {}", e).yellow());
-                        }
+        let documents: Vec<String> = results.iter().map(|r| r.content.clone()).collect();
+        match rerank_scores(
+            query,
+            &documents,
+            &db_path,
+            rerank_model_path.as_deref(),
+            rerank_batch_size,
+            rerank_cache_only,
+        ) {
+            Ok(scored) => {
+                let reranked = scored.len();
+                results = apply_rerank_scores(results, &scored, rerank_weight);
+                if progress {
+                    if rerank_cache_only {
+                        println!(
+                            "{}",
+                            format!(
+                                "✅ Reranked {} of {} results from the rerank cache ({} un-reranked)",
+                                reranked,
+                                results.len(),
+                                results.len() - reranked
+                            )
+                            .green()
+                        );
+                    } else {
+                        println!("{}", "✅ Neural reranking applied".green());
                     }
                 }
             }
             Err(e) => {
-                if !json {
-                    eprintln!("{}", format!("⚠️  Could not load reranker: {}", e).yellow());
+                if progress {
+                    eprintln!("{}", format!("⚠️  Reranking failed: {:#}", e).yellow());
                 }
             }
         }
         rerank_duration = start.elapsed();
     }
 
+    // Path boosts are a soft preference, applied after fusion/reranking
+    apply_path_boosts(&mut results, &boosts, &project_root);
+
     // Filter by path if specified
     if let Some(ref filter) = filter_path {
         let filter_normalized = filter.trim_start_matches("./");
@@ -327,37 +977,46 @@ pub async fn search(
     }
 
     // Filter by chunk kind if specified
-    if let Some(ref kind) = kind_filter {
-        let kind_lower = kind.to_lowercase();
-        results.retain(|r| r.kind.to_lowercase().contains(&kind_lower));
+    apply_kind_filters(&mut results, kind_filter, &exclude_kinds);
+    if definitions_only {
+        retain_definitions(&mut results);
+    }
+
+    // Filter by source language if specified
+    if let Some(language) = language_filter {
+        results.retain(|r| matches_language(r, language));
     }
 
+    // Symbol paths are only shown with --highlight-context
+    if !highlight_context {
+        for result in &mut results {
+            result.scope = None;
+        }
+    }
+
+    sort_results(&mut results, sort);
+
     // Apply pagination after reranking and filtering
     let total_available = results.len();
     let paginated_results: Vec<crate::vectordb::SearchResult> =
         results.into_iter().skip(offset).take(max_results).collect();
     let has_more = total_available > offset + paginated_results.len();
 
+    if count {
+        let counted = MatchCount::of(&paginated_results);
+        if json {
+            println!("{}", to_json(&counted, json_pretty)?);
+        } else {
+            println!("{}", counted);
+        }
+        return Ok(());
+    }
+
     // Output results
     if json {
         let json_results: Vec<JsonResult> = paginated_results
             .iter()
-            .map(|r| JsonResult {
-                path: r.path.clone(),
-                start_line: r.start_line,
-                end_line: r.end_line,
-                kind: r.kind.clone(),
-                content: r.content.clone(),
-                score: r.score,
-                signature: r.signature.clone(),
-                context_prev: r.context_prev.clone(),
-                context_next: r.context_next.clone(),
-                vector_score: if explain { r.vector_score } else { None },
-                fts_score: if explain { r.fts_score } else { None },
-                vector_rank: if explain { r.vector_rank } else { None },
-                fts_rank: if explain { r.fts_rank } else { None },
-                rerank_score: if explain { r.rerank_score } else { None },
-            })
+            .map(|r| JsonResult::new(r, explain))
             .collect();
 
         let timing = if scores {
@@ -380,15 +1039,22 @@ pub async fn search(
             None
         };
 
+        // --first: just the single result object (or null)
+        if first {
+            println!("{}", to_json(&json_results.first(), json_pretty)?);
+            return Ok(());
+        }
+
         let output = JsonOutput {
             query: query.to_string(),
             results: json_results,
             total_available,
             has_more,
             timing,
+            stale_files: (stale_files > 0).then_some(stale_files),
         };
 
-        println!("{}", serde_json::to_string(&output)?);
+        println!("{}", to_json(&output, json_pretty)?);
         return Ok(());
     }
 
@@ -418,6 +1084,16 @@ pub async fn search(
         let shown_to = offset + paginated_results.len();
         println!("Showing {}-{} of {}", shown_from, shown_to, total_available);
     }
+    if stale_files > 0 {
+        println!(
+            "{}",
+            format!(
+                "⚠️  {} file(s) changed since the last index; add --sync to update it (--no-sync-warn to hide this)",
+                stale_files
+            )
+            .yellow()
+        );
+    }
     println!();
 
     if scores {
@@ -480,95 +1156,255 @@ pub async fn search(
             file_results.truncate(per_file);
 
             for (idx, result) in file_results.iter().enumerate() {
-                print_result(result, idx == 0, content, scores, explain)?;
+                print_result(
+                    result,
+                    idx == 0,
+                    content,
+                    scores,
+                    explain,
+                    &open_format,
+                    highlight_terms.as_ref(),
+                    snippet,
+                )?;
             }
         }
     } else {
         // Show all results
         for result in &paginated_results {
-            print_result(result, true, content, scores, explain)?;
+            print_result(
+                result,
+                true,
+                content,
+                scores,
+                explain,
+                &open_format,
+                highlight_terms.as_ref(),
+                snippet,
+            )?;
         }
     }
 
     Ok(())
 }
 
+/// Raw reranker scores for `documents`, reusing scores cached under `db_path`
+///
+/// The reranker is only loaded for documents without a cached score; with
+/// `cache_only` those are left unscored instead and it's never loaded.
+fn rerank_scores(
+    query: &str,
+    documents: &[String],
+    db_path: &Path,
+    model_path: Option<&Path>,
+    batch_size: usize,
+    cache_only: bool,
+) -> Result<Vec<(usize, f32)>> {
+    let score = |documents: &[String]| {
+        let mut reranker = NeuralReranker::load(model_path)
+            .map_err(|e| e.context("Could not load reranker"))?
+            .with_batch_size(batch_size);
+        let scores = reranker.rerank(query, documents)?;
+        Ok((reranker.model_name().to_string(), scores))
+    };
+    match RerankScoreCache::new(db_path, &NeuralReranker::model_name_for(model_path)) {
+        Ok(cache) => cache.scores(query, documents, cache_only, score),
+        Err(e) if cache_only => Err(e.context("Could not open the rerank cache")),
+        Err(_) => score(documents).map(|(_, scores)| scores),
+    }
+}
+
+/// Reorder results by their reranker scores blended with the fusion scores
+///
+/// `scored` holds (index, raw score) pairs. Results without a score keep
+/// their fusion score and no `rerank_score`, and follow the reranked ones in
+/// their original order.
+fn apply_rerank_scores(
+    results: Vec<crate::vectordb::SearchResult>,
+    scored: &[(usize, f32)],
+    rerank_weight: f32,
+) -> Vec<crate::vectordb::SearchResult> {
+    let rrf_scores: Vec<f32> = results.iter().map(|r| r.score).collect();
+    let mut reranked: Vec<crate::vectordb::SearchResult> = Vec::with_capacity(results.len());
+    for (idx, score) in blend_scores(scored, &rrf_scores, rerank_weight) {
+        let mut result = results[idx].clone();
+        result.rerank_score = Some(score);
+        result.score = score;
+        reranked.push(result);
+    }
+    let scored: HashSet<usize> = scored.iter().map(|&(idx, _)| idx).collect();
+    reranked.extend(
+        results
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, _)| !scored.contains(idx))
+            .map(|(_, result)| result),
+    );
+    reranked
+}
+
+/// Why `db_path` can't be searched with `model_type` at `dimensions`, if its
+/// metadata records a different model or size
+fn model_mismatch(db_path: &Path, model_type: ModelType, dimensions: usize) -> Option<String> {
+    let (indexed, indexed_dims) =
+        EmbeddingService::index_model(&db_path.join("metadata.json")).ok()?;
+    (indexed != model_type || indexed_dims != dimensions).then(|| {
+        format!(
+            "Skipping {}: indexed with {} ({} dims), searching with {} ({} dims)",
+            db_path.display(),
+            indexed.short_name(),
+            indexed_dims,
+            model_type.short_name(),
+            dimensions
+        )
+    })
+}
+
 /// Sync database by re-indexing changed files
 pub fn sync_database(db_path: &PathBuf, project_root: &Path, model_type: ModelType) -> Result<()> {
     let project_path = project_root;
 
+    // Keep any Matryoshka truncation the index was built with
+    let dimensions = EmbeddingService::index_model(&db_path.join("metadata.json"))
+        .map(|(_, dims)| dims)
+        .ok()
+        .unwrap_or_else(|| model_type.dimensions());
+
     // Load file metadata store
     let mut file_meta =
-        FileMetaStore::load_or_create(db_path, model_type.short_name(), model_type.dimensions())?;
-
-    // Walk the file system
-    let walker = FileWalker::new(project_path.to_path_buf());
-    let (files, _stats) = walker.walk()?;
+        FileMetaStore::load_or_create(db_path, model_type.short_name(), dimensions)?;
 
     // Initialize services
-    let mut embedding_service = EmbeddingService::with_model(model_type)?;
-    let mut chunker = SemanticChunker::new(100, 2000, 10);
-    let mut store = VectorStore::new(db_path, model_type.dimensions())?;
+    let config = crate::config::ProjectConfig::load(Some(project_path));
+    let mut embedding_service =
+        EmbeddingService::from_config(&config.embedding, model_type, None, dimensions)?;
+    let index_metadata = DatabaseManager::index_metadata(db_path);
+    let mut chunker =
+        crate::index::indexing_chunker(&config.chunking).with_doc_chunks(index_metadata.docs);
+    let mut store = VectorStore::new(db_path, dimensions)?;
+
+    let summary = sync_files(
+        project_path,
+        &index_metadata.roots,
+        false,
+        &mut store,
+        &mut file_meta,
+        &mut chunker,
+        |chunks| embedding_service.embed_chunks(chunks),
+    )?;
+    if summary == SyncSummary::default() {
+        crate::info_print!("  ✅ Already up to date");
+    } else {
+        crate::info_print!("  ✅ {} file(s) synced", summary.indexed + summary.removed);
+    }
+
+    // Always persist: unchanged files may have had their mtime refreshed
+    file_meta.save(db_path)?;
+    crate::index::mark_indexed_now(db_path)?;
 
-    let mut changes = 0;
+    Ok(())
+}
+
+/// Files touched by [`sync_files`]
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct SyncSummary {
+    pub indexed: usize,
+    pub removed: usize,
+}
+
+/// Bring `store` and `file_meta` up to date with the files under `root`, or
+/// only under `roots` (relative to it) for an index built from some
+/// directories
+///
+/// Re-indexes new and changed files, handing the chunks whose content
+/// changed to `embed`, drops deleted ones and rebuilds the vector index; on
+/// an empty database this is a full index. Backs `search --sync`, the MCP
+/// `reindex` tool and `serve --reindex-on-start`, which embeds with the
+/// server's shared model.
+pub(crate) fn sync_files<F>(
+    root: &Path,
+    roots: &[PathBuf],
+    include_hidden: bool,
+    store: &mut VectorStore,
+    file_meta: &mut FileMetaStore,
+    chunker: &mut SemanticChunker,
+    mut embed: F,
+) -> Result<SyncSummary>
+where
+    F: FnMut(Vec<Chunk>) -> Result<Vec<EmbeddedChunk>>,
+{
+    let walker = crate::index::project_walker(root, roots).include_hidden(include_hidden);
+    let (files, _stats) = walker.walk()?;
+    let mut summary = SyncSummary::default();
 
     // Check for changed files
     for file in &files {
         let (needs_reindex, old_chunk_ids) = file_meta.check_file(&file.path)?;
-
         if !needs_reindex {
             continue;
         }
-
-        changes += 1;
-        crate::info_print!("  📝 {}", file.path.display());
-
-        // Delete old chunks
-        if !old_chunk_ids.is_empty() {
-            store.delete_chunks(&old_chunk_ids)?;
-        }
-
-        // Read and chunk file
         let source_code = match std::fs::read_to_string(&file.path) {
             Ok(content) => content,
             Err(_) => continue,
         };
+        crate::info_print!("  📝 {}", file.path.display());
 
-        let chunks = chunker.chunk_semantic(file.language, &file.path, &source_code)?;
-
-        if chunks.is_empty() {
-            file_meta.update_file(&file.path, vec![])?;
-            continue;
+        // Delete old chunks, keeping their embeddings for unchanged content
+        let stored = store.embeddings_by_hash(&old_chunk_ids)?;
+        if !old_chunk_ids.is_empty() {
+            store.delete_chunks(&old_chunk_ids)?;
         }
 
-        // Embed and insert
-        let embedded_chunks = embedding_service.embed_chunks(chunks)?;
-        let chunk_ids = store.insert_chunks_with_ids(embedded_chunks)?;
-        file_meta.update_file(&file.path, chunk_ids)?;
+        let chunks = chunker.chunk_semantic(file.language, &file.path, &source_code)?;
+        let chunk_ids = if chunks.is_empty() {
+            vec![]
+        } else {
+            let embedded = crate::index::embed_changed_chunks(chunks, &stored, &mut embed)?;
+            store.insert_chunks_with_ids(embedded)?
+        };
+        file_meta.update_file(&file.path, chunk_ids.clone())?;
+        store.update_file_metadata(&file.path, chunk_ids)?;
+        summary.indexed += 1;
     }
 
     // Check for deleted files
-    let deleted_files = file_meta.find_deleted_files();
-    for (path, chunk_ids) in &deleted_files {
-        changes += 1;
+    for (path, chunk_ids) in file_meta.find_deleted_files() {
         crate::info_print!("  🗑️  {} (deleted)", path);
         if !chunk_ids.is_empty() {
-            store.delete_chunks(chunk_ids)?;
+            store.delete_chunks(&chunk_ids)?;
         }
-        file_meta.remove_file(std::path::Path::new(path));
+        file_meta.remove_file(Path::new(&path));
+        store.remove_file_metadata(Path::new(&path))?;
+        summary.removed += 1;
     }
 
-    // Rebuild index if changes were made
-    if changes > 0 {
+    if summary != SyncSummary::default() || !store.is_indexed() {
         crate::info_print!("  🔨 Rebuilding index...");
         store.build_index()?;
-        file_meta.save(db_path)?;
-        crate::info_print!("  ✅ {} file(s) synced", changes);
-    } else {
-        crate::info_print!("  ✅ Already up to date");
     }
+    Ok(summary)
+}
 
-    Ok(())
+/// Location and kind line for a result (dimmed when `color` is set)
+fn location_line(
+    result: &crate::vectordb::SearchResult,
+    open_format: &OpenFormat,
+    color: bool,
+) -> String {
+    let mut line = format!(
+        "   {} • Lines {}-{} • {}",
+        open_format.render(&result.path, result.start_line, result.end_line),
+        result.start_line,
+        result.end_line,
+        result.kind
+    );
+    if let Some(database) = result.database {
+        line.push_str(&format!(" [{}]", database.tag()));
+    }
+    if color {
+        line.dimmed().to_string()
+    } else {
+        line
+    }
 }
 
 fn print_result(
@@ -577,6 +1413,9 @@ fn print_result(
     show_content: bool,
     show_scores: bool,
     explain: bool,
+    open_format: &OpenFormat,
+    highlight_terms: Option<&HashSet<String>>,
+    snippet: SnippetSize,
 ) -> Result<()> {
     if show_file {
         println!("{}", "─".repeat(60));
@@ -584,12 +1423,15 @@ fn print_result(
         println!("{}", file_display.bright_green());
     }
 
+    let color = crate::output::color_enabled();
+
     // Show location and kind
-    let location = format!(
-        "   Lines {}-{} • {}",
-        result.start_line, result.end_line, result.kind
-    );
-    println!("{}", location.dimmed());
+    println!("{}", location_line(result, open_format, color));
+
+    // Show symbol path if available (--highlight-context)
+    if let Some(scope) = &result.scope {
+        println!("   {}", scope.bright_magenta());
+    }
 
     // Show signature if available
     if let Some(sig) = &result.signature {
@@ -654,7 +1496,7 @@ fn print_result(
 
         println!("\n   {}:", "Content".bright_yellow());
         for line in result.content.lines().take(10) {
-            println!("   │ {}", line.dimmed());
+            println!("   │ {}", highlight_line(line, highlight_terms, color));
         }
         if result.content.lines().count() > 10 {
             println!("   │ {}", "...".dimmed());
@@ -669,23 +1511,452 @@ fn print_result(
         }
     } else {
         // Show a snippet
-        let snippet: String = result.content.lines().take(3).collect::<Vec<_>>().join(" ");
+        let snippet = snippet.render(&result.content);
+        println!("   {}", highlight_line(&snippet, highlight_terms, color));
+    }
 
-        let snippet = if snippet.len() > 100 {
-            // Find a valid UTF-8 boundary to avoid panic on multi-byte chars
-            let mut end = 100;
-            while end > 0 && !snippet.is_char_boundary(end) {
-                end -= 1;
-            }
-            format!("{}...", &snippet[..end])
-        } else {
-            snippet
+    println!();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_slow_search_times_out() {
+        let slow = tokio::time::sleep(Duration::from_secs(5));
+        let err = with_timeout(Duration::from_millis(20), slow)
+            .await
+            .unwrap_err();
+        assert_eq!(err, "timed out after 20ms");
+
+        // A blocking step, like a model load that never finishes, only times
+        // out on its own task
+        let blocking = tokio::spawn(async {
+            std::thread::sleep(Duration::from_millis(500));
+        });
+        assert!(with_timeout(Duration::from_millis(50), blocking)
+            .await
+            .is_err());
+
+        let fast = with_timeout(Duration::from_secs(5), async { 42 }).await;
+        assert_eq!(fast, Ok(42));
+    }
+
+    #[test]
+    fn test_empty_query_is_rejected() {
+        for query in ["", "   ", "\n\t "] {
+            let err = validate_query(query).unwrap_err();
+            assert!(matches!(err, DemongrepError::SearchError { .. }));
+            assert_eq!(err.code(), "search_failed");
+            assert!(err.to_string().contains("query is empty"), "{}", err);
+        }
+        assert!(validate_query(" where is auth handled? ").is_ok());
+    }
+
+    #[test]
+    fn test_databases_with_another_model_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let model = ModelType::default();
+        std::fs::write(
+            dir.path().join("metadata.json"),
+            format!(
+                r#"{{"model_short_name": "{}", "dimensions": {}}}"#,
+                model.short_name(),
+                model.dimensions()
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(model_mismatch(dir.path(), model, model.dimensions()), None);
+        let truncated = model_mismatch(dir.path(), model, model.dimensions() / 2).unwrap();
+        assert!(truncated.contains(&format!("{} dims", model.dimensions())));
+        let other = ModelType::AllMiniLML6V2Q;
+        if other != model {
+            assert!(model_mismatch(dir.path(), other, model.dimensions()).is_some());
+        }
+
+        // Without readable metadata there's nothing to compare
+        let empty = tempfile::tempdir().unwrap();
+        assert_eq!(model_mismatch(empty.path(), model, 1), None);
+    }
+
+    #[test]
+    fn test_count_matches_result_length() {
+        let results = vec![
+            test_result("src/auth.rs", 0.9),
+            test_result("src/auth.rs", 0.8),
+            test_result("src/session.rs", 0.7),
+        ];
+        let counted = MatchCount::of(&results);
+        assert_eq!(counted, MatchCount { count: 3, files: 2 });
+
+        let printed = counted.to_string();
+        let number: usize = printed.split_whitespace().next().unwrap().parse().unwrap();
+        assert_eq!(number, results.len());
+        assert_eq!(printed, "3 results in 2 files");
+        assert_eq!(
+            to_json(&counted, false).unwrap(),
+            r#"{"count":3,"files":2}"#
+        );
+        assert_eq!(
+            MatchCount::of(&results[..1]).to_string(),
+            "1 result in 1 file"
+        );
+    }
+
+    fn test_result(path: &str, score: f32) -> crate::vectordb::SearchResult {
+        crate::vectordb::SearchResult {
+            id: 0,
+            content: "fn main() {}".to_string(),
+            path: path.to_string(),
+            start_line: 1,
+            end_line: 3,
+            kind: "Function".to_string(),
+            language: Some("Rust".to_string()),
+            scope: None,
+            signature: None,
+            docstring: None,
+            context: None,
+            hash: String::new(),
+            distance: 0.0,
+            score,
+            context_prev: None,
+            context_next: None,
+            vector_score: None,
+            fts_score: None,
+            vector_rank: None,
+            fts_rank: None,
+            rerank_score: None,
+            mtime: None,
+            database: None,
+        }
+    }
+
+    #[test]
+    fn test_json_output_compact_by_default() {
+        let output = JsonOutput {
+            query: "main".to_string(),
+            results: vec![
+                JsonResult::new(&test_result("src/main.rs", 0.9), false),
+                JsonResult::new(&test_result("src/lib.rs", 0.5), false),
+            ],
+            total_available: 2,
+            has_more: false,
+            timing: None,
+            stale_files: None,
         };
 
-        println!("   {}", snippet.dimmed());
+        let compact = to_json(&output, false).unwrap();
+        assert!(!compact.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap()["results"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let pretty = to_json(&output, true).unwrap();
+        let results = &pretty[pretty.find("\"results\"").unwrap()..];
+        assert!(results[..results.find(']').unwrap()].contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap()
+        );
     }
 
-    println!();
+    #[test]
+    fn test_open_format_render() {
+        let path = "/repo/src/lib.rs";
 
-    Ok(())
+        assert_eq!(OpenFormat::Grep.render(path, 10, 20), "/repo/src/lib.rs:10");
+        assert_eq!(
+            OpenFormat::VsCode.render(path, 10, 20),
+            "vscode://file/repo/src/lib.rs:10"
+        );
+
+        let template = OpenFormat::parse("{path}#L{line}-L{end_line}").unwrap();
+        assert_eq!(template.render(path, 10, 20), "/repo/src/lib.rs#L10-L20");
+    }
+
+    #[test]
+    fn test_highlight_marks_matched_segments() {
+        let terms = query_terms("user config");
+        let text = "fn parseConfig(user_id: u32) -> Settings";
+
+        let ranges = highlight_ranges(text, &terms);
+        let marked = render_highlighted(text, &ranges, |s| format!("[{}]", s), String::from);
+        assert_eq!(marked, "fn parse[Config]([user]_id: u32) -> Settings");
+    }
+
+    #[test]
+    fn test_highlight_no_matches() {
+        let terms = query_terms("database");
+        let text = "let x = compute();";
+        assert!(highlight_ranges(text, &terms).is_empty());
+    }
+
+    #[test]
+    fn test_open_format_parse() {
+        assert_eq!(OpenFormat::parse("grep"), Some(OpenFormat::Grep));
+        assert_eq!(OpenFormat::parse("VSCode"), Some(OpenFormat::VsCode));
+        assert_eq!(OpenFormat::parse("emacs"), None);
+    }
+
+    #[test]
+    fn test_color_never_has_no_ansi_codes() {
+        let result = test_result("src/main.rs", 1.0);
+
+        let line = location_line(&result, &OpenFormat::Grep, false);
+        let highlighted = highlight_line("fn main() {}", Some(&query_terms("main")), false);
+
+        assert_eq!(line, "   src/main.rs:1 • Lines 1-3 • Function");
+        assert!(!line.contains('\x1b'));
+        assert_eq!(highlighted, "fn main() {}");
+    }
+
+    #[test]
+    fn test_path_boost_parse() {
+        assert_eq!(
+            PathBoost::parse("./src/core:1.5"),
+            Some(PathBoost {
+                prefix: "src/core".to_string(),
+                factor: 1.5
+            })
+        );
+        assert_eq!(PathBoost::parse("src/core"), None);
+        assert_eq!(PathBoost::parse("src:abc"), None);
+        assert_eq!(PathBoost::parse("src:-1"), None);
+        assert_eq!(PathBoost::parse(":2"), None);
+    }
+
+    #[test]
+    fn test_path_boost_promotes_lower_ranked_result() {
+        let root = Path::new("/repo");
+        let mut results = vec![
+            test_result("/repo/tests/fixtures.rs", 0.9),
+            test_result("/repo/docs/guide.md", 0.8),
+            test_result("/repo/src/core_utils/strings.rs", 0.7),
+            test_result("/repo/src/core/engine.rs", 0.6),
+        ];
+        let boosts = vec![PathBoost::parse("src/core:2").unwrap()];
+
+        apply_path_boosts(&mut results, &boosts, root);
+
+        assert_eq!(results[0].path, "/repo/src/core/engine.rs");
+        assert!((results[0].score - 1.2).abs() < 1e-6);
+        // Unmatched results keep their score and relative order
+        assert_eq!(results[1].path, "/repo/tests/fixtures.rs");
+        assert_eq!(results[2].path, "/repo/docs/guide.md");
+        // A sibling sharing the prefix's leading characters isn't boosted
+        assert_eq!(results[3].path, "/repo/src/core_utils/strings.rs");
+        assert!((results[3].score - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_snippet_truncation_is_multibyte_safe() {
+        let snippet = SnippetSize {
+            lines: 3,
+            max_chars: 10,
+        };
+        // Byte 10 falls inside "語", where a byte-based cut would panic
+        assert_eq!(
+            snippet.render("// 日本語のコメントです"),
+            "// 日本語のコメン..."
+        );
+        assert_eq!(snippet.render("let café = '🦀🦀';"), "let café =...");
+        assert_eq!(snippet.render("short"), "short");
+
+        let wide = SnippetSize {
+            lines: 2,
+            max_chars: 0,
+        };
+        assert_eq!(
+            wide.render("fn a() {\n    body();\n}"),
+            "fn a() {     body();"
+        );
+        assert_eq!(SnippetSize::default().render("a\nb\nc\nd"), "a b c");
+    }
+
+    #[test]
+    fn test_recency_sort_prefers_recently_modified_file() {
+        let with_mtime = |path: &str, mtime: Option<u64>| {
+            let mut result = test_result(path, 0.8);
+            result.mtime = mtime;
+            result
+        };
+        let mut results = vec![
+            with_mtime("/repo/src/legacy.rs", Some(1_500_000_000)),
+            with_mtime("/repo/src/unknown.rs", None),
+            with_mtime("/repo/src/fresh.rs", Some(1_700_000_000)),
+        ];
+
+        sort_results(&mut results, SortOrder::Relevance);
+        assert_eq!(results[0].path, "/repo/src/legacy.rs");
+
+        sort_results(&mut results, SortOrder::Recency);
+        let paths: Vec<&str> = results.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/repo/src/fresh.rs",
+                "/repo/src/legacy.rs",
+                "/repo/src/unknown.rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_mode_returns_one_result_without_reranking() {
+        let first = Retrieval::new(25, 10, true, false, true);
+        assert_eq!(first.max_results, 1);
+        assert_eq!(first.offset, 0);
+        assert!(!first.rerank);
+
+        let hybrid = Retrieval::new(25, 10, true, false, false);
+        assert_eq!(hybrid.max_results, 25);
+        assert_eq!(hybrid.offset, 10);
+        assert!(hybrid.rerank);
+        assert!(first.retrieval_limit < hybrid.retrieval_limit);
+
+        // The reranking flag is what gates loading the reranker
+        assert!(!Retrieval::new(25, 0, true, true, true).rerank);
+    }
+
+    #[test]
+    fn test_exclude_kind_filters_results() {
+        let with_kind = |path: &str, kind: &str| {
+            let mut result = test_result(path, 1.0);
+            result.kind = kind.to_string();
+            result
+        };
+        let all = vec![
+            with_kind("a.rs", "Function"),
+            with_kind("b.rs", "Block"),
+            with_kind("c.rs", "Other"),
+            with_kind("d.rs", "Method"),
+        ];
+        let paths = |results: &[crate::vectordb::SearchResult]| {
+            results.iter().map(|r| r.path.clone()).collect::<Vec<_>>()
+        };
+
+        let mut results = all.clone();
+        apply_kind_filters(&mut results, None, &[ChunkKind::Block, ChunkKind::Other]);
+        assert_eq!(paths(&results), vec!["a.rs", "d.rs"]);
+
+        // Composes with --kind, and exclusion wins on conflict
+        let mut results = all.clone();
+        apply_kind_filters(&mut results, Some(ChunkKind::Function), &[ChunkKind::Block]);
+        assert_eq!(paths(&results), vec!["a.rs"]);
+
+        let mut results = all;
+        apply_kind_filters(
+            &mut results,
+            Some(ChunkKind::Function),
+            &[ChunkKind::Function],
+        );
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_language_filter_matches_notebook_cells() {
+        let mut cell = test_result("analysis.ipynb", 1.0);
+        cell.language = Some("Python".to_string());
+        let rust = test_result("src/main.rs", 1.0);
+
+        assert!(matches_language(&cell, Language::Jupyter));
+        assert!(matches_language(&cell, Language::Python));
+        assert!(!matches_language(&rust, Language::Jupyter));
+        assert!(matches_language(&rust, Language::Rust));
+    }
+
+    #[test]
+    fn test_definitions_only_drops_other_chunks() {
+        let with_kind = |path: &str, kind: &str, signature: Option<&str>| {
+            let mut result = test_result(path, 1.0);
+            result.kind = kind.to_string();
+            result.signature = signature.map(str::to_string);
+            result
+        };
+        let mut results = vec![
+            with_kind("a.rs", "Function", Some("fn login(user: &str)")),
+            with_kind("b.rs", "Other", Some("login")),
+            with_kind("c.rs", "Struct", Some("pub struct Session")),
+            with_kind("d.rs", "Block", None),
+            with_kind("e.rs", "Method", Some("  ")),
+            with_kind("f.rs", "Const", Some("const TIMEOUT: u64")),
+        ];
+
+        retain_definitions(&mut results);
+        let paths: Vec<&str> = results.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.rs", "c.rs"]);
+    }
+
+    #[test]
+    fn test_context_file_promotes_related_results() {
+        use crate::chunker::{Chunk, ChunkKind};
+        use crate::embed::EmbeddedChunk;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("auth")).unwrap();
+        std::fs::create_dir_all(root.join("billing")).unwrap();
+
+        // "validate" is ambiguous: token validation in auth/, invoice
+        // validation in billing/; the latter is a slightly closer match
+        let files = [
+            ("auth/login.rs", "fn login() {}", vec![1.0, 0.0, 0.0, 0.0]),
+            (
+                "auth/token.rs",
+                "fn validate_token() {}",
+                vec![0.6, 0.8, 0.0, 0.0],
+            ),
+            (
+                "billing/invoice.rs",
+                "fn validate_invoice() {}",
+                vec![0.0, 0.8, 0.6, 0.0],
+            ),
+        ];
+        let mut store = VectorStore::new(&root.join("store"), 4).unwrap();
+        for (file, content, embedding) in &files {
+            let path = root.join(file);
+            std::fs::write(&path, content).unwrap();
+            let chunk = Chunk::new(
+                content.to_string(),
+                0,
+                1,
+                ChunkKind::Function,
+                path.to_string_lossy().to_string(),
+            );
+            let ids = store
+                .insert_chunks_with_ids(vec![EmbeddedChunk::new(chunk, embedding.clone())])
+                .unwrap();
+            store.update_file_metadata(&path, ids).unwrap();
+        }
+        store.build_index().unwrap();
+
+        let query = vec![0.0, 1.0, 0.05, 0.0];
+        let top = |embedding: &[f32]| {
+            let results = store.search(embedding, 2).unwrap();
+            results
+                .iter()
+                .map(|r| r.path.rsplit('/').next().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(top(&query), vec!["invoice.rs", "token.rs"]);
+
+        let context = context_file_embedding(&store, &root.join("auth/login.rs"))
+            .unwrap()
+            .unwrap();
+        let blended = blend_context(&query, &context, CONTEXT_FILE_WEIGHT);
+        // The auth result moves up, and the query still beats the context file itself
+        assert_eq!(top(&blended), vec!["token.rs", "invoice.rs"]);
+
+        assert!(context_file_embedding(&store, &root.join("missing.rs"))
+            .unwrap()
+            .is_none());
+    }
 }
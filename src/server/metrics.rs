@@ -0,0 +1,138 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Number of recent search latencies kept for the percentiles
+const LATENCY_WINDOW: usize = 1024;
+
+/// Weight of the newest sample in the latency moving average
+const EMA_ALPHA: f64 = 0.1;
+
+/// Request counters and search latencies of a running server
+#[derive(Default)]
+pub(super) struct ServerMetrics {
+    inner: Mutex<MetricsInner>,
+}
+
+#[derive(Default)]
+struct MetricsInner {
+    requests: u64,
+    searches: u64,
+    errors: u64,
+    /// Latencies (ms) of the most recent searches, oldest first
+    latencies: VecDeque<f64>,
+    latency_ema: Option<f64>,
+    last_reindex: Option<u64>,
+}
+
+/// Point-in-time view of [`ServerMetrics`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(super) struct MetricsSnapshot {
+    /// Requests to any endpoint, including `/metrics` itself
+    pub requests_total: u64,
+    pub searches_total: u64,
+    pub search_errors_total: u64,
+    /// Exponential moving average of search latency
+    pub search_latency_ema_ms: f64,
+    pub search_latency_p50_ms: f64,
+    pub search_latency_p95_ms: f64,
+    /// Unix time of the last incremental re-index, if any since startup
+    pub last_reindex_unix: Option<u64>,
+}
+
+impl ServerMetrics {
+    /// Record an incoming request to any endpoint
+    pub fn record_request(&self) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .requests += 1;
+    }
+
+    /// Record a completed `/search` request
+    pub fn record_search(&self, took: Duration, ok: bool) {
+        let ms = took.as_secs_f64() * 1000.0;
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.searches += 1;
+        if !ok {
+            inner.errors += 1;
+            return;
+        }
+        if inner.latencies.len() == LATENCY_WINDOW {
+            inner.latencies.pop_front();
+        }
+        inner.latencies.push_back(ms);
+        inner.latency_ema = Some(match inner.latency_ema {
+            Some(ema) => EMA_ALPHA * ms + (1.0 - EMA_ALPHA) * ema,
+            None => ms,
+        });
+    }
+
+    /// Record that the file watcher re-indexed changed files
+    pub fn record_reindex(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .last_reindex = Some(now);
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let mut sorted: Vec<f64> = inner.latencies.iter().copied().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        MetricsSnapshot {
+            requests_total: inner.requests,
+            searches_total: inner.searches,
+            search_errors_total: inner.errors,
+            search_latency_ema_ms: inner.latency_ema.unwrap_or(0.0),
+            search_latency_p50_ms: percentile(&sorted, 0.50),
+            search_latency_p95_ms: percentile(&sorted, 0.95),
+            last_reindex_unix: inner.last_reindex,
+        }
+    }
+}
+
+/// Nearest-rank percentile of ascending `sorted` values (0 when empty)
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let values: Vec<f64> = (1..=20).map(f64::from).collect();
+        assert_eq!(percentile(&values, 0.50), 10.0);
+        assert_eq!(percentile(&values, 0.95), 19.0);
+        assert_eq!(percentile(&[7.0], 0.95), 7.0);
+        assert_eq!(percentile(&[], 0.5), 0.0);
+    }
+
+    #[test]
+    fn test_latency_window_drops_oldest() {
+        let metrics = ServerMetrics::default();
+        for _ in 0..LATENCY_WINDOW {
+            metrics.record_search(Duration::from_millis(1000), true);
+        }
+        for _ in 0..LATENCY_WINDOW {
+            metrics.record_search(Duration::from_millis(2), true);
+        }
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.searches_total, 2 * LATENCY_WINDOW as u64);
+        assert_eq!(snapshot.search_latency_p95_ms, 2.0);
+        assert!(snapshot.search_latency_ema_ms < 3.0);
+    }
+}
@@ -1,28 +1,39 @@
 use anyhow::anyhow;
 use anyhow::Result;
 use axum::{
-    extract::{Json, State},
+    extract::{Json, Request, State},
     http::StatusCode,
+    middleware::{self, Next},
+    response::Response,
     routing::{get, post},
     Router,
 };
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{Mutex, RwLock};
 
-use crate::cache::FileMetaStore;
+use crate::cache::{index_state_hash, FileMetaStore, SearchResultCache};
 use crate::chunker::SemanticChunker;
-use crate::embed::{EmbeddingService, ModelType};
-use crate::file::FileWalker;
-use crate::index::get_search_db_paths;
-use crate::index::is_local_db_path;
+use crate::config::{EmbeddingProvider, ProjectConfig};
+use crate::database::{DatabaseManager, DatabaseType};
+use crate::embed::{CacheStats, EmbeddingCache, EmbeddingService, ModelType};
+use crate::index::{
+    canonical_project_path, embed_changed_chunks, get_search_db_paths, indexing_chunker,
+    project_walker,
+};
+use crate::index::{is_local_db_path, local_db_path};
+use crate::search::{sync_files, with_timeout};
 use crate::vectordb::VectorStore;
 use crate::watch::{FileEvent, FileWatcher};
 
+mod metrics;
+use metrics::{MetricsSnapshot, ServerMetrics};
+
 #[allow(dead_code)]
 /// Database entry with its metadata
 struct DatabaseEntry {
@@ -56,21 +67,48 @@ struct ServerState {
 
     /// Global database - read-only for searching
     global_store: Option<RwLock<VectorStore>>,
-    #[allow(dead_code)]
     global_db_path: Option<PathBuf>,
 
     /// Shared services
     embedding_service: Mutex<EmbeddingService>,
+    /// `embedding_service`'s in-memory cache, so `/metrics` can read its
+    /// stats while a sync holds the service
+    embedding_cache: Arc<EmbeddingCache>,
     chunker: Mutex<SemanticChunker>,
 
     /// File metadata (only for local database)
     file_meta: Option<RwLock<FileMetaStore>>,
+    /// Model name and dimensions reported by `/health` and `/status`, kept
+    /// apart from `file_meta` so they answer while a sync holds it
+    model: (String, usize),
 
     /// Project root (for file watching)
     root: PathBuf,
+
+    /// Cached search responses, keyed by request and index state
+    result_cache: SearchResultCache<SearchResponse>,
+
+    /// Search counters and latencies reported by `/metrics`
+    metrics: ServerMetrics,
+
+    /// False while `--reindex-on-start` is still syncing the index
+    ready: AtomicBool,
+
+    /// How long a search may run before `/search` answers 504
+    request_timeout: Duration,
 }
 
 impl ServerState {
+    /// Hash of the on-disk state of the databases being served
+    fn index_state(&self) -> u64 {
+        index_state_hash(
+            self.local_db_path
+                .iter()
+                .chain(self.global_db_path.iter())
+                .map(|p| p.as_path()),
+        )
+    }
+
     /// Search across all available databases
     async fn search_all(
         &self,
@@ -85,8 +123,11 @@ impl ServerState {
         if let Some(ref local_store) = self.local_store {
             let store = local_store.read().await;
             match store.search(query_embedding, retrieval_limit) {
-                Ok(mut results) => {
-                    all_results.append(&mut results);
+                Ok(results) => {
+                    all_results.extend(results.into_iter().map(|mut r| {
+                        r.database = Some(DatabaseType::Local);
+                        r
+                    }));
                 }
                 Err(e) => {
                     eprintln!("Warning: Local database search failed: {}", e);
@@ -98,8 +139,11 @@ impl ServerState {
         if let Some(ref global_store) = self.global_store {
             let store = global_store.read().await;
             match store.search(query_embedding, retrieval_limit) {
-                Ok(mut results) => {
-                    all_results.append(&mut results);
+                Ok(results) => {
+                    all_results.extend(results.into_iter().map(|mut r| {
+                        r.database = Some(DatabaseType::Global);
+                        r
+                    }));
                 }
                 Err(e) => {
                     eprintln!("Warning: Global database search failed: {}", e);
@@ -131,19 +175,20 @@ impl ServerState {
                 .partial_cmp(&a.score)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
-        let total_available = deduped_results.len();
-        let paginated_results: Vec<crate::vectordb::SearchResult> = deduped_results
-            .into_iter()
-            .skip(offset)
-            .take(limit)
-            .collect();
-        let has_more = total_available > offset + paginated_results.len();
 
-        Ok((paginated_results, total_available, has_more))
+        Ok(paginate(deduped_results, offset, limit))
     }
 
-    /// Get combined statistics
+    fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Get combined statistics (all zero until the startup sync finishes)
     async fn get_combined_stats(&self) -> CombinedStats {
+        if !self.is_ready() {
+            return CombinedStats::default();
+        }
+
         let mut total_chunks = 0;
         let mut total_files = 0;
         let mut local_chunks = 0;
@@ -182,6 +227,17 @@ impl ServerState {
     }
 }
 
+/// Take one page of ranked results
+///
+/// Returns the page, the total number of ranked results and whether more follow.
+fn paginate<T>(results: Vec<T>, offset: usize, limit: usize) -> (Vec<T>, usize, bool) {
+    let total_available = results.len();
+    let page: Vec<T> = results.into_iter().skip(offset).take(limit).collect();
+    let has_more = total_available > offset + page.len();
+    (page, total_available, has_more)
+}
+
+#[derive(Default)]
 struct CombinedStats {
     total_chunks: usize,
     total_files: usize,
@@ -194,6 +250,7 @@ struct CombinedStats {
 /// Search request body
 #[derive(Debug, Deserialize)]
 struct SearchRequest {
+    #[serde(default)]
     query: String,
     #[serde(default = "default_limit")]
     limit: usize,
@@ -201,14 +258,73 @@ struct SearchRequest {
     offset: usize,
     #[serde(default)]
     path: Option<String>,
+    /// `next_cursor` from a previous response; replaces the other fields
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+/// Opaque pagination token returned as `next_cursor`
+///
+/// Encodes the request for the next page plus the index state the first page
+/// was ranked against, so a cursor is rejected once the index changes instead
+/// of silently skipping or repeating results.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct SearchCursor {
+    query: String,
+    limit: usize,
+    offset: usize,
+    path: Option<String>,
+    index_state: u64,
 }
 
+impl SearchCursor {
+    /// Cursor for the page following `req`, if there is one
+    fn after(req: &SearchRequest, has_more: bool, index_state: u64) -> Option<Self> {
+        has_more.then(|| Self {
+            query: req.query.clone(),
+            limit: req.limit,
+            offset: req.offset.saturating_add(req.limit),
+            path: req.path.clone(),
+            index_state,
+        })
+    }
+
+    fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        json.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        if cursor.len() % 2 != 0 || !cursor.is_ascii() {
+            return None;
+        }
+        let bytes = (0..cursor.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn into_request(self) -> SearchRequest {
+        SearchRequest {
+            query: self.query,
+            limit: self.limit,
+            offset: self.offset,
+            path: self.path,
+            cursor: None,
+        }
+    }
+}
+
+/// Number of search responses kept in the server's result cache
+const RESULT_CACHE_ENTRIES: u64 = 256;
+
 fn default_limit() -> usize {
     25
 }
 
 /// Search response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct SearchResponse {
     results: Vec<SearchResult>,
     query: String,
@@ -216,9 +332,12 @@ struct SearchResponse {
     databases_searched: usize,
     total_available: usize,
     has_more: bool,
+    /// Pass back as `cursor` to fetch the next page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct SearchResult {
     path: String,
     content: String,
@@ -237,6 +356,9 @@ struct SearchResult {
     fts_rank: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     rerank_score: Option<f32>,
+    /// Retrieval sources that matched the chunk ("vector", "fts")
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    sources: Vec<&'static str>,
 }
 
 /// Health check response
@@ -267,6 +389,29 @@ struct StatusResponse {
     databases_available: usize,
 }
 
+/// Metrics response
+#[derive(Debug, Serialize)]
+struct MetricsResponse {
+    #[serde(flatten)]
+    search: MetricsSnapshot,
+    embedding_cache_hits: usize,
+    embedding_cache_misses: usize,
+    embedding_cache_hit_rate: f32,
+    total_chunks: usize,
+}
+
+impl MetricsResponse {
+    fn new(search: MetricsSnapshot, cache: &CacheStats, total_chunks: usize) -> Self {
+        Self {
+            search,
+            embedding_cache_hits: cache.hits,
+            embedding_cache_misses: cache.misses,
+            embedding_cache_hit_rate: cache.hit_rate(),
+            total_chunks,
+        }
+    }
+}
+
 /// Run the background server with live file watching and dual-database support
 ///
 /// Improvements over osgrep:
@@ -275,11 +420,21 @@ struct StatusResponse {
 /// 3. Two-level change detection (mtime + hash)
 /// 4. Tracks chunk IDs for efficient incremental updates
 /// 5. **Dual-database support**: Searches both local and global databases
-pub async fn serve(port: u16, path: Option<PathBuf>) -> Result<()> {
-    let root = path
-        .clone()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .canonicalize()?;
+pub async fn serve(
+    port: u16,
+    path: Option<PathBuf>,
+    include_hidden: bool,
+    debounce_ms: Option<u64>,
+    reindex_on_start: bool,
+    timeout: Option<u64>,
+) -> Result<()> {
+    let root = canonical_project_path(path.as_deref().unwrap_or(Path::new(".")))?;
+    let config = ProjectConfig::load(Some(&root));
+    // --debounce-ms and --timeout win over the project config
+    let server_config = config.server;
+    let debounce_ms = debounce_ms.unwrap_or(server_config.debounce_ms);
+    let request_timeout =
+        Duration::from_secs(timeout.unwrap_or(server_config.request_timeout_secs));
 
     println!("{}", "🚀 Demongrep Server".bright_cyan().bold());
     println!("{}", "=".repeat(60));
@@ -287,7 +442,12 @@ pub async fn serve(port: u16, path: Option<PathBuf>) -> Result<()> {
     println!("🌐 Port: {}", port);
 
     // Get all available database paths
-    let db_paths = get_search_db_paths(path)?;
+    let mut db_paths = get_search_db_paths(path)?;
+
+    // --reindex-on-start builds a fresh local index when there is none
+    if db_paths.is_empty() && reindex_on_start {
+        db_paths.push(local_db_path(&root));
+    }
 
     if db_paths.is_empty() {
         println!("\n{}", "❌ No databases found!".red());
@@ -319,12 +479,32 @@ pub async fn serve(port: u16, path: Option<PathBuf>) -> Result<()> {
         println!("   🌍 Global: {}", path.display());
     }
 
-    // Initialize embedding service
-    let model_type = ModelType::default();
+    // Embed like the index was built (its model and possibly truncated
+    // dimensions), or with the configured model for a database still to build
+    let indexed = local_db_path
+        .iter()
+        .chain(&global_db_path)
+        .find_map(|db_path| DatabaseManager::read_metadata(db_path));
+    let (model_type, dimensions) = match indexed {
+        Some(indexed) => indexed,
+        None => {
+            let model_type = crate::index::configured_model(&config)?;
+            let dimensions = match config.embedding.provider {
+                EmbeddingProvider::Remote => config.embedding.dimensions,
+                EmbeddingProvider::Local => None,
+            };
+            (model_type, dimensions.unwrap_or(model_type.dimensions()))
+        }
+    };
     println!("\n🔄 Loading embedding model...");
-    let embedding_service = EmbeddingService::with_model(model_type)?;
+    let embedding_service =
+        EmbeddingService::from_config(&config.embedding, model_type, None, dimensions)?;
     let dimensions = embedding_service.dimensions();
-    println!("   Model: {} ({} dims)", model_type.name(), dimensions);
+    println!(
+        "   Model: {} ({} dims)",
+        embedding_service.model_name(),
+        dimensions
+    );
 
     // Load local database (if exists)
     let (local_store, local_file_meta) = if let Some(ref local_path) = local_db_path {
@@ -333,13 +513,19 @@ pub async fn serve(port: u16, path: Option<PathBuf>) -> Result<()> {
         let store = VectorStore::new(local_path, dimensions)?;
         let stats = store.stats()?;
 
-        if stats.total_chunks == 0 {
+        if stats.total_chunks == 0 && !reindex_on_start {
             println!(
                 "\n{}",
                 "📦 Local database empty, performing initial index...".yellow()
             );
-            let (store, file_meta) =
-                initial_index(root.clone(), local_path.clone(), model_type).await?;
+            let (store, file_meta) = initial_index(
+                root.clone(),
+                local_path.clone(),
+                model_type,
+                dimensions,
+                include_hidden,
+            )
+            .await?;
             (Some(store), Some(file_meta))
         } else {
             println!(
@@ -356,7 +542,12 @@ pub async fn serve(port: u16, path: Option<PathBuf>) -> Result<()> {
     // If local exists, global is read-only for search
     // If local doesn't exist, global can be written to (for file watching)
     let (global_store, global_file_meta) = if let Some(ref global_path) = global_db_path {
-        match VectorStore::new(global_path, dimensions) {
+        let opened = if local_db_path.is_some() {
+            VectorStore::open_readonly(global_path, dimensions)
+        } else {
+            VectorStore::new(global_path, dimensions)
+        };
+        match opened {
             Ok(store) => {
                 let stats = store.stats()?;
 
@@ -368,13 +559,19 @@ pub async fn serve(port: u16, path: Option<PathBuf>) -> Result<()> {
                         dimensions,
                     )?;
 
-                    if stats.total_chunks == 0 {
+                    if stats.total_chunks == 0 && !reindex_on_start {
                         println!(
                             "\n{}",
                             "📦 Global database empty, performing initial index...".yellow()
                         );
-                        let (store, file_meta) =
-                            initial_index(root.clone(), global_path.clone(), model_type).await?;
+                        let (store, file_meta) = initial_index(
+                            root.clone(),
+                            global_path.clone(),
+                            model_type,
+                            dimensions,
+                            include_hidden,
+                        )
+                        .await?;
                         (Some(store), Some(file_meta))
                     } else {
                         println!(
@@ -401,6 +598,13 @@ pub async fn serve(port: u16, path: Option<PathBuf>) -> Result<()> {
         (None, None)
     };
 
+    // Chunk like the database being written was indexed (`--index-docs`)
+    let chunking = config.chunking;
+    let doc_chunker = |db_path: Option<&Path>| {
+        indexing_chunker(&chunking)
+            .with_doc_chunks(db_path.is_some_and(|p| DatabaseManager::index_metadata(p).docs))
+    };
+
     // Determine which database to use for file watching and how to set up the state
     // Priority: local > global
     let state = if local_store.is_some() {
@@ -410,55 +614,96 @@ pub async fn serve(port: u16, path: Option<PathBuf>) -> Result<()> {
             local_db_path: local_db_path.clone(),
             global_store: global_store.map(RwLock::new),
             global_db_path,
+            embedding_cache: embedding_service.shared_cache(),
             embedding_service: Mutex::new(embedding_service),
-            chunker: Mutex::new(SemanticChunker::new(100, 2000, 10)),
+            chunker: Mutex::new(doc_chunker(local_db_path.as_deref())),
+            model: model_info(local_file_meta.as_ref()),
             file_meta: local_file_meta.map(RwLock::new),
             root: root.clone(),
+            result_cache: SearchResultCache::new(RESULT_CACHE_ENTRIES),
+            metrics: ServerMetrics::default(),
+            ready: AtomicBool::new(!reindex_on_start),
+            request_timeout,
         })
     } else if global_store.is_some() {
         // Only global database exists - use it as primary (writable)
         Arc::new(ServerState {
             local_store: global_store.map(RwLock::new),
+            chunker: Mutex::new(doc_chunker(global_db_path.as_deref())),
             local_db_path: global_db_path,
             global_store: None,
             global_db_path: None,
+            embedding_cache: embedding_service.shared_cache(),
             embedding_service: Mutex::new(embedding_service),
-            chunker: Mutex::new(SemanticChunker::new(100, 2000, 10)),
+            model: model_info(global_file_meta.as_ref()),
             file_meta: global_file_meta.map(RwLock::new),
             root: root.clone(),
+            result_cache: SearchResultCache::new(RESULT_CACHE_ENTRIES),
+            metrics: ServerMetrics::default(),
+            ready: AtomicBool::new(!reindex_on_start),
+            request_timeout,
         })
     } else {
         // No databases - shouldn't happen because we checked earlier
         return Err(anyhow!("No databases available"));
     };
 
-    start_server(state, port, root).await
+    start_server(
+        state,
+        port,
+        root,
+        include_hidden,
+        debounce_ms,
+        reindex_on_start,
+    )
+    .await
+}
+
+/// Model name and dimensions of the writable database, or the default model
+fn model_info(file_meta: Option<&FileMetaStore>) -> (String, usize) {
+    match file_meta {
+        Some(meta) => (meta.model_name.clone(), meta.dimensions),
+        None => {
+            let model = ModelType::default();
+            (model.name().to_string(), model.dimensions())
+        }
+    }
 }
 
 async fn initial_index(
     root: PathBuf,
     db_path: PathBuf,
     model_type: ModelType,
+    dimensions: usize,
+    include_hidden: bool,
 ) -> Result<(VectorStore, FileMetaStore)> {
+    // Keep the roots an existing (empty) index was built from, and its
+    // metadata.json so later runs reuse them too
+    let roots = DatabaseManager::index_metadata(&db_path).roots;
+    let metadata = std::fs::read(db_path.join("metadata.json")).ok();
+
     // Clear existing database if any
     if db_path.exists() {
         std::fs::remove_dir_all(&db_path)?;
     }
+    if let Some(metadata) = metadata {
+        std::fs::create_dir_all(&db_path)?;
+        std::fs::write(db_path.join("metadata.json"), metadata)?;
+    }
 
     // File discovery
-    let walker = FileWalker::new(root.clone());
+    let walker = project_walker(&root, &roots).include_hidden(include_hidden);
     let (files, _stats) = walker.walk()?;
     println!("  Found {} files", files.len());
 
     if files.is_empty() {
-        let store = VectorStore::new(&db_path, model_type.dimensions())?;
-        let file_meta =
-            FileMetaStore::new(model_type.short_name().to_string(), model_type.dimensions());
+        let store = VectorStore::new(&db_path, dimensions)?;
+        let file_meta = FileMetaStore::new(model_type.short_name().to_string(), dimensions);
         return Ok((store, file_meta));
     }
 
     // Chunking
-    let mut chunker = SemanticChunker::new(100, 2000, 10);
+    let mut chunker = indexing_chunker(&ProjectConfig::load(Some(&root)).chunking);
     let mut all_chunks = Vec::new();
     let mut file_chunks: HashMap<String, Vec<crate::chunker::Chunk>> = HashMap::new();
 
@@ -475,25 +720,30 @@ async fn initial_index(
     println!("  Created {} chunks", all_chunks.len());
 
     // Embedding
-    let mut embedding_service = EmbeddingService::with_model(model_type)?;
+    let mut embedding_service = EmbeddingService::from_config(
+        &ProjectConfig::load(Some(&root)).embedding,
+        model_type,
+        None,
+        dimensions,
+    )?;
     let embedded_chunks = embedding_service.embed_chunks(all_chunks)?;
     println!("  Generated {} embeddings", embedded_chunks.len());
 
     // Storage
-    let mut store = VectorStore::new(&db_path, model_type.dimensions())?;
+    let mut store = VectorStore::new(&db_path, dimensions)?;
     let chunk_ids = store.insert_chunks_with_ids(embedded_chunks)?;
     store.build_index()?;
 
     // Build file metadata
-    let mut file_meta =
-        FileMetaStore::new(model_type.short_name().to_string(), model_type.dimensions());
+    let mut file_meta = FileMetaStore::new(model_type.short_name().to_string(), dimensions);
 
     let mut chunk_id_iter = chunk_ids.iter();
     for file in &files {
         let path_str = file.path.to_string_lossy().to_string();
         if let Some(chunks) = file_chunks.get(&path_str) {
             let ids: Vec<u32> = chunk_id_iter.by_ref().take(chunks.len()).copied().collect();
-            file_meta.update_file(&file.path, ids)?;
+            file_meta.update_file(&file.path, ids.clone())?;
+            store.update_file_metadata(&file.path, ids)?;
         }
     }
     file_meta.mark_full_index();
@@ -504,39 +754,65 @@ async fn initial_index(
     Ok((store, file_meta))
 }
 
-async fn start_server(state: Arc<ServerState>, port: u16, root: PathBuf) -> Result<()> {
+async fn start_server(
+    state: Arc<ServerState>,
+    port: u16,
+    root: PathBuf,
+    include_hidden: bool,
+    debounce_ms: u64,
+    reindex_on_start: bool,
+) -> Result<()> {
     // Check if we have a writable database (local_store contains the primary/writable database)
     let has_writable_store = state.local_store.is_some() && state.file_meta.is_some();
 
-    // Start file watcher in background (if we have a writable database)
-    if has_writable_store {
+    // Sync the index (if requested), then start the file watcher in background
+    // (if we have a writable database). Searches get 503 until the sync is done.
+    if has_writable_store || reindex_on_start {
         let watcher_state = state.clone();
         let watcher_root = root.clone();
         tokio::spawn(async move {
-            if let Err(e) = run_file_watcher(watcher_state, watcher_root).await {
+            if reindex_on_start {
+                sync_on_start(&watcher_state, &watcher_root, include_hidden).await;
+            }
+            if !has_writable_store {
+                return;
+            }
+            if let Err(e) =
+                run_file_watcher(watcher_state, watcher_root, include_hidden, debounce_ms).await
+            {
                 eprintln!("File watcher error: {}", e);
             }
         });
-    } else {
+    }
+    if !has_writable_store {
         println!(
             "\n{}",
             "ℹ️  No writable database - file watching disabled".dimmed()
         );
     }
 
-    // Build HTTP router
-    let app = Router::new()
-        .route("/health", get(health_handler))
-        .route("/status", get(status_handler))
-        .route("/search", post(search_handler))
-        .with_state(state);
+    let app = router(state);
 
     let addr = format!("127.0.0.1:{}", port);
     println!("\n{}", "🌐 Server ready!".bright_green().bold());
     println!("  Health: http://{}/health", addr);
     println!("  Search: POST http://{}/search", addr);
+    println!("  Metrics: http://{}/metrics", addr);
+    if reindex_on_start {
+        println!(
+            "\n{}",
+            "🔄 Syncing index - searches return 503 until it is ready".yellow()
+        );
+    }
     if has_writable_store {
-        println!("\n{}", "👀 Watching for file changes...".dimmed());
+        println!(
+            "\n{}",
+            format!(
+                "👀 Watching for file changes ({}ms debounce)...",
+                debounce_ms
+            )
+            .dimmed()
+        );
     }
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
@@ -545,9 +821,78 @@ async fn start_server(state: Arc<ServerState>, port: u16, root: PathBuf) -> Resu
     Ok(())
 }
 
-async fn run_file_watcher(state: Arc<ServerState>, root: PathBuf) -> Result<()> {
-    let mut watcher = FileWatcher::new(root);
-    watcher.start(300)?; // 300ms debounce
+/// Run the `--reindex-on-start` sync, then let searches through (also if
+/// the sync failed, to serve the index as it is)
+async fn sync_on_start(state: &ServerState, root: &Path, include_hidden: bool) {
+    if let Err(e) = startup_sync(state, root, include_hidden).await {
+        eprintln!("Startup index error: {}", e);
+    }
+    state.ready.store(true, Ordering::Release);
+}
+
+/// Sync the writable database with the files under `root` (`--reindex-on-start`)
+async fn startup_sync(state: &ServerState, root: &Path, include_hidden: bool) -> Result<()> {
+    let (Some(local_store), Some(file_meta), Some(db_path)) =
+        (&state.local_store, &state.file_meta, &state.local_db_path)
+    else {
+        return Ok(());
+    };
+
+    let mut store = local_store.write().await;
+    let mut file_meta = file_meta.write().await;
+    let mut chunker = state.chunker.lock().await;
+    let mut embedding_service = state.embedding_service.lock().await;
+
+    let roots = DatabaseManager::index_metadata(db_path).roots;
+    let summary = sync_files(
+        root,
+        &roots,
+        include_hidden,
+        &mut store,
+        &mut file_meta,
+        &mut chunker,
+        |chunks| embedding_service.embed_chunks(chunks),
+    )?;
+    file_meta.save(db_path)?;
+    crate::index::mark_indexed_now(db_path)?;
+    state.metrics.record_reindex();
+
+    println!(
+        "  ✅ Index ready: {} files indexed, {} removed",
+        summary.indexed, summary.removed
+    );
+    Ok(())
+}
+
+/// Start watching `root` with the given debounce interval
+fn start_file_watcher(
+    root: PathBuf,
+    include_hidden: bool,
+    debounce_ms: u64,
+) -> Result<FileWatcher> {
+    let allow_generated = ProjectConfig::load(Some(&root)).indexing.allow_generated;
+    let mut watcher = FileWatcher::new(root)
+        .include_hidden(include_hidden)
+        .allow_generated(&allow_generated);
+    watcher.start(debounce_ms)?;
+    Ok(watcher)
+}
+
+async fn run_file_watcher(
+    state: Arc<ServerState>,
+    root: PathBuf,
+    include_hidden: bool,
+    debounce_ms: u64,
+) -> Result<()> {
+    // Changes outside the roots the index was built from aren't indexed
+    let roots = state
+        .local_db_path
+        .as_deref()
+        .map(|db_path| DatabaseManager::index_metadata(db_path).roots)
+        .unwrap_or_default();
+    let in_roots =
+        |path: &Path| roots.is_empty() || roots.iter().any(|r| path.starts_with(root.join(r)));
+    let mut watcher = start_file_watcher(root.clone(), include_hidden, debounce_ms)?;
 
     loop {
         // Poll for events (non-blocking)
@@ -565,7 +910,7 @@ async fn run_file_watcher(state: Arc<ServerState>, root: PathBuf) -> Result<()>
             match event {
                 FileEvent::Modified(path) => {
                     // Skip directories
-                    if path.is_dir() {
+                    if path.is_dir() || !in_roots(&path) {
                         continue;
                     }
                     if let Err(e) = handle_file_modified(&state, &path).await {
@@ -588,7 +933,9 @@ async fn run_file_watcher(state: Arc<ServerState>, root: PathBuf) -> Result<()>
                     }
                     // Treat as delete + create
                     let _ = handle_file_deleted(&state, &from).await;
-                    let _ = handle_file_modified(&state, &to).await;
+                    if in_roots(&to) {
+                        let _ = handle_file_modified(&state, &to).await;
+                    }
                 }
             }
         }
@@ -607,7 +954,11 @@ async fn run_file_watcher(state: Arc<ServerState>, root: PathBuf) -> Result<()>
         if let (Some(ref file_meta), Some(ref db_path)) = (&state.file_meta, &state.local_db_path) {
             let file_meta = file_meta.read().await;
             file_meta.save(db_path)?;
+            crate::index::mark_indexed_now(db_path)?;
         }
+
+        state.result_cache.invalidate_all();
+        state.metrics.record_reindex();
     }
 }
 
@@ -624,9 +975,10 @@ async fn handle_file_modified(state: &ServerState, path: &PathBuf) -> Result<()>
         .ok_or_else(|| anyhow!("No local database available"))?;
 
     // Check if file needs re-indexing
-    let file_meta_read: tokio::sync::RwLockReadGuard<'_, FileMetaStore> = file_meta.read().await;
-    let (needs_reindex, old_chunk_ids) = file_meta_read.check_file(path)?;
-    drop(file_meta_read);
+    let mut file_meta_write: tokio::sync::RwLockWriteGuard<'_, FileMetaStore> =
+        file_meta.write().await;
+    let (needs_reindex, old_chunk_ids) = file_meta_write.check_file(path)?;
+    drop(file_meta_write);
 
     if !needs_reindex {
         return Ok(());
@@ -634,17 +986,19 @@ async fn handle_file_modified(state: &ServerState, path: &PathBuf) -> Result<()>
 
     println!("  📝 Re-indexing: {}", path.display());
 
-    // Delete old chunks if any
+    // Delete old chunks if any, keeping their embeddings for unchanged content
+    let mut stored = HashMap::new();
     if !old_chunk_ids.is_empty() {
         if let Some(ref local_store) = state.local_store {
             let mut store = local_store.write().await;
+            stored = store.embeddings_by_hash(&old_chunk_ids)?;
             store.delete_chunks(&old_chunk_ids)?;
         }
     }
 
     // Read and chunk file
     let source_code = std::fs::read_to_string(path)?;
-    let language = crate::file::Language::from_path(path);
+    let language = crate::file::Language::detect(path, &source_code);
 
     let chunks = {
         let mut chunker = state.chunker.lock().await;
@@ -656,19 +1010,29 @@ async fn handle_file_modified(state: &ServerState, path: &PathBuf) -> Result<()>
         let mut file_meta_write: tokio::sync::RwLockWriteGuard<'_, FileMetaStore> =
             file_meta.write().await;
         file_meta_write.update_file(path, vec![])?;
+        if let Some(ref local_store) = state.local_store {
+            local_store
+                .write()
+                .await
+                .update_file_metadata(path, vec![])?;
+        }
         return Ok(());
     }
 
-    // Embed chunks
+    // Embed the chunks whose content changed
     let embedded_chunks = {
         let mut embedding_service = state.embedding_service.lock().await;
-        embedding_service.embed_chunks(chunks)?
+        embed_changed_chunks(chunks, &stored, |chunks| {
+            embedding_service.embed_chunks(chunks)
+        })?
     };
 
-    // Insert into store
+    // Insert into store, recording the file's new mtime for recency sorting
     let chunk_ids = if let Some(ref local_store) = state.local_store {
         let mut store = local_store.write().await;
-        store.insert_chunks_with_ids(embedded_chunks)?
+        let chunk_ids = store.insert_chunks_with_ids(embedded_chunks)?;
+        store.update_file_metadata(path, chunk_ids.clone())?;
+        chunk_ids
     } else {
         vec![]
     };
@@ -697,14 +1061,15 @@ async fn handle_file_deleted(state: &ServerState, path: &PathBuf) -> Result<()>
         file_meta.write().await;
 
     if let Some(meta) = file_meta_write.remove_file(path) {
-        if !meta.chunk_ids.is_empty() {
-            println!(
-                "  🗑️  Removing: {} ({} chunks)",
-                path.display(),
-                meta.chunk_ids.len()
-            );
-            if let Some(ref local_store) = state.local_store {
-                let mut store = local_store.write().await;
+        if let Some(ref local_store) = state.local_store {
+            let mut store = local_store.write().await;
+            store.remove_file_metadata(path)?;
+            if !meta.chunk_ids.is_empty() {
+                println!(
+                    "  🗑️  Removing: {} ({} chunks)",
+                    path.display(),
+                    meta.chunk_ids.len()
+                );
                 store.delete_chunks(&meta.chunk_ids)?;
             }
         }
@@ -716,20 +1081,19 @@ async fn handle_file_deleted(state: &ServerState, path: &PathBuf) -> Result<()>
 // HTTP Handlers
 
 async fn health_handler(State(state): State<Arc<ServerState>>) -> Json<HealthResponse> {
-    let stats = state.get_combined_stats().await;
-
-    let model_name = if let Some(ref file_meta) = state.file_meta {
-        let meta = file_meta.read().await;
-        meta.model_name.clone()
+    let status = if state.is_ready() {
+        "ready"
     } else {
-        ModelType::default().name().to_string()
+        "indexing"
     };
+    let stats = state.get_combined_stats().await;
+    let (model_name, _) = state.model.clone();
 
     let databases_available = (if state.local_store.is_some() { 1 } else { 0 })
         + (if state.global_store.is_some() { 1 } else { 0 });
 
     Json(HealthResponse {
-        status: "ready".to_string(),
+        status: status.to_string(),
         total_files: stats.total_files,
         total_chunks: stats.total_chunks,
         local_files: stats.local_files,
@@ -743,14 +1107,7 @@ async fn health_handler(State(state): State<Arc<ServerState>>) -> Json<HealthRes
 
 async fn status_handler(State(state): State<Arc<ServerState>>) -> Json<StatusResponse> {
     let stats = state.get_combined_stats().await;
-
-    let (model_name, dimensions) = if let Some(ref file_meta) = state.file_meta {
-        let meta = file_meta.read().await;
-        (meta.model_name.clone(), meta.dimensions)
-    } else {
-        let model = ModelType::default();
-        (model.name().to_string(), model.dimensions())
-    };
+    let (model_name, dimensions) = state.model.clone();
 
     let databases_available = (if state.local_store.is_some() { 1 } else { 0 })
         + (if state.global_store.is_some() { 1 } else { 0 });
@@ -768,12 +1125,101 @@ async fn status_handler(State(state): State<Arc<ServerState>>) -> Json<StatusRes
     })
 }
 
+/// HTTP routes, counting every request for `/metrics`
+fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route("/health", get(health_handler))
+        .route("/status", get(status_handler))
+        .route("/search", post(search_handler))
+        .route("/metrics", get(metrics_handler))
+        .layer(middleware::from_fn_with_state(state.clone(), count_request))
+        .with_state(state)
+}
+
+async fn count_request(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    state.metrics.record_request();
+    next.run(request).await
+}
+
+async fn metrics_handler(State(state): State<Arc<ServerState>>) -> Json<MetricsResponse> {
+    // Never waits on the embedding service: a startup sync holds it for
+    // minutes, and /metrics has to answer meanwhile
+    let stats = state.get_combined_stats().await;
+    let cache = state.embedding_cache.stats();
+
+    Json(MetricsResponse::new(
+        state.metrics.snapshot(),
+        &cache,
+        stats.total_chunks,
+    ))
+}
+
 async fn search_handler(
     State(state): State<Arc<ServerState>>,
     Json(req): Json<SearchRequest>,
+) -> Result<Json<SearchResponse>, (StatusCode, String)> {
+    require_ready(state.is_ready())?;
+
+    let start = std::time::Instant::now();
+    // On its own task, so blocking steps (model loading, embedding,
+    // reranking) don't also stall the timer
+    let search_state = state.clone();
+    let task = tokio::spawn(async move { run_search(&search_state, req).await });
+    let abort = task.abort_handle();
+    let response = match with_timeout(state.request_timeout, task).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(timed_out) => {
+            abort.abort();
+            Err((StatusCode::GATEWAY_TIMEOUT, format!("Search {}", timed_out)))
+        }
+    };
+    state
+        .metrics
+        .record_search(start.elapsed(), response.is_ok());
+    response
+}
+
+async fn run_search(
+    state: &ServerState,
+    req: SearchRequest,
 ) -> Result<Json<SearchResponse>, (StatusCode, String)> {
     let start = std::time::Instant::now();
 
+    let index_state = state.index_state();
+    let req = match req.cursor {
+        Some(ref cursor) => {
+            let cursor = SearchCursor::decode(cursor)
+                .ok_or((StatusCode::BAD_REQUEST, "Invalid cursor".to_string()))?;
+            if cursor.index_state != index_state {
+                return Err((
+                    StatusCode::GONE,
+                    "Cursor expired: the index changed since it was issued; repeat the search"
+                        .to_string(),
+                ));
+            }
+            cursor.into_request()
+        }
+        None => req,
+    };
+    if req.query.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Either query or cursor is required".to_string(),
+        ));
+    }
+
+    let cache_key = format!("{:?}", req);
+    if let Some(cached) = state.result_cache.get(&cache_key, index_state) {
+        let mut response = (*cached).clone();
+        response.took_ms = start.elapsed().as_millis() as u64;
+        return Ok(Json(response));
+    }
+
     // Embed query
     let query_embedding = {
         let mut embedding_service = state.embedding_service.lock().await;
@@ -803,16 +1249,10 @@ async fn search_handler(
             }
         })
         .map(|r| {
-            // Determine which database this result came from
-            let database = if let Some(ref _local_path) = state.local_db_path {
-                if r.path.starts_with(state.root.to_str().unwrap_or("")) {
-                    "local".to_string()
-                } else {
-                    "global".to_string()
-                }
-            } else {
-                "global".to_string()
-            };
+            let database = r
+                .database
+                .map_or("global", |database| database.tag())
+                .to_string();
 
             // Make path relative to root
             let rel_path = r
@@ -824,7 +1264,7 @@ async fn search_handler(
 
             SearchResult {
                 path: rel_path,
-                content: truncate_content(&r.content, 200),
+                content: crate::output::truncate_chars(&r.content, 200),
                 start_line: r.start_line,
                 end_line: r.end_line,
                 kind: r.kind.clone(),
@@ -835,31 +1275,475 @@ async fn search_handler(
                 vector_rank: r.vector_rank,
                 fts_rank: r.fts_rank,
                 rerank_score: r.rerank_score,
+                sources: crate::rerank::match_sources(r.vector_rank, r.fts_rank),
             }
         })
         .collect();
 
     let took_ms = start.elapsed().as_millis() as u64;
+    let next_cursor = SearchCursor::after(&req, has_more, index_state).map(|c| c.encode());
 
-    Ok(Json(SearchResponse {
+    let response = SearchResponse {
         results: search_results,
         query: req.query,
         took_ms,
         databases_searched,
         total_available,
         has_more,
-    }))
+        next_cursor,
+    };
+    state
+        .result_cache
+        .insert(&cache_key, index_state, response.clone());
+
+    Ok(Json(response))
 }
 
-fn truncate_content(content: &str, max_len: usize) -> String {
-    if content.len() <= max_len {
-        content.to_string()
+/// 503 while the startup sync is still building the index
+fn require_ready(ready: bool) -> Result<(), (StatusCode, String)> {
+    if ready {
+        Ok(())
     } else {
-        // Find a valid UTF-8 boundary to avoid panic on multi-byte chars
-        let mut end = max_len;
-        while end > 0 && !content.is_char_boundary(end) {
-            end -= 1;
+        Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Index is being built; retry shortly".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunker::Chunk;
+    use crate::embed::testing::{stub_embed, StubEmbedder};
+    use crate::embed::EmbeddedChunk;
+    use crate::search::SyncSummary;
+
+    fn request(query: &str, limit: usize) -> SearchRequest {
+        SearchRequest {
+            query: query.to_string(),
+            limit,
+            offset: 0,
+            path: None,
+            cursor: None,
+        }
+    }
+
+    #[test]
+    fn test_cursor_pagination_has_no_duplicates_or_gaps() {
+        let ranked: Vec<usize> = (0..23).collect();
+        let index_state = 42;
+
+        let mut req = request("auth", 5);
+        let mut seen = Vec::new();
+        let mut pages = 0;
+        loop {
+            let (page, total, has_more) = paginate(ranked.clone(), req.offset, req.limit);
+            assert_eq!(total, 23);
+            seen.extend(page);
+            pages += 1;
+
+            let Some(cursor) = SearchCursor::after(&req, has_more, index_state) else {
+                break;
+            };
+            let decoded = SearchCursor::decode(&cursor.encode()).unwrap();
+            assert_eq!(decoded, cursor);
+            req = decoded.into_request();
+            assert_eq!(req.query, "auth");
+        }
+
+        assert_eq!(pages, 5);
+        assert_eq!(seen, ranked);
+    }
+
+    #[test]
+    fn test_file_watcher_uses_requested_debounce() {
+        let dir = tempfile::tempdir().unwrap();
+        let watcher = start_file_watcher(dir.path().to_path_buf(), false, 42).unwrap();
+        assert_eq!(watcher.debounce_ms(), Some(42));
+    }
+
+    #[test]
+    fn test_metrics_count_searches() {
+        let metrics = ServerMetrics::default();
+        let idle = CacheStats {
+            size: 0,
+            hits: 0,
+            misses: 0,
+        };
+        let before = MetricsResponse::new(metrics.snapshot(), &idle, 10);
+        assert_eq!(before.search.searches_total, 0);
+        assert_eq!(before.search.search_latency_p95_ms, 0.0);
+
+        for ms in [5, 10, 40] {
+            metrics.record_search(Duration::from_millis(ms), true);
+        }
+        metrics.record_search(Duration::from_millis(1), false);
+        let cache = CacheStats {
+            size: 2,
+            hits: 3,
+            misses: 1,
+        };
+
+        let json =
+            serde_json::to_value(MetricsResponse::new(metrics.snapshot(), &cache, 10)).unwrap();
+        assert_eq!(json["searches_total"], 4);
+        assert_eq!(json["search_errors_total"], 1);
+        assert_eq!(json["search_latency_p50_ms"], 10.0);
+        assert_eq!(json["search_latency_p95_ms"], 40.0);
+        assert_eq!(json["embedding_cache_hit_rate"], 0.75);
+        assert_eq!(json["total_chunks"], 10);
+        assert!(json["last_reindex_unix"].is_null());
+
+        metrics.record_reindex();
+        assert!(metrics.snapshot().last_reindex_unix.is_some());
+    }
+
+    /// Server state without databases, embedding with [`StubEmbedder`]
+    fn stub_state(root: &Path, ready: bool) -> ServerState {
+        let batch_embedder =
+            crate::embed::BatchEmbedder::new(Arc::new(std::sync::Mutex::new(StubEmbedder)));
+        let embedding_service = EmbeddingService::with_batch_embedder(
+            batch_embedder,
+            None,
+            "stub",
+            ModelType::default(),
+        )
+        .unwrap();
+        ServerState {
+            local_store: None,
+            local_db_path: None,
+            global_store: None,
+            global_db_path: None,
+            embedding_cache: embedding_service.shared_cache(),
+            embedding_service: Mutex::new(embedding_service),
+            chunker: Mutex::new(SemanticChunker::new(100, 2000, 10)),
+            file_meta: None,
+            model: model_info(None),
+            root: root.to_path_buf(),
+            result_cache: SearchResultCache::new(RESULT_CACHE_ENTRIES),
+            metrics: ServerMetrics::default(),
+            ready: AtomicBool::new(ready),
+            request_timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn get_request(uri: &str) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::builder()
+            .uri(uri)
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_counts_every_request() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let app = router(Arc::new(stub_state(dir.path(), true)));
+
+        for uri in ["/health", "/status", "/missing"] {
+            app.clone().oneshot(get_request(uri)).await.unwrap();
+        }
+        let response = app.oneshot(get_request("/metrics")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["requests_total"], 4);
+        assert_eq!(json["searches_total"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_and_health_answer_during_a_sync() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut state = stub_state(dir.path(), false);
+        state.file_meta = Some(RwLock::new(FileMetaStore::new("stub".to_string(), 4)));
+        let state = Arc::new(state);
+        let app = router(state.clone());
+
+        // As startup_sync does for the whole sync
+        let _embedding_service = state.embedding_service.lock().await;
+        let _file_meta = state.file_meta.as_ref().unwrap().write().await;
+        for uri in ["/metrics", "/health", "/status"] {
+            let response = tokio::time::timeout(
+                Duration::from_secs(5),
+                app.clone().oneshot(get_request(uri)),
+            )
+            .await
+            .unwrap_or_else(|_| panic!("{} waited on the sync", uri))
+            .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_searches_get_503_until_the_startup_sync_is_done() {
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("auth.rs"),
+            "fn authenticate() -> bool {\n    true\n}\n",
+        )
+        .unwrap();
+        let db_path = local_db_path(root);
+        let mut state = stub_state(root, false);
+        state.local_store = Some(RwLock::new(VectorStore::new(&db_path, 4).unwrap()));
+        state.local_db_path = Some(db_path);
+        state.file_meta = Some(RwLock::new(FileMetaStore::new("test".to_string(), 4)));
+        let state = Arc::new(state);
+        let app = router(state.clone());
+        let search = || {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/search")
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(r#"{"query": "authenticate"}"#))
+                .unwrap()
+        };
+
+        // Hold the embedder so the sync can't finish before the first search
+        let embedding_service = state.embedding_service.lock().await;
+        let sync = tokio::spawn({
+            let state = state.clone();
+            let root = root.to_path_buf();
+            async move { sync_on_start(&state, &root, false).await }
+        });
+        let response = app.clone().oneshot(search()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        drop(embedding_service);
+        sync.await.unwrap();
+        let response = app.oneshot(search()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["results"][0]["path"], "auth.rs", "{}", json);
+    }
+
+    #[test]
+    fn test_reindex_on_start_builds_index_before_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let file = root.join("auth.rs");
+        std::fs::write(&file, "fn authenticate() -> bool {\n    true\n}\n").unwrap();
+
+        let db_path = local_db_path(root);
+        let mut store = VectorStore::new(&db_path, 4).unwrap();
+        let mut file_meta = FileMetaStore::new("test".to_string(), 4);
+        let mut chunker = SemanticChunker::new(100, 2000, 10);
+        let embed = stub_embed;
+
+        let summary = sync_files(
+            root,
+            &[],
+            false,
+            &mut store,
+            &mut file_meta,
+            &mut chunker,
+            embed,
+        )
+        .unwrap();
+        assert_eq!(
+            summary,
+            SyncSummary {
+                indexed: 1,
+                removed: 0
+            }
+        );
+        assert!(store.is_indexed());
+        let results = store.search(&[1.0, 0.0, 0.0, 0.0], 5).unwrap();
+        assert!(results.iter().any(|r| r.content.contains("authenticate")));
+
+        // The store records the mtime used by `--sort recency`
+        let key = file.to_string_lossy().to_string();
+        let synced_mtime = store.file_mtime(&key).unwrap().unwrap();
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        std::fs::write(&file, "fn authenticate() -> bool {\n    false\n}\n").unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        let summary = sync_files(
+            root,
+            &[],
+            false,
+            &mut store,
+            &mut file_meta,
+            &mut chunker,
+            embed,
+        )
+        .unwrap();
+        assert_eq!(summary.indexed, 1);
+        assert!(store.file_mtime(&key).unwrap().unwrap() > synced_mtime);
+
+        // An existing index only picks up the changes
+        let summary = sync_files(
+            root,
+            &[],
+            false,
+            &mut store,
+            &mut file_meta,
+            &mut chunker,
+            embed,
+        )
+        .unwrap();
+        assert_eq!(summary, SyncSummary::default());
+
+        std::fs::remove_file(&file).unwrap();
+        let summary = sync_files(
+            root,
+            &[],
+            false,
+            &mut store,
+            &mut file_meta,
+            &mut chunker,
+            embed,
+        )
+        .unwrap();
+        assert_eq!(
+            summary,
+            SyncSummary {
+                indexed: 0,
+                removed: 1
+            }
+        );
+        assert_eq!(store.stats().unwrap().total_chunks, 0);
+        assert_eq!(store.file_mtime(&key).unwrap(), None);
+    }
+
+    #[test]
+    fn test_sync_reembeds_only_changed_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let file = root.join("shapes.rs");
+        let source = "fn area(w: f64, h: f64) -> f64 {\n    w * h\n}\n\n\
+                      fn perimeter(w: f64, h: f64) -> f64 {\n    2.0 * (w + h)\n}\n\n\
+                      fn diagonal(w: f64, h: f64) -> f64 {\n    (w * w + h * h).sqrt()\n}\n";
+        std::fs::write(&file, source).unwrap();
+
+        let db_path = local_db_path(root);
+        let mut store = VectorStore::new(&db_path, 4).unwrap();
+        let mut file_meta = FileMetaStore::new("test".to_string(), 4);
+        let mut chunker = SemanticChunker::new(100, 2000, 10);
+        let embedded = std::cell::RefCell::new(Vec::new());
+        let embed = |chunks: Vec<Chunk>| -> Result<Vec<EmbeddedChunk>> {
+            embedded
+                .borrow_mut()
+                .extend(chunks.iter().map(|chunk| chunk.content.clone()));
+            stub_embed(chunks)
+        };
+
+        sync_files(
+            root,
+            &[],
+            false,
+            &mut store,
+            &mut file_meta,
+            &mut chunker,
+            embed,
+        )
+        .unwrap();
+        let total_chunks = store.stats().unwrap().total_chunks;
+        assert!(embedded.borrow().len() >= 3);
+
+        // Edit one function's body
+        embedded.borrow_mut().clear();
+        std::fs::write(&file, source.replace("w * h\n", "h * w * 1.0\n")).unwrap();
+        let summary = sync_files(
+            root,
+            &[],
+            false,
+            &mut store,
+            &mut file_meta,
+            &mut chunker,
+            embed,
+        )
+        .unwrap();
+        assert_eq!(summary.indexed, 1);
+
+        let reembedded = embedded.borrow();
+        assert_eq!(reembedded.len(), 1, "re-embedded: {:?}", reembedded);
+        assert!(reembedded[0].contains("fn area"));
+        assert_eq!(store.stats().unwrap().total_chunks, total_chunks);
+        let contents: Vec<String> = store
+            .chunk_ids()
+            .unwrap()
+            .into_iter()
+            .map(|id| store.get_chunk(id).unwrap().unwrap().content)
+            .collect();
+        assert!(contents.iter().any(|c| c.contains("h * w * 1.0")));
+        assert!(contents.iter().any(|c| c.contains("fn diagonal")));
+    }
+
+    #[test]
+    fn test_sync_only_walks_index_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("scripts")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn library() {}\n").unwrap();
+        std::fs::write(root.join("scripts/build.rs"), "fn script() {}\n").unwrap();
+
+        let db_path = local_db_path(root);
+        let mut store = VectorStore::new(&db_path, 4).unwrap();
+        let mut file_meta = FileMetaStore::new("test".to_string(), 4);
+        let mut chunker = SemanticChunker::new(100, 2000, 10);
+        let embed = stub_embed;
+
+        let summary = sync_files(
+            root,
+            &[PathBuf::from("src")],
+            false,
+            &mut store,
+            &mut file_meta,
+            &mut chunker,
+            embed,
+        )
+        .unwrap();
+        assert_eq!(summary.indexed, 1);
+        assert!(store
+            .chunks_for_path(&root.join("scripts/build.rs").to_string_lossy())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_cursor_survives_cache_writes_beside_the_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = local_db_path(dir.path());
+        drop(VectorStore::new(&db_path, 4).unwrap());
+        let mut state = stub_state(dir.path(), true);
+        state.local_db_path = Some(db_path.clone());
+
+        let cursor = SearchCursor::after(&request("auth", 5), true, state.index_state())
+            .unwrap()
+            .encode();
+
+        // Embedding the next query or reranking its results writes these
+        for cache in ["embedding_cache", "rerank_cache"] {
+            std::fs::create_dir_all(db_path.join(cache)).unwrap();
+            std::fs::write(db_path.join(cache).join("data.mdb"), "cached").unwrap();
         }
-        format!("{}...", &content[..end])
+        let cursor = SearchCursor::decode(&cursor).unwrap();
+        assert_eq!(cursor.index_state, state.index_state());
+    }
+
+    #[test]
+    fn test_cursor_decode_rejects_garbage() {
+        assert!(SearchCursor::decode("not a cursor").is_none());
+        assert!(SearchCursor::decode("abc").is_none());
+        assert!(SearchCursor::decode("7b7d").is_none()); // "{}"
+        assert!(SearchCursor::after(&request("auth", 5), false, 0).is_none());
     }
 }
@@ -4,7 +4,10 @@ use std::env;
 
 mod store;
 
-pub use store::{SearchResult, StoreStats, VectorStore};
+pub use store::{
+    ChunkMetadata, IndexMode, SearchResult, StoreStats, VectorStorage, VectorStore,
+    EXACT_INDEX_MAX_CHUNKS, RESIDENT_VECTORS_MAX_BYTES,
+};
 
 /// Supported vector backends.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -1,4 +1,6 @@
-use crate::embed::EmbeddedChunk;
+use crate::database::DatabaseType;
+use crate::embed::{l2_normalize, EmbeddedChunk};
+use crate::file::Language;
 use crate::info_print;
 use crate::vectordb::{requested_backend, selected_backend};
 use anyhow::{anyhow, Result};
@@ -6,13 +8,15 @@ use arroy::distances::Cosine;
 use arroy::{Database as ArroyDatabase, ItemId, Reader, Writer};
 use heed::byteorder::BigEndian;
 use heed::types::*;
-use heed::{Database, EnvOpenOptions};
+use heed::{Database, EnvOpenOptions, RoTxn};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 
 /// Chunk metadata stored in the database
@@ -33,6 +37,12 @@ pub struct ChunkMetadata {
     /// Lines of code immediately after this chunk (for context)
     #[serde(default)]
     pub context_next: Option<String>,
+    /// Language of the source file (e.g. "Rust"); None for unknown languages
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Symbol path of the chunk (e.g. "auth::Session::verify")
+    #[serde(default)]
+    pub scope: Option<String>,
 }
 
 /// File metadata for incremental indexing
@@ -70,7 +80,7 @@ impl ChunkMetadata {
             path: chunk.chunk.path.clone(),
             start_line: chunk.chunk.start_line,
             end_line: chunk.chunk.end_line,
-            kind: format!("{:?}", chunk.chunk.kind),
+            kind: chunk.chunk.kind.as_str().to_string(),
             signature: chunk.chunk.signature.clone(),
             docstring: chunk.chunk.docstring.clone(),
             context: if chunk.chunk.context.is_empty() {
@@ -81,10 +91,134 @@ impl ChunkMetadata {
             hash: chunk.chunk.hash.clone(),
             context_prev: chunk.chunk.context_prev.clone(),
             context_next: chunk.chunk.context_next.clone(),
+            language: (chunk.chunk.language != Language::Unknown)
+                .then(|| chunk.chunk.language.name().to_string()),
+            scope: chunk.chunk.scope_path(),
         }
     }
 }
 
+/// Chunk count up to which `IndexMode::Auto` picks exact search
+pub const EXACT_INDEX_MAX_CHUNKS: usize = 10_000;
+
+/// Key under which the resolved index mode is persisted
+const INDEX_MODE_KEY: &str = "index_mode";
+/// Key under which the requested index mode is persisted (may be `auto`)
+const REQUESTED_INDEX_MODE_KEY: &str = "requested_index_mode";
+/// Key recording whether every stored vector is L2-normalized
+const NORMALIZED_KEY: &str = "normalized";
+
+/// Vector index strategy
+///
+/// `Exact` scans every stored vector (brute force), `Approx` builds and queries
+/// arroy's random-projection trees. `Auto` resolves to one of them at build time
+/// based on the number of chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexMode {
+    #[default]
+    Auto,
+    Exact,
+    Approx,
+}
+
+impl IndexMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndexMode::Auto => "auto",
+            IndexMode::Exact => "exact",
+            IndexMode::Approx => "approx",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "auto" => Some(IndexMode::Auto),
+            "exact" | "brute-force" | "flat" => Some(IndexMode::Exact),
+            "approx" | "approximate" | "ann" => Some(IndexMode::Approx),
+            _ => None,
+        }
+    }
+
+    /// Resolve `Auto` into a concrete strategy for the given chunk count
+    pub fn resolve(self, total_chunks: usize) -> Self {
+        match self {
+            IndexMode::Auto if total_chunks <= EXACT_INDEX_MAX_CHUNKS => IndexMode::Exact,
+            IndexMode::Auto => IndexMode::Approx,
+            mode => mode,
+        }
+    }
+}
+
+/// Stored vector size up to which `VectorStorage::Auto` keeps vectors resident
+///
+/// About 40k chunks of a 384-dimension model: small indexes get the faster
+/// scans, larger ones stay memory-mapped so a long-running `serve` or MCP
+/// server doesn't pin the whole index in RAM.
+pub const RESIDENT_VECTORS_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Where exact search reads stored vectors from
+///
+/// `Mmap` scans them straight out of LMDB's memory map, so the OS decides how
+/// much of the database stays in RAM. `Resident` copies them into one
+/// contiguous matrix on the first search and reuses it until the database
+/// changes: faster repeated scans, at the cost of holding every vector in
+/// memory. `Auto` picks `Resident` up to [`RESIDENT_VECTORS_MAX_BYTES`].
+/// Approximate search always reads arroy's trees from the map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VectorStorage {
+    #[default]
+    Auto,
+    Resident,
+    Mmap,
+}
+
+impl VectorStorage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VectorStorage::Auto => "auto",
+            VectorStorage::Resident => "resident",
+            VectorStorage::Mmap => "mmap",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "auto" => Some(VectorStorage::Auto),
+            "resident" | "memory" | "ram" => Some(VectorStorage::Resident),
+            "mmap" | "disk" => Some(VectorStorage::Mmap),
+            _ => None,
+        }
+    }
+
+    /// Storage requested through `DEMONGREP_VECTOR_STORAGE` (default: auto)
+    pub fn from_env() -> Self {
+        std::env::var("DEMONGREP_VECTOR_STORAGE")
+            .ok()
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+
+    /// Resolve `Auto` for `total_chunks` vectors of `dimensions` floats
+    pub fn resolve(self, total_chunks: usize, dimensions: usize) -> Self {
+        let bytes = (total_chunks * dimensions * std::mem::size_of::<f32>()) as u64;
+        match self {
+            VectorStorage::Auto if bytes <= RESIDENT_VECTORS_MAX_BYTES => VectorStorage::Resident,
+            VectorStorage::Auto => VectorStorage::Mmap,
+            storage => storage,
+        }
+    }
+}
+
+/// Stored vectors copied into memory for `VectorStorage::Resident`
+struct ResidentVectors {
+    /// Last LMDB transaction when the copy was taken; any write invalidates it
+    txn_id: usize,
+    ids: Vec<ItemId>,
+    /// Row-major, `dimensions` floats per id
+    matrix: Vec<f32>,
+    norms: Vec<f32>,
+}
+
 /// Vector database using arroy + heed (LMDB)
 ///
 /// Single-file database with:
@@ -99,9 +233,25 @@ pub struct VectorStore {
     chunks: Database<U32<BigEndian>, SerdeBincode<ChunkMetadata>>,
     file_metadata: Database<Str, SerdeBincode<FileMeta>>,
     db_metadata: Database<Str, SerdeBincode<DbMetadata>>,
+    /// Index mode and normalization flags; None for read-only stores written
+    /// before the table existed (read as the defaults)
+    index_settings: Option<Database<Str, Str>>,
+    /// Chunk IDs per file path (without a leading `./`); None for read-only
+    /// stores written before the table existed
+    path_chunks: Option<Database<Str, SerdeBincode<Vec<u32>>>>,
     next_id: u32,
     dimensions: usize,
     indexed: bool,
+    /// Strategy requested for `build_index` (may be `Auto`)
+    requested_mode: IndexMode,
+    /// Strategy the current index was built with (never `Auto`)
+    active_mode: IndexMode,
+    /// Whether every stored vector is L2-normalized
+    normalized: bool,
+    /// Where exact search reads vectors from (may be `Auto`)
+    storage: VectorStorage,
+    /// In-memory copy of the vectors when exact search runs resident
+    resident: Mutex<Option<Arc<ResidentVectors>>>,
 }
 
 impl VectorStore {
@@ -144,19 +294,20 @@ impl VectorStore {
             env.create_database(&mut wtxn, Some("file_metadata"))?;
         let db_metadata: Database<Str, SerdeBincode<DbMetadata>> =
             env.create_database(&mut wtxn, Some("db_metadata"))?;
+        let index_settings: Database<Str, Str> =
+            env.create_database(&mut wtxn, Some("index_settings"))?;
+        let path_chunks: Database<Str, SerdeBincode<Vec<u32>>> =
+            env.create_database(&mut wtxn, Some("path_chunks"))?;
 
-        // Get the next ID by counting existing chunks
-        let next_id = chunks.len(&wtxn)? as u32;
+        let (next_id, requested_mode, active_mode, normalized) =
+            Self::read_settings(&wtxn, chunks, Some(index_settings))?;
+        Self::backfill_path_chunks(&mut wtxn, chunks, path_chunks)?;
 
         wtxn.commit()?;
 
-        // Check if database is already indexed by trying to open a reader
-        let indexed = if next_id > 0 {
-            let rtxn = env.read_txn()?;
-            Reader::open(&rtxn, 0, vectors).is_ok()
-        } else {
-            false
-        };
+        let rtxn = env.read_txn()?;
+        let indexed = Self::has_index(&rtxn, vectors, next_id, active_mode);
+        drop(rtxn);
 
         info_print!("✅ Database opened (next_id: {})", next_id);
 
@@ -166,12 +317,260 @@ impl VectorStore {
             chunks,
             file_metadata,
             db_metadata,
+            index_settings: Some(index_settings),
+            path_chunks: Some(path_chunks),
+            next_id,
+            dimensions,
+            indexed,
+            requested_mode,
+            active_mode,
+            normalized,
+            storage: VectorStorage::from_env(),
+            resident: Mutex::default(),
+        })
+    }
+
+    /// Open an existing vector store in read-only mode (for search)
+    ///
+    /// Only ever uses read transactions, so it never waits on the LMDB write
+    /// lock: several processes can search while a `serve`/`watch` process
+    /// keeps updating the same database. Unlike `new`, the database must
+    /// already exist.
+    pub fn open_readonly(db_path: &Path, dimensions: usize) -> Result<Self> {
+        if !db_path.join("data.mdb").exists() {
+            return Err(anyhow!("Vector database not found at {:?}", db_path));
+        }
+
+        // Same options as `new` so a writer in this process can share the env
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024 * 1024) // 10GB max
+                .max_dbs(10)
+                .open(db_path)?
+        };
+
+        let rtxn = env.read_txn()?;
+        let missing = |name: &str| anyhow!("Vector database is missing the '{}' table", name);
+
+        let vectors: ArroyDatabase<Cosine> = env
+            .open_database(&rtxn, Some("vectors"))?
+            .ok_or_else(|| missing("vectors"))?;
+        let chunks: Database<U32<BigEndian>, SerdeBincode<ChunkMetadata>> = env
+            .open_database(&rtxn, Some("chunks"))?
+            .ok_or_else(|| missing("chunks"))?;
+        let file_metadata: Database<Str, SerdeBincode<FileMeta>> = env
+            .open_database(&rtxn, Some("file_metadata"))?
+            .ok_or_else(|| missing("file_metadata"))?;
+        let db_metadata: Database<Str, SerdeBincode<DbMetadata>> = env
+            .open_database(&rtxn, Some("db_metadata"))?
+            .ok_or_else(|| missing("db_metadata"))?;
+        let index_settings: Option<Database<Str, Str>> =
+            env.open_database(&rtxn, Some("index_settings"))?;
+        let path_chunks: Option<Database<Str, SerdeBincode<Vec<u32>>>> =
+            env.open_database(&rtxn, Some("path_chunks"))?;
+
+        let (next_id, requested_mode, active_mode, normalized) =
+            Self::read_settings(&rtxn, chunks, index_settings)?;
+        let indexed = Self::has_index(&rtxn, vectors, next_id, active_mode);
+        drop(rtxn);
+
+        Ok(Self {
+            env,
+            vectors,
+            chunks,
+            file_metadata,
+            db_metadata,
+            index_settings,
+            path_chunks,
             next_id,
             dimensions,
             indexed,
+            requested_mode,
+            active_mode,
+            normalized,
+            storage: VectorStorage::from_env(),
+            resident: Mutex::default(),
         })
     }
 
+    /// Fill the path index from the stored chunks when it's empty but chunks
+    /// exist (databases written before the index was added)
+    fn backfill_path_chunks(
+        wtxn: &mut heed::RwTxn,
+        chunks: Database<U32<BigEndian>, SerdeBincode<ChunkMetadata>>,
+        path_chunks: Database<Str, SerdeBincode<Vec<u32>>>,
+    ) -> Result<()> {
+        if !path_chunks.is_empty(wtxn)? || chunks.is_empty(wtxn)? {
+            return Ok(());
+        }
+
+        let mut by_path: HashMap<String, Vec<u32>> = HashMap::new();
+        for item in chunks.iter(wtxn)? {
+            let (id, metadata) = item?;
+            by_path
+                .entry(path_key(&metadata.path).to_string())
+                .or_default()
+                .push(id);
+        }
+        for (path, ids) in &by_path {
+            path_chunks.put(wtxn, path, ids)?;
+        }
+        Ok(())
+    }
+
+    /// Record `id` under its chunk's path in the path index
+    fn link_path(&self, wtxn: &mut heed::RwTxn, path: &str, id: u32) -> Result<()> {
+        let Some(path_chunks) = self.path_chunks else {
+            return Ok(());
+        };
+        let key = path_key(path);
+        let mut ids = path_chunks.get(wtxn, key)?.unwrap_or_default();
+        ids.push(id);
+        path_chunks.put(wtxn, key, &ids)?;
+        Ok(())
+    }
+
+    /// Drop `id` from the path index, removing the path once it has no chunks
+    fn unlink_path(&self, wtxn: &mut heed::RwTxn, path: &str, id: u32) -> Result<()> {
+        let Some(path_chunks) = self.path_chunks else {
+            return Ok(());
+        };
+        let key = path_key(path);
+        let Some(mut ids) = path_chunks.get(wtxn, key)? else {
+            return Ok(());
+        };
+        ids.retain(|&other| other != id);
+        if ids.is_empty() {
+            path_chunks.delete(wtxn, key)?;
+        } else {
+            path_chunks.put(wtxn, key, &ids)?;
+        }
+        Ok(())
+    }
+
+    /// Read next_id, requested/active index modes and the normalization flag
+    ///
+    /// Without an `index_settings` table every setting takes its default.
+    fn read_settings(
+        txn: &RoTxn,
+        chunks: Database<U32<BigEndian>, SerdeBincode<ChunkMetadata>>,
+        index_settings: Option<Database<Str, Str>>,
+    ) -> Result<(u32, IndexMode, IndexMode, bool)> {
+        // Get the next ID by counting existing chunks
+        let next_id = chunks.len(txn)? as u32;
+        let setting = |key| Self::read_setting(txn, index_settings, key);
+
+        // Databases created before index modes existed were always approximate
+        let active_mode = setting(INDEX_MODE_KEY)?
+            .and_then(IndexMode::parse)
+            .unwrap_or(IndexMode::Approx);
+        let requested_mode = setting(REQUESTED_INDEX_MODE_KEY)?
+            .and_then(IndexMode::parse)
+            .unwrap_or_default();
+        // Vectors are normalized on insert; older non-empty databases may not be
+        let normalized = match setting(NORMALIZED_KEY)? {
+            Some(value) => value == "true",
+            None => next_id == 0,
+        };
+
+        Ok((next_id, requested_mode, active_mode, normalized))
+    }
+
+    /// Value of `key` in the settings table, if the table exists
+    fn read_setting<'t>(
+        txn: &'t RoTxn,
+        index_settings: Option<Database<Str, Str>>,
+        key: &str,
+    ) -> Result<Option<&'t str>> {
+        Ok(match index_settings {
+            Some(index_settings) => index_settings.get(txn, key)?,
+            None => None,
+        })
+    }
+
+    /// Check if database is already indexed by trying to open a reader
+    fn has_index(
+        txn: &RoTxn,
+        vectors: ArroyDatabase<Cosine>,
+        next_id: u32,
+        active_mode: IndexMode,
+    ) -> bool {
+        if next_id == 0 {
+            return false;
+        }
+        match active_mode {
+            IndexMode::Exact => true,
+            _ => Reader::open(txn, 0, vectors).is_ok(),
+        }
+    }
+
+    /// Check whether every stored vector is L2-normalized
+    pub fn is_normalized(&self) -> bool {
+        self.normalized
+    }
+
+    /// Normalize an embedding and add it to the vector database
+    fn put_vector(
+        &self,
+        writer: &Writer<Cosine>,
+        wtxn: &mut heed::RwTxn,
+        id: u32,
+        embedding: &[f32],
+    ) -> Result<()> {
+        if embedding.len() != self.dimensions {
+            return Err(anyhow!(
+                "Embedding dimension mismatch: expected {}, got {}",
+                self.dimensions,
+                embedding.len()
+            ));
+        }
+
+        let mut vector = embedding.to_vec();
+        l2_normalize(&mut vector);
+        writer.add_item(wtxn, id, &vector)?;
+        Ok(())
+    }
+
+    /// Persist the normalization flag after inserting normalized vectors
+    fn record_normalization(&self, wtxn: &mut heed::RwTxn) -> Result<()> {
+        let value = if self.normalized { "true" } else { "false" };
+        if let Some(index_settings) = self.index_settings {
+            index_settings.put(wtxn, NORMALIZED_KEY, value)?;
+        }
+        Ok(())
+    }
+
+    /// Set the index strategy used by `build_index`
+    ///
+    /// Defaults to the mode last requested for this database, or auto.
+    pub fn set_index_mode(&mut self, mode: IndexMode) {
+        self.requested_mode = mode;
+    }
+
+    /// Strategy the current index was built with
+    pub fn index_mode(&self) -> IndexMode {
+        self.active_mode
+    }
+
+    /// Set where exact search reads stored vectors from
+    ///
+    /// Defaults to `DEMONGREP_VECTOR_STORAGE`, or auto.
+    pub fn set_vector_storage(&mut self, storage: VectorStorage) {
+        self.storage = storage;
+        if self.storage == VectorStorage::Mmap {
+            self.drop_resident();
+        }
+    }
+
+    /// Storage exact search uses for the current number of chunks (never `Auto`)
+    pub fn vector_storage(&self) -> VectorStorage {
+        self.storage.resolve(self.next_id as usize, self.dimensions)
+    }
+
+    fn drop_resident(&mut self) {
+        *self.resident.get_mut().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
     /// Insert embedded chunks into the database
     ///
     /// Returns the number of chunks inserted
@@ -188,25 +587,18 @@ impl VectorStore {
         for chunk in &chunks {
             let id = self.next_id;
 
-            // Check embedding dimensions
-            if chunk.embedding.len() != self.dimensions {
-                return Err(anyhow!(
-                    "Embedding dimension mismatch: expected {}, got {}",
-                    self.dimensions,
-                    chunk.embedding.len()
-                ));
-            }
-
-            // Add vector to arroy
-            writer.add_item(&mut wtxn, id, &chunk.embedding)?;
+            // Add normalized vector to arroy
+            self.put_vector(&writer, &mut wtxn, id, &chunk.embedding)?;
 
             // Store metadata
             let metadata = ChunkMetadata::from_embedded_chunk(chunk);
             self.chunks.put(&mut wtxn, &id, &metadata)?;
+            self.link_path(&mut wtxn, &metadata.path, id)?;
 
             self.next_id += 1;
         }
 
+        self.record_normalization(&mut wtxn)?;
         wtxn.commit()?;
 
         // Mark as not indexed (need to rebuild index after inserts)
@@ -224,18 +616,35 @@ impl VectorStore {
 
     /// Build the vector index
     ///
-    /// Must be called after inserting chunks and before searching
+    /// Must be called after inserting chunks and before searching.
+    /// The strategy comes from `set_index_mode` (default: auto) and is persisted
+    /// so later searches use the same one.
     pub fn build_index(&mut self) -> Result<()> {
-        println!("🔨 Building vector index...");
-
         let mut wtxn = self.env.write_txn()?;
-        let writer = Writer::new(self.vectors, 0, self.dimensions);
+        let total_chunks = self.chunks.len(&wtxn)? as usize;
+        let mode = self.requested_mode.resolve(total_chunks);
+
+        println!("🔨 Building vector index ({})...", mode.as_str());
+
+        // Exact search scans stored vectors directly, no trees needed
+        if mode == IndexMode::Approx {
+            let writer = Writer::new(self.vectors, 0, self.dimensions);
+            let mut rng = StdRng::seed_from_u64(rand::random());
+            writer.builder(&mut rng).build(&mut wtxn)?;
+        }
 
-        let mut rng = StdRng::seed_from_u64(rand::random());
-        writer.builder(&mut rng).build(&mut wtxn)?;
+        if let Some(index_settings) = self.index_settings {
+            index_settings.put(&mut wtxn, INDEX_MODE_KEY, mode.as_str())?;
+            index_settings.put(
+                &mut wtxn,
+                REQUESTED_INDEX_MODE_KEY,
+                self.requested_mode.as_str(),
+            )?;
+        }
 
         wtxn.commit()?;
 
+        self.active_mode = mode;
         self.indexed = true;
 
         println!("✅ Index built successfully");
@@ -265,20 +674,28 @@ impl VectorStore {
             ));
         }
 
+        // Taken before the snapshot, so a write in between only causes a refresh
+        let txn_id = self.env.info().last_txn_id;
         let rtxn = self.env.read_txn()?;
-        let reader = Reader::open(&rtxn, 0, self.vectors)?;
 
-        // Perform ANN search with quality boost
-        let mut query = reader.nns(limit);
+        let results = match self.active_mode {
+            IndexMode::Exact => self.exact_nns(&rtxn, txn_id, query_embedding, limit)?,
+            _ => {
+                let reader = Reader::open(&rtxn, 0, self.vectors)?;
 
-        // Improve search quality by exploring more candidates
-        if let Some(n_trees) = NonZeroUsize::new(reader.n_trees()) {
-            if let Some(search_k) = NonZeroUsize::new(limit * n_trees.get() * 15) {
-                query.search_k(search_k);
-            }
-        }
+                // Perform ANN search with quality boost
+                let mut query = reader.nns(limit);
+
+                // Improve search quality by exploring more candidates
+                if let Some(n_trees) = NonZeroUsize::new(reader.n_trees()) {
+                    if let Some(search_k) = NonZeroUsize::new(limit * n_trees.get() * 15) {
+                        query.search_k(search_k);
+                    }
+                }
 
-        let results = query.by_vector(&rtxn, query_embedding)?;
+                query.by_vector(&rtxn, query_embedding)?
+            }
+        };
 
         // Fetch metadata for each result
         let mut search_results = Vec::new();
@@ -292,6 +709,8 @@ impl VectorStore {
                     start_line: metadata.start_line,
                     end_line: metadata.end_line,
                     kind: metadata.kind,
+                    language: metadata.language,
+                    scope: metadata.scope,
                     signature: metadata.signature,
                     docstring: metadata.docstring,
                     context: metadata.context,
@@ -305,6 +724,8 @@ impl VectorStore {
                     vector_rank: None,
                     fts_rank: None,
                     rerank_score: None,
+                    mtime: None,
+                    database: None,
                 });
             }
         }
@@ -312,6 +733,80 @@ impl VectorStore {
         Ok(search_results)
     }
 
+    /// Brute-force nearest neighbors over every stored vector
+    ///
+    /// Distances use the same scale as arroy's cosine distance: (1 - cos) / 2
+    fn exact_nns(
+        &self,
+        rtxn: &heed::RoTxn,
+        txn_id: usize,
+        query_embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<(ItemId, f32)>> {
+        let query_norm = norm(query_embedding);
+        let total_chunks = self.chunks.len(rtxn)? as usize;
+
+        let mut scored = Vec::with_capacity(total_chunks);
+        match self.storage.resolve(total_chunks, self.dimensions) {
+            VectorStorage::Resident => {
+                let resident = self.resident_vectors(rtxn, txn_id)?;
+                let rows = resident.matrix.chunks_exact(self.dimensions);
+                for ((&id, vector), &vector_norm) in
+                    resident.ids.iter().zip(rows).zip(&resident.norms)
+                {
+                    let distance =
+                        cosine_distance(vector, vector_norm, query_embedding, query_norm);
+                    scored.push((id, distance));
+                }
+            }
+            _ => {
+                let writer = Writer::new(self.vectors, 0, self.dimensions);
+                for item in writer.iter(rtxn)? {
+                    let (id, vector) = item?;
+                    let distance =
+                        cosine_distance(&vector, norm(&vector), query_embedding, query_norm);
+                    scored.push((id, distance));
+                }
+            }
+        }
+
+        scored.sort_by(|a, b| a.1.total_cmp(&b.1));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+
+    /// The in-memory copy of every stored vector, (re)loading it if the
+    /// database changed since it was taken
+    fn resident_vectors(&self, rtxn: &heed::RoTxn, txn_id: usize) -> Result<Arc<ResidentVectors>> {
+        let mut cached = self.resident.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(resident) = cached.as_ref().filter(|r| r.txn_id == txn_id) {
+            return Ok(Arc::clone(resident));
+        }
+        // Free the stale copy before building the new one
+        *cached = None;
+
+        let count = self.chunks.len(rtxn)? as usize;
+        let mut ids = Vec::with_capacity(count);
+        let mut matrix = Vec::with_capacity(count * self.dimensions);
+        let mut norms = Vec::with_capacity(count);
+        let writer = Writer::new(self.vectors, 0, self.dimensions);
+        for item in writer.iter(rtxn)? {
+            let (id, vector) = item?;
+            ids.push(id);
+            norms.push(norm(&vector));
+            matrix.extend_from_slice(&vector);
+        }
+
+        let resident = Arc::new(ResidentVectors {
+            txn_id,
+            ids,
+            matrix,
+            norms,
+        });
+        *cached = Some(Arc::clone(&resident));
+        Ok(resident)
+    }
+
     /// Get statistics about the vector store
     pub fn stats(&self) -> Result<StoreStats> {
         let rtxn = self.env.read_txn()?;
@@ -351,6 +846,9 @@ impl VectorStore {
                 deleted += 1;
             }
             // Delete from metadata
+            if let Some(metadata) = self.chunks.get(&wtxn, &id)? {
+                self.unlink_path(&mut wtxn, &metadata.path, id)?;
+            }
             self.chunks.delete(&mut wtxn, &id)?;
         }
 
@@ -394,6 +892,23 @@ impl VectorStore {
     ///
     /// Useful for tracking which chunks belong to which file
     pub fn insert_chunks_with_ids(&mut self, chunks: Vec<EmbeddedChunk>) -> Result<Vec<u32>> {
+        self.insert_chunks_with(&chunks, |_, _| Ok(()))
+    }
+
+    /// Insert chunks in a single write transaction, calling `on_insert` with
+    /// each assigned ID so callers can feed other indexes in the same pass
+    ///
+    /// IDs are assigned sequentially in input order. If `on_insert` fails the
+    /// transaction is aborted and no IDs are consumed, but whatever
+    /// `on_insert` already did for earlier chunks is not undone.
+    pub fn insert_chunks_with<F>(
+        &mut self,
+        chunks: &[EmbeddedChunk],
+        mut on_insert: F,
+    ) -> Result<Vec<u32>>
+    where
+        F: FnMut(u32, &EmbeddedChunk) -> Result<()>,
+    {
         if chunks.is_empty() {
             return Ok(vec![]);
         }
@@ -402,25 +917,20 @@ impl VectorStore {
         let mut wtxn = self.env.write_txn()?;
         let writer = Writer::new(self.vectors, 0, self.dimensions);
 
-        for chunk in &chunks {
-            let id = self.next_id;
+        for (offset, chunk) in chunks.iter().enumerate() {
+            let id = start_id + offset as u32;
 
-            if chunk.embedding.len() != self.dimensions {
-                return Err(anyhow!(
-                    "Embedding dimension mismatch: expected {}, got {}",
-                    self.dimensions,
-                    chunk.embedding.len()
-                ));
-            }
-
-            writer.add_item(&mut wtxn, id, &chunk.embedding)?;
+            self.put_vector(&writer, &mut wtxn, id, &chunk.embedding)?;
             let metadata = ChunkMetadata::from_embedded_chunk(chunk);
             self.chunks.put(&mut wtxn, &id, &metadata)?;
+            self.link_path(&mut wtxn, &metadata.path, id)?;
 
-            self.next_id += 1;
+            on_insert(id, chunk)?;
         }
 
+        self.record_normalization(&mut wtxn)?;
         wtxn.commit()?;
+        self.next_id = start_id + chunks.len() as u32;
         self.indexed = false;
 
         let ids: Vec<u32> = (start_id..self.next_id).collect();
@@ -438,11 +948,19 @@ impl VectorStore {
         self.vectors.clear(&mut wtxn)?;
         self.file_metadata.clear(&mut wtxn)?;
         self.db_metadata.clear(&mut wtxn)?;
+        if let Some(index_settings) = self.index_settings {
+            index_settings.clear(&mut wtxn)?;
+        }
+        if let Some(path_chunks) = self.path_chunks {
+            path_chunks.clear(&mut wtxn)?;
+        }
 
         wtxn.commit()?;
 
         self.next_id = 0;
         self.indexed = false;
+        self.normalized = true;
+        self.drop_resident();
 
         println!("✅ Database cleared");
         Ok(())
@@ -454,6 +972,91 @@ impl VectorStore {
         Ok(self.chunks.get(&rtxn, &id)?)
     }
 
+    /// Every chunk of a file with its ID, ordered by start line
+    ///
+    /// The path matches with or without a leading `./`. Reads only the file's
+    /// own chunks through the path index; stores opened read-only from before
+    /// the index existed fall back to scanning every chunk.
+    pub fn chunks_for_path(&self, path: &str) -> Result<Vec<(u32, ChunkMetadata)>> {
+        let rtxn = self.env.read_txn()?;
+        let key = path_key(path);
+        let mut found = Vec::new();
+
+        match self.path_chunks {
+            Some(path_chunks) => {
+                for id in path_chunks.get(&rtxn, key)?.unwrap_or_default() {
+                    note_chunk_read();
+                    if let Some(metadata) = self.chunks.get(&rtxn, &id)? {
+                        found.push((id, metadata));
+                    }
+                }
+            }
+            None => {
+                for item in self.chunks.iter(&rtxn)? {
+                    note_chunk_read();
+                    let (id, metadata) = item?;
+                    if path_key(&metadata.path) == key {
+                        found.push((id, metadata));
+                    }
+                }
+            }
+        }
+
+        found.sort_by_key(|(id, metadata)| (metadata.start_line, *id));
+        Ok(found)
+    }
+
+    /// IDs of every stored chunk, in ascending order
+    pub fn chunk_ids(&self) -> Result<Vec<u32>> {
+        let rtxn = self.env.read_txn()?;
+        let mut ids = Vec::new();
+        for item in self.chunks.iter(&rtxn)? {
+            let (id, _) = item?;
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// Get the stored (L2-normalized) embedding for a chunk
+    pub fn get_embedding(&self, id: u32) -> Result<Option<Vec<f32>>> {
+        let rtxn = self.env.read_txn()?;
+        let writer = Writer::new(self.vectors, 0, self.dimensions);
+        Ok(writer.item_vector(&rtxn, id)?)
+    }
+
+    /// Nearest neighbors of a stored chunk, searched with its own embedding
+    ///
+    /// Nothing is re-embedded and the chunk itself is left out of the results.
+    /// Returns no results when `id` isn't in the store.
+    pub fn nearest_to_id(&self, id: u32, limit: usize) -> Result<Vec<SearchResult>> {
+        let Some(embedding) = self.get_embedding(id)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut results = self.search(&embedding, limit.saturating_add(1))?;
+        results.retain(|r| r.id != id);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Stored embeddings of the given chunks, keyed by content hash
+    ///
+    /// Lets a re-index reuse the vectors of chunks whose content didn't change.
+    pub fn embeddings_by_hash(&self, ids: &[u32]) -> Result<HashMap<String, Vec<f32>>> {
+        let rtxn = self.env.read_txn()?;
+        let writer = Writer::new(self.vectors, 0, self.dimensions);
+        let mut embeddings = HashMap::new();
+        for &id in ids {
+            let Some(metadata) = self.chunks.get(&rtxn, &id)? else {
+                continue;
+            };
+            if let Some(vector) = writer.item_vector(&rtxn, id)? {
+                embeddings.insert(metadata.hash, vector);
+            }
+        }
+        Ok(embeddings)
+    }
+
     /// Get a chunk as SearchResult (for hybrid search)
     pub fn get_chunk_as_result(&self, id: u32) -> Result<Option<SearchResult>> {
         let rtxn = self.env.read_txn()?;
@@ -465,6 +1068,8 @@ impl VectorStore {
                 start_line: meta.start_line,
                 end_line: meta.end_line,
                 kind: meta.kind,
+                language: meta.language,
+                scope: meta.scope,
                 signature: meta.signature,
                 docstring: meta.docstring,
                 context: meta.context,
@@ -478,6 +1083,8 @@ impl VectorStore {
                 vector_rank: None,
                 fts_rank: None,
                 rerank_score: None,
+                mtime: None,
+                database: None,
             }))
         } else {
             Ok(None)
@@ -496,6 +1103,37 @@ impl VectorStore {
     }
 }
 
+/// Key of a chunk path in the path index
+fn path_key(path: &str) -> &str {
+    path.trim_start_matches("./")
+}
+
+#[cfg(test)]
+thread_local! {
+    static CHUNK_READS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Count one chunk metadata read, so tests can check lookups stay O(matches)
+fn note_chunk_read() {
+    #[cfg(test)]
+    CHUNK_READS.with(|reads| reads.set(reads.get() + 1));
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    vector.iter().map(|x| x * x).sum::<f32>().sqrt()
+}
+
+/// Cosine distance on arroy's scale: (1 - cos) / 2
+fn cosine_distance(vector: &[f32], vector_norm: f32, query: &[f32], query_norm: f32) -> f32 {
+    let dot: f32 = vector.iter().zip(query).map(|(a, b)| a * b).sum();
+    let denom = vector_norm * query_norm;
+    if denom != 0.0 {
+        (1.0 - dot / denom) / 2.0
+    } else {
+        0.0
+    }
+}
+
 /// Search result with metadata
 #[derive(Debug, Clone)]
 pub struct SearchResult {
@@ -505,6 +1143,10 @@ pub struct SearchResult {
     pub start_line: usize,
     pub end_line: usize,
     pub kind: String,
+    /// Language of the source file (e.g. "Rust")
+    pub language: Option<String>,
+    /// Symbol path of the chunk (e.g. "auth::Session::verify")
+    pub scope: Option<String>,
     pub signature: Option<String>,
     pub docstring: Option<String>,
     pub context: Option<String>,
@@ -525,10 +1167,15 @@ pub struct SearchResult {
     pub fts_rank: Option<usize>,
     /// Explain mode: neural reranker blended score
     pub rerank_score: Option<f32>,
+    /// Modification time of the source file when it was indexed (unix seconds),
+    /// filled in for `--sort recency`
+    pub mtime: Option<u64>,
+    /// Database the result came from, set when results of several are merged
+    pub database: Option<DatabaseType>,
 }
 
 /// Statistics about the vector store
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct StoreStats {
     pub total_chunks: usize,
     pub total_files: usize,
@@ -625,6 +1272,59 @@ impl VectorStore {
         Ok(chunk_ids)
     }
 
+    /// Look up the tracked metadata key and chunk IDs for a file
+    ///
+    /// Tries the path as given first, then any tracked path that resolves to
+    /// the same file (keys keep whatever form the file was indexed under).
+    pub fn file_chunk_ids(&self, path: &Path) -> Result<Option<(String, Vec<u32>)>> {
+        let rtxn = self.env.read_txn()?;
+        let path_str = path.to_string_lossy().to_string();
+
+        if let Some(meta) = self.file_metadata.get(&rtxn, &path_str)? {
+            return Ok(Some((path_str, meta.chunk_ids)));
+        }
+
+        let target = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        for item in self.file_metadata.iter(&rtxn)? {
+            let (key, meta) = item?;
+            let key_path = PathBuf::from(key);
+            let resolved = key_path.canonicalize().unwrap_or(key_path);
+            if resolved == target {
+                return Ok(Some((key.to_string(), meta.chunk_ids)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Modification time recorded for a file when it was indexed
+    pub fn file_mtime(&self, path: &str) -> Result<Option<u64>> {
+        let rtxn = self.env.read_txn()?;
+        Ok(self.file_metadata.get(&rtxn, path)?.map(|meta| meta.mtime))
+    }
+
+    /// Every tracked file with the chunk IDs recorded for it
+    pub fn tracked_files(&self) -> Result<Vec<(String, Vec<u32>)>> {
+        let rtxn = self.env.read_txn()?;
+        let mut files = Vec::new();
+        for item in self.file_metadata.iter(&rtxn)? {
+            let (path_str, meta) = item?;
+            files.push((path_str.to_string(), meta.chunk_ids));
+        }
+        Ok(files)
+    }
+
+    /// Every tracked file with the modification time recorded when it was indexed
+    pub fn tracked_file_mtimes(&self) -> Result<Vec<(String, u64)>> {
+        let rtxn = self.env.read_txn()?;
+        let mut files = Vec::new();
+        for item in self.file_metadata.iter(&rtxn)? {
+            let (path_str, meta) = item?;
+            files.push((path_str.to_string(), meta.mtime));
+        }
+        Ok(files)
+    }
+
     /// Find files that were deleted (exist in metadata but not on disk)
     pub fn find_deleted_files(&self) -> Result<Vec<(String, Vec<u32>)>> {
         let rtxn = self.env.read_txn()?;
@@ -785,6 +1485,311 @@ mod tests {
         assert!(results[0].score > results[1].score);
     }
 
+    #[test]
+    fn test_exact_mode_returns_true_nearest_neighbor() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let vectors: Vec<Vec<f32>> = (0..50)
+            .map(|i| {
+                let t = i as f32 * 0.37;
+                vec![t.sin(), t.cos(), (t * 0.5).sin(), 1.0]
+            })
+            .collect();
+        let store = exact_store(&db_path, &vectors);
+        assert_eq!(store.index_mode(), IndexMode::Exact);
+
+        let query = vec![0.3, -0.8, 0.2, 0.9];
+        let cosine = |v: &[f32]| {
+            let dot: f32 = v.iter().zip(&query).map(|(a, b)| a * b).sum();
+            let nv = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+            let nq = query.iter().map(|x| x * x).sum::<f32>().sqrt();
+            dot / (nv * nq)
+        };
+        let expected = (0..vectors.len())
+            .max_by(|&a, &b| cosine(&vectors[a]).total_cmp(&cosine(&vectors[b])))
+            .unwrap();
+
+        let results = store.search(&query, 5).unwrap();
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].id, expected as u32);
+
+        // Mode is persisted and reused after reopening
+        drop(store);
+        let store = VectorStore::new(&db_path, 4).unwrap();
+        assert_eq!(store.index_mode(), IndexMode::Exact);
+        assert!(store.is_indexed());
+        assert_eq!(store.search(&query, 1).unwrap()[0].id, expected as u32);
+    }
+
+    #[test]
+    fn test_get_embedding_roundtrip() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let mut store = VectorStore::new(&db_path, 4).unwrap();
+
+        // Already unit length, so normalization leaves it untouched
+        let embedding = vec![0.5, -0.5, 0.5, -0.5];
+        let ids = store
+            .insert_chunks_with_ids(vec![EmbeddedChunk::new(
+                Chunk::new(
+                    "fn test() {}".to_string(),
+                    0,
+                    1,
+                    ChunkKind::Function,
+                    "test.rs".to_string(),
+                ),
+                embedding.clone(),
+            )])
+            .unwrap();
+
+        let stored = store.get_embedding(ids[0]).unwrap().unwrap();
+        let bits = |v: &[f32]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&stored), bits(&embedding));
+
+        assert!(store.get_embedding(ids[0] + 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_nearest_to_id_returns_cluster_neighbors() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let mut store = VectorStore::new(&db_path, 4).unwrap();
+
+        // Two tight clusters: around the x axis (auth code) and the y axis (math code)
+        let vectors = [
+            ("auth_login", vec![1.0, 0.05, 0.0, 0.0]),
+            ("auth_logout", vec![1.0, 0.0, 0.05, 0.0]),
+            ("auth_session", vec![1.0, 0.1, 0.0, 0.05]),
+            ("math_add", vec![0.05, 1.0, 0.0, 0.0]),
+            ("math_mul", vec![0.0, 1.0, 0.05, 0.0]),
+            ("math_div", vec![0.0, 1.0, 0.1, 0.05]),
+        ];
+        let chunks = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, (name, v))| {
+                EmbeddedChunk::new(
+                    Chunk::new(
+                        format!("fn {}() {{}}", name),
+                        i,
+                        i + 1,
+                        ChunkKind::Function,
+                        format!("{}.rs", name),
+                    ),
+                    v.clone(),
+                )
+            })
+            .collect();
+        let ids = store.insert_chunks_with_ids(chunks).unwrap();
+        store.build_index().unwrap();
+
+        let names = |results: Vec<SearchResult>| {
+            let mut names: Vec<String> = results
+                .into_iter()
+                .map(|r| r.path.trim_end_matches(".rs").to_string())
+                .collect();
+            names.sort();
+            names
+        };
+
+        let related = store.nearest_to_id(ids[0], 2).unwrap();
+        assert_eq!(names(related), ["auth_logout", "auth_session"]);
+
+        let related = store.nearest_to_id(ids[4], 2).unwrap();
+        assert_eq!(names(related), ["math_add", "math_div"]);
+
+        // The chunk itself never comes back, even when asking for everything
+        let all = store.nearest_to_id(ids[0], 10).unwrap();
+        assert_eq!(all.len(), 5);
+        assert!(all.iter().all(|r| r.id != ids[0]));
+
+        assert!(store.nearest_to_id(ids[5] + 100, 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_insert_normalizes_vectors() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let mut store = VectorStore::new(&db_path, 4).unwrap();
+        assert!(store.is_normalized());
+
+        let chunks = vec![EmbeddedChunk::new(
+            Chunk::new(
+                "fn test() {}".to_string(),
+                0,
+                1,
+                ChunkKind::Function,
+                "test.rs".to_string(),
+            ),
+            vec![3.0, 4.0, 0.0, 0.0], // Length 5, not unit
+        )];
+        store.insert_chunks(chunks).unwrap();
+
+        let rtxn = store.env.read_txn().unwrap();
+        let writer = Writer::<Cosine>::new(store.vectors, 0, 4);
+        let stored = writer.item_vector(&rtxn, 0).unwrap().unwrap();
+        drop(rtxn);
+
+        let magnitude: f32 = stored.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((magnitude - 1.0).abs() < 1e-6);
+        assert!((stored[0] - 0.6).abs() < 1e-6);
+
+        // Flag is persisted in the database
+        drop(store);
+        let store = VectorStore::new(&db_path, 4).unwrap();
+        assert!(store.is_normalized());
+    }
+
+    #[test]
+    fn test_index_mode_auto_resolution() {
+        assert_eq!(IndexMode::Auto.resolve(10), IndexMode::Exact);
+        assert_eq!(
+            IndexMode::Auto.resolve(EXACT_INDEX_MAX_CHUNKS + 1),
+            IndexMode::Approx
+        );
+        assert_eq!(IndexMode::Approx.resolve(10), IndexMode::Approx);
+        assert_eq!(IndexMode::parse("exact"), Some(IndexMode::Exact));
+        assert_eq!(IndexMode::parse("bogus"), None);
+    }
+
+    #[test]
+    fn test_vector_storage_auto_resolution() {
+        let max_chunks = RESIDENT_VECTORS_MAX_BYTES as usize / (384 * 4);
+        assert_eq!(
+            VectorStorage::Auto.resolve(max_chunks, 384),
+            VectorStorage::Resident
+        );
+        assert_eq!(
+            VectorStorage::Auto.resolve(max_chunks + 1, 384),
+            VectorStorage::Mmap
+        );
+        assert_eq!(VectorStorage::Mmap.resolve(10, 384), VectorStorage::Mmap);
+        assert_eq!(VectorStorage::parse("MMAP"), Some(VectorStorage::Mmap));
+        assert_eq!(VectorStorage::parse("bogus"), None);
+    }
+
+    /// Exact-mode store with one chunk per vector
+    fn exact_store(db_path: &Path, vectors: &[Vec<f32>]) -> VectorStore {
+        let mut store = VectorStore::new(db_path, vectors[0].len()).unwrap();
+        store.set_index_mode(IndexMode::Exact);
+        let chunks = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                EmbeddedChunk::new(
+                    Chunk::new(
+                        format!("fn f{}() {{}}", i),
+                        i,
+                        i + 1,
+                        ChunkKind::Function,
+                        format!("f{}.rs", i),
+                    ),
+                    v.clone(),
+                )
+            })
+            .collect();
+        store.insert_chunks(chunks).unwrap();
+        store.build_index().unwrap();
+        store
+    }
+
+    #[test]
+    fn test_resident_and_mmap_storage_agree() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let vectors: Vec<Vec<f32>> = (0..40)
+            .map(|i| {
+                let t = i as f32 * 0.61;
+                vec![t.cos(), t.sin(), 0.5, (t * 0.3).cos()]
+            })
+            .collect();
+        let mut store = exact_store(&db_path, &vectors);
+        let query = [0.2, 0.9, -0.1, 0.4];
+        let ranked = |store: &VectorStore| -> Vec<(u32, f32)> {
+            let results = store.search(&query, 10).unwrap();
+            results.iter().map(|r| (r.id, r.distance)).collect()
+        };
+
+        store.set_vector_storage(VectorStorage::Mmap);
+        assert_eq!(store.vector_storage(), VectorStorage::Mmap);
+        let from_mmap = ranked(&store);
+        store.set_vector_storage(VectorStorage::Resident);
+        assert_eq!(ranked(&store), from_mmap);
+
+        // Writes invalidate the resident copy
+        let exact_match = EmbeddedChunk::new(
+            Chunk::new(
+                "fn exact() {}".to_string(),
+                0,
+                1,
+                ChunkKind::Function,
+                "exact.rs".to_string(),
+            ),
+            query.to_vec(),
+        );
+        store.insert_chunks(vec![exact_match]).unwrap();
+        store.build_index().unwrap();
+        assert_eq!(store.search(&query, 1).unwrap()[0].content, "fn exact() {}");
+    }
+
+    /// Anonymous (heap) resident set size of this process, in bytes
+    #[cfg(target_os = "linux")]
+    fn rss_anon_bytes() -> u64 {
+        let status = std::fs::read_to_string("/proc/self/status").unwrap();
+        let kb: u64 = status
+            .lines()
+            .find_map(|line| line.strip_prefix("RssAnon:"))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|kb| kb.parse().ok())
+            .unwrap();
+        kb * 1024
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[ignore] // Builds a ~150 MB index
+    fn test_mmap_storage_keeps_vectors_off_the_heap() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let dimensions = 384;
+        let vectors: Vec<Vec<f32>> = (0..100_000)
+            .map(|i| {
+                (0..dimensions)
+                    .map(|d| ((i * 31 + d * 7) % 97) as f32 - 48.0)
+                    .collect()
+            })
+            .collect();
+        let mut store = exact_store(&db_path, &vectors);
+        drop(vectors);
+        let matrix_bytes = (100_000 * dimensions * 4) as u64;
+        let query = vec![1.0; dimensions];
+
+        store.set_vector_storage(VectorStorage::Mmap);
+        let before = rss_anon_bytes();
+        store.search(&query, 10).unwrap();
+        let mmap_growth = rss_anon_bytes().saturating_sub(before);
+
+        store.set_vector_storage(VectorStorage::Resident);
+        let before = rss_anon_bytes();
+        store.search(&query, 10).unwrap();
+        let resident_growth = rss_anon_bytes().saturating_sub(before);
+
+        assert!(
+            resident_growth > matrix_bytes / 2,
+            "resident: {} bytes",
+            resident_growth
+        );
+        assert!(
+            mmap_growth < matrix_bytes / 8,
+            "mmap: {} bytes",
+            mmap_growth
+        );
+    }
+
     #[test]
     fn test_stats() {
         let temp_dir = tempdir().unwrap();
@@ -856,6 +1861,60 @@ mod tests {
         assert!(!stats.indexed);
     }
 
+    #[test]
+    fn test_language_round_trips_through_storage() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let mut rust_chunk = Chunk::new(
+            "fn test() {}".to_string(),
+            0,
+            1,
+            ChunkKind::Function,
+            "test.rs".to_string(),
+        );
+        rust_chunk.language = Language::Rust;
+        let unknown_chunk = Chunk::new(
+            "notes".to_string(),
+            0,
+            1,
+            ChunkKind::Block,
+            "NOTES".to_string(),
+        );
+
+        {
+            let mut store = VectorStore::new(&db_path, 4).unwrap();
+            store
+                .insert_chunks(vec![
+                    EmbeddedChunk::new(rust_chunk, vec![1.0, 0.0, 0.0, 0.0]),
+                    EmbeddedChunk::new(unknown_chunk, vec![0.0, 1.0, 0.0, 0.0]),
+                ])
+                .unwrap();
+            store.build_index().unwrap();
+        }
+
+        let store = VectorStore::open_readonly(&db_path, 4).unwrap();
+        assert_eq!(
+            store.get_chunk(0).unwrap().unwrap().language.as_deref(),
+            Some("Rust")
+        );
+        assert_eq!(store.get_chunk(1).unwrap().unwrap().language, None);
+
+        let results = store.search(&[1.0, 0.0, 0.0, 0.0], 2).unwrap();
+        assert_eq!(results[0].path, "test.rs");
+        assert_eq!(results[0].language.as_deref(), Some("Rust"));
+        assert_eq!(results[1].language, None);
+        assert_eq!(
+            store
+                .get_chunk_as_result(0)
+                .unwrap()
+                .unwrap()
+                .language
+                .as_deref(),
+            Some("Rust")
+        );
+    }
+
     #[test]
     fn test_get_chunk() {
         let temp_dir = tempdir().unwrap();
@@ -884,6 +1943,45 @@ mod tests {
         assert_eq!(metadata.path, "test.rs");
     }
 
+    #[test]
+    fn test_chunks_for_path_reads_only_matches() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let mut store = VectorStore::new(&db_path, 4).unwrap();
+
+        let chunk = |path: &str, start_line: usize| {
+            EmbeddedChunk::new(
+                Chunk::new(
+                    format!("fn f{}() {{}}", start_line),
+                    start_line,
+                    start_line + 1,
+                    ChunkKind::Function,
+                    path.to_string(),
+                ),
+                vec![1.0, 0.0, 0.0, 0.0],
+            )
+        };
+        let mut chunks: Vec<EmbeddedChunk> = (0..50).map(|i| chunk("src/other.rs", i)).collect();
+        chunks.push(chunk("./src/target.rs", 40));
+        chunks.push(chunk("./src/target.rs", 10));
+        store.insert_chunks(chunks).unwrap();
+
+        let reads_before = CHUNK_READS.with(|reads| reads.get());
+        let found = store.chunks_for_path("src/target.rs").unwrap();
+        let reads = CHUNK_READS.with(|reads| reads.get()) - reads_before;
+
+        let lines: Vec<usize> = found.iter().map(|(_, c)| c.start_line).collect();
+        assert_eq!(lines, vec![10, 40]);
+        assert!(found.iter().all(|(_, c)| c.path == "./src/target.rs"));
+        assert_eq!(reads, 2, "lookup should read only the file's chunks");
+
+        // Deleting a chunk drops it from the index; clearing empties it
+        store.delete_chunks(&[found[0].0]).unwrap();
+        assert_eq!(store.chunks_for_path("./src/target.rs").unwrap().len(), 1);
+        store.clear().unwrap();
+        assert!(store.chunks_for_path("src/target.rs").unwrap().is_empty());
+    }
+
     #[test]
     fn test_persistence() {
         let temp_dir = tempdir().unwrap();
@@ -919,4 +2017,117 @@ mod tests {
             assert!(metadata.is_some());
         }
     }
+
+    fn two_chunks() -> Vec<EmbeddedChunk> {
+        vec![
+            EmbeddedChunk::new(
+                Chunk::new(
+                    "fn authenticate() {}".to_string(),
+                    0,
+                    1,
+                    ChunkKind::Function,
+                    "auth.rs".to_string(),
+                ),
+                vec![1.0, 0.0, 0.0, 0.0],
+            ),
+            EmbeddedChunk::new(
+                Chunk::new(
+                    "fn render() {}".to_string(),
+                    0,
+                    1,
+                    ChunkKind::Function,
+                    "view.rs".to_string(),
+                ),
+                vec![0.0, 1.0, 0.0, 0.0],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_readonly_search_while_writer_holds_db() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let mut writer = VectorStore::new(&db_path, 4).unwrap();
+        writer.insert_chunks(two_chunks()).unwrap();
+        writer.build_index().unwrap();
+
+        // Writer holds the LMDB write lock while a reader searches
+        let wtxn = writer.env.write_txn().unwrap();
+        let results = std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let reader = VectorStore::open_readonly(&db_path, 4).unwrap();
+                    assert!(reader.is_indexed());
+                    reader.search(&[0.9, 0.1, 0.0, 0.0], 2).unwrap()
+                })
+                .join()
+                .unwrap()
+        });
+        drop(wtxn);
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].content.contains("authenticate"));
+
+        // The writer can keep updating while the reader stays open
+        let reader = VectorStore::open_readonly(&db_path, 4).unwrap();
+        writer.insert_chunks(two_chunks()).unwrap();
+        writer.build_index().unwrap();
+        assert_eq!(reader.search(&[0.0, 1.0, 0.0, 0.0], 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_readonly_opens_database_without_newer_tables() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("baseline.db");
+        std::fs::create_dir_all(&db_path).unwrap();
+
+        // The layout written before `index_settings` and `path_chunks` existed
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(10 * 1024 * 1024 * 1024)
+                .max_dbs(10)
+                .open(&db_path)
+                .unwrap()
+        };
+        let mut wtxn = env.write_txn().unwrap();
+        let vectors: ArroyDatabase<Cosine> =
+            env.create_database(&mut wtxn, Some("vectors")).unwrap();
+        let chunks: Database<U32<BigEndian>, SerdeBincode<ChunkMetadata>> =
+            env.create_database(&mut wtxn, Some("chunks")).unwrap();
+        env.create_database::<Str, SerdeBincode<FileMeta>>(&mut wtxn, Some("file_metadata"))
+            .unwrap();
+        env.create_database::<Str, SerdeBincode<DbMetadata>>(&mut wtxn, Some("db_metadata"))
+            .unwrap();
+        let writer = Writer::new(vectors, 0, 4);
+        for (id, chunk) in two_chunks().iter().enumerate() {
+            let id = id as u32;
+            writer.add_item(&mut wtxn, id, &chunk.embedding).unwrap();
+            chunks
+                .put(&mut wtxn, &id, &ChunkMetadata::from_embedded_chunk(chunk))
+                .unwrap();
+        }
+        writer
+            .builder(&mut StdRng::seed_from_u64(42))
+            .build(&mut wtxn)
+            .unwrap();
+        wtxn.commit().unwrap();
+        env.prepare_for_closing().wait();
+
+        let store = VectorStore::open_readonly(&db_path, 4).unwrap();
+        assert!(store.is_indexed());
+        let results = store.search(&[1.0, 0.0, 0.0, 0.0], 1).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].content.contains("authenticate"));
+        assert_eq!(store.chunks_for_path("view.rs").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_readonly_requires_existing_db() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("missing.db");
+
+        assert!(VectorStore::open_readonly(&db_path, 4).is_err());
+        assert!(!db_path.exists());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::file::{is_generated_file, AllowGenerated};
 use anyhow::{anyhow, Result};
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
@@ -31,6 +32,9 @@ pub struct FileWatcher {
     debouncer: Option<Debouncer<RecommendedWatcher, FileIdMap>>,
     receiver: Option<Receiver<DebounceEventResult>>,
     gitignore: Option<Gitignore>,
+    include_hidden: bool,
+    allow_generated: AllowGenerated,
+    debounce_ms: Option<u64>,
 }
 
 impl FileWatcher {
@@ -44,9 +48,31 @@ impl FileWatcher {
             debouncer: None,
             receiver: None,
             gitignore,
+            include_hidden: false,
+            allow_generated: AllowGenerated::default(),
+            debounce_ms: None,
         }
     }
 
+    /// Set whether to watch hidden files and directories (default: false)
+    ///
+    /// Mirrors `FileWalker::include_hidden`; gitignore and the built-in
+    /// guards (`.git`, `.demongrep`, `node_modules`, ...) still apply.
+    pub fn include_hidden(mut self, include: bool) -> Self {
+        self.include_hidden = include;
+        self
+    }
+
+    /// Watch generated-looking files matching these `.gitignore`-style
+    /// patterns (relative to the root) too
+    ///
+    /// Mirrors `FileWalker::allow_generated`: other generated and minified
+    /// files are ignored, as they aren't indexed.
+    pub fn allow_generated(mut self, patterns: &[String]) -> Self {
+        self.allow_generated = AllowGenerated::new(&self.root, patterns);
+        self
+    }
+
     /// Build gitignore matcher from .gitignore, .demongrepignore, and .osgrepignore
     fn build_gitignore(root: &Path) -> Option<Gitignore> {
         let mut builder = GitignoreBuilder::new(root);
@@ -107,6 +133,7 @@ impl FileWatcher {
 
         self.receiver = Some(rx);
         self.debouncer = Some(debouncer);
+        self.debounce_ms = Some(debounce_ms);
 
         // Start watching the root directory
         if let Some(ref mut debouncer) = self.debouncer {
@@ -131,10 +158,27 @@ impl FileWatcher {
         }
         self.debouncer = None;
         self.receiver = None;
+        self.debounce_ms = None;
+    }
+
+    /// Debounce interval passed to `start`, while watching
+    pub fn debounce_ms(&self) -> Option<u64> {
+        self.debounce_ms
     }
 
     /// Check if a path should be ignored
     fn should_ignore(&self, path: &Path) -> bool {
+        // Skip hidden files and directories unless opted in
+        if !self.include_hidden {
+            let relative_path = path.strip_prefix(&self.root).unwrap_or(path);
+            let hidden = relative_path
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+            if hidden {
+                return true;
+            }
+        }
+
         // Use gitignore matcher if available
         if let Some(ref gitignore) = self.gitignore {
             // Make path relative to root for gitignore matching
@@ -208,7 +252,9 @@ impl FileWatcher {
             }
         }
 
-        false
+        // Generated and minified files aren't indexed either; deletions still
+        // go through so chunks indexed earlier are removed
+        path.is_file() && is_generated_file(path) && !self.allow_generated.allows(path)
     }
 
     /// Poll for file events (non-blocking)
@@ -362,6 +408,49 @@ mod tests {
         assert!(!watcher.should_ignore(&rs_path));
     }
 
+    #[test]
+    fn test_should_ignore_generated() {
+        let dir = tempdir().unwrap();
+        let bundle = dir.path().join("app.min.js");
+        fs::write(&bundle, "console.log(1);\n").unwrap();
+        let header = dir.path().join("api.go");
+        fs::write(
+            &header,
+            "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n",
+        )
+        .unwrap();
+        let source = dir.path().join("main.go");
+        fs::write(&source, "package main\n").unwrap();
+
+        let watcher = FileWatcher::new(dir.path().to_path_buf());
+        assert!(watcher.should_ignore(&bundle));
+        assert!(watcher.should_ignore(&header));
+        assert!(!watcher.should_ignore(&source));
+        // A deleted generated file is still reported
+        assert!(!watcher.should_ignore(&dir.path().join("gone.min.js")));
+
+        let watcher =
+            FileWatcher::new(dir.path().to_path_buf()).allow_generated(&["api.go".to_string()]);
+        assert!(!watcher.should_ignore(&header));
+        assert!(watcher.should_ignore(&bundle));
+    }
+
+    #[test]
+    fn test_should_ignore_hidden() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join(".config/setup.sh");
+        let git_path = dir.path().join(".git/config");
+        let db_path = dir.path().join(".demongrep/store/data.mdb");
+
+        let watcher = FileWatcher::new(dir.path().to_path_buf());
+        assert!(watcher.should_ignore(&script));
+
+        let watcher = FileWatcher::new(dir.path().to_path_buf()).include_hidden(true);
+        assert!(!watcher.should_ignore(&script));
+        assert!(watcher.should_ignore(&git_path));
+        assert!(watcher.should_ignore(&db_path));
+    }
+
     #[test]
     #[ignore] // Requires actual filesystem events
     fn test_file_watcher() {
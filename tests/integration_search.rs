@@ -1,8 +1,10 @@
 use anyhow::Result;
-use demongrep::chunker::{Chunk, ChunkKind};
+use demongrep::chunker::{Chunk, ChunkKind, SemanticChunker};
 use demongrep::database::DatabaseManagerBuilder;
 use demongrep::embed::{EmbeddedChunk, ModelType};
+use demongrep::file::FileWalker;
 use demongrep::fts::FtsStore;
+use demongrep::index::{ingest_chunks, reindex_file_in_store};
 use demongrep::vectordb::VectorStore;
 use tempfile::Builder;
 
@@ -11,6 +13,14 @@ fn workspace_tempdir(prefix: &str) -> Result<tempfile::TempDir> {
     Ok(Builder::new().prefix(prefix).tempdir_in(cwd)?)
 }
 
+/// Embeds every chunk as the same unit vector, without a model
+fn stub_embed(chunks: Vec<Chunk>) -> Result<Vec<EmbeddedChunk>> {
+    Ok(chunks
+        .into_iter()
+        .map(|chunk| EmbeddedChunk::new(chunk, vec![1.0, 0.0, 0.0, 0.0]))
+        .collect())
+}
+
 fn make_embedded_chunk(
     path: &str,
     content: &str,
@@ -146,3 +156,231 @@ fn integration_hybrid_search_respects_offset_and_code_tokenization() -> Result<(
 
     Ok(())
 }
+
+#[test]
+fn integration_reindex_file_only_touches_that_file() -> Result<()> {
+    let root = workspace_tempdir("itest-reindex-")?;
+    let db_path = root.path().join(".demongrep.db");
+    let edited = root.path().join("edited.rs");
+    let untouched = root.path().join("untouched.rs");
+    std::fs::write(&edited, "fn original_name() -> u32 {\n    1\n}\n")?;
+    std::fs::write(&untouched, "fn stable_helper() -> u32 {\n    2\n}\n")?;
+
+    let mut store = VectorStore::new(&db_path, 4)?;
+    let mut fts = FtsStore::new(&db_path)?;
+    let mut chunker = SemanticChunker::new(100, 2000, 10);
+    reindex_file_in_store(
+        &mut store,
+        Some(&mut fts),
+        &mut chunker,
+        &edited,
+        stub_embed,
+    )?;
+    let untouched_ids = reindex_file_in_store(
+        &mut store,
+        Some(&mut fts),
+        &mut chunker,
+        &untouched,
+        stub_embed,
+    )?
+    .chunk_ids;
+    assert!(!untouched_ids.is_empty());
+
+    std::fs::write(&edited, "fn renamed_function() -> u32 {\n    3\n}\n")?;
+    let outcome = reindex_file_in_store(
+        &mut store,
+        Some(&mut fts),
+        &mut chunker,
+        &edited,
+        stub_embed,
+    )?;
+    assert!(outcome.removed > 0);
+    assert!(!outcome.chunk_ids.is_empty());
+
+    // The untouched file keeps its chunks and metadata
+    let (_, ids) = store
+        .file_chunk_ids(&untouched)?
+        .expect("untouched file tracked");
+    assert_eq!(ids, untouched_ids);
+    for id in &untouched_ids {
+        assert!(store.get_chunk(*id)?.is_some());
+    }
+
+    // Only the new version of the edited file is searchable
+    assert!(fts.search("original_name", 10)?.is_empty());
+    let hits = fts.search("renamed_function", 10)?;
+    assert!(!hits.is_empty());
+    assert!(hits
+        .iter()
+        .all(|hit| outcome.chunk_ids.contains(&hit.chunk_id)));
+    assert!(store.is_indexed());
+
+    Ok(())
+}
+
+#[test]
+fn integration_no_fts_index_is_vector_searchable() -> Result<()> {
+    let root = workspace_tempdir("itest-nofts-")?;
+    let db_path = root.path().join(".demongrep.db");
+
+    let mut store = VectorStore::new(&db_path, 4)?;
+    let ids = ingest_chunks(
+        &mut store,
+        None,
+        &[
+            make_embedded_chunk(
+                "auth.rs",
+                "fn authenticate() {}",
+                Some("authenticate"),
+                ChunkKind::Function,
+                vec![1.0, 0.0, 0.0, 0.0],
+            ),
+            make_embedded_chunk(
+                "math.rs",
+                "fn calculate() {}",
+                Some("calculate"),
+                ChunkKind::Function,
+                vec![0.0, 1.0, 0.0, 0.0],
+            ),
+        ],
+    )?;
+    store.build_index()?;
+    drop(store);
+    assert_eq!(ids, vec![0, 1]);
+    assert!(!db_path.join("fts").exists());
+
+    let manager = DatabaseManagerBuilder::new()
+        .add_database(db_path)
+        .with_model_type(ModelType::default())
+        .with_dimensions(4)
+        .build()?;
+    assert!(manager.databases()[0].fts_store().is_none());
+
+    // Hybrid search falls back to vector similarity alone
+    let query_embedding = vec![1.0, 0.0, 0.0, 0.0];
+    let results = manager.hybrid_search_all("authenticate", &query_embedding, 2, 0, 20.0)?;
+    let paths: Vec<&str> = results.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(paths, vec!["auth.rs", "math.rs"]);
+    assert!(results.iter().all(|r| r.fts_score.is_none()));
+
+    Ok(())
+}
+
+#[test]
+fn integration_json_search_without_database_prints_json_error() -> Result<()> {
+    let root = workspace_tempdir("itest-json-err-")?;
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_demongrep"))
+        .args(["search", "auth", "--json", "--path"])
+        .arg(root.path())
+        .env("HOME", root.path())
+        .output()?;
+
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["error"]["code"], "database_not_found");
+    assert!(json["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Database not found"));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_demongrep"))
+        .args([
+            "search",
+            "auth",
+            "--json",
+            "--model",
+            "no-such-model",
+            "--path",
+        ])
+        .arg(root.path())
+        .env("HOME", root.path())
+        .output()?;
+
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["error"]["code"], "unknown_model");
+    Ok(())
+}
+
+#[test]
+fn integration_search_with_missing_path_names_it() -> Result<()> {
+    let root = workspace_tempdir("itest-missing-path-")?;
+    let missing = root.path().join("no-such-dir");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_demongrep"))
+        .args(["search", "auth", "--json", "--path"])
+        .arg(&missing)
+        .env("HOME", root.path())
+        .output()?;
+
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["error"]["code"], "invalid_config");
+    let message = json["error"]["message"].as_str().unwrap();
+    assert!(message.contains(&missing.display().to_string()));
+    assert!(message.contains("check the spelling"));
+    Ok(())
+}
+
+#[test]
+fn integration_multi_root_index_searches_across_roots() -> Result<()> {
+    let root = workspace_tempdir("itest-roots-")?;
+    let db_path = root.path().join(".demongrep.db");
+    for (dir, source) in [
+        ("backend", "fn handle_request() -> u32 {\n    1\n}\n"),
+        ("shared", "fn shared_helper() -> u32 {\n    2\n}\n"),
+        ("frontend", "fn render_page() -> u32 {\n    3\n}\n"),
+    ] {
+        std::fs::create_dir_all(root.path().join(dir))?;
+        std::fs::write(root.path().join(dir).join("lib.rs"), source)?;
+    }
+
+    // Walk backend/ and shared/ into one store, leaving frontend/ out
+    let (files, _) =
+        FileWalker::with_roots([root.path().join("backend"), root.path().join("shared")]).walk()?;
+    assert_eq!(files.len(), 2);
+
+    let mut store = VectorStore::new(&db_path, 4)?;
+    let mut fts = FtsStore::new(&db_path)?;
+    let mut chunker = SemanticChunker::new(100, 2000, 10);
+    for file in &files {
+        reindex_file_in_store(
+            &mut store,
+            Some(&mut fts),
+            &mut chunker,
+            &file.path,
+            stub_embed,
+        )?;
+    }
+    assert!(!fts.search("handle_request", 10)?.is_empty());
+    assert!(!fts.search("shared_helper", 10)?.is_empty());
+    assert!(fts.search("render_page", 10)?.is_empty());
+    drop(store);
+    drop(fts);
+
+    let manager = DatabaseManagerBuilder::new()
+        .add_database(db_path)
+        .with_model_type(ModelType::default())
+        .with_dimensions(4)
+        .build()?;
+    let query_embedding = vec![1.0, 0.0, 0.0, 0.0];
+    let dirs_for = |query: &str| -> Result<Vec<String>> {
+        let results = manager.hybrid_search_all(query, &query_embedding, 10, 0, 20.0)?;
+        let mut dirs: Vec<String> = results
+            .iter()
+            .map(|r| {
+                let path = std::path::Path::new(&r.path);
+                let parent = path.parent().and_then(|p| p.file_name()).unwrap();
+                parent.to_string_lossy().to_string()
+            })
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        Ok(dirs)
+    };
+
+    assert_eq!(dirs_for("function")?, vec!["backend", "shared"]);
+    assert_eq!(dirs_for("shared_helper")?, vec!["backend", "shared"]);
+    Ok(())
+}